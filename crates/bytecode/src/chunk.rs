@@ -1,16 +1,31 @@
 use crate::InstructionReader;
 use koto_memory::Ptr;
-use koto_parser::{ConstantPool, KString, Span};
+use koto_parser::{ConstantIndex, ConstantPool, KString, Span};
 use std::fmt::{self, Write};
 
 /// Debug information for a Koto program
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DebugInfo {
     source_map: Vec<(u32, Span)>,
+    frames: Vec<FrameDebugInfo>,
     /// The source of the program that the debug info was derived from
     pub source: String,
 }
 
+/// Debug information for a compiled frame, i.e. a function body or a script's main block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameDebugInfo {
+    /// The ip of the frame's first instruction
+    pub start_ip: u32,
+    /// The ip following the frame's last instruction
+    pub end_ip: u32,
+    /// The frame's named local values
+    ///
+    /// Each entry contains the register that holds the local value,
+    /// along with the constant index of the local's name.
+    pub locals: Vec<(u8, ConstantIndex)>,
+}
+
 impl DebugInfo {
     /// Adds a span to the source map for a given ip
     ///
@@ -31,17 +46,38 @@ impl DebugInfo {
     /// Returns a source span for a given instruction pointer
     pub fn get_source_span(&self, ip: u32) -> Option<Span> {
         // Find the last entry with an ip less than or equal to the input
-        // an upper_bound would nice here, but this isn't currently a performance sensitive function
-        // so a scan through the entries will do.
-        let mut result = None;
-        for entry in self.source_map.iter() {
-            if entry.0 <= ip {
-                result = Some(entry.1);
-            } else {
-                break;
-            }
-        }
-        result
+        let entry_count = self
+            .source_map
+            .partition_point(|(entry_ip, _)| *entry_ip <= ip);
+        entry_count
+            .checked_sub(1)
+            .map(|index| self.source_map[index].1)
+    }
+
+    /// Returns the entries of the source map
+    ///
+    /// Each entry contains the ip of the first instruction that was produced by the span,
+    /// with the entry covering all following instructions up until the next entry.
+    pub fn source_map(&self) -> &[(u32, Span)] {
+        &self.source_map
+    }
+
+    /// Adds debug information for a compiled frame
+    pub fn push_frame(&mut self, frame: FrameDebugInfo) {
+        self.frames.push(frame);
+    }
+
+    /// Returns the debug information for all of the chunk's frames
+    pub fn frames(&self) -> &[FrameDebugInfo] {
+        &self.frames
+    }
+
+    /// Returns the debug information for the innermost frame that contains the given ip
+    pub fn get_frame(&self, ip: u32) -> Option<&FrameDebugInfo> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.start_ip <= ip && ip < frame.end_ip)
+            .min_by_key(|frame| frame.end_ip - frame.start_ip)
     }
 }
 
@@ -52,6 +88,11 @@ pub struct Chunk {
     pub bytes: Vec<u8>,
     /// The constant data associated with the chunk's bytecode
     pub constants: ConstantPool,
+    /// The number of access cache slots that are used by the chunk's bytecode
+    ///
+    /// Each `.` access instruction refers to its own cache slot, which the runtime uses to
+    /// remember where the accessed value was found during the previous lookup.
+    pub access_cache_count: u32,
     /// The path of the program's source file
     pub path: Option<KString>,
     /// Debug information associated with the chunk's bytecode
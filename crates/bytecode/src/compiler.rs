@@ -1,15 +1,18 @@
 use crate::{
-    Chunk, DebugInfo, FunctionFlags, Op, StringFormatFlags,
-    frame::{Arg, AssignedOrReserved, Frame, FrameError},
+    Chunk, DebugInfo, FrameDebugInfo, FunctionFlags, Op, StringFormatFlags, TypeCheckError,
+    check_types,
+    constant_value::ConstantValue,
+    frame::{Arg, AssignedOrReserved, Const, Frame, FrameError},
 };
 use circular_buffer::CircularBuffer;
 use derive_name::VariantName;
 use koto_parser::{
-    Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstTry, AstUnaryOp, AstVec, ChainNode,
-    ConstantIndex, Function, ImportItem, KString, MetaKeyId, Node, Parser, Span, StringContents,
-    StringFormatOptions, StringNode,
+    Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstString, AstTry, AstUnaryOp, AstVec,
+    ChainNode, ConstantIndex, ConstantPoolBuilder, Function, ImportItem, KString, MetaKeyId, Node,
+    Parser, Span, StringContents, StringFormatOptions, StringNode,
 };
 use smallvec::{SmallVec, smallvec};
+use std::iter;
 use thiserror::Error;
 
 /// The different error types that can be thrown by the Koto runtime
@@ -20,6 +23,10 @@ enum ErrorKind {
     UnexpectedNode { expected: String, unexpected: Node },
     #[error("attempting to assign to a temporary value")]
     AssigningToATemporaryValue,
+    #[error("'{0}' has already been declared in this scope and can't be redeclared as a const")]
+    ConstAlreadyDeclared(String),
+    #[error("'{0}' is a const and can't be reassigned")]
+    ConstReassignment(String),
     #[error("all arguments following an optional argument must also be optional")]
     ExpectedOptionalArgumentValue,
     #[error("invalid {kind} op ({op:?})")]
@@ -62,6 +69,8 @@ enum ErrorKind {
     MultipleMatchEllipses,
     #[error("the compiled expression has no output")]
     NoResultInExpressionOutput,
+    #[error("the match on '{name}' is missing variants ({missing}), add them or use an `else` arm")]
+    NonExhaustiveEnumMatch { name: String, missing: String },
     #[error("child chain node out of position")]
     OutOfPositionChildNodeInChain,
     #[error("matching with ellipses is only allowed in first or last position")]
@@ -91,6 +100,8 @@ enum ErrorKind {
     #[error("{0}")]
     Parser(#[from] koto_parser::Error),
     #[error(transparent)]
+    TypeCheck(#[from] TypeCheckError),
+    #[error(transparent)]
     FrameError(#[from] FrameError),
 }
 
@@ -262,6 +273,26 @@ pub struct CompilerSettings {
     ///
     /// Enabled by default.
     pub enable_type_checks: bool,
+
+    /// When enabled, the compiler will optimize the generated bytecode
+    ///
+    /// Expressions that only contain literals (e.g. `1 + 2`, or `'a' + 'b'`) are evaluated
+    /// during compilation, and branches in `if` and `switch` expressions that can't be reached
+    /// are removed.
+    ///
+    /// Function calls in tail position (e.g. `return f x`) are compiled as tail calls,
+    /// which reuse the calling function's frame.
+    ///
+    /// Enabled by default.
+    pub enable_optimizations: bool,
+
+    /// When enabled, the compiler will check type hints ahead of time, returning an error for
+    /// values that would definitely fail a type check at runtime
+    ///
+    /// See [check_types](crate::check_types).
+    ///
+    /// Disabled by default.
+    pub enable_static_type_checks: bool,
 }
 
 impl Default for CompilerSettings {
@@ -269,6 +300,8 @@ impl Default for CompilerSettings {
         Self {
             export_top_level_ids: false,
             enable_type_checks: true,
+            enable_optimizations: true,
+            enable_static_type_checks: false,
         }
     }
 }
@@ -281,6 +314,11 @@ pub struct Compiler {
     frame_stack: Vec<Frame>,
     span_stack: Vec<Span>,
     settings: CompilerSettings,
+    // Constants produced during compilation are added to a copy of the AST's constant pool,
+    // which is only prepared when it's first needed.
+    constants: Option<ConstantPoolBuilder>,
+    // The number of access cache slots that have been assigned to access instructions
+    access_cache_count: u32,
 }
 
 impl Compiler {
@@ -302,6 +340,15 @@ impl Compiler {
         script_path: Option<KString>,
         settings: CompilerSettings,
     ) -> Result<Chunk> {
+        if settings.enable_static_type_checks
+            && let Some(error) = check_types(&ast).into_iter().next()
+        {
+            return Err(CompilerError {
+                span: error.span,
+                error: error.into(),
+            });
+        }
+
         let mut compiler = Compiler {
             settings,
             ..Default::default()
@@ -318,9 +365,15 @@ impl Compiler {
             return compiler.error(ErrorKind::ResultingBytecodeIsTooLarge(compiler.bytes.len()));
         }
 
+        let constants = match compiler.constants {
+            Some(constants) => constants.build(),
+            None => ast.consume_constants(),
+        };
+
         let result = Chunk {
             bytes: compiler.bytes,
-            constants: ast.consume_constants(),
+            constants,
+            access_cache_count: compiler.access_cache_count,
             path: script_path,
             debug_info: compiler.debug_info,
         };
@@ -530,8 +583,29 @@ impl Compiler {
                 expression,
                 ..
             } => self.compile_multi_assign(targets, *expression, false, ctx)?,
-            Node::UnaryOp { op, value } => self.compile_unary_op(*op, *value, ctx)?,
-            Node::BinaryOp { op, lhs, rhs } => self.compile_binary_op(*op, *lhs, *rhs, ctx)?,
+            Node::Const { target, expression } => {
+                self.compile_const(*target, *expression, false, ctx)?
+            }
+            Node::Record { id, fields, .. } => self.compile_record(*id, fields, false, ctx)?,
+            Node::Enum { id, variants, .. } => self.compile_enum(*id, variants, false, ctx)?,
+            Node::EnumVariant { .. } => {
+                // Enum variants are only compiled in `self.compile_enum`.
+                unreachable!();
+            }
+            Node::EnumPattern { .. } => {
+                // Enum patterns are only compiled in `self.compile_match_arm_patterns`.
+                unreachable!();
+            }
+            Node::UnaryOp { op, value } => match self.compile_folded_constant(node_index, ctx)? {
+                Some(result) => result,
+                None => self.compile_unary_op(*op, *value, ctx)?,
+            },
+            Node::BinaryOp { op, lhs, rhs } => {
+                match self.compile_folded_constant(node_index, ctx)? {
+                    Some(result) => result,
+                    None => self.compile_binary_op(*op, *lhs, *rhs, ctx)?,
+                }
+            }
             Node::If(ast_if) => self.compile_if(ast_if, ctx)?,
             Node::Match { expression, arms } => self.compile_match(*expression, arms, ctx)?,
             Node::MatchArm { .. } => {
@@ -595,6 +669,7 @@ impl Compiler {
             },
             Node::Return(expression) => self.compile_return(*expression, node_index, ctx)?,
             Node::Yield(expression) => self.compile_yield(*expression, node_index, ctx)?,
+            Node::Await(expression) => self.compile_await(*expression, ctx)?,
             Node::Throw(expression) => {
                 // A throw will prevent the result from being used, but the caller should be
                 // provided with a result register regardless.
@@ -662,6 +737,7 @@ impl Compiler {
     fn compile_frame(&mut self, params: FrameParameters, ctx: CompileNodeContext) -> Result<()> {
         // Push a NewFrame op, and keep track of the register count's byte index so that it can be
         // updated after the frame has been compiled.
+        let frame_start_ip = self.bytes.len() as u32;
         self.push_op(Op::NewFrame, &[0]);
         let register_count_byte_index = self.bytes.len() - 1;
 
@@ -675,13 +751,38 @@ impl Compiler {
             is_generator,
         } = params;
 
-        self.frame_stack.push(Frame::new(
+        // Captured consts remain const in the nested frame
+        let captured_consts: Vec<(ConstantIndex, Const)> = match self.frame_stack.last() {
+            Some(parent) => captures
+                .iter()
+                .filter_map(|id| parent.get_const(*id).map(|c| (*id, c.clone())))
+                .collect(),
+            None => Vec::new(),
+        };
+        // Captured enums keep their variants so that matches on them can still be checked
+        let captured_enums: Vec<(ConstantIndex, Vec<ConstantIndex>)> = match self.frame_stack.last()
+        {
+            Some(parent) => captures
+                .iter()
+                .filter_map(|id| parent.get_enum(*id).map(|e| (*id, e.clone())))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut frame = Frame::new(
             local_count,
             &self.collect_args(args, ctx)?,
             captures,
             output_type,
             is_generator,
-        ));
+        );
+        for (id, captured) in captured_consts {
+            frame.add_const(id, captured);
+        }
+        for (id, variants) in captured_enums {
+            frame.add_enum(id, variants);
+        }
+        self.frame_stack.push(frame);
 
         // Check argument types and unpack nested args
         for (arg_index, arg) in args.iter().enumerate() {
@@ -697,6 +798,9 @@ impl Compiler {
         }
 
         let result_register = if allow_implicit_return {
+            if let Some(last_expression) = expressions.last() {
+                self.collect_tail_calls(*last_expression, ctx);
+            }
             ResultRegister::Any
         } else {
             ResultRegister::None
@@ -731,6 +835,12 @@ impl Compiler {
         let frame = self.frame_stack.pop().unwrap();
         self.bytes[register_count_byte_index] = frame.registers_used();
 
+        self.debug_info.push_frame(FrameDebugInfo {
+            start_ip: frame_start_ip,
+            end_ip: self.bytes.len() as u32,
+            locals: frame.assigned_locals().collect(),
+        });
+
         Ok(())
     }
 
@@ -745,6 +855,7 @@ impl Compiler {
         let check_return_type = !self.frame().is_generator;
 
         let result = if let Some(expression) = expression {
+            self.collect_tail_calls(expression, ctx);
             let expression_result = self.compile_node(expression, ctx.with_any_register())?;
             let expression_register = expression_result.unwrap(self)?;
             if check_return_type {
@@ -823,6 +934,86 @@ impl Compiler {
         Ok(result)
     }
 
+    fn compile_await(
+        &mut self,
+        expression: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Result<CompileNodeOutput> {
+        let result = self.assign_result_register(ctx)?;
+
+        let expression_result = self.compile_node(expression, ctx.with_any_register())?;
+        let expression_register = expression_result.unwrap(self)?;
+
+        // The awaited value is placed in the result register if one is needed,
+        // otherwise the expression's register is reused.
+        let result_register = result.register.unwrap_or(expression_register);
+        self.push_op(Op::Await, &[result_register, expression_register]);
+
+        if expression_result.is_temporary {
+            self.pop_register()?;
+        }
+
+        Ok(result)
+    }
+
+    // Finds calls in tail position in an expression that provides the frame's return value
+    //
+    // The calls are recorded in the frame's `tail_calls` set, and are then compiled as tail calls
+    // in `compile_chain`.
+    fn collect_tail_calls(&mut self, node: AstIndex, ctx: CompileNodeContext) {
+        let frame = self.frame();
+        if !self.settings.enable_optimizations
+            // The top-level frame isn't a function frame, so it can't be replaced by a tail call
+            || self.frame_stack.len() < 2
+            || frame.is_generator
+            // The return value's type needs to be checked after the call
+            || (frame.output_type.is_some() && self.settings.enable_type_checks)
+        {
+            return;
+        }
+
+        match ctx.node(node) {
+            Node::Nested(nested) => self.collect_tail_calls(*nested, ctx),
+            Node::Block(expressions) => {
+                if let Some(last_expression) = expressions.last() {
+                    self.collect_tail_calls(*last_expression, ctx);
+                }
+            }
+            Node::If(ast_if) => {
+                self.collect_tail_calls(ast_if.then_node, ctx);
+                for (_, else_if_body) in ast_if.else_if_blocks.iter() {
+                    self.collect_tail_calls(*else_if_body, ctx);
+                }
+                if let Some(else_node) = ast_if.else_node {
+                    self.collect_tail_calls(else_node, ctx);
+                }
+            }
+            Node::Match { arms, .. } | Node::Switch(arms) => {
+                for arm in arms.iter() {
+                    if let Node::MatchArm { expression, .. } | Node::SwitchArm { expression, .. } =
+                        ctx.node(*arm)
+                    {
+                        self.collect_tail_calls(*expression, ctx);
+                    }
+                }
+            }
+            Node::Chain(_) => {
+                // Find the chain's end node, which needs to be a call
+                let mut end_node = node;
+                while let Node::Chain((_, Some(next))) = ctx.node(end_node) {
+                    end_node = *next;
+                }
+                if matches!(
+                    ctx.node(end_node),
+                    Node::Chain((ChainNode::Call { .. }, None))
+                ) {
+                    self.frame_mut().tail_calls.push(end_node);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn compile_check_output_type(
         &mut self,
         register: u8,
@@ -1597,155 +1788,608 @@ impl Compiler {
         Ok(result)
     }
 
-    fn compile_load_id(
+    fn compile_const(
         &mut self,
-        id: ConstantIndex,
+        target: AstIndex,
+        expression: AstIndex,
+        export_assignment: bool,
         ctx: CompileNodeContext,
     ) -> Result<CompileNodeOutput> {
-        let result = if let Some(local_register) = self.frame().get_local_assigned_register(id) {
-            match ctx.result_register {
-                ResultRegister::None => CompileNodeOutput::none(),
-                ResultRegister::Any => CompileNodeOutput::with_assigned(local_register),
-                ResultRegister::Fixed(register) => {
-                    self.push_op(Op::Copy, &[register, local_register]);
-                    CompileNodeOutput::with_assigned(register)
-                }
-            }
-        } else {
-            let result = self.assign_result_register(ctx)?;
-            if let Some(result_register) = result.register {
-                self.compile_load_non_local(result_register, id);
-                result
-            } else {
-                let register = self.push_register()?;
-                self.compile_load_non_local(register, id);
-                CompileNodeOutput::with_temporary(register)
-            }
+        let target_node = ctx.node_with_span(target);
+        let Node::Id(id, type_hint) = target_node.node else {
+            return self.error(ErrorKind::UnexpectedNode {
+                expected: "ID".into(),
+                unexpected: target_node.node.clone(),
+            });
         };
 
-        Ok(result)
-    }
+        let name = ctx.ast.constants().get_str(id).to_string();
 
-    // Compiles a type check using the AssertType instruction
-    //
-    // If the type check fails then an exception will be thrown.
-    //
-    // If the `enable_type_checks` flag is disabled then no instructions are emitted.
-    //
-    // See also: compile_check_type
-    fn compile_assert_type(
-        &mut self,
-        value_register: u8,
-        type_hint: AstIndex,
-        span: Option<AstIndex>, // The assertion should be made using this node's span
-        ctx: CompileNodeContext,
-    ) -> Result<()> {
-        let type_node = ctx.node_with_span(type_hint);
-        match &type_node.node {
-            Node::Type {
-                type_index,
-                allow_null,
-            } => {
-                if self.settings.enable_type_checks {
-                    if let Some(span_node_index) = span {
-                        let span_node = ctx.node_with_span(span_node_index);
-                        self.push_span(span_node, ctx.ast);
-                    }
+        // Consts can't shadow existing values in the same scope
+        if self.frame().get_local_assigned_or_reserved_register(id)
+            != AssignedOrReserved::Unassigned
+        {
+            return self.error(ErrorKind::ConstAlreadyDeclared(name));
+        }
 
-                    let op = if *allow_null {
-                        Op::AssertOptionalType
-                    } else {
-                        Op::AssertType
-                    };
-                    self.push_op(op, &[value_register]);
-                    self.push_var_u32((*type_index).into());
+        let value = self.evaluate_constant(expression, ctx);
 
-                    if span.is_some() {
-                        self.pop_span();
+        let result = match &value {
+            Some(value) => {
+                // The value has been evaluated at compile time, so it can be loaded directly
+                // rather than compiling the expression.
+                let value_register = self.reserve_local_register(id)?;
+                self.compile_load_constant_value(value_register, value, ctx)?;
+                self.commit_local_register(value_register)?;
+
+                self.push_span(target_node, ctx.ast);
+
+                if let Some(type_hint) = type_hint {
+                    self.compile_assert_type(value_register, type_hint, Some(target), ctx)?;
+                }
+
+                if export_assignment || self.force_export_assignment() {
+                    self.compile_value_export(id, value_register)?;
+                }
+
+                self.pop_span();
+
+                match ctx.result_register {
+                    ResultRegister::Fixed(register) => {
+                        if register != value_register {
+                            self.push_op(Op::Copy, &[register, value_register]);
+                        }
+                        CompileNodeOutput::with_assigned(register)
                     }
+                    ResultRegister::Any => CompileNodeOutput::with_assigned(value_register),
+                    ResultRegister::None => CompileNodeOutput::none(),
                 }
-                Ok(())
             }
-            unexpected => self.error(ErrorKind::UnexpectedNode {
-                expected: "Type".into(),
-                unexpected: unexpected.clone(),
-            }),
-        }
+            None => self.compile_assign(target, expression, export_assignment, ctx)?,
+        };
+
+        self.frame_mut().add_const(id, Const { name, value });
+
+        Ok(result)
     }
 
-    // Compiles a type check using the CheckType instruction
-    //
-    // Returns the jump placeholder for a failed type check; the caller needs to update the
-    // placeholder with the offset to the jump target.
-    //
-    // This is used for type checks that conditionally affect logic flow,
-    // and are therefore emitted without checking the `enable_type_checks` flag.
-    //
-    // See also: compile_assert_type
-    fn compile_check_type(
+    fn compile_record(
         &mut self,
-        value_register: u8,
-        type_hint: AstIndex,
+        id: AstIndex,
+        fields: &[AstIndex],
+        export_assignment: bool,
         ctx: CompileNodeContext,
-    ) -> Result<usize> {
-        let type_node = ctx.node_with_span(type_hint);
-        match &type_node.node {
-            Node::Type {
-                type_index,
-                allow_null,
-            } => {
-                self.push_span(type_node, ctx.ast);
+    ) -> Result<CompileNodeOutput> {
+        use Op::*;
+
+        let id_node = ctx.node_with_span(id);
+        let Node::Id(id, _) = id_node.node else {
+            return self.error(ErrorKind::UnexpectedNode {
+                expected: "ID".into(),
+                unexpected: id_node.node.clone(),
+            });
+        };
+
+        let record_register = self.reserve_local_register(id)?;
+        self.compile_constant_op(record_register, id, RecordType);
+
+        for field in fields {
+            let (field_id, default_value) = match ctx.node(*field) {
+                Node::Assign {
+                    target, expression, ..
+                } => (*target, Some(*expression)),
+                _ => (*field, None),
+            };
+
+            let field_node = ctx.node_with_span(field_id);
+            let Node::Id(field_name, type_hint) = field_node.node else {
+                return self.error(ErrorKind::UnexpectedNode {
+                    expected: "ID".into(),
+                    unexpected: field_node.node.clone(),
+                });
+            };
+
+            self.push_span(field_node, ctx.ast);
+            self.compile_constant_op(record_register, field_name, RecordField);
+
+            // The type hint is set before the default value so that the default can be checked
+            if let Some(type_hint) = type_hint
+                && self.settings.enable_type_checks
+            {
+                let Node::Type {
+                    type_index,
+                    allow_null,
+                } = ctx.node(type_hint)
+                else {
+                    return self.error(ErrorKind::UnexpectedNode {
+                        expected: "Type".into(),
+                        unexpected: ctx.node(type_hint).clone(),
+                    });
+                };
 
                 let op = if *allow_null {
-                    Op::CheckOptionalType
+                    RecordFieldOptionalType
                 } else {
-                    Op::CheckType
+                    RecordFieldType
                 };
-                self.push_op(op, &[value_register]);
-                self.push_var_u32((*type_index).into());
+                self.compile_constant_op(record_register, *type_index, op);
+            }
 
-                let jump_placeholder = self.push_offset_placeholder();
-                self.pop_span();
-                Ok(jump_placeholder)
+            if let Some(default_value) = default_value {
+                let value = self.compile_node(default_value, ctx.with_any_register())?;
+                let value_register = value.unwrap(self)?;
+                self.push_op(RecordFieldDefault, &[record_register, value_register]);
+                if value.is_temporary {
+                    self.pop_register()?;
+                }
             }
-            unexpected => self.error(ErrorKind::UnexpectedNode {
-                expected: "Type".into(),
-                unexpected: unexpected.clone(),
-            }),
+
+            self.pop_span();
         }
-    }
 
-    fn compile_value_export(&mut self, id: ConstantIndex, value_register: u8) -> Result<()> {
-        let id_register = self.push_register()?;
-        self.compile_load_string_constant(id_register, id);
-        self.push_op(Op::ExportValue, &[id_register, value_register]);
-        self.pop_register()?;
-        self.frame_mut().add_to_exported_ids(id);
-        Ok(())
-    }
+        self.commit_local_register(record_register)?;
 
-    fn compile_meta_export(
-        &mut self,
-        meta_id: MetaKeyId,
-        name: Option<ConstantIndex>,
-        value_register: u8,
-    ) -> Result<()> {
-        if let Some(name) = name {
-            let name_register = self.push_register()?;
-            self.compile_load_string_constant(name_register, name);
-            self.push_op_without_span(
-                Op::MetaExportNamed,
-                &[meta_id as u8, name_register, value_register],
-            );
-            self.pop_register()?;
-        } else {
-            self.push_op(Op::MetaExport, &[meta_id as u8, value_register]);
+        if export_assignment || self.force_export_assignment() {
+            self.compile_value_export(id, record_register)?;
         }
-        Ok(())
-    }
 
-    fn compile_load_string_constant(&mut self, result_register: u8, index: ConstantIndex) {
-        self.compile_constant_op(result_register, index, Op::LoadString);
+        let result = match ctx.result_register {
+            ResultRegister::Fixed(register) => {
+                if register != record_register {
+                    self.push_op(Copy, &[register, record_register]);
+                }
+                CompileNodeOutput::with_assigned(register)
+            }
+            ResultRegister::Any => CompileNodeOutput::with_assigned(record_register),
+            ResultRegister::None => CompileNodeOutput::none(),
+        };
+
+        Ok(result)
+    }
+
+    fn compile_enum(
+        &mut self,
+        id: AstIndex,
+        variants: &[AstIndex],
+        export_assignment: bool,
+        ctx: CompileNodeContext,
+    ) -> Result<CompileNodeOutput> {
+        use Op::*;
+
+        let id_node = ctx.node_with_span(id);
+        let Node::Id(id, _) = id_node.node else {
+            return self.error(ErrorKind::UnexpectedNode {
+                expected: "ID".into(),
+                unexpected: id_node.node.clone(),
+            });
+        };
+
+        let enum_register = self.reserve_local_register(id)?;
+        self.compile_constant_op(enum_register, id, EnumType);
+
+        let mut variant_names = Vec::with_capacity(variants.len());
+
+        for variant in variants {
+            let variant_node = ctx.node_with_span(*variant);
+            let Node::EnumVariant {
+                id: variant_name,
+                fields,
+            } = &variant_node.node
+            else {
+                return self.error(ErrorKind::UnexpectedNode {
+                    expected: "EnumVariant".into(),
+                    unexpected: variant_node.node.clone(),
+                });
+            };
+
+            self.push_span(variant_node, ctx.ast);
+            self.compile_constant_op(enum_register, *variant_name, EnumVariant);
+
+            for field in fields {
+                let Node::Id(field_name, _) = ctx.node(*field) else {
+                    return self.error(ErrorKind::UnexpectedNode {
+                        expected: "ID".into(),
+                        unexpected: ctx.node(*field).clone(),
+                    });
+                };
+                self.compile_constant_op(enum_register, *field_name, EnumVariantField);
+            }
+
+            self.pop_span();
+            variant_names.push(*variant_name);
+        }
+
+        self.commit_local_register(enum_register)?;
+        self.frame_mut().add_enum(id, variant_names);
+
+        if export_assignment || self.force_export_assignment() {
+            self.compile_value_export(id, enum_register)?;
+        }
+
+        let result = match ctx.result_register {
+            ResultRegister::Fixed(register) => {
+                if register != enum_register {
+                    self.push_op(Copy, &[register, enum_register]);
+                }
+                CompileNodeOutput::with_assigned(register)
+            }
+            ResultRegister::Any => CompileNodeOutput::with_assigned(enum_register),
+            ResultRegister::None => CompileNodeOutput::none(),
+        };
+
+        Ok(result)
+    }
+
+    // Attempts to evaluate an expression at compile time
+    //
+    // Literals, consts with known values, and operations on those values can be evaluated,
+    // `None` is returned for anything else.
+    fn evaluate_constant(&self, node: AstIndex, ctx: CompileNodeContext) -> Option<ConstantValue> {
+        let constants = ctx.ast.constants();
+
+        let result = match ctx.node(node) {
+            Node::Null => ConstantValue::Null,
+            Node::BoolTrue => ConstantValue::Bool(true),
+            Node::BoolFalse => ConstantValue::Bool(false),
+            Node::SmallInt(n) => ConstantValue::Int(*n as i64),
+            Node::Int(constant) => ConstantValue::Int(constants.get_i64(*constant)),
+            Node::Float(constant) => ConstantValue::Float(constants.get_f64(*constant)),
+            Node::Str(AstString {
+                contents: StringContents::Literal(constant) | StringContents::Raw { constant, .. },
+                ..
+            }) => ConstantValue::Str(constants.get_str(*constant).into()),
+            Node::Nested(nested) => return self.evaluate_constant(*nested, ctx),
+            Node::Id(id, ..) => return self.frame().get_const(*id)?.value.clone(),
+            Node::UnaryOp { op, value } => {
+                return self.evaluate_constant(*value, ctx)?.unary_op(*op);
+            }
+            Node::BinaryOp { op, lhs, rhs } => {
+                let lhs = self.evaluate_constant(*lhs, ctx)?;
+
+                // Logic ops short-circuit, so the RHS doesn't need to be evaluated
+                // if the result is determined by the LHS.
+                let short_circuit = match op {
+                    AstBinaryOp::And => !lhs.is_truthy(),
+                    AstBinaryOp::Or => lhs.is_truthy(),
+                    _ => false,
+                };
+                if short_circuit {
+                    return Some(lhs);
+                }
+
+                let rhs = self.evaluate_constant(*rhs, ctx)?;
+                return lhs.binary_op(*op, &rhs);
+            }
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    // Compiles an expression that can be evaluated at compile time as a single constant
+    //
+    // `None` is returned if optimizations are disabled, or if the expression can't be evaluated,
+    // in which case the expression needs to be compiled normally.
+    fn compile_folded_constant(
+        &mut self,
+        node: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Result<Option<CompileNodeOutput>> {
+        if !self.settings.enable_optimizations {
+            return Ok(None);
+        }
+
+        let Some(value) = self.evaluate_constant(node, ctx) else {
+            return Ok(None);
+        };
+
+        let result = self.assign_result_register(ctx)?;
+        if let Some(result_register) = result.register {
+            self.compile_load_constant_value(result_register, &value, ctx)?;
+        }
+
+        Ok(Some(result))
+    }
+
+    // Evaluates a branch condition at compile time, if optimizations are enabled
+    //
+    // Returns `Some(true)` if the branch will always be taken, `Some(false)` if it will never be
+    // taken, or `None` if the condition needs to be evaluated at runtime.
+    fn evaluate_branch_condition(
+        &self,
+        condition: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Option<bool> {
+        if self.settings.enable_optimizations {
+            self.evaluate_constant(condition, ctx)
+                .map(|value| value.is_truthy())
+        } else {
+            None
+        }
+    }
+
+    // Compiles a condition followed by a jump that's taken if the condition is false
+    //
+    // The jump's offset placeholder needs to be pushed by the caller.
+    //
+    // When optimizations are enabled, comparisons are compiled as combined compare-and-jump
+    // instructions, avoiding the need to place the comparison's result in a register.
+    fn compile_jump_if_false(
+        &mut self,
+        condition: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        if let Some((op, lhs, rhs)) = self.compare_and_jump_op(condition, ctx) {
+            let stack_count = self.stack_count();
+
+            // Use the condition's span in case the comparison throws an error
+            self.push_span(ctx.node_with_span(condition), ctx.ast);
+            let lhs_register = self
+                .compile_node(lhs, ctx.with_any_register())?
+                .unwrap(self)?;
+            let rhs_register = self
+                .compile_node(rhs, ctx.with_any_register())?
+                .unwrap(self)?;
+            self.push_op(op, &[lhs_register, rhs_register]);
+            self.pop_span();
+
+            self.truncate_register_stack(stack_count)
+        } else {
+            let condition_register = self.compile_node(condition, ctx.with_any_register())?;
+            self.push_op_without_span(Op::JumpIfFalse, &[condition_register.unwrap(self)?]);
+            if condition_register.is_temporary {
+                self.pop_register()?;
+            }
+            Ok(())
+        }
+    }
+
+    // Returns the compare-and-jump op that can be used for a condition, along with its operands
+    fn compare_and_jump_op(
+        &self,
+        condition: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Option<(Op, AstIndex, AstIndex)> {
+        use AstBinaryOp::*;
+
+        if !self.settings.enable_optimizations {
+            return None;
+        }
+
+        let is_comparison = |op: &AstBinaryOp| {
+            matches!(
+                op,
+                Less | LessOrEqual | Greater | GreaterOrEqual | Equal | NotEqual
+            )
+        };
+
+        match ctx.node(condition) {
+            Node::Nested(nested) => self.compare_and_jump_op(*nested, ctx),
+            Node::BinaryOp { op, lhs, rhs } if is_comparison(op) => {
+                // Chained comparisons like `a < b < c` are left to `compile_comparison_op`
+                if matches!(ctx.node(*rhs), Node::BinaryOp { op, .. } if is_comparison(op)) {
+                    return None;
+                }
+
+                let jump_op = match op {
+                    Less => Op::JumpIfNotLess,
+                    LessOrEqual => Op::JumpIfNotLessOrEqual,
+                    Greater => Op::JumpIfNotGreater,
+                    GreaterOrEqual => Op::JumpIfNotGreaterOrEqual,
+                    Equal => Op::JumpIfNotEqual,
+                    NotEqual => Op::JumpIfEqual,
+                    _ => return None,
+                };
+
+                Some((jump_op, *lhs, *rhs))
+            }
+            _ => None,
+        }
+    }
+
+    // Loads a value that was evaluated at compile time into the result register
+    fn compile_load_constant_value(
+        &mut self,
+        result_register: u8,
+        value: &ConstantValue,
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        use Op::*;
+
+        match value {
+            ConstantValue::Null => self.push_op(SetNull, &[result_register]),
+            ConstantValue::Bool(true) => self.push_op(SetTrue, &[result_register]),
+            ConstantValue::Bool(false) => self.push_op(SetFalse, &[result_register]),
+            ConstantValue::Int(0) => self.push_op(Set0, &[result_register]),
+            ConstantValue::Int(1) => self.push_op(Set1, &[result_register]),
+            ConstantValue::Int(n @ 0..=255) => {
+                self.push_op(SetNumberU8, &[result_register, *n as u8])
+            }
+            ConstantValue::Int(n @ -255..=-1) => {
+                self.push_op(SetNumberNegU8, &[result_register, n.unsigned_abs() as u8])
+            }
+            ConstantValue::Int(n) => {
+                let index = self.add_constant(ctx, |constants| constants.add_i64(*n))?;
+                self.compile_constant_op(result_register, index, LoadInt);
+            }
+            ConstantValue::Float(n) => {
+                let index = self.add_constant(ctx, |constants| constants.add_f64(*n))?;
+                self.compile_constant_op(result_register, index, LoadFloat);
+            }
+            ConstantValue::Str(s) => {
+                let index = self.add_constant(ctx, |constants| constants.add_string(s))?;
+                self.compile_load_string_constant(result_register, index);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Adds a constant to the chunk's constant pool
+    fn add_constant(
+        &mut self,
+        ctx: CompileNodeContext,
+        add: impl FnOnce(
+            &mut ConstantPoolBuilder,
+        ) -> std::result::Result<ConstantIndex, koto_parser::InternalError>,
+    ) -> Result<ConstantIndex> {
+        let constants = self
+            .constants
+            .get_or_insert_with(|| ctx.ast.constants().to_builder());
+
+        add(constants)
+            .map_err(|error| self.make_error(koto_parser::Error::new(error.into(), self.span())))
+    }
+
+    fn compile_load_id(
+        &mut self,
+        id: ConstantIndex,
+        ctx: CompileNodeContext,
+    ) -> Result<CompileNodeOutput> {
+        let result = if let Some(local_register) = self.frame().get_local_assigned_register(id) {
+            match ctx.result_register {
+                ResultRegister::None => CompileNodeOutput::none(),
+                ResultRegister::Any => CompileNodeOutput::with_assigned(local_register),
+                ResultRegister::Fixed(register) => {
+                    self.push_op(Op::Copy, &[register, local_register]);
+                    CompileNodeOutput::with_assigned(register)
+                }
+            }
+        } else {
+            let result = self.assign_result_register(ctx)?;
+            if let Some(result_register) = result.register {
+                self.compile_load_non_local(result_register, id);
+                result
+            } else {
+                let register = self.push_register()?;
+                self.compile_load_non_local(register, id);
+                CompileNodeOutput::with_temporary(register)
+            }
+        };
+
+        Ok(result)
+    }
+
+    // Compiles a type check using the AssertType instruction
+    //
+    // If the type check fails then an exception will be thrown.
+    //
+    // If the `enable_type_checks` flag is disabled then no instructions are emitted.
+    //
+    // See also: compile_check_type
+    fn compile_assert_type(
+        &mut self,
+        value_register: u8,
+        type_hint: AstIndex,
+        span: Option<AstIndex>, // The assertion should be made using this node's span
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        let type_node = ctx.node_with_span(type_hint);
+        match &type_node.node {
+            Node::Type {
+                type_index,
+                allow_null,
+            } => {
+                if self.settings.enable_type_checks {
+                    if let Some(span_node_index) = span {
+                        let span_node = ctx.node_with_span(span_node_index);
+                        self.push_span(span_node, ctx.ast);
+                    }
+
+                    let op = if *allow_null {
+                        Op::AssertOptionalType
+                    } else {
+                        Op::AssertType
+                    };
+                    self.push_op(op, &[value_register]);
+                    self.push_var_u32((*type_index).into());
+
+                    if span.is_some() {
+                        self.pop_span();
+                    }
+                }
+                Ok(())
+            }
+            unexpected => self.error(ErrorKind::UnexpectedNode {
+                expected: "Type".into(),
+                unexpected: unexpected.clone(),
+            }),
+        }
+    }
+
+    // Compiles a type check using the CheckType instruction
+    //
+    // Returns the jump placeholder for a failed type check; the caller needs to update the
+    // placeholder with the offset to the jump target.
+    //
+    // This is used for type checks that conditionally affect logic flow,
+    // and are therefore emitted without checking the `enable_type_checks` flag.
+    //
+    // See also: compile_assert_type
+    fn compile_check_type(
+        &mut self,
+        value_register: u8,
+        type_hint: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> Result<usize> {
+        let type_node = ctx.node_with_span(type_hint);
+        match &type_node.node {
+            Node::Type {
+                type_index,
+                allow_null,
+            } => {
+                self.push_span(type_node, ctx.ast);
+
+                let op = if *allow_null {
+                    Op::CheckOptionalType
+                } else {
+                    Op::CheckType
+                };
+                self.push_op(op, &[value_register]);
+                self.push_var_u32((*type_index).into());
+
+                let jump_placeholder = self.push_offset_placeholder();
+                self.pop_span();
+                Ok(jump_placeholder)
+            }
+            unexpected => self.error(ErrorKind::UnexpectedNode {
+                expected: "Type".into(),
+                unexpected: unexpected.clone(),
+            }),
+        }
+    }
+
+    fn compile_value_export(&mut self, id: ConstantIndex, value_register: u8) -> Result<()> {
+        let id_register = self.push_register()?;
+        self.compile_load_string_constant(id_register, id);
+        self.push_op(Op::ExportValue, &[id_register, value_register]);
+        self.pop_register()?;
+        self.frame_mut().add_to_exported_ids(id);
+        Ok(())
+    }
+
+    fn compile_meta_export(
+        &mut self,
+        meta_id: MetaKeyId,
+        name: Option<ConstantIndex>,
+        value_register: u8,
+    ) -> Result<()> {
+        if let Some(name) = name {
+            let name_register = self.push_register()?;
+            self.compile_load_string_constant(name_register, name);
+            self.push_op_without_span(
+                Op::MetaExportNamed,
+                &[meta_id as u8, name_register, value_register],
+            );
+            self.pop_register()?;
+        } else {
+            self.push_op(Op::MetaExport, &[meta_id as u8, value_register]);
+        }
+        Ok(())
+    }
+
+    fn compile_load_string_constant(&mut self, result_register: u8, index: ConstantIndex) {
+        self.compile_constant_op(result_register, index, Op::LoadString);
     }
 
     fn compile_load_non_local(&mut self, result_register: u8, id: ConstantIndex) {
@@ -1974,6 +2618,11 @@ impl Compiler {
                 expression,
                 ..
             } => self.compile_multi_assign(targets, *expression, true, ctx),
+            Node::Const { target, expression } => {
+                self.compile_const(*target, *expression, true, ctx)
+            }
+            Node::Record { id, fields, .. } => self.compile_record(*id, fields, true, ctx),
+            Node::Enum { id, variants, .. } => self.compile_enum(*id, variants, true, ctx),
             // Maps can be exported directly rather than relying on the iterator logic below
             Node::Map { entries, .. } => self.compile_make_map(entries, true, ctx),
             // Other expressions can be evaluated and then assumed to be iterable
@@ -2318,6 +2967,17 @@ impl Compiler {
         if let Some(result_register) = result.register {
             let lhs = self.compile_node(lhs, ctx.with_any_register())?;
             let lhs_register = lhs.unwrap(self)?;
+
+            // Adding or subtracting a small integer can be performed without loading the
+            // integer into a register, e.g. `n - 1`
+            if let Some((op, n)) = self.arithmetic_u8_op(op, rhs, ctx) {
+                self.push_op(op, &[result_register, lhs_register, n]);
+                if lhs.is_temporary {
+                    self.pop_register()?;
+                }
+                return Ok(result);
+            }
+
             let rhs = self.compile_node(rhs, ctx.with_any_register())?;
             let rhs_register = rhs.unwrap(self)?;
 
@@ -2337,6 +2997,24 @@ impl Compiler {
         Ok(result)
     }
 
+    // Returns the specialized op that should be used for an arithmetic op with a small integer rhs
+    fn arithmetic_u8_op(&self, op: Op, rhs: AstIndex, ctx: CompileNodeContext) -> Option<(Op, u8)> {
+        if !self.settings.enable_optimizations {
+            return None;
+        }
+
+        let op = match op {
+            Op::Add => Op::AddU8,
+            Op::Subtract => Op::SubtractU8,
+            _ => return None,
+        };
+
+        match self.evaluate_constant(rhs, ctx)? {
+            ConstantValue::Int(n) => u8::try_from(n).ok().map(|n| (op, n)),
+            _ => None,
+        }
+    }
+
     fn compile_compound_assignment_op(
         &mut self,
         ast_op: AstBinaryOp,
@@ -2378,6 +3056,10 @@ impl Compiler {
                 ctx.with_fixed_register_or_none(result.register),
             )?;
         } else {
+            if let Node::Id(id, ..) = lhs_node {
+                self.check_const_reassignment(*id)?;
+            }
+
             let lhs = self.compile_node(lhs, ctx.with_any_register())?;
             let lhs_register = lhs.unwrap(self)?;
 
@@ -3042,6 +3724,8 @@ impl Compiler {
         let span_stack_count = self.span_stack.len();
 
         let mut current_node = root_node.clone();
+        // The index of the AST node containing `current_node`
+        let mut current_node_index = None;
 
         let mut null_check_jump_placeholders = SmallVec::<[usize; 4]>::new();
         let mut null_check_on_end_node = false;
@@ -3142,6 +3826,7 @@ impl Compiler {
                         args,
                         None,
                         instance_register,
+                        false,
                         ctx.with_fixed_register(node_register),
                     )?;
                 }
@@ -3158,6 +3843,7 @@ impl Compiler {
 
             let next_chain_node = ctx.node_with_span(next);
             self.push_span(next_chain_node, ctx.ast);
+            current_node_index = Some(next);
 
             match &next_chain_node.node {
                 Node::Chain((node, next)) => {
@@ -3243,6 +3929,7 @@ impl Compiler {
                         string_key.unwrap(self)?,
                     ],
                 );
+                self.push_access_cache_slot();
                 chain_nodes.push(output_register, false);
             }
             ChainNode::Index(_) if access_end_node => {
@@ -3267,11 +3954,18 @@ impl Compiler {
                         return self.error(ErrorKind::OutOfPositionChildNodeInChain);
                     };
 
+                    let tail_call = !compound_assignment
+                        && piped_arg_register.is_none()
+                        && result.register.is_some()
+                        && current_node_index
+                            .is_some_and(|index| self.frame().tail_calls.contains(&index));
+
                     self.compile_call(
                         function_register,
                         args,
                         None,
                         instance_register,
+                        tail_call,
                         ctx.with_fixed_register(output_register),
                     )?;
                     chain_nodes.push(output_register, false);
@@ -3358,6 +4052,7 @@ impl Compiler {
                 &piped_call_args,
                 piped_arg_register,
                 parent_register,
+                false,
                 ctx.with_register(call_result),
             )?;
         }
@@ -3479,6 +4174,7 @@ impl Compiler {
     fn compile_access_id(&mut self, result: u8, value: u8, key: ConstantIndex) {
         self.push_op(Op::Access, &[result, value]);
         self.push_var_u32(key.into());
+        self.push_access_cache_slot();
     }
 
     /// Returns the jump placeholder for a failed access; the caller needs to update the
@@ -3489,6 +4185,7 @@ impl Compiler {
     fn compile_try_access_id(&mut self, result: u8, value: u8, key: ConstantIndex) -> usize {
         self.push_op(Op::TryAccess, &[result, value]);
         self.push_var_u32(key.into());
+        self.push_access_cache_slot();
         self.push_offset_placeholder()
     }
 
@@ -3505,6 +4202,7 @@ impl Compiler {
             Op::AccessString,
             &[result_register, value_register, key_register],
         );
+        self.push_access_cache_slot();
         self.pop_register()?;
         Ok(())
     }
@@ -3526,6 +4224,7 @@ impl Compiler {
             Op::TryAccessString,
             &[result_register, value_register, key_register],
         );
+        self.push_access_cache_slot();
         let jump = self.push_offset_placeholder();
         self.pop_register()?;
         Ok(jump)
@@ -3557,7 +4256,7 @@ impl Compiler {
             Node::Id(id, ..) => {
                 // Compile a call with the piped arg, using the id to access the function
                 if let Some(function_register) = self.frame().get_local_assigned_register(*id) {
-                    self.compile_call(function_register, &[], pipe_register, None, ctx)
+                    self.compile_call(function_register, &[], pipe_register, None, false, ctx)
                 } else {
                     let call_result_register = if let Some(result_register) = result.register {
                         ResultRegister::Fixed(result_register)
@@ -3569,7 +4268,14 @@ impl Compiler {
                     self.compile_load_non_local(function_register, *id);
 
                     let call_context = ctx.with_register(call_result_register);
-                    self.compile_call(function_register, &[], pipe_register, None, call_context)?;
+                    self.compile_call(
+                        function_register,
+                        &[],
+                        pipe_register,
+                        None,
+                        false,
+                        call_context,
+                    )?;
 
                     self.pop_register()?; // function_register
                     Ok(result)
@@ -3587,8 +4293,14 @@ impl Compiler {
                 let function = self.compile_node(rhs, ctx.with_any_register())?;
                 let function_register = function.unwrap(self)?;
                 let call_context = ctx.with_register(call_result_register);
-                let result =
-                    self.compile_call(function_register, &[], pipe_register, None, call_context)?;
+                let result = self.compile_call(
+                    function_register,
+                    &[],
+                    pipe_register,
+                    None,
+                    false,
+                    call_context,
+                )?;
                 if function.is_temporary {
                     self.pop_register()?;
                 }
@@ -3609,6 +4321,7 @@ impl Compiler {
         args: &[AstIndex],
         piped_arg: Option<u8>,
         instance: Option<u8>,
+        tail_call: bool,
         ctx: CompileNodeContext,
     ) -> Result<CompileNodeOutput> {
         use Op::*;
@@ -3669,7 +4382,11 @@ impl Compiler {
 
         if let Some(instance_register) = instance {
             self.push_op(
-                CallInstance,
+                if tail_call {
+                    TailCallInstance
+                } else {
+                    CallInstance
+                },
                 &[
                     call_result_register,
                     function_register,
@@ -3681,7 +4398,7 @@ impl Compiler {
             );
         } else {
             self.push_op(
-                Call,
+                if tail_call { TailCall } else { Call },
                 &[
                     call_result_register,
                     function_register,
@@ -3692,6 +4409,12 @@ impl Compiler {
             );
         }
 
+        // Tail calls are followed by a return, which is used when the runtime performs a regular
+        // call in place of the tail call.
+        if tail_call {
+            self.push_op_without_span(Return, &[call_result_register]);
+        }
+
         self.truncate_register_stack(stack_count)?;
 
         Ok(result)
@@ -3708,75 +4431,75 @@ impl Compiler {
             ..
         } = ast_if;
 
-        let result = self.assign_result_register(ctx)?;
-
-        // If
-        let condition_register = self.compile_node(*condition, ctx.with_any_register())?;
-
-        self.push_op_without_span(JumpIfFalse, &[condition_register.unwrap(self)?]);
-        let condition_jump_ip = self.push_offset_placeholder();
+        // Gather the if and else if branches, skipping any that can't be reached
+        let mut branches = Vec::with_capacity(1 + else_if_blocks.len());
+        let mut else_node = *else_node;
+        for (condition, body) in
+            iter::once((*condition, *then_node)).chain(else_if_blocks.iter().copied())
+        {
+            match self.evaluate_branch_condition(condition, ctx) {
+                Some(false) => continue,
+                // The branch will always be taken, so any following branches can be dropped
+                Some(true) => {
+                    else_node = Some(body);
+                    break;
+                }
+                None => branches.push((condition, body)),
+            }
+        }
 
-        if condition_register.is_temporary {
-            self.pop_register()?;
+        // If none of the conditions need to be checked, then only the else block is compiled
+        if branches.is_empty() {
+            return match else_node {
+                Some(else_node) => self.compile_node(else_node, ctx),
+                None => {
+                    let result = self.assign_result_register(ctx)?;
+                    if let Some(result_register) = result.register {
+                        self.push_op_without_span(SetNull, &[result_register]);
+                    }
+                    Ok(result)
+                }
+            };
         }
 
+        let result = self.assign_result_register(ctx)?;
+
         let expression_context = ctx.with_register(
             result
                 .register
                 .map_or(ResultRegister::None, ResultRegister::Fixed),
         );
-        self.compile_node(*then_node, expression_context)?;
-
-        let if_jump_ip = {
-            if !else_if_blocks.is_empty() || else_node.is_some() || result.register.is_some() {
-                self.push_op_without_span(Jump, &[]);
-                Some(self.push_offset_placeholder())
-            } else {
-                None
-            }
-        };
-
-        // A failing condition for the if jumps to here, at the start of the else if / else blocks
-        self.update_offset_placeholder(condition_jump_ip)?;
 
-        // Iterate through the else if blocks and collect their end jump placeholders
-        let else_if_jump_ips = else_if_blocks
-            .iter()
-            .map(|(else_if_condition, else_if_node)| -> Result<usize> {
-                let condition = self.compile_node(*else_if_condition, ctx.with_any_register())?;
-
-                self.push_op_without_span(JumpIfFalse, &[condition.unwrap(self)?]);
-                let conditon_jump_ip = self.push_offset_placeholder();
+        let mut end_jump_ips = Vec::with_capacity(branches.len());
+        let last_branch = branches.len() - 1;
 
-                if condition.is_temporary {
-                    self.pop_register()?;
-                }
+        for (i, (condition, body)) in branches.into_iter().enumerate() {
+            self.compile_jump_if_false(condition, ctx)?;
+            let condition_jump_ip = self.push_offset_placeholder();
 
-                self.compile_node(*else_if_node, expression_context)?;
+            self.compile_node(body, expression_context)?;
 
+            // Jump to the end of the if expression,
+            // unless there's nothing following the last branch that needs to be skipped.
+            if i < last_branch || else_node.is_some() || result.register.is_some() {
                 self.push_op_without_span(Jump, &[]);
-                let else_if_jump_ip = self.push_offset_placeholder();
-
-                self.update_offset_placeholder(conditon_jump_ip)?;
+                end_jump_ips.push(self.push_offset_placeholder());
+            }
 
-                Ok(else_if_jump_ip)
-            })
-            .collect::<Result<Vec<_>>>()?;
+            // A failing condition jumps to here, at the start of the next branch
+            self.update_offset_placeholder(condition_jump_ip)?;
+        }
 
         // Else - either compile the else block, or set the result to empty
         if let Some(else_node) = else_node {
-            self.compile_node(*else_node, expression_context)?;
+            self.compile_node(else_node, expression_context)?;
         } else if let Some(result_register) = result.register {
             self.push_op_without_span(SetNull, &[result_register]);
         }
 
-        // We're at the end, so update the if and else if jump placeholders
-        if let Some(if_jump_ip) = if_jump_ip {
-            self.update_offset_placeholder(if_jump_ip)?;
-        }
-
-        for else_if_jump_ip in else_if_jump_ips.iter() {
-            self.update_offset_placeholder(*else_if_jump_ip)?;
+        // We're at the end, so update the branches' end jump placeholders
+        for end_jump_ip in end_jump_ips {
+            self.update_offset_placeholder(end_jump_ip)?;
         }
 
         Ok(result)
@@ -3799,7 +4522,8 @@ impl Compiler {
                 .map_or(ResultRegister::None, ResultRegister::Fixed),
         );
 
-        let mut last_arm_is_else = false;
+        // Gather the switch arms, skipping any that can't be reached
+        let mut switch_arms = Vec::with_capacity(arms.len());
         for arm in arms.iter() {
             let arm_node = ctx.node(*arm);
             let Node::SwitchArm {
@@ -3813,24 +4537,32 @@ impl Compiler {
                 });
             };
 
-            let arm_end_jump_placeholder = if let Some(condition) = condition {
-                let condition_register = self.compile_node(*condition, ctx.with_any_register())?;
-
-                self.push_op_without_span(Op::JumpIfFalse, &[condition_register.unwrap(self)?]);
-
-                if condition_register.is_temporary {
-                    self.pop_register()?;
+            match condition.and_then(|condition| self.evaluate_branch_condition(condition, ctx)) {
+                Some(false) => continue,
+                // The arm will always be taken, so it can be treated as an `else` arm
+                Some(true) => {
+                    switch_arms.push((None, *expression));
+                    break;
                 }
+                None => switch_arms.push((*condition, *expression)),
+            }
+        }
 
+        let mut last_arm_is_else = false;
+        let last_arm = switch_arms.len().saturating_sub(1);
+        for (i, (condition, expression)) in switch_arms.into_iter().enumerate() {
+            let arm_end_jump_placeholder = if let Some(condition) = condition {
+                self.compile_jump_if_false(condition, ctx)?;
                 Some(self.push_offset_placeholder())
             } else {
                 None
             };
 
-            self.compile_node(*expression, switch_arm_context)?;
+            self.compile_node(expression, switch_arm_context)?;
 
-            // Add a jump instruction if this anything other than an `else` arm
-            if condition.is_some() {
+            // Add a jump instruction if this is anything other than an `else` arm,
+            // unless it's the last arm and there's nothing following it that needs to be skipped.
+            if condition.is_some() && (i < last_arm || result.register.is_some()) {
                 self.push_op_without_span(Op::Jump, &[]);
                 result_jump_placeholders.push(self.push_offset_placeholder())
             }
@@ -3865,6 +4597,8 @@ impl Compiler {
         arms: &[AstIndex],
         ctx: CompileNodeContext,
     ) -> Result<CompileNodeOutput> {
+        self.check_enum_match_is_exhaustive(arms, ctx)?;
+
         let result = self.assign_result_register(ctx)?;
 
         let stack_count = self.stack_count();
@@ -3911,6 +4645,95 @@ impl Compiler {
         Ok(result)
     }
 
+    // Matches against the variants of a declared enum need to cover all of the enum's variants,
+    // unless the match has an `else` arm or a catch-all pattern.
+    fn check_enum_match_is_exhaustive(
+        &self,
+        arms: &[AstIndex],
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        let mut matched_enum = None;
+        let mut covered_variants = Vec::new();
+
+        for arm in arms {
+            let Node::MatchArm {
+                patterns,
+                condition,
+                ..
+            } = ctx.node(*arm)
+            else {
+                continue;
+            };
+
+            if patterns.is_empty() {
+                // `else` arm
+                return Ok(());
+            }
+
+            // Arms with conditions don't count towards covering a variant
+            let unconditional = condition.is_none();
+
+            for pattern in patterns {
+                let (enum_id, variant) = match ctx.node(*pattern) {
+                    Node::Id(_, None) | Node::Ignored(_, None) if unconditional => return Ok(()),
+                    Node::EnumPattern {
+                        enum_id,
+                        variant,
+                        payload,
+                    } => {
+                        let payload_is_irrefutable = payload.iter().all(|nested| {
+                            matches!(
+                                ctx.node(*nested),
+                                Node::Id(_, None) | Node::Ignored(_, None) | Node::PackedId(_)
+                            )
+                        });
+                        if !payload_is_irrefutable {
+                            continue;
+                        }
+                        (*enum_id, *variant)
+                    }
+                    // Variants without payloads are matched by value, e.g. `Shape.Empty`
+                    Node::Chain((ChainNode::Root(root), Some(next))) => match ctx.node(*next) {
+                        Node::Chain((ChainNode::Id(variant), None)) => (*root, *variant),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+
+                let Node::Id(enum_id, _) = ctx.node(enum_id) else {
+                    continue;
+                };
+                if self.frame().get_enum(*enum_id).is_none() {
+                    continue;
+                }
+
+                if *matched_enum.get_or_insert(*enum_id) == *enum_id && unconditional {
+                    covered_variants.push(variant);
+                }
+            }
+        }
+
+        let Some(variants) = matched_enum.and_then(|id| self.frame().get_enum(id)) else {
+            return Ok(());
+        };
+
+        let constants = ctx.ast.constants();
+        let missing = variants
+            .iter()
+            .filter(|variant| !covered_variants.contains(variant))
+            .map(|variant| constants.get_str(*variant))
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            self.error(ErrorKind::NonExhaustiveEnumMatch {
+                name: constants.get_str(matched_enum.unwrap()).into(),
+                missing: missing.join(", "),
+            })
+        }
+    }
+
     fn compile_match_arm(
         &mut self,
         result: CompileNodeOutput,
@@ -4022,14 +4845,8 @@ impl Compiler {
         // match foo
         //   x if x > 10 then 99
         if let Some(condition) = condition {
-            let condition_register = self.compile_node(*condition, ctx.with_any_register())?;
-
-            self.push_op_without_span(Op::JumpIfFalse, &[condition_register.unwrap(self)?]);
+            self.compile_jump_if_false(*condition, ctx)?;
             jumps.arm_end.push(self.push_offset_placeholder());
-
-            if condition_register.is_temporary {
-                self.pop_register()?;
-            }
         }
 
         let body_result_register = result
@@ -4270,6 +5087,69 @@ impl Compiler {
                         self.pop_register()?; // map_register
                     }
                 }
+                Node::EnumPattern {
+                    enum_id,
+                    variant,
+                    payload,
+                } => {
+                    let value_register = if match_is_container {
+                        let value_register = self.push_register()?;
+                        self.push_op(
+                            TempIndex,
+                            &[value_register, params.match_register, pattern_index as u8],
+                        );
+                        value_register
+                    } else {
+                        params.match_register
+                    };
+
+                    // Check that the value is the expected variant
+                    let enum_type = self.compile_node(*enum_id, ctx.with_any_register())?;
+                    let comparison = self.push_register()?;
+                    self.push_op(
+                        IsEnumVariant,
+                        &[comparison, value_register, enum_type.unwrap(self)?],
+                    );
+                    self.push_var_u32((*variant).into());
+                    self.push_op(JumpIfFalse, &[comparison]);
+                    if params.is_last_alternative {
+                        params.jumps.arm_end.push(self.push_offset_placeholder());
+                    } else {
+                        params
+                            .jumps
+                            .alternative_end
+                            .push(self.push_offset_placeholder());
+                    }
+                    self.pop_register()?; // comparison
+                    if enum_type.is_temporary {
+                        self.pop_register()?;
+                    }
+
+                    if payload.is_empty() {
+                        // The variant has been matched, is a jump needed?
+                        if is_last_pattern && !params.is_last_alternative {
+                            self.push_op(Jump, &[]);
+                            params.jumps.match_end.push(self.push_offset_placeholder());
+                        }
+                    } else {
+                        // Match the payload as nested patterns
+                        self.compile_nested_match_arm_patterns(
+                            MatchArmParameters {
+                                match_register: value_register,
+                                is_last_alternative: params.is_last_alternative,
+                                has_last_pattern: params.has_last_pattern,
+                                jumps: params.jumps,
+                            },
+                            None, // pattern index
+                            payload,
+                            ctx,
+                        )?;
+                    }
+
+                    if match_is_container {
+                        self.pop_register()?; // value_register
+                    }
+                }
                 unexpected => {
                     return self.error(ErrorKind::InvalidMatchPattern(unexpected.clone()));
                 }
@@ -4479,144 +5359,94 @@ impl Compiler {
 
         let stack_count = self.stack_count();
 
-        let iterator_register = {
-            let iterator_register = self.push_register()?;
-            let iterable_register = self.compile_node(*iterable, ctx.with_any_register())?;
-
-            // Make the iterator, using the iterator's span in case of errors
-            self.push_span(ctx.node_with_span(*iterable), ctx.ast);
-            self.push_op(
-                MakeIterator,
-                &[iterator_register, iterable_register.unwrap(self)?],
-            );
-            self.pop_span();
-
-            if iterable_register.is_temporary {
-                self.pop_register()?;
-            }
-
-            iterator_register
-        };
-
-        let loop_start_ip = self.bytes.len();
-        self.frame_mut()
-            .push_loop(loop_start_ip, body_result_register);
+        let loop_start_ip = if self.is_range_loop(args, *iterable, ctx) {
+            self.compile_range_loop_next(args[0], *iterable, body_result_register, ctx)?
+        } else {
+            let iterator_register = {
+                let iterator_register = self.push_register()?;
+                let iterable_register = self.compile_node(*iterable, ctx.with_any_register())?;
 
-        match args.as_slice() {
-            [] => return self.error(ErrorKind::MissingArgumentInForLoop),
-            [single_arg] => {
-                match ctx.node(*single_arg) {
-                    Node::Id(id, maybe_type) => {
-                        // e.g. for i in 0..10
-                        let arg_register = self.assign_local_register(*id)?;
-                        self.push_op_without_span(IterNext, &[arg_register, iterator_register]);
-                        self.push_loop_jump_placeholder()?;
-                        if let Some(type_hint) = maybe_type {
-                            self.compile_assert_type(
-                                arg_register,
-                                *type_hint,
-                                Some(*single_arg),
-                                ctx,
-                            )?;
-                        }
-                    }
-                    Node::Ignored(_, maybe_type) => {
-                        if let Some(type_hint) = maybe_type {
-                            // e.g. for _: Number in 0..10
-                            let temp_register = self.push_register()?;
-                            self.push_op_without_span(
-                                IterNext,
-                                &[temp_register, iterator_register],
-                            );
-                            self.push_loop_jump_placeholder()?;
-                            self.compile_assert_type(
-                                temp_register,
-                                *type_hint,
-                                Some(*single_arg),
-                                ctx,
-                            )?;
-                            self.pop_register()?; // temp_register
-                        } else {
-                            // e.g. for _ in 0..10
-                            self.push_op_without_span(IterNextQuiet, &[iterator_register]);
-                            self.push_loop_jump_placeholder()?;
-                        }
-                    }
-                    Node::MapPattern { .. } => {
-                        // e.g. for {x} in [{x: 1}, {x: 2}]
-                        // e.g. for {x}: Number in [{x: 1}, {x: 2}]
-                        let map_register = self.push_register()?;
-                        self.push_op_without_span(IterNext, &[map_register, iterator_register]);
-                        self.push_loop_jump_placeholder()?;
-
-                        let target = *single_arg;
-                        let target_registers =
-                            self.local_registers_for_assign_target(target, ctx)?;
-
-                        self.compile_assign_to_map_finish(
-                            None,
-                            target,
-                            &target_registers,
-                            map_register,
-                            false,
-                            ctx,
-                        )?;
+                // Make the iterator, using the iterator's span in case of errors
+                self.push_span(ctx.node_with_span(*iterable), ctx.ast);
+                self.push_op(
+                    MakeIterator,
+                    &[iterator_register, iterable_register.unwrap(self)?],
+                );
+                self.pop_span();
 
-                        self.pop_register()?; // map_register
-                    }
-                    unexpected => {
-                        return self.error(ErrorKind::UnexpectedNode {
-                            expected: "ID in for loop args".into(),
-                            unexpected: unexpected.clone(),
-                        });
-                    }
+                if iterable_register.is_temporary {
+                    self.pop_register()?;
                 }
-            }
-            args => {
-                // e.g. for a, b, c in list_of_lists()
-                // e.g. for key, value in map
-
-                // A temporary register for the iterator's output.
-                // Args are unpacked via iteration from the temp register
-                let output_register = self.push_register()?;
 
-                self.push_op_without_span(IterNextTemp, &[output_register, iterator_register]);
-                self.push_loop_jump_placeholder()?;
+                iterator_register
+            };
 
-                self.push_op_without_span(MakeIterator, &[output_register, output_register]);
+            let loop_start_ip = self.bytes.len();
+            self.frame_mut()
+                .push_loop(loop_start_ip, body_result_register);
 
-                for arg in args.iter() {
-                    match ctx.node(*arg) {
+            match args.as_slice() {
+                [] => return self.error(ErrorKind::MissingArgumentInForLoop),
+                [single_arg] => {
+                    match ctx.node(*single_arg) {
                         Node::Id(id, maybe_type) => {
+                            // e.g. for i in 0..10
                             let arg_register = self.assign_local_register(*id)?;
-                            self.push_op_without_span(IterUnpack, &[arg_register, output_register]);
+                            self.push_op_without_span(IterNext, &[arg_register, iterator_register]);
+                            self.push_loop_jump_placeholder()?;
                             if let Some(type_hint) = maybe_type {
                                 self.compile_assert_type(
                                     arg_register,
                                     *type_hint,
-                                    Some(*arg),
+                                    Some(*single_arg),
                                     ctx,
                                 )?;
                             }
                         }
                         Node::Ignored(_, maybe_type) => {
                             if let Some(type_hint) = maybe_type {
-                                let arg_register = self.push_register()?;
+                                // e.g. for _: Number in 0..10
+                                let temp_register = self.push_register()?;
                                 self.push_op_without_span(
-                                    IterUnpack,
-                                    &[arg_register, output_register],
+                                    IterNext,
+                                    &[temp_register, iterator_register],
                                 );
+                                self.push_loop_jump_placeholder()?;
                                 self.compile_assert_type(
-                                    arg_register,
+                                    temp_register,
                                     *type_hint,
-                                    Some(*arg),
+                                    Some(*single_arg),
                                     ctx,
                                 )?;
-                                self.pop_register()?; // arg_register
+                                self.pop_register()?; // temp_register
                             } else {
-                                self.push_op_without_span(IterNextQuiet, &[output_register, 0, 0]);
+                                // e.g. for _ in 0..10
+                                self.push_op_without_span(IterNextQuiet, &[iterator_register]);
+                                self.push_loop_jump_placeholder()?;
                             }
                         }
+                        Node::MapPattern { .. } => {
+                            // e.g. for {x} in [{x: 1}, {x: 2}]
+                            // e.g. for {x}: Number in [{x: 1}, {x: 2}]
+                            let map_register = self.push_register()?;
+                            self.push_op_without_span(IterNext, &[map_register, iterator_register]);
+                            self.push_loop_jump_placeholder()?;
+
+                            let target = *single_arg;
+                            let target_registers =
+                                self.local_registers_for_assign_target(target, ctx)?;
+
+                            self.compile_assign_to_map_finish(
+                                None,
+                                target,
+                                &target_registers,
+                                map_register,
+                                false,
+                                ctx,
+                            )?;
+
+                            self.pop_register()?; // map_register
+                        }
                         unexpected => {
                             return self.error(ErrorKind::UnexpectedNode {
                                 expected: "ID in for loop args".into(),
@@ -4625,10 +5455,72 @@ impl Compiler {
                         }
                     }
                 }
+                args => {
+                    // e.g. for a, b, c in list_of_lists()
+                    // e.g. for key, value in map
+
+                    // A temporary register for the iterator's output.
+                    // Args are unpacked via iteration from the temp register
+                    let output_register = self.push_register()?;
+
+                    self.push_op_without_span(IterNextTemp, &[output_register, iterator_register]);
+                    self.push_loop_jump_placeholder()?;
+
+                    self.push_op_without_span(MakeIterator, &[output_register, output_register]);
+
+                    for arg in args.iter() {
+                        match ctx.node(*arg) {
+                            Node::Id(id, maybe_type) => {
+                                let arg_register = self.assign_local_register(*id)?;
+                                self.push_op_without_span(
+                                    IterUnpack,
+                                    &[arg_register, output_register],
+                                );
+                                if let Some(type_hint) = maybe_type {
+                                    self.compile_assert_type(
+                                        arg_register,
+                                        *type_hint,
+                                        Some(*arg),
+                                        ctx,
+                                    )?;
+                                }
+                            }
+                            Node::Ignored(_, maybe_type) => {
+                                if let Some(type_hint) = maybe_type {
+                                    let arg_register = self.push_register()?;
+                                    self.push_op_without_span(
+                                        IterUnpack,
+                                        &[arg_register, output_register],
+                                    );
+                                    self.compile_assert_type(
+                                        arg_register,
+                                        *type_hint,
+                                        Some(*arg),
+                                        ctx,
+                                    )?;
+                                    self.pop_register()?; // arg_register
+                                } else {
+                                    self.push_op_without_span(
+                                        IterNextQuiet,
+                                        &[output_register, 0, 0],
+                                    );
+                                }
+                            }
+                            unexpected => {
+                                return self.error(ErrorKind::UnexpectedNode {
+                                    expected: "ID in for loop args".into(),
+                                    unexpected: unexpected.clone(),
+                                });
+                            }
+                        }
+                    }
 
-                self.pop_register()?; // output_register
+                    self.pop_register()?; // output_register
+                }
             }
-        }
+
+            loop_start_ip
+        };
 
         self.compile_node(
             *body,
@@ -4657,6 +5549,83 @@ impl Compiler {
         Ok(result)
     }
 
+    // Returns true if the for loop is iterating over a range literal that can be iterated over
+    // directly, e.g. `for i in 0..10`
+    fn is_range_loop(
+        &self,
+        args: &[AstIndex],
+        iterable: AstIndex,
+        ctx: CompileNodeContext,
+    ) -> bool {
+        self.settings.enable_optimizations
+            && matches!(ctx.node(iterable), Node::Range { .. })
+            && matches!(args, [arg] if matches!(ctx.node(*arg), Node::Id(..) | Node::Ignored(..)))
+    }
+
+    // Compiles the start of a loop that iterates over a range literal
+    //
+    // The range's bounds are placed in temporary registers, with the start register being used
+    // as the loop's counter. Returns the ip of the loop's start.
+    fn compile_range_loop_next(
+        &mut self,
+        arg: AstIndex,
+        range: AstIndex,
+        body_result_register: Option<u8>,
+        ctx: CompileNodeContext,
+    ) -> Result<usize> {
+        let range_node = ctx.node_with_span(range);
+        let Node::Range {
+            start,
+            end,
+            inclusive,
+        } = range_node.node
+        else {
+            return self.error(ErrorKind::UnexpectedNode {
+                expected: "Range".into(),
+                unexpected: range_node.node.clone(),
+            });
+        };
+
+        // The bounds are always placed in new registers, the counter is modified during the loop
+        // so it mustn't refer to an existing local value.
+        let start_register = self.push_register()?;
+        self.compile_node(start, ctx.with_fixed_register(start_register))?;
+        let end_register = self.push_register()?;
+        self.compile_node(end, ctx.with_fixed_register(end_register))?;
+
+        let loop_start_ip = self.bytes.len();
+        self.frame_mut()
+            .push_loop(loop_start_ip, body_result_register);
+
+        let (output_register, type_hint) = match ctx.node(arg) {
+            Node::Id(id, maybe_type) => (self.assign_local_register(*id)?, *maybe_type),
+            Node::Ignored(_, maybe_type) => (self.push_register()?, *maybe_type),
+            unexpected => {
+                return self.error(ErrorKind::UnexpectedNode {
+                    expected: "ID in for loop args".into(),
+                    unexpected: unexpected.clone(),
+                });
+            }
+        };
+
+        let op = if inclusive {
+            Op::IterNextRangeInclusive
+        } else {
+            Op::IterNextRange
+        };
+        // Use the range's span in case the bounds aren't numbers
+        self.push_span(range_node, ctx.ast);
+        self.push_op(op, &[output_register, start_register, end_register]);
+        self.pop_span();
+        self.push_loop_jump_placeholder()?;
+
+        if let Some(type_hint) = type_hint {
+            self.compile_assert_type(output_register, type_hint, Some(arg), ctx)?;
+        }
+
+        Ok(loop_start_ip)
+    }
+
     // Used to compile `loop`, `while`, and `until`
     fn compile_loop(
         &mut self,
@@ -4682,19 +5651,20 @@ impl Compiler {
         self.frame_mut()
             .push_loop(loop_start_ip, body_result_register);
 
-        if let Some((condition, negate_condition)) = condition {
-            // Condition
-            let condition_register = self.compile_node(condition, ctx.with_any_register())?;
-            let op = if negate_condition {
-                JumpIfTrue
-            } else {
-                JumpIfFalse
-            };
-            self.push_op_without_span(op, &[condition_register.unwrap(self)?]);
-            self.push_loop_jump_placeholder()?;
-            if condition_register.is_temporary {
-                self.pop_register()?;
+        match condition {
+            Some((condition, false)) => {
+                self.compile_jump_if_false(condition, ctx)?;
+                self.push_loop_jump_placeholder()?;
+            }
+            Some((condition, true)) => {
+                let condition_register = self.compile_node(condition, ctx.with_any_register())?;
+                self.push_op_without_span(JumpIfTrue, &[condition_register.unwrap(self)?]);
+                self.push_loop_jump_placeholder()?;
+                if condition_register.is_temporary {
+                    self.pop_register()?;
+                }
             }
+            None => {}
         }
 
         let body_result = self.compile_node(
@@ -4732,6 +5702,12 @@ impl Compiler {
         self.push_bytes(&(offset as u16).to_le_bytes());
     }
 
+    // Assigns a new access cache slot to the access instruction that's being pushed
+    fn push_access_cache_slot(&mut self) {
+        self.push_var_u32(self.access_cache_count);
+        self.access_cache_count += 1;
+    }
+
     // For offset placeholders to work correctly,
     // ensure that they're the last value in the instruction.
     fn push_offset_placeholder(&mut self) -> usize {
@@ -4830,6 +5806,7 @@ impl Compiler {
 
     // Used for values that can be assigned directly to a register
     fn assign_local_register(&mut self, local: ConstantIndex) -> Result<u8> {
+        self.check_const_reassignment(local)?;
         self.frame_mut()
             .assign_local_register(local)
             .map_err(|e| self.make_error(e))
@@ -4843,11 +5820,20 @@ impl Compiler {
     // Reserving is necessary to avoid bringing the local's name into scope during the RHS's
     // evaluation before it's been assigned.
     fn reserve_local_register(&mut self, local: ConstantIndex) -> Result<u8> {
+        self.check_const_reassignment(local)?;
         self.frame_mut()
             .reserve_local_register(local)
             .map_err(|e| self.make_error(e))
     }
 
+    // Consts can't be assigned to after they've been declared
+    fn check_const_reassignment(&self, local: ConstantIndex) -> Result<()> {
+        match self.frame().get_const(local) {
+            Some(existing) => self.error(ErrorKind::ConstReassignment(existing.name.clone())),
+            None => Ok(()),
+        }
+    }
+
     // Commit a register now that the RHS expression for an assignment has been computed
     fn commit_local_register(&mut self, register: u8) -> Result<u8> {
         for deferred_op in self
@@ -0,0 +1,143 @@
+use koto_parser::{AstBinaryOp, AstUnaryOp};
+use std::cmp::Ordering;
+
+/// A value that has been evaluated at compile time
+///
+/// The operations defined here mirror the behaviour of the corresponding runtime operations,
+/// returning `None` when a result can't be determined without running the script
+/// (e.g. when the operation would throw an error).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ConstantValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl ConstantValue {
+    // Null and false are the only falsy values, matching the runtime's `JumpIfFalse` behaviour
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Self::Null | Self::Bool(false))
+    }
+
+    pub fn unary_op(&self, op: AstUnaryOp) -> Option<Self> {
+        use ConstantValue::*;
+
+        match (op, self) {
+            (AstUnaryOp::Negate, Int(n)) => Some(Int(n.wrapping_neg())),
+            (AstUnaryOp::Negate, Float(n)) => Some(Float(-n)),
+            (AstUnaryOp::Not, value) => Some(Bool(!value.is_truthy())),
+            _ => None,
+        }
+    }
+
+    pub fn binary_op(&self, op: AstBinaryOp, rhs: &Self) -> Option<Self> {
+        use AstBinaryOp::*;
+        use ConstantValue::*;
+
+        let result = match (op, self, rhs) {
+            (Add, Str(a), Str(b)) => Str(format!("{a}{b}")),
+            (Add | Subtract | Multiply | Remainder | Power, Int(a), Int(b)) => {
+                let (a, b) = (*a, *b);
+                match op {
+                    Add => Int(a.wrapping_add(b)),
+                    Subtract => Int(a.wrapping_sub(b)),
+                    Multiply => Int(a.wrapping_mul(b)),
+                    // Integer remainders with a zero divisor produce NaN rather than panicking
+                    Remainder if b == 0 => Float(f64::NAN),
+                    Remainder => Int(a.wrapping_rem(b)),
+                    Power if b < 0 => Float((a as f64).powf(b as f64)),
+                    Power => Int(a.wrapping_pow(b as u32)),
+                    _ => unreachable!(),
+                }
+            }
+            (Add | Subtract | Multiply | Divide | Remainder | Power, a, b) => {
+                let (a, b) = (a.as_f64()?, b.as_f64()?);
+                Float(match op {
+                    Add => a + b,
+                    Subtract => a - b,
+                    Multiply => a * b,
+                    Divide => a / b,
+                    Remainder => a % b,
+                    Power => a.powf(b),
+                    _ => unreachable!(),
+                })
+            }
+            (Equal, a, b) => Bool(a.is_equal(b)),
+            (NotEqual, a, b) => Bool(!a.is_equal(b)),
+            (Less | LessOrEqual | Greater | GreaterOrEqual, a, b) => {
+                let ordering = a.compare(b)?;
+                Bool(match op {
+                    Less => ordering.is_lt(),
+                    LessOrEqual => ordering.is_le(),
+                    Greater => ordering.is_gt(),
+                    GreaterOrEqual => ordering.is_ge(),
+                    _ => unreachable!(),
+                })
+            }
+            (And, a, b) => {
+                if a.is_truthy() {
+                    b.clone()
+                } else {
+                    a.clone()
+                }
+            }
+            (Or, a, b) => {
+                if a.is_truthy() {
+                    a.clone()
+                } else {
+                    b.clone()
+                }
+            }
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(n) => Some(*n as f64),
+            Self::Float(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn is_equal(&self, other: &Self) -> bool {
+        use ConstantValue::*;
+
+        match (self, other) {
+            (Null, Null) => true,
+            (Bool(a), Bool(b)) => a == b,
+            (Int(a), Int(b)) => a == b,
+            (Str(a), Str(b)) => a == b,
+            (a, b) => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => a == b,
+                // Values with different types are never equal
+                _ => false,
+            },
+        }
+    }
+
+    // Numbers are ordered in the same way as the runtime, with NaN being greater than all numbers
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        use ConstantValue::*;
+
+        match (self, other) {
+            (Int(a), Int(b)) => Some(a.cmp(b)),
+            (Str(a), Str(b)) => Some(a.as_str().cmp(b.as_str())),
+            (a, b) => {
+                let (a, b) = (a.as_f64()?, b.as_f64()?);
+                Some(
+                    a.partial_cmp(&b)
+                        .unwrap_or_else(|| match (a.is_nan(), b.is_nan()) {
+                            (false, true) => Ordering::Less,
+                            (true, false) => Ordering::Greater,
+                            _ => Ordering::Equal,
+                        }),
+                )
+            }
+        }
+    }
+}
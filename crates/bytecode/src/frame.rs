@@ -1,8 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use koto_parser::{AstIndex, ConstantIndex, Span};
 use thiserror::Error;
 
+use crate::constant_value::ConstantValue;
+
 /// The different error types that can be thrown while compiling a [Frame]
 #[derive(Error, Clone, Debug)]
 pub enum FrameError {
@@ -60,6 +62,15 @@ enum LocalRegister {
     Allocated,
 }
 
+// A value that has been declared with `const`
+#[derive(Clone, Debug)]
+pub(crate) struct Const {
+    // The const's name, used when reporting errors
+    pub name: String,
+    // The const's value, if it could be evaluated at compile time
+    pub value: Option<ConstantValue>,
+}
+
 pub(crate) enum Arg {
     Local(ConstantIndex),
     Unpacked(ConstantIndex),
@@ -72,6 +83,11 @@ pub(crate) struct Frame {
     register_stack: Vec<u8>,
     local_registers: Vec<LocalRegister>,
     exported_ids: HashSet<ConstantIndex>,
+    // Locals that have been declared with `const`, including captured consts
+    consts: HashMap<ConstantIndex, Const>,
+    // Locals that have been declared with `enum`, including captured enums,
+    // along with the names of the enum's variants
+    enums: HashMap<ConstantIndex, Vec<ConstantIndex>>,
     temporary_base: u8,
     temporary_count: u8,
     // Tracks the number of temporary registers used by the frame
@@ -90,6 +106,8 @@ pub(crate) struct Frame {
     // Used to decide if return types should be checked (output type hints only apply to yield
     // expressions in genertors).
     pub is_generator: bool,
+    // The chain nodes in tail position that end with a call that can be compiled as a tail call.
+    pub tail_calls: Vec<AstIndex>,
 }
 
 impl Frame {
@@ -154,6 +172,17 @@ impl Frame {
             .map(|position| position as u8)
     }
 
+    // Returns an iterator over the frame's assigned local registers, along with their names
+    pub fn assigned_locals(&self) -> impl Iterator<Item = (u8, ConstantIndex)> + '_ {
+        self.local_registers
+            .iter()
+            .enumerate()
+            .filter_map(|(register, local_register)| match local_register {
+                LocalRegister::Assigned(name) => Some((register as u8, *name)),
+                _ => None,
+            })
+    }
+
     pub fn get_local_assigned_or_reserved_register(
         &self,
         local_name: ConstantIndex,
@@ -173,6 +202,9 @@ impl Frame {
     }
 
     pub fn reserve_local_register(&mut self, local: ConstantIndex) -> Result<u8, FrameError> {
+        // The local is being reassigned, so it can no longer be treated as an enum
+        self.enums.remove(&local);
+
         match self.get_local_assigned_or_reserved_register(local) {
             AssignedOrReserved::Assigned(assigned) => Ok(assigned),
             AssignedOrReserved::Reserved(reserved) => Ok(reserved),
@@ -191,6 +223,22 @@ impl Frame {
         }
     }
 
+    pub fn add_const(&mut self, id: ConstantIndex, value: Const) {
+        self.consts.insert(id, value);
+    }
+
+    pub fn get_const(&self, id: ConstantIndex) -> Option<&Const> {
+        self.consts.get(&id)
+    }
+
+    pub fn add_enum(&mut self, id: ConstantIndex, variants: Vec<ConstantIndex>) {
+        self.enums.insert(id, variants);
+    }
+
+    pub fn get_enum(&self, id: ConstantIndex) -> Option<&Vec<ConstantIndex>> {
+        self.enums.get(&id)
+    }
+
     pub fn add_to_exported_ids(&mut self, id: ConstantIndex) {
         self.exported_ids.insert(id);
     }
@@ -228,6 +276,8 @@ impl Frame {
     }
 
     pub fn assign_local_register(&mut self, local: ConstantIndex) -> Result<u8, FrameError> {
+        self.enums.remove(&local);
+
         match self.get_local_assigned_or_reserved_register(local) {
             AssignedOrReserved::Assigned(assigned) => Ok(assigned),
             AssignedOrReserved::Reserved(reserved) => {
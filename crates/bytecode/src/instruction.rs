@@ -129,6 +129,41 @@ pub enum Instruction {
         target: u8,
         source: u8,
     },
+    RecordType {
+        register: u8,
+        name: ConstantIndex,
+    },
+    RecordField {
+        record: u8,
+        name: ConstantIndex,
+    },
+    RecordFieldDefault {
+        record: u8,
+        value: u8,
+    },
+    RecordFieldType {
+        record: u8,
+        allow_null: bool,
+        type_string: ConstantIndex,
+    },
+    EnumType {
+        register: u8,
+        name: ConstantIndex,
+    },
+    EnumVariant {
+        enum_type: u8,
+        name: ConstantIndex,
+    },
+    EnumVariantField {
+        enum_type: u8,
+        name: ConstantIndex,
+    },
+    IsEnumVariant {
+        register: u8,
+        value: u8,
+        enum_type: u8,
+        variant: ConstantIndex,
+    },
     Negate {
         register: u8,
         value: u8,
@@ -147,6 +182,16 @@ pub enum Instruction {
         lhs: u8,
         rhs: u8,
     },
+    AddU8 {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
+    SubtractU8 {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
     Multiply {
         register: u8,
         lhs: u8,
@@ -239,6 +284,36 @@ pub enum Instruction {
         register: u8,
         offset: u16,
     },
+    JumpIfNotLess {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
+    JumpIfNotLessOrEqual {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
+    JumpIfNotGreater {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
+    JumpIfNotGreaterOrEqual {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
+    JumpIfNotEqual {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
+    JumpIfEqual {
+        lhs: u8,
+        rhs: u8,
+        offset: u16,
+    },
     Call {
         result: u8,
         function: u8,
@@ -254,12 +329,31 @@ pub enum Instruction {
         arg_count: u8,
         packed_arg_count: u8,
     },
+    TailCall {
+        result: u8,
+        function: u8,
+        frame_base: u8,
+        arg_count: u8,
+        packed_arg_count: u8,
+    },
+    TailCallInstance {
+        result: u8,
+        function: u8,
+        instance: u8,
+        frame_base: u8,
+        arg_count: u8,
+        packed_arg_count: u8,
+    },
     Return {
         register: u8,
     },
     Yield {
         register: u8,
     },
+    Await {
+        register: u8,
+        value: u8,
+    },
     Throw {
         register: u8,
     },
@@ -273,6 +367,13 @@ pub enum Instruction {
         jump_offset: u16,
         temporary_output: bool,
     },
+    IterNextRange {
+        result: u8,
+        start: u8,
+        end: u8,
+        jump_offset: u16,
+        inclusive: bool,
+    },
     TempIndex {
         register: u8,
         value: u8,
@@ -322,22 +423,26 @@ pub enum Instruction {
         register: u8,
         value: u8,
         key: ConstantIndex,
+        cache: u32,
     },
     TryAccess {
         register: u8,
         value: u8,
         key: ConstantIndex,
+        cache: u32,
         jump_offset: u16,
     },
     AccessString {
         register: u8,
         value: u8,
         key: u8,
+        cache: u32,
     },
     TryAccessString {
         register: u8,
         value: u8,
         key: u8,
+        cache: u32,
         jump_offset: u16,
     },
     AccessAssign {
@@ -692,6 +797,43 @@ impl fmt::Debug for Instruction {
                 f,
                 "Capture         function: {function:<5} target: {target:<7} source: {source}",
             ),
+            RecordType { register, name } => {
+                write!(f, "RecordType      result: {register:<7} name: {name}")
+            }
+            RecordField { record, name } => {
+                write!(f, "RecordField     record: {record:<7} name: {name}")
+            }
+            RecordFieldDefault { record, value } => {
+                write!(f, "RecordFieldDef  record: {record:<7} value: {value}")
+            }
+            RecordFieldType {
+                record,
+                type_string,
+                allow_null,
+            } => write!(
+                f,
+                "RecordFieldType record: {record:<7} type: {type_string:<9} \
+                 allow null: {allow_null}"
+            ),
+            EnumType { register, name } => {
+                write!(f, "EnumType        result: {register:<7} name: {name}")
+            }
+            EnumVariant { enum_type, name } => {
+                write!(f, "EnumVariant     enum: {enum_type:<9} name: {name}")
+            }
+            EnumVariantField { enum_type, name } => {
+                write!(f, "EnumVariantField enum: {enum_type:<8} name: {name}")
+            }
+            IsEnumVariant {
+                register,
+                value,
+                enum_type,
+                variant,
+            } => write!(
+                f,
+                "IsEnumVariant   result: {register:<7} value: {value:<8} \
+                 enum: {enum_type:<9} variant: {variant}"
+            ),
             Negate { register, value } => {
                 write!(f, "Negate          result: {register:<7} source: {value}")
             }
@@ -710,6 +852,18 @@ impl fmt::Debug for Instruction {
                     "Subtract        result: {register:<7} lhs: {lhs:<10} rhs: {rhs}"
                 )
             }
+            AddU8 { register, lhs, rhs } => {
+                write!(
+                    f,
+                    "AddU8           result: {register:<7} lhs: {lhs:<10} value: {rhs}"
+                )
+            }
+            SubtractU8 { register, lhs, rhs } => {
+                write!(
+                    f,
+                    "SubtractU8      result: {register:<7} lhs: {lhs:<10} value: {rhs}"
+                )
+            }
             Multiply { register, lhs, rhs } => {
                 write!(
                     f,
@@ -804,6 +958,30 @@ impl fmt::Debug for Instruction {
                     "JumpIfNull      register: {register:<5} offset: {offset}"
                 )
             }
+            JumpIfNotLess { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfNotLt     lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
+            JumpIfNotLessOrEqual { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfNotLe     lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
+            JumpIfNotGreater { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfNotGt     lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
+            JumpIfNotGreaterOrEqual { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfNotGe     lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
+            JumpIfNotEqual { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfNotEq     lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
+            JumpIfEqual { lhs, rhs, offset } => write!(
+                f,
+                "JumpIfEq        lhs: {lhs:<10} rhs: {rhs:<10} offset: {offset}"
+            ),
             Call {
                 result,
                 function,
@@ -831,8 +1009,38 @@ impl fmt::Debug for Instruction {
                 frame base: {frame_base:<3} args: {arg_count}
                 instance: {instance:<5} packed args: {packed_arg_count}",
             ),
+            TailCall {
+                result,
+                function,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => write!(
+                f,
+                "TailCall        \
+                 result: {result:<7} function: {function:<5} \
+                 frame base: {frame_base}
+                args: {arg_count:<9} packed args: {packed_arg_count}",
+            ),
+            TailCallInstance {
+                result,
+                function,
+                instance,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => write!(
+                f,
+                "TailCallInstance \
+                result: {result:<7} function: {function:<5} \
+                frame base: {frame_base:<3} args: {arg_count}
+                instance: {instance:<5} packed args: {packed_arg_count}",
+            ),
             Return { register } => write!(f, "Return          register: {register}"),
             Yield { register } => write!(f, "Yield           register: {register}"),
+            Await { register, value } => {
+                write!(f, "Await           result: {register:<7} value: {value}")
+            }
             Throw { register } => write!(f, "Throw           register: {register}"),
             Size { register, value } => {
                 write!(f, "Size            result: {register:<7} value: {value}")
@@ -848,6 +1056,17 @@ impl fmt::Debug for Instruction {
                 jump: {jump_offset:<9} temp: {temporary_output}",
                 result.map_or(String::new(), |result| format!(" result: {result:<7}")),
             ),
+            IterNextRange {
+                result,
+                start,
+                end,
+                jump_offset,
+                inclusive,
+            } => write!(
+                f,
+                "IterNextRange   result: {result:<7} start: {start:<8} end: {end:<5} \
+                jump: {jump_offset:<9} inclusive: {inclusive}",
+            ),
             TempIndex {
                 register,
                 value,
@@ -931,35 +1150,39 @@ impl fmt::Debug for Instruction {
                 register,
                 value,
                 key,
+                cache,
             } => write!(
                 f,
-                "Access          result: {register:<7} source: {value:<7} key: {key}"
+                "Access          result: {register:<7} source: {value:<7} key: {key:<10} cache: {cache}"
             ),
             TryAccess {
                 register,
                 value,
                 key,
+                cache,
                 jump_offset,
             } => write!(
                 f,
-                "TryAccess       result: {register:<7} source: {value:<7} key: {key} offset: {jump_offset}"
+                "TryAccess       result: {register:<7} source: {value:<7} key: {key:<10} cache: {cache:<4} offset: {jump_offset}"
             ),
             AccessString {
                 register,
                 value,
                 key,
+                cache,
             } => write!(
                 f,
-                "AccessString    result: {register:<7} source: {value:<7} key: {key}"
+                "AccessString    result: {register:<7} source: {value:<7} key: {key:<10} cache: {cache}"
             ),
             TryAccessString {
                 register,
                 value,
                 key,
+                cache,
                 jump_offset,
             } => write!(
                 f,
-                "TryAccessString result: {register:<7} source: {value:<7} key: {key} offset: {jump_offset}"
+                "TryAccessString result: {register:<7} source: {value:<7} key: {key:<10} cache: {cache:<4} offset: {jump_offset}"
             ),
             TryStart {
                 arg_register,
@@ -307,6 +307,22 @@ impl Iterator for InstructionReader {
                     rhs: byte_c,
                 }
             }
+            Op::AddU8 => {
+                let [byte_b, byte_c] = get_u8x2!();
+                AddU8 {
+                    register: byte_a,
+                    lhs: byte_b,
+                    rhs: byte_c,
+                }
+            }
+            Op::SubtractU8 => {
+                let [byte_b, byte_c] = get_u8x2!();
+                SubtractU8 {
+                    register: byte_a,
+                    lhs: byte_b,
+                    rhs: byte_c,
+                }
+            }
             Op::Multiply => {
                 let [byte_b, byte_c] = get_u8x2!();
                 Multiply {
@@ -429,6 +445,36 @@ impl Iterator for InstructionReader {
                 register: byte_a,
                 offset: get_u16!(),
             },
+            Op::JumpIfNotLess => JumpIfNotLess {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
+            Op::JumpIfNotLessOrEqual => JumpIfNotLessOrEqual {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
+            Op::JumpIfNotGreater => JumpIfNotGreater {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
+            Op::JumpIfNotGreaterOrEqual => JumpIfNotGreaterOrEqual {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
+            Op::JumpIfNotEqual => JumpIfNotEqual {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
+            Op::JumpIfEqual => JumpIfEqual {
+                lhs: byte_a,
+                rhs: get_u8!(),
+                offset: get_u16!(),
+            },
             Op::Call => {
                 let [function, frame_base, arg_count, unpacked_arg_count] = get_u8x4!();
                 Call {
@@ -456,8 +502,76 @@ impl Iterator for InstructionReader {
                     packed_arg_count: unpacked_arg_count,
                 }
             }
+            Op::TailCall => {
+                let [function, frame_base, arg_count, packed_arg_count] = get_u8x4!();
+                TailCall {
+                    result: byte_a,
+                    function,
+                    frame_base,
+                    arg_count,
+                    packed_arg_count,
+                }
+            }
+            Op::TailCallInstance => {
+                let [function, instance, frame_base, arg_count, packed_arg_count] = get_u8x5!();
+                TailCallInstance {
+                    result: byte_a,
+                    function,
+                    instance,
+                    frame_base,
+                    arg_count,
+                    packed_arg_count,
+                }
+            }
+            Op::RecordType => RecordType {
+                register: byte_a,
+                name: get_var_u32!().into(),
+            },
+            Op::RecordField => RecordField {
+                record: byte_a,
+                name: get_var_u32!().into(),
+            },
+            Op::RecordFieldDefault => RecordFieldDefault {
+                record: byte_a,
+                value: get_u8!(),
+            },
+            Op::RecordFieldType => RecordFieldType {
+                record: byte_a,
+                allow_null: false,
+                type_string: get_var_u32!().into(),
+            },
+            Op::RecordFieldOptionalType => RecordFieldType {
+                record: byte_a,
+                allow_null: true,
+                type_string: get_var_u32!().into(),
+            },
+            Op::EnumType => EnumType {
+                register: byte_a,
+                name: get_var_u32!().into(),
+            },
+            Op::EnumVariant => EnumVariant {
+                enum_type: byte_a,
+                name: get_var_u32!().into(),
+            },
+            Op::EnumVariantField => EnumVariantField {
+                enum_type: byte_a,
+                name: get_var_u32!().into(),
+            },
+            Op::IsEnumVariant => {
+                let [value, enum_type] = get_u8x2!();
+                IsEnumVariant {
+                    register: byte_a,
+                    value,
+                    enum_type,
+                    variant: get_var_u32!().into(),
+                }
+            }
             Op::Return => Return { register: byte_a },
             Op::Yield => Yield { register: byte_a },
+            Op::Await => Await {
+                register: byte_a,
+                value: get_u8!(),
+            },
             Op::Throw => Throw { register: byte_a },
             Op::Size => Size {
                 register: byte_a,
@@ -481,6 +595,26 @@ impl Iterator for InstructionReader {
                     temporary_output: true,
                 }
             }
+            Op::IterNextRange => {
+                let [byte_b, byte_c, byte_d, byte_e] = get_u8x4!();
+                IterNextRange {
+                    result: byte_a,
+                    start: byte_b,
+                    end: byte_c,
+                    jump_offset: u16::from_le_bytes([byte_d, byte_e]),
+                    inclusive: false,
+                }
+            }
+            Op::IterNextRangeInclusive => {
+                let [byte_b, byte_c, byte_d, byte_e] = get_u8x4!();
+                IterNextRange {
+                    result: byte_a,
+                    start: byte_b,
+                    end: byte_c,
+                    jump_offset: u16::from_le_bytes([byte_d, byte_e]),
+                    inclusive: true,
+                }
+            }
             Op::IterNextQuiet => IterNext {
                 result: None,
                 iterator: byte_a,
@@ -610,6 +744,7 @@ impl Iterator for InstructionReader {
                     register: byte_a,
                     value,
                     key: get_var_u32_with_first_byte!(key_a).into(),
+                    cache: get_var_u32!(),
                 }
             }
             Op::TryAccess => {
@@ -618,6 +753,7 @@ impl Iterator for InstructionReader {
                     register: byte_a,
                     value,
                     key: get_var_u32_with_first_byte!(key_a).into(),
+                    cache: get_var_u32!(),
                     jump_offset: get_u16!(),
                 }
             }
@@ -627,6 +763,7 @@ impl Iterator for InstructionReader {
                     register: byte_a,
                     value: byte_b,
                     key: byte_c,
+                    cache: get_var_u32!(),
                 }
             }
             Op::TryAccessString => {
@@ -635,6 +772,7 @@ impl Iterator for InstructionReader {
                     register: byte_a,
                     value: byte_b,
                     key: byte_c,
+                    cache: get_var_u32!(),
                     jump_offset: get_u16!(),
                 }
             }
@@ -4,17 +4,30 @@
 
 mod chunk;
 mod compiler;
+mod constant_value;
 mod frame;
 mod instruction;
 mod instruction_reader;
+mod lint;
 mod module_loader;
+mod module_resolver;
 mod op;
+mod serialization;
+mod type_checker;
+mod verifier;
 
 pub use crate::{
-    chunk::{Chunk, DebugInfo},
+    chunk::{Chunk, DebugInfo, FrameDebugInfo},
     compiler::{Compiler, CompilerError, CompilerSettings},
     instruction::{FunctionFlags, Instruction, StringFormatFlags},
     instruction_reader::InstructionReader,
-    module_loader::{ModuleLoader, ModuleLoaderError, find_module},
+    lint::{Lint, LintKind, LintSettings, lint},
+    module_loader::{
+        CompileModuleResult, ModuleLoader, ModuleLoaderError, ModuleLoaderErrorKind, find_module,
+    },
+    module_resolver::{FileSystemModuleResolver, MemoryModuleResolver, ModuleResolver},
     op::Op,
+    serialization::{CHUNK_FORMAT_VERSION, ChunkDeserializationError},
+    type_checker::{TypeCheckError, check_types},
+    verifier::{VerifierError, VerifierErrorKind, verify_chunk},
 };
@@ -0,0 +1,577 @@
+use koto_parser::{
+    Ast, AstIndex, AstString, AstTry, ChainNode, ConstantIndex, ImportItem, Node, Span,
+    StringContents, StringNode,
+};
+use rustc_hash::FxHashSet;
+use thiserror::Error;
+
+/// The kinds of warnings that can be returned from [lint]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum LintKind {
+    #[error("unused variable '{0}' (prefix the name with '_' to ignore it)")]
+    UnusedVariable(String),
+    #[error("unused import '{0}'")]
+    UnusedImport(String),
+    #[error("'{0}' shadows a value with the same name from an outer scope")]
+    ShadowedBinding(String),
+    #[error("unreachable code")]
+    UnreachableCode,
+    #[error("'{0}' is not defined")]
+    UndefinedId(String),
+}
+
+/// A warning returned by [lint]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("{kind}")]
+pub struct Lint {
+    /// The kind of warning
+    pub kind: LintKind,
+    /// The span in the source where the warning applies
+    pub span: Span,
+}
+
+/// The settings used by [lint]
+#[derive(Clone, Debug, Default)]
+pub struct LintSettings {
+    /// The names of the values that are available in the runtime's prelude
+    ///
+    /// Accessed ids that aren't locally defined, captured, or exported by the script are reported
+    /// as undefined unless they're included in the prelude. If `None` is provided then undefined
+    /// ids won't be reported.
+    ///
+    /// Default: `None`
+    pub prelude: Option<Vec<String>>,
+}
+
+/// Analyzes a script's AST, returning warnings for likely mistakes
+///
+/// The following checks are performed:
+///   - Variables and imported items that are defined but never accessed.
+///     Names that start with `_` are ignored.
+///   - Function arguments and variables that shadow values from an outer scope.
+///   - Expressions that follow a `return`, `throw`, `break`, or `continue` in the same block.
+///   - Ids that aren't defined locally, captured from an outer scope, exported, or included in the
+///     prelude (see [LintSettings::prelude]).
+///
+/// The returned warnings are sorted by their position in the script.
+pub fn lint(ast: &Ast, settings: &LintSettings) -> Vec<Lint> {
+    let Some(entry_point) = ast.entry_point() else {
+        return Vec::new();
+    };
+
+    let mut linter = Linter {
+        ast,
+        settings,
+        exports: exported_names(ast),
+        scopes: vec![Scope::default()],
+        exporting: false,
+        wildcard_import: false,
+        lints: Vec::new(),
+    };
+
+    linter.visit(entry_point);
+    linter.end_scope();
+
+    let mut result = linter.lints;
+    result.sort_by_key(|lint| lint.span.start);
+    result
+}
+
+struct Linter<'a> {
+    ast: &'a Ast,
+    settings: &'a LintSettings,
+    // The names of all values that are exported by the script
+    exports: FxHashSet<&'a str>,
+    // The stack of function scopes, with the script's top level at the bottom
+    scopes: Vec<Scope<'a>>,
+    // True while visiting the target of an `export` expression
+    exporting: bool,
+    // True once a wildcard import has been visited
+    wildcard_import: bool,
+    lints: Vec<Lint>,
+}
+
+// The variables that have been defined in a function (or in the script's top level)
+//
+// Blocks don't introduce scopes, so variables that are assigned in loops or in branches are
+// available in the rest of the function.
+#[derive(Default)]
+struct Scope<'a> {
+    variables: Vec<Variable<'a>>,
+}
+
+struct Variable<'a> {
+    name: &'a str,
+    // The span of the variable's first definition
+    span: Span,
+    is_import: bool,
+    used: bool,
+}
+
+// Returns the names of the values exported by the script, which can be accessed from anywhere
+pub(crate) fn exported_names(ast: &Ast) -> FxHashSet<&str> {
+    let mut result = FxHashSet::default();
+    let mut add_id = |index: AstIndex| {
+        if let Node::Id(name, _) = &ast.node(index).node {
+            result.insert(ast.constants().get_str(*name));
+        }
+    };
+
+    for node in ast.nodes() {
+        let Node::Export(exported) = &node.node else {
+            continue;
+        };
+        match &ast.node(*exported).node {
+            Node::Map { entries, .. } => {
+                for entry in entries {
+                    match &ast.node(*entry).node {
+                        Node::MapEntry(key, _) => add_id(*key),
+                        _ => add_id(*entry),
+                    }
+                }
+            }
+            Node::Assign { target, .. }
+            | Node::Const { target, .. }
+            | Node::Record { id: target, .. }
+            | Node::Enum { id: target, .. } => add_id(*target),
+            Node::MultiAssign { targets, .. } => targets.iter().for_each(|target| add_id(*target)),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+impl<'a> Linter<'a> {
+    fn visit(&mut self, index: AstIndex) {
+        let ast = self.ast;
+        let node = ast.node(index);
+
+        match &node.node {
+            Node::Nested(expression)
+            | Node::RangeFrom { start: expression }
+            | Node::RangeTo {
+                end: expression, ..
+            }
+            | Node::PackedExpression(expression)
+            | Node::Loop { body: expression }
+            | Node::Throw(expression)
+            | Node::Yield(expression)
+            | Node::Await(expression)
+            | Node::MapEntry(_, expression)
+            | Node::SwitchArm {
+                condition: None,
+                expression,
+            }
+            | Node::Debug { expression, .. }
+            | Node::Break(Some(expression))
+            | Node::Return(Some(expression)) => self.visit(*expression),
+            Node::Id(name, _) => self.access(*name, node.span),
+            Node::Chain(_) => self.visit_chain(index),
+            Node::Str(string) => self.visit_string(string),
+            Node::List(elements)
+            | Node::TempTuple(elements)
+            | Node::Tuple { elements, .. }
+            | Node::Switch(elements) => self.visit_all(elements),
+            Node::MainBlock { body, .. } | Node::Block(body) => {
+                self.check_unreachable_code(body);
+                self.visit_all(body);
+            }
+            Node::Range { start, end, .. } => {
+                self.visit(*start);
+                self.visit(*end);
+            }
+            Node::Map { entries, .. } => {
+                for entry in entries {
+                    match &ast.node(*entry).node {
+                        Node::MapEntry(key, value) => {
+                            if let Node::Str(key) = &ast.node(*key).node {
+                                self.visit_string(key);
+                            }
+                            self.visit(*value);
+                        }
+                        // Entries without values access values with the same name
+                        _ => self.visit(*entry),
+                    }
+                }
+            }
+            Node::Function(function) => {
+                self.scopes.push(Scope::default());
+
+                if let Node::FunctionArgs { args, .. } = &ast.node(function.args).node {
+                    for arg in args {
+                        self.visit_pattern(*arg);
+                    }
+                }
+                self.visit(function.body);
+
+                self.end_scope();
+            }
+            Node::Import { from, items } => self.visit_import(from, items, false),
+            Node::Export(expression) => match &ast.node(*expression).node {
+                Node::Map { entries, .. } => {
+                    for entry in entries {
+                        if let Node::MapEntry(_, value) = &ast.node(*entry).node {
+                            self.visit(*value);
+                        }
+                    }
+                }
+                _ => {
+                    self.exporting = true;
+                    self.visit(*expression);
+                    self.exporting = false;
+                }
+            },
+            Node::Assign {
+                target, expression, ..
+            }
+            | Node::Const { target, expression } => {
+                let target_node = &ast.node(*target).node;
+
+                // Functions can refer to themselves, so the target is defined first
+                if matches!(target_node, Node::Id(..))
+                    && matches!(ast.node(*expression).node, Node::Function(_))
+                {
+                    self.visit_pattern(*target);
+                    self.visit(*expression);
+                } else {
+                    self.visit_assigned(*expression);
+                    self.visit_pattern(*target);
+                }
+            }
+            Node::Record { id, fields, .. } => {
+                for field in fields {
+                    if let Node::Assign { expression, .. } = &ast.node(*field).node {
+                        self.visit(*expression);
+                    }
+                }
+                self.visit_pattern(*id);
+            }
+            Node::Enum { id, .. } => self.visit_pattern(*id),
+            Node::MultiAssign {
+                targets,
+                expression,
+                ..
+            } => {
+                self.visit_assigned(*expression);
+                for target in targets {
+                    self.visit_pattern(*target);
+                }
+            }
+            Node::UnaryOp { value, .. } => self.visit(*value),
+            Node::BinaryOp { lhs, rhs, .. } => {
+                self.visit(*lhs);
+                self.visit(*rhs);
+            }
+            Node::If(if_expression) => {
+                self.visit(if_expression.condition);
+                self.visit(if_expression.then_node);
+                for (condition, block) in &if_expression.else_if_blocks {
+                    self.visit(*condition);
+                    self.visit(*block);
+                }
+                if let Some(else_node) = if_expression.else_node {
+                    self.visit(else_node);
+                }
+            }
+            Node::Match { expression, arms } => {
+                self.visit(*expression);
+                self.visit_all(arms);
+            }
+            Node::MatchArm {
+                patterns,
+                condition,
+                expression,
+            } => {
+                for pattern in patterns {
+                    self.visit_pattern(*pattern);
+                }
+                if let Some(condition) = condition {
+                    self.visit(*condition);
+                }
+                self.visit(*expression);
+            }
+            Node::SwitchArm {
+                condition: Some(condition),
+                expression,
+            } => {
+                self.visit(*condition);
+                self.visit(*expression);
+            }
+            Node::For(for_loop) => {
+                self.visit(for_loop.iterable);
+                for arg in &for_loop.args {
+                    self.visit_pattern(*arg);
+                }
+                self.visit(for_loop.body);
+            }
+            Node::While { condition, body } | Node::Until { condition, body } => {
+                self.visit(*condition);
+                self.visit(*body);
+            }
+            Node::Try(AstTry {
+                try_block,
+                catch_blocks,
+                finally_block,
+            }) => {
+                self.visit(*try_block);
+                for catch in catch_blocks {
+                    self.visit_pattern(catch.arg);
+                    self.visit(catch.block);
+                }
+                if let Some(finally_block) = finally_block {
+                    self.visit(*finally_block);
+                }
+            }
+            Node::Null
+            | Node::Meta(..)
+            | Node::BoolTrue
+            | Node::BoolFalse
+            | Node::SmallInt(_)
+            | Node::Int(_)
+            | Node::Float(_)
+            | Node::RangeFull
+            | Node::MapPattern { .. }
+            | Node::MapKeyRebind { .. }
+            | Node::EnumVariant { .. }
+            | Node::EnumPattern { .. }
+            | Node::Self_
+            | Node::FunctionArgs { .. }
+            | Node::Ignored(..)
+            | Node::PackedId(_)
+            | Node::Break(None)
+            | Node::Continue
+            | Node::Return(None)
+            | Node::Type { .. } => {}
+        }
+    }
+
+    // Visits an expression whose result is assigned to a target
+    fn visit_assigned(&mut self, index: AstIndex) {
+        match &self.ast.node(index).node {
+            Node::Import { from, items } => self.visit_import(from, items, true),
+            _ => self.visit(index),
+        }
+    }
+
+    fn visit_all(&mut self, nodes: &[AstIndex]) {
+        for node in nodes {
+            self.visit(*node);
+        }
+    }
+
+    // Visits a node that defines values, e.g. an assignment target or a function argument
+    fn visit_pattern(&mut self, index: AstIndex) {
+        let ast = self.ast;
+        let node = ast.node(index);
+
+        match &node.node {
+            Node::Id(name, _) | Node::PackedId(Some(name)) => {
+                if self.exporting && self.scopes.len() == 1 {
+                    // Exported values are accessed via the module's exports
+                } else {
+                    self.define(*name, node.span, false);
+                }
+            }
+            Node::Nested(nested) => self.visit_pattern(*nested),
+            Node::List(elements)
+            | Node::TempTuple(elements)
+            | Node::Tuple { elements, .. }
+            | Node::MapPattern {
+                entries: elements, ..
+            } => {
+                for element in elements {
+                    self.visit_pattern(*element);
+                }
+            }
+            Node::MapKeyRebind { id_or_ignored, .. } => self.visit_pattern(*id_or_ignored),
+            Node::EnumPattern {
+                enum_id, payload, ..
+            } => {
+                self.visit(*enum_id);
+                for element in payload {
+                    self.visit_pattern(*element);
+                }
+            }
+            // Arguments with default values
+            Node::Assign {
+                target, expression, ..
+            } => {
+                self.visit(*expression);
+                self.visit_pattern(*target);
+            }
+            Node::Ignored(..) | Node::PackedId(None) | Node::Str(_) | Node::Meta(..) => {}
+            // Chained assignment targets, and literals in match patterns
+            _ => self.visit(index),
+        }
+    }
+
+    fn visit_string(&mut self, string: &AstString) {
+        if let StringContents::Interpolated(nodes) = &string.contents {
+            for node in nodes {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.visit(*expression);
+                }
+            }
+        }
+    }
+
+    fn visit_chain(&mut self, mut index: AstIndex) {
+        let ast = self.ast;
+
+        while let Node::Chain((chain_node, next)) = &ast.node(index).node {
+            match chain_node {
+                ChainNode::Root(root) => self.visit(*root),
+                ChainNode::Str(string) => self.visit_string(string),
+                ChainNode::Index(expression) => self.visit(*expression),
+                ChainNode::Call { args, .. } => self.visit_all(args),
+                ChainNode::Id(_) | ChainNode::NullCheck => {}
+            }
+
+            match next {
+                Some(next) => index = *next,
+                None => break,
+            }
+        }
+    }
+
+    fn visit_import(&mut self, from: &[AstIndex], items: &[ImportItem], assigned: bool) {
+        let ast = self.ast;
+
+        for (i, module) in from.iter().enumerate() {
+            match &ast.node(*module).node {
+                // The start of the path can refer to a local value, otherwise it's a module name
+                Node::Id(name, _) if i == 0 => {
+                    let name = self.name(*name);
+                    if let Some(variable) = self.find_variable(name) {
+                        variable.used = true;
+                    }
+                }
+                Node::Str(string) => self.visit_string(string),
+                _ => {}
+            }
+        }
+
+        if items.is_empty() {
+            // Wildcard imports bring unknown names into scope
+            self.wildcard_import = true;
+        }
+
+        for item in items {
+            if let Node::Str(string) = &ast.node(item.item).node {
+                self.visit_string(string);
+            }
+
+            // Items are only assigned to locals when the import's result isn't being assigned,
+            // unless they're renamed with `as`.
+            let id = match item.name {
+                Some(name) => name,
+                None if !assigned => item.item,
+                None => continue,
+            };
+            let id_node = ast.node(id);
+            if let Node::Id(name, _) = id_node.node {
+                self.define(name, id_node.span, true);
+            }
+        }
+    }
+
+    fn check_unreachable_code(&mut self, body: &[AstIndex]) {
+        let ast = self.ast;
+
+        let exit = body.iter().position(|expression| {
+            matches!(
+                ast.node(*expression).node,
+                Node::Return(_) | Node::Throw(_) | Node::Break(_) | Node::Continue
+            )
+        });
+
+        if let Some(unreachable) = exit.and_then(|exit| body.get(exit + 1)) {
+            self.add_lint(
+                LintKind::UnreachableCode,
+                *ast.span(ast.node(*unreachable).span),
+            );
+        }
+    }
+
+    fn define(&mut self, name: ConstantIndex, span: AstIndex, is_import: bool) {
+        let name = self.name(name);
+        if name.starts_with('_') {
+            return;
+        }
+
+        let span = *self.ast.span(span);
+        let (scope, outer_scopes) = self.scopes.split_last_mut().unwrap();
+
+        // Assigning to a name that's already defined in the scope reuses the existing variable
+        if scope.variables.iter().any(|variable| variable.name == name) {
+            return;
+        }
+
+        let shadows_outer_value = outer_scopes
+            .iter()
+            .any(|outer| outer.variables.iter().any(|variable| variable.name == name));
+
+        scope.variables.push(Variable {
+            name,
+            span,
+            is_import,
+            used: false,
+        });
+
+        if shadows_outer_value {
+            self.add_lint(LintKind::ShadowedBinding(name.into()), span);
+        }
+    }
+
+    fn access(&mut self, name: ConstantIndex, span: AstIndex) {
+        let name = self.name(name);
+
+        match self.find_variable(name) {
+            Some(variable) => variable.used = true,
+            None => {
+                let is_defined = self.wildcard_import
+                    || self.exports.contains(name)
+                    || self.settings.prelude.as_ref().is_none_or(|prelude| {
+                        prelude.iter().any(|prelude_name| prelude_name == name)
+                    });
+                if !is_defined {
+                    self.add_lint(LintKind::UndefinedId(name.into()), *self.ast.span(span));
+                }
+            }
+        }
+    }
+
+    fn find_variable(&mut self, name: &str) -> Option<&mut Variable<'a>> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.variables.iter_mut().find(|v| v.name == name))
+    }
+
+    // Reports the unused variables in the current scope, and then removes the scope
+    fn end_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+
+        for variable in scope.variables {
+            if variable.used {
+                continue;
+            }
+            let kind = if variable.is_import {
+                LintKind::UnusedImport(variable.name.into())
+            } else {
+                LintKind::UnusedVariable(variable.name.into())
+            };
+            self.add_lint(kind, variable.span);
+        }
+    }
+
+    fn add_lint(&mut self, kind: LintKind, span: Span) {
+        self.lints.push(Lint { kind, span });
+    }
+
+    fn name(&self, constant: ConstantIndex) -> &'a str {
+        self.ast.constants().get_str(constant)
+    }
+}
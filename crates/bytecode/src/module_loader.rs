@@ -1,11 +1,14 @@
-use crate::{Chunk, Compiler, CompilerError, CompilerSettings};
+use crate::{
+    Chunk, Compiler, CompilerError, CompilerSettings, FileSystemModuleResolver, ModuleResolver,
+    verify_chunk,
+};
 use dunce::canonicalize;
-use koto_memory::Ptr;
+use koto_memory::{Ptr, make_ptr};
 use koto_parser::{KString, Span, format_source_excerpt};
 use rustc_hash::FxHasher;
 use std::{
     collections::HashMap,
-    error, fmt,
+    error, fmt, fs,
     hash::BuildHasherDefault,
     io,
     ops::Deref,
@@ -27,8 +30,24 @@ pub enum ModuleLoaderErrorKind {
     FailedToGetCurrentDir(io::Error),
     #[error("failed to get parent of path ('{0}')")]
     FailedToGetPathParent(PathBuf),
-    #[error("unable to find module '{0}'")]
-    UnableToFindModule(String),
+    #[error("unable to find module '{name}'{}", format_searched_paths(.searched))]
+    UnableToFindModule {
+        name: String,
+        searched: Vec<PathBuf>,
+    },
+}
+
+fn format_searched_paths(searched: &[PathBuf]) -> String {
+    if searched.is_empty() {
+        return String::new();
+    }
+
+    let paths = searched
+        .iter()
+        .map(|path| format!("'{}'", path.display()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" (tried {paths})")
 }
 
 /// The error type used by the [ModuleLoader]
@@ -103,31 +122,74 @@ impl From<ModuleLoaderErrorKind> for ModuleLoaderError {
 }
 
 /// Helper for loading, compiling, and caching Koto modules
-#[derive(Clone, Default)]
+///
+/// Modules are found using a [ModuleResolver], by default modules are loaded from disk with
+/// [FileSystemModuleResolver].
+#[derive(Clone)]
 pub struct ModuleLoader {
     chunks: HashMap<PathBuf, Ptr<Chunk>, BuildHasherDefault<FxHasher>>,
+    resolver: Ptr<dyn ModuleResolver>,
+    search_paths: Vec<PathBuf>,
+    bytecode_cache: Option<PathBuf>,
+}
+
+impl Default for ModuleLoader {
+    fn default() -> Self {
+        Self {
+            chunks: HashMap::default(),
+            resolver: make_ptr!(FileSystemModuleResolver),
+            search_paths: Vec::new(),
+            bytecode_cache: None,
+        }
+    }
 }
 
 impl ModuleLoader {
     /// Compiles a script, deferring to [Compiler::compile]
+    ///
+    /// If a bytecode cache has been set (see [ModuleLoader::set_bytecode_cache]) and the script
+    /// has a path, then the cache will be checked for a matching compiled chunk before compiling.
     pub fn compile_script(
         &mut self,
         script: &str,
         script_path: Option<KString>,
         settings: CompilerSettings,
     ) -> Result<Ptr<Chunk>, ModuleLoaderError> {
-        Compiler::compile(script, script_path.clone(), settings)
-            .map(Ptr::from)
-            .map_err(|e| ModuleLoaderError::from_compiler_error(e, script, script_path))
+        let cache_file = match (&self.bytecode_cache, &script_path) {
+            (Some(cache_dir), Some(path)) => {
+                let key = bytecode_cache_key(script, path, &settings);
+                Some(cache_dir.join(format!("{key:016x}.kotoc")))
+            }
+            _ => None,
+        };
+
+        if let Some(cache_file) = &cache_file
+            && let Some(chunk) = load_cached_chunk(cache_file, script, &script_path)
+        {
+            return Ok(chunk);
+        }
+
+        let chunk = Compiler::compile(script, script_path.clone(), settings)
+            .map_err(|e| ModuleLoaderError::from_compiler_error(e, script, script_path))?;
+
+        if let Some(cache_file) = &cache_file {
+            save_cached_chunk(cache_file, &chunk);
+        }
+
+        Ok(chunk.into())
     }
 
-    /// Finds a module from its name, and then compiles it
+    /// Finds a module from its name using the loader's [ModuleResolver], and then compiles it
+    ///
+    /// The loader's search paths are passed to the resolver, see [ModuleLoader::set_search_paths].
     pub fn compile_module(
         &mut self,
         module_name: &str,
         current_script_path: Option<&Path>,
     ) -> Result<CompileModuleResult, ModuleLoaderError> {
-        let module_path = find_module(module_name, current_script_path)?;
+        let module_path =
+            self.resolver
+                .resolve(module_name, current_script_path, &self.search_paths)?;
 
         match self.chunks.get(&module_path) {
             Some(chunk) => Ok(CompileModuleResult {
@@ -136,12 +198,7 @@ impl ModuleLoader {
                 loaded_from_cache: true,
             }),
             None => {
-                let script = std::fs::read_to_string(&module_path).map_err(|error| {
-                    ModuleLoaderErrorKind::FailedToReadScript {
-                        path: module_path.clone(),
-                        error,
-                    }
-                })?;
+                let script = self.resolver.load(&module_path)?;
 
                 let chunk = self.compile_script(
                     &script,
@@ -161,31 +218,148 @@ impl ModuleLoader {
     }
 
     /// Clears the compiled module cache
+    ///
+    /// The on-disk bytecode cache isn't affected, see [ModuleLoader::set_bytecode_cache].
     pub fn clear_cache(&mut self) {
         self.chunks.clear();
     }
+
+    /// Sets the [ModuleResolver] that should be used to find imported modules
+    ///
+    /// The compiled module cache is cleared when the resolver is changed.
+    pub fn set_module_resolver(&mut self, resolver: Ptr<dyn ModuleResolver>) {
+        self.resolver = resolver;
+        self.clear_cache();
+    }
+
+    /// Returns the [ModuleResolver] that's used to find imported modules
+    pub fn module_resolver(&self) -> &Ptr<dyn ModuleResolver> {
+        &self.resolver
+    }
+
+    /// Sets the directories that should be searched for modules
+    ///
+    /// Modules are first looked for relative to the importing script, and then in each of the
+    /// search paths in order, allowing shared modules to be imported from any script.
+    pub fn set_search_paths(&mut self, search_paths: Vec<PathBuf>) {
+        self.search_paths = search_paths;
+    }
+
+    /// Returns the directories that are searched for modules
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// Sets a directory that should be used to cache compiled scripts
+    ///
+    /// When set, compiled scripts that have a path are saved to the directory as `.kotoc` files,
+    /// keyed by a hash of the script's source, path, and compiler settings.
+    /// Compiling an unchanged script will then load the cached chunk rather than recompiling it.
+    ///
+    /// Errors that occur while reading from or writing to the cache are ignored, with the script
+    /// being compiled as normal.
+    pub fn set_bytecode_cache(&mut self, cache_dir: Option<PathBuf>) {
+        self.bytecode_cache = cache_dir;
+    }
+
+    /// Returns the directory used to cache compiled scripts, if one has been set
+    pub fn bytecode_cache(&self) -> Option<&Path> {
+        self.bytecode_cache.as_deref()
+    }
+}
+
+// Returns the key used to identify a script in the bytecode cache
+//
+// A stable hash (FNV-1a) is used so that keys match between runs and between platforms.
+fn bytecode_cache_key(script: &str, path: &str, settings: &CompilerSettings) -> u64 {
+    let settings_flags = [
+        settings.export_top_level_ids as u8,
+        settings.enable_type_checks as u8,
+        settings.enable_optimizations as u8,
+        settings.enable_static_type_checks as u8,
+    ];
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in [path.as_bytes(), &settings_flags, script.as_bytes()] {
+        // The part's length is included to avoid ambiguities between adjacent parts
+        for byte in (part.len() as u64).to_le_bytes().iter().chain(part) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+// Loads a chunk from the bytecode cache, if the cached chunk matches the script
+fn load_cached_chunk(
+    cache_file: &Path,
+    script: &str,
+    script_path: &Option<KString>,
+) -> Option<Ptr<Chunk>> {
+    let data = fs::read(cache_file).ok()?;
+    let chunk: Ptr<Chunk> = Chunk::deserialize(&data).ok()?.into();
+
+    // Guard against hash collisions by checking that the cached chunk was compiled from the
+    // same script
+    if chunk.debug_info.source != script || chunk.path != *script_path {
+        return None;
+    }
+
+    // The cache file may have been modified since it was written, so the bytecode is verified
+    // before being used
+    verify_chunk(&chunk).ok()?;
+
+    Some(chunk)
+}
+
+// Saves a chunk to the bytecode cache
+//
+// The chunk is first written to a temporary file which is then renamed,
+// avoiding partially written files being seen by other processes.
+fn save_cached_chunk(cache_file: &Path, chunk: &Chunk) {
+    let Some(cache_dir) = cache_file.parent() else {
+        return;
+    };
+    if fs::create_dir_all(cache_dir).is_err() {
+        return;
+    }
+
+    let temp_file = cache_file.with_extension(format!("kotoc.{}.tmp", std::process::id()));
+    if fs::write(&temp_file, chunk.serialize()).is_ok()
+        && fs::rename(&temp_file, cache_file).is_err()
+    {
+        fs::remove_file(&temp_file).ok();
+    }
 }
 
 /// Returned from [ModuleLoader::compile_module]
 pub struct CompileModuleResult {
     /// The compiled module
     pub chunk: Ptr<Chunk>,
-    // The path of the compiled module
+    /// The path of the compiled module
     pub path: PathBuf,
-    // True if the module was found in the [ModuleLoader] cache
+    /// True if the module was found in the [ModuleLoader] cache
     pub loaded_from_cache: bool,
 }
 
-/// Finds a module that matches the given name
+/// Finds a module on disk that matches the given name
+///
+/// A module named `foo` will be found in either `foo.koto`, or in `foo/main.koto`,
+/// relative to the directory containing the current script.
 ///
 /// The `current_script_path` argument gives a location to start searching from,
 /// if `None` is provided then `std::env::current_dir` will be used instead.
+///
+/// If the module isn't found relative to the current script, then each of the directories in
+/// `search_paths` will be checked in order. If no match is found then the returned
+/// [ModuleLoaderErrorKind::UnableToFindModule] error lists all of the paths that were tried.
 pub fn find_module(
     module_name: &str,
     current_script_path: Option<&Path>,
+    search_paths: &[PathBuf],
 ) -> Result<PathBuf, ModuleLoaderError> {
     // Get the directory of the provided script path, or the current working directory
-    let search_folder = match &current_script_path {
+    let script_folder = match &current_script_path {
         Some(path) => {
             let canonicalized = canonicalize(path).map_err(|error| {
                 ModuleLoaderErrorKind::FailedToCanonicalizePath {
@@ -208,28 +382,43 @@ pub fn find_module(
         None => std::env::current_dir().map_err(ModuleLoaderErrorKind::FailedToGetCurrentDir)?,
     };
 
-    // First, check for a neighboring file with a matching name.
+    let mut searched = Vec::new();
+
+    for search_folder in
+        std::iter::once(script_folder.as_path()).chain(search_paths.iter().map(PathBuf::as_path))
+    {
+        for candidate in module_candidates(search_folder, module_name) {
+            if candidate.exists() {
+                return canonicalize(&candidate).map_err(|error| {
+                    ModuleLoaderErrorKind::FailedToCanonicalizePath {
+                        path: candidate,
+                        error,
+                    }
+                    .into()
+                });
+            }
+            searched.push(candidate);
+        }
+    }
+
+    Err(ModuleLoaderErrorKind::UnableToFindModule {
+        name: module_name.into(),
+        searched,
+    }
+    .into())
+}
+
+/// Returns the paths that could contain a module with the given name in a search folder
+///
+/// A module named `foo` could either be a neighboring file named `foo.koto`,
+/// or a neighboring directory containing a main file, `foo/main.koto`.
+pub(crate) fn module_candidates(search_folder: &Path, module_name: &str) -> [PathBuf; 2] {
     let extension = "koto";
-    let result = search_folder.join(module_name).with_extension(extension);
-    if result.exists() {
-        Ok(result)
-    } else {
-        // Alternatively, check for a neighboring directory with a matching name,
-        // that also contains a main file.
-        let result = search_folder
+    [
+        search_folder.join(module_name).with_extension(extension),
+        search_folder
             .join(module_name)
             .join("main")
-            .with_extension(extension);
-        if result.exists() {
-            canonicalize(&result).map_err(|error| {
-                ModuleLoaderErrorKind::FailedToCanonicalizePath {
-                    path: result,
-                    error,
-                }
-                .into()
-            })
-        } else {
-            Err(ModuleLoaderErrorKind::UnableToFindModule(module_name.into()).into())
-        }
-    }
+            .with_extension(extension),
+    ]
 }
@@ -0,0 +1,143 @@
+use crate::{
+    ModuleLoaderError, ModuleLoaderErrorKind, find_module, module_loader::module_candidates,
+};
+use koto_memory::{KotoSend, KotoSync};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// A trait for finding and loading the modules that are imported by Koto scripts
+///
+/// The [ModuleLoader](crate::ModuleLoader) defers to a resolver when compiling imported modules.
+/// By default modules are loaded from disk (see [FileSystemModuleResolver]), implementing this
+/// trait allows modules to be provided from other sources, like scripts that are embedded in a
+/// binary, or that are stored in an asset archive.
+pub trait ModuleResolver: KotoSend + KotoSync {
+    /// Returns the path of the module that matches the given name
+    ///
+    /// The path is used to identify the module, with the loader caching compiled modules by path.
+    /// It's also used as the script path when compiling the module, so it will be passed back
+    /// as `current_script_path` when resolving the module's own imports.
+    ///
+    /// `current_script_path` is the path of the importing script, if it has one.
+    ///
+    /// `search_paths` are additional locations that should be searched in order if the module
+    /// isn't found relative to the importing script (see [ModuleLoader::set_search_paths]).
+    ///
+    /// [ModuleLoader::set_search_paths]: crate::ModuleLoader::set_search_paths
+    fn resolve(
+        &self,
+        module_name: &str,
+        current_script_path: Option<&Path>,
+        search_paths: &[PathBuf],
+    ) -> Result<PathBuf, ModuleLoaderError>;
+
+    /// Returns the source of the module with the given path
+    ///
+    /// The path will have been previously returned by [ModuleResolver::resolve].
+    fn load(&self, module_path: &Path) -> Result<String, ModuleLoaderError>;
+}
+
+/// The default [ModuleResolver], which loads modules from disk
+///
+/// See [find_module] for a description of how modules are found.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileSystemModuleResolver;
+
+impl ModuleResolver for FileSystemModuleResolver {
+    fn resolve(
+        &self,
+        module_name: &str,
+        current_script_path: Option<&Path>,
+        search_paths: &[PathBuf],
+    ) -> Result<PathBuf, ModuleLoaderError> {
+        find_module(module_name, current_script_path, search_paths)
+    }
+
+    fn load(&self, module_path: &Path) -> Result<String, ModuleLoaderError> {
+        std::fs::read_to_string(module_path).map_err(|error| {
+            ModuleLoaderErrorKind::FailedToReadScript {
+                path: module_path.to_path_buf(),
+                error,
+            }
+            .into()
+        })
+    }
+}
+
+/// A [ModuleResolver] that provides modules from memory
+///
+/// Modules are added with a path, and are resolved following the same rules as modules on disk,
+/// i.e. importing `foo` will look for `foo.koto` or `foo/main.koto` relative to the directory of
+/// the importing script, and then in each of the search paths. Scripts without a path import
+/// modules relative to the root directory.
+///
+/// ```
+/// use koto_bytecode::{MemoryModuleResolver, ModuleResolver};
+///
+/// let mut resolver = MemoryModuleResolver::default();
+/// resolver.add_module("foo.koto", "export x = 42");
+/// resolver.add_module("bar/main.koto", "export y = 99");
+///
+/// resolver.add_module("lib/baz.koto", "export z = -1");
+///
+/// let foo = resolver.resolve("foo", None, &[]).unwrap();
+/// assert_eq!(resolver.load(&foo).unwrap(), "export x = 42");
+/// assert!(resolver.resolve("bar", None, &[]).is_ok());
+/// assert!(resolver.resolve("baz", None, &[]).is_err());
+/// assert!(resolver.resolve("baz", None, &["lib".into()]).is_ok());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryModuleResolver {
+    modules: HashMap<PathBuf, String>,
+}
+
+impl MemoryModuleResolver {
+    /// Adds a module to the resolver with the given path and source
+    pub fn add_module(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) {
+        self.modules.insert(path.into(), source.into());
+    }
+}
+
+impl ModuleResolver for MemoryModuleResolver {
+    fn resolve(
+        &self,
+        module_name: &str,
+        current_script_path: Option<&Path>,
+        search_paths: &[PathBuf],
+    ) -> Result<PathBuf, ModuleLoaderError> {
+        let script_folder = current_script_path
+            .and_then(|path| path.parent())
+            .unwrap_or(Path::new(""));
+
+        let mut searched = Vec::new();
+
+        for search_folder in
+            std::iter::once(script_folder).chain(search_paths.iter().map(PathBuf::as_path))
+        {
+            for candidate in module_candidates(search_folder, module_name) {
+                if self.modules.contains_key(&candidate) {
+                    return Ok(candidate);
+                }
+                searched.push(candidate);
+            }
+        }
+
+        Err(ModuleLoaderErrorKind::UnableToFindModule {
+            name: module_name.into(),
+            searched,
+        }
+        .into())
+    }
+
+    fn load(&self, module_path: &Path) -> Result<String, ModuleLoaderError> {
+        self.modules.get(module_path).cloned().ok_or_else(|| {
+            ModuleLoaderErrorKind::UnableToFindModule {
+                name: module_path.to_string_lossy().into(),
+                searched: vec![module_path.to_path_buf()],
+            }
+            .into()
+        })
+    }
+}
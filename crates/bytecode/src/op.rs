@@ -501,14 +501,17 @@ pub enum Op {
 
     /// Accesses a contained value via a constant key
     ///
-    /// `[*container, @constant]`
+    /// The cache operand is the index of the instruction's access cache slot, see
+    /// [Chunk::access_cache_count](crate::Chunk::access_cache_count).
+    ///
+    /// `[*result, *container, @constant, @cache]`
     Access,
 
     /// Accesses a contained value via a string key
     ///
     /// Used in `.` access operations that use a quoted string, e.g. `foo."bar"`.
     ///
-    /// `[*result, *container, *key]`
+    /// `[*result, *container, *key, @cache]`
     AccessString,
 
     /// Assigns a key/value entry via `.` access
@@ -599,7 +602,7 @@ pub enum Op {
     ///
     /// This is used for match patterns.
     ///
-    /// `[*container, @constant, jump_offset[2]]`
+    /// `[*result, *container, @constant, @cache, jump_offset[2]]`
     TryAccess,
 
     /// Tries to access a contained value via a string key
@@ -609,32 +612,165 @@ pub enum Op {
     ///
     /// This is used for match patterns.
     ///
-    /// `[*result, *container, *key, jump_offset[2]]`
+    /// `[*result, *container, *key, @cache, jump_offset[2]]`
     TryAccessString,
 
+    /// Awaits a value
+    ///
+    /// If the value is pending then execution will be suspended until the VM is resumed with the
+    /// awaited result, otherwise the value is copied to the result register.
+    ///
+    /// `[*result, *value]`
+    Await,
+
+    /// Jumps the instruction pointer forward if lhs isn't less than rhs
+    ///
+    /// Used in place of a `Less` followed by a `JumpIfFalse` when the comparison's result isn't
+    /// needed, e.g. in `if` and `while` conditions.
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfNotLess,
+
+    /// Jumps the instruction pointer forward if lhs isn't less than or equal to rhs
+    ///
+    /// See [Op::JumpIfNotLess].
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfNotLessOrEqual,
+
+    /// Jumps the instruction pointer forward if lhs isn't greater than rhs
+    ///
+    /// See [Op::JumpIfNotLess].
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfNotGreater,
+
+    /// Jumps the instruction pointer forward if lhs isn't greater than or equal to rhs
+    ///
+    /// See [Op::JumpIfNotLess].
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfNotGreaterOrEqual,
+
+    /// Jumps the instruction pointer forward if lhs isn't equal to rhs
+    ///
+    /// See [Op::JumpIfNotLess].
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfNotEqual,
+
+    /// Jumps the instruction pointer forward if the '!=' comparison of lhs and rhs is false
+    ///
+    /// See [Op::JumpIfNotLess].
+    ///
+    /// `[*lhs, *rhs, offset[2]]`
+    JumpIfEqual,
+
+    /// Adds a u8 to lhs
+    ///
+    /// Used in place of `Add` when the rhs is a small integer literal, e.g. `x + 1`
+    ///
+    /// `[*result, *lhs, n]`
+    AddU8,
+
+    /// Subtracts a u8 from lhs
+    ///
+    /// Used in place of `Subtract` when the rhs is a small integer literal, e.g. `x - 1`
+    ///
+    /// `[*result, *lhs, n]`
+    SubtractU8,
+
+    /// Gets the next value from a numeric range in a `for` loop
+    ///
+    /// Used in place of `Range`, `MakeIterator`, and `IterNext` when iterating over a range
+    /// literal, e.g. `for i in 0..n`.
+    ///
+    /// The start register is used as the loop's counter, and is incremented after the current
+    /// value is placed in the output register. Once the counter reaches the end of the range,
+    /// the instruction jumps forward by the given offset.
+    ///
+    /// `[*output, *start, *end, offset[2]]`
+    IterNextRange,
+
+    /// Gets the next value from an inclusive numeric range in a `for` loop
+    ///
+    /// See [Op::IterNextRange].
+    ///
+    /// `[*output, *start, *end, offset[2]]`
+    IterNextRangeInclusive,
+
+    /// Calls a standalone function in tail position, reusing the current frame
+    ///
+    /// Used in place of `Call` when the result of the call is returned from the current function,
+    /// e.g. `return f x`, or when the call is the function's last expression.
+    ///
+    /// The instruction is always followed by a `Return` instruction for the result register.
+    /// If the current frame can't be reused (e.g. if the called function is a native function,
+    /// or if the call is being made inside a `try` block), then the runtime performs a regular
+    /// call, and the following `Return` instruction returns the result.
+    ///
+    /// See [Op::Call].
+    ///
+    /// `[*result, *function, *frame base, arg count, packed arg count]`
+    TailCall,
+
+    /// Calls an instance function in tail position, reusing the current frame
+    ///
+    /// See [Op::TailCall] and [Op::CallInstance].
+    ///
+    /// `[*result, *function, *instance, *frame base, arg count, packed arg count]`
+    TailCallInstance,
+
+    /// Makes a record type
+    ///
+    /// The record type's fields are added by following [Op::RecordField] instructions.
+    ///
+    /// `[*target, @name]`
+    RecordType,
+
+    /// Adds a field to a record type
+    ///
+    /// `[*record type, @name]`
+    RecordField,
+
+    /// Sets the default value of the record type's most recently added field
+    ///
+    /// `[*record type, *value]`
+    RecordFieldDefault,
+
+    /// Sets the type hint of the record type's most recently added field
+    ///
+    /// `[*record type, @type constant]`
+    RecordFieldType,
+
+    /// Sets an optional type hint for the record type's most recently added field
+    ///
+    /// `[*record type, @type constant]`
+    RecordFieldOptionalType,
+
+    /// Makes an enum type
+    ///
+    /// The enum's variants are added by following [Op::EnumVariant] instructions.
+    ///
+    /// `[*target, @name]`
+    EnumType,
+
+    /// Adds a variant to an enum type
+    ///
+    /// `[*enum type, @name]`
+    EnumVariant,
+
+    /// Adds a payload field to the enum type's most recently added variant
+    ///
+    /// `[*enum type, @name]`
+    EnumVariantField,
+
+    /// Checks if a value is a specific variant of an enum type
+    ///
+    /// `[*result, *value, *enum type, @variant name]`
+    IsEnumVariant,
+
     // Unused opcodes, allowing for a direct transmutation from a byte to an Op.
-    Unused95,
-    Unused96,
-    Unused97,
-    Unused98,
-    Unused99,
-    Unused100,
-    Unused101,
-    Unused102,
-    Unused103,
-    Unused104,
-    Unused105,
-    Unused106,
-    Unused107,
-    Unused108,
-    Unused109,
-    Unused110,
-    Unused111,
-    Unused112,
-    Unused113,
-    Unused114,
-    Unused115,
-    Unused116,
     Unused117,
     Unused118,
     Unused119,
@@ -776,6 +912,13 @@ pub enum Op {
     Unused255,
 }
 
+impl Op {
+    /// Returns true if the op is one of the unused placeholder ops
+    pub fn is_unused(self) -> bool {
+        self as u8 >= Op::Unused117 as u8
+    }
+}
+
 impl From<u8> for Op {
     fn from(op: u8) -> Op {
         // Safety:
@@ -0,0 +1,309 @@
+use crate::{Chunk, DebugInfo, FrameDebugInfo};
+use koto_parser::{Constant, ConstantPool, KString, Position, Span};
+use thiserror::Error;
+
+/// The version of the binary format produced by [Chunk::serialize]
+///
+/// The version needs to be incremented whenever the layout of the serialized data changes.
+/// Serialized chunks also include the version of Koto that produced them, so changes to the
+/// bytecode itself are covered by Koto's version.
+pub const CHUNK_FORMAT_VERSION: u32 = 2;
+
+// The bytes that serialized chunks start with
+const MAGIC: &[u8; 5] = b"KOTOC";
+
+// The version of Koto that's included in serialized chunks
+const KOTO_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Tags used to identify the types of serialized constants
+const CONSTANT_F64: u8 = 0;
+const CONSTANT_I64: u8 = 1;
+const CONSTANT_STR: u8 = 2;
+
+/// Errors that can be returned from [Chunk::deserialize]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum ChunkDeserializationError {
+    #[error("the data doesn't contain a serialized chunk")]
+    MissingHeader,
+    #[error("unsupported chunk format version ({0}, expected {CHUNK_FORMAT_VERSION})")]
+    UnsupportedFormatVersion(u32),
+    #[error("the chunk was produced by a different version of Koto ({0}, expected {KOTO_VERSION})")]
+    KotoVersionMismatch(String),
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("unexpected data following the end of the chunk")]
+    UnexpectedTrailingData,
+    #[error("invalid UTF-8 in string data")]
+    InvalidString,
+    #[error("invalid constant type ({0})")]
+    InvalidConstantType(u8),
+    #[error("the constant pool contains duplicate constants")]
+    InvalidConstantPool,
+    #[error("invalid debug info ({0})")]
+    InvalidDebugInfo(&'static str),
+}
+
+impl Chunk {
+    /// Serializes the chunk into Koto's binary bytecode format
+    ///
+    /// The chunk can be restored with [Chunk::deserialize].
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.write_u32(CHUNK_FORMAT_VERSION);
+        writer.write_str(KOTO_VERSION);
+
+        match &self.path {
+            Some(path) => {
+                writer.write_u8(1);
+                writer.write_str(path);
+            }
+            None => writer.write_u8(0),
+        }
+
+        writer.write_len(self.bytes.len());
+        writer.bytes.extend_from_slice(&self.bytes);
+        writer.write_u32(self.access_cache_count);
+
+        writer.write_len(self.constants.size());
+        for constant in self.constants.iter() {
+            match constant {
+                Constant::F64(n) => {
+                    writer.write_u8(CONSTANT_F64);
+                    writer.write_u64(n.to_bits());
+                }
+                Constant::I64(n) => {
+                    writer.write_u8(CONSTANT_I64);
+                    writer.write_u64(n as u64);
+                }
+                Constant::Str(s) => {
+                    writer.write_u8(CONSTANT_STR);
+                    writer.write_str(s);
+                }
+            }
+        }
+
+        let debug_info = &self.debug_info;
+        writer.write_str(&debug_info.source);
+
+        writer.write_len(debug_info.source_map().len());
+        for (ip, span) in debug_info.source_map() {
+            writer.write_u32(*ip);
+            writer.write_u32(span.start.line);
+            writer.write_u32(span.start.column);
+            writer.write_u32(span.end.line);
+            writer.write_u32(span.end.column);
+        }
+
+        writer.write_len(debug_info.frames().len());
+        for frame in debug_info.frames() {
+            writer.write_u32(frame.start_ip);
+            writer.write_u32(frame.end_ip);
+            writer.write_len(frame.locals.len());
+            for (register, name) in frame.locals.iter() {
+                writer.write_u8(*register);
+                writer.write_u32((*name).into());
+            }
+        }
+
+        writer.bytes
+    }
+
+    /// Deserializes a chunk that was produced by [Chunk::serialize]
+    ///
+    /// The data is validated while loading, with an error returned if the data is incomplete,
+    /// if it was produced by an incompatible version of Koto, or if it contains references that
+    /// are out of bounds.
+    ///
+    /// The chunk's bytecode isn't checked here, chunks from untrusted sources should also be
+    /// checked with [verify_chunk](crate::verify_chunk) before being executed.
+    pub fn deserialize(data: &[u8]) -> Result<Self, ChunkDeserializationError> {
+        use ChunkDeserializationError::*;
+
+        let mut reader = Reader { data, position: 0 };
+
+        if reader.read_bytes(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+            return Err(MissingHeader);
+        }
+
+        let format_version = reader.read_u32()?;
+        if format_version != CHUNK_FORMAT_VERSION {
+            return Err(UnsupportedFormatVersion(format_version));
+        }
+
+        let koto_version = reader.read_str()?;
+        if koto_version != KOTO_VERSION {
+            return Err(KotoVersionMismatch(koto_version.into()));
+        }
+
+        let path = match reader.read_u8()? {
+            0 => None,
+            _ => Some(KString::from(reader.read_str()?)),
+        };
+
+        let bytes_len = reader.read_len()?;
+        let bytes = reader.read_bytes(bytes_len)?.to_vec();
+        let access_cache_count = reader.read_u32()?;
+
+        let constant_count = reader.read_len()?;
+        let mut constants = Vec::with_capacity(constant_count);
+        for _ in 0..constant_count {
+            let constant = match reader.read_u8()? {
+                CONSTANT_F64 => Constant::F64(f64::from_bits(reader.read_u64()?)),
+                CONSTANT_I64 => Constant::I64(reader.read_u64()? as i64),
+                CONSTANT_STR => Constant::Str(reader.read_str()?),
+                unexpected => return Err(InvalidConstantType(unexpected)),
+            };
+            constants.push(constant);
+        }
+        let constants = ConstantPool::from_constants(constants).ok_or(InvalidConstantPool)?;
+
+        let mut debug_info = DebugInfo::default();
+        debug_info.source = reader.read_str()?.into();
+
+        let source_map_len = reader.read_len()?;
+        let mut previous_ip = 0;
+        for _ in 0..source_map_len {
+            let ip = reader.read_u32()?;
+            if ip < previous_ip || ip as usize >= bytes.len() {
+                return Err(InvalidDebugInfo(
+                    "source map ip out of order or out of bounds",
+                ));
+            }
+            previous_ip = ip;
+
+            let start = reader.read_position()?;
+            let end = reader.read_position()?;
+            debug_info.push(ip, Span { start, end });
+        }
+
+        let frame_count = reader.read_len()?;
+        for _ in 0..frame_count {
+            let start_ip = reader.read_u32()?;
+            let end_ip = reader.read_u32()?;
+            if start_ip > end_ip || end_ip as usize > bytes.len() {
+                return Err(InvalidDebugInfo("frame ip range out of bounds"));
+            }
+
+            let locals_len = reader.read_len()?;
+            let mut locals = Vec::with_capacity(locals_len);
+            for _ in 0..locals_len {
+                let register = reader.read_u8()?;
+                let name = reader.read_u32()?;
+                if !matches!(constants.get(name as usize), Some(Constant::Str(_))) {
+                    return Err(InvalidDebugInfo("local name isn't a string constant"));
+                }
+                locals.push((register, name.into()));
+            }
+
+            debug_info.push_frame(FrameDebugInfo {
+                start_ip,
+                end_ip,
+                locals,
+            });
+        }
+
+        if reader.position != data.len() {
+            return Err(UnexpectedTrailingData);
+        }
+
+        Ok(Self {
+            bytes,
+            constants,
+            access_cache_count,
+            path,
+            debug_info,
+        })
+    }
+}
+
+// Serialized values are written in little-endian order,
+// with lengths written as u64s to keep the format independent of the platform's pointer size.
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u8(&mut self, n: u8) {
+        self.bytes.push(n);
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.bytes.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.bytes.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ChunkDeserializationError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(ChunkDeserializationError::UnexpectedEnd)?;
+        let result = &self.data[self.position..end];
+        self.position = end;
+        Ok(result)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ChunkDeserializationError> {
+        let mut result = [0; N];
+        result.copy_from_slice(self.read_bytes(N)?);
+        Ok(result)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ChunkDeserializationError> {
+        self.read_array::<1>().map(|bytes| bytes[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ChunkDeserializationError> {
+        self.read_array().map(u32::from_le_bytes)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ChunkDeserializationError> {
+        self.read_array().map(u64::from_le_bytes)
+    }
+
+    // Reads a length, which is checked against the remaining data to avoid over-allocating
+    fn read_len(&mut self) -> Result<usize, ChunkDeserializationError> {
+        let len = self.read_u64()?;
+        let remaining = self.data.len() - self.position;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= remaining)
+            .ok_or(ChunkDeserializationError::UnexpectedEnd)
+    }
+
+    fn read_str(&mut self) -> Result<&'a str, ChunkDeserializationError> {
+        let len = self.read_len()?;
+        let bytes = self.read_bytes(len)?;
+        std::str::from_utf8(bytes).map_err(|_| ChunkDeserializationError::InvalidString)
+    }
+
+    fn read_position(&mut self) -> Result<Position, ChunkDeserializationError> {
+        Ok(Position {
+            line: self.read_u32()?,
+            column: self.read_u32()?,
+        })
+    }
+}
@@ -0,0 +1,1226 @@
+use crate::lint::exported_names;
+use koto_parser::{
+    Ast, AstBinaryOp, AstIndex, AstString, AstTry, AstUnaryOp, ChainNode, Function, Node, Span,
+    StringContents, StringNode,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// An error returned by [check_types] for a value that will definitely fail a type check
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("expected a value of type '{expected}', found '{found}'")]
+pub struct TypeCheckError {
+    /// The type that was expected by the type hint, e.g. `Number` or `String?`
+    pub expected: String,
+    /// The type of the value that was found
+    pub found: String,
+    /// The span in the source of the value that doesn't match the type hint
+    pub span: Span,
+}
+
+/// Checks a script's type hints ahead of time
+///
+/// Types are propagated from literals, type hints, function signatures, and the signatures of
+/// core library functions. Values with types that can't be determined statically are skipped,
+/// so errors are only returned for values that would definitely fail a type check at runtime:
+///   - Values assigned with `let` or `const` that don't match their type hints.
+///   - Default values of record fields that don't match the fields' type hints.
+///   - Arguments that don't match the type hints of the called function's arguments,
+///     including missing arguments, which will be `null` when the function is called.
+///   - Return values (or yielded values in generators) that don't match a function's output type.
+///   - Arguments in `for` loops that don't match the type of the iterated values.
+///
+/// Type hints in `match` patterns and `catch` blocks narrow the type of the value in the arm's
+/// body, with the types of each arm merged to produce the type of the `match` expression.
+///
+/// The returned errors are sorted by their position in the script.
+pub fn check_types(ast: &Ast) -> Vec<TypeCheckError> {
+    let Some(entry_point) = ast.entry_point() else {
+        return Vec::new();
+    };
+
+    let mut checker = TypeChecker {
+        ast,
+        exports: exported_names(ast),
+        frames: vec![Frame::default()],
+        suppress_errors: 0,
+        errors: Vec::new(),
+    };
+    checker.check(entry_point);
+
+    let mut result = checker.errors;
+    result.sort_by_key(|error| error.span.start);
+    result.dedup();
+    result
+}
+
+// Signatures of core library functions that only accept a single set of argument types
+//
+// Arguments are listed in order, including the instance for functions that are called as methods.
+const CORE_LIB_FUNCTIONS: &[(&str, &[&str], &str)] = &[
+    ("koto.size", &["Any"], "Number"),
+    ("koto.type", &["Any"], "String"),
+    ("list.contains", &["List", "Any"], "Bool"),
+    ("list.is_empty", &["List"], "Bool"),
+    ("list.push", &["List", "Any"], "List"),
+    ("list.reverse", &["List"], "List"),
+    ("list.to_tuple", &["List"], "Tuple"),
+    ("number.abs", &["Number"], "Number"),
+    ("number.acos", &["Number"], "Number"),
+    ("number.asin", &["Number"], "Number"),
+    ("number.atan", &["Number"], "Number"),
+    ("number.atan2", &["Number", "Number"], "Number"),
+    ("number.ceil", &["Number"], "Number"),
+    ("number.clamp", &["Number", "Number", "Number"], "Number"),
+    ("number.cos", &["Number"], "Number"),
+    ("number.exp", &["Number"], "Number"),
+    ("number.floor", &["Number"], "Number"),
+    ("number.is_int", &["Number"], "Bool"),
+    ("number.is_nan", &["Number"], "Bool"),
+    ("number.lerp", &["Number", "Number", "Number"], "Number"),
+    ("number.ln", &["Number"], "Number"),
+    ("number.max", &["Number", "Number"], "Number"),
+    ("number.min", &["Number", "Number"], "Number"),
+    ("number.round", &["Number"], "Number"),
+    ("number.sin", &["Number"], "Number"),
+    ("number.sqrt", &["Number"], "Number"),
+    ("number.tan", &["Number"], "Number"),
+    ("number.to_int", &["Number"], "Number"),
+    ("string.bytes", &["String"], "Iterator"),
+    ("string.chars", &["String"], "Iterator"),
+    ("string.contains", &["String", "String"], "Bool"),
+    ("string.ends_with", &["String", "String"], "Bool"),
+    ("string.escape", &["String"], "String"),
+    ("string.is_empty", &["String"], "Bool"),
+    ("string.lines", &["String"], "Iterator"),
+    ("string.repeat", &["String", "Number"], "String"),
+    ("string.replace", &["String", "String", "String"], "String"),
+    ("string.starts_with", &["String", "String"], "Bool"),
+    ("string.strip_prefix", &["String", "String"], "String?"),
+    ("string.strip_suffix", &["String", "String"], "String?"),
+    ("string.to_lowercase", &["String"], "String"),
+    ("string.to_uppercase", &["String"], "String"),
+    ("tuple.contains", &["Tuple", "Any"], "Bool"),
+    ("tuple.is_empty", &["Tuple"], "Bool"),
+    ("tuple.to_list", &["Tuple"], "List"),
+];
+
+// The names of the core library modules that are available in the prelude
+const CORE_LIB_MODULES: &[&str] = &["koto", "list", "number", "string", "tuple"];
+
+// Core library functions that are available directly in the prelude
+const PRELUDE_FUNCTIONS: &[(&str, &str)] = &[("size", "koto.size"), ("type", "koto.type")];
+
+// Returns the name of a core type as a static string
+fn core_type(name: &str) -> Option<&'static str> {
+    let result = match name {
+        "Null" => "Null",
+        "Bool" => "Bool",
+        "Number" => "Number",
+        "String" => "String",
+        "List" => "List",
+        "Tuple" => "Tuple",
+        "Map" => "Map",
+        "Range" => "Range",
+        "Function" => "Function",
+        "Generator" => "Generator",
+        "Iterator" => "Iterator",
+        _ => return None,
+    };
+    Some(result)
+}
+
+// The statically known type of a value
+#[derive(Clone, Debug)]
+enum Type<'a> {
+    // The type can't be determined statically
+    Unknown,
+    // The expression doesn't produce a value, e.g. `return` or `throw`
+    Never,
+    // A value with a core type, e.g. `Number` or `String`
+    Core(&'static str),
+    // Either a value with a core type, or `null`
+    Optional(&'static str),
+    // A function with a known signature
+    Function(Rc<Signature<'a>>),
+}
+
+impl<'a> Type<'a> {
+    // Returns the non-null type name, and whether or not the value could be null
+    fn parts(&self) -> (Option<&'static str>, bool) {
+        match self {
+            Type::Core("Null") => (None, true),
+            Type::Core(name) => (Some(name), false),
+            Type::Optional(name) => (Some(name), true),
+            Type::Function(signature) => (Some(signature.type_name()), false),
+            Type::Unknown | Type::Never => (None, false),
+        }
+    }
+
+    // Returns the type that results from a value that could have either of the two types
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (Type::Never, other) | (other, Type::Never) => other,
+            (Type::Unknown, _) | (_, Type::Unknown) => Type::Unknown,
+            (Type::Function(a), Type::Function(b)) if Rc::ptr_eq(&a, &b) => Type::Function(a),
+            (a, b) => {
+                let (a_name, a_null) = a.parts();
+                let (b_name, b_null) = b.parts();
+                let name = match (a_name, b_name) {
+                    (Some(a_name), Some(b_name)) if a_name != b_name => return Type::Unknown,
+                    (Some(name), _) | (_, Some(name)) => name,
+                    (None, None) => return Type::Core("Null"),
+                };
+                if a_null || b_null {
+                    Type::Optional(name)
+                } else {
+                    Type::Core(name)
+                }
+            }
+        }
+    }
+
+    // Returns the type's name if the type is known
+    fn name(&self) -> Option<String> {
+        match self.parts() {
+            (Some(name), false) => Some(name.into()),
+            (Some(name), true) => Some(format!("{name}?")),
+            (None, true) => Some("Null".into()),
+            (None, false) => None,
+        }
+    }
+}
+
+impl PartialEq for Type<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Type::Unknown, Type::Unknown) | (Type::Never, Type::Never) => true,
+            (Type::Core(a), Type::Core(b)) | (Type::Optional(a), Type::Optional(b)) => a == b,
+            (Type::Function(a), Type::Function(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+// A type hint, e.g. `Number` or `String?`
+#[derive(Clone, Copy, Debug)]
+struct Hint<'a> {
+    name: &'a str,
+    allow_null: bool,
+}
+
+impl<'a> Hint<'a> {
+    // Returns true if a value with the given type name will pass the type check
+    fn accepts(&self, name: &str) -> bool {
+        if self.allow_null && name == "Null" {
+            return true;
+        }
+
+        match self.name {
+            "Any" => true,
+            "Callable" => name == "Function",
+            "Indexable" => matches!(name, "List" | "Map" | "String" | "Tuple"),
+            "Iterable" => matches!(
+                name,
+                "Iterator" | "List" | "Map" | "Range" | "String" | "Tuple"
+            ),
+            expected => expected == name,
+        }
+    }
+
+    // Returns the name of the found type if it will definitely fail the type check
+    fn mismatch(&self, found: &Type) -> Option<String> {
+        let (name, nullable) = found.parts();
+        let accepted = match (name, nullable) {
+            (Some(name), false) => self.accepts(name),
+            // Optional values are only a definite mismatch if neither possibility is accepted
+            (Some(name), true) => self.accepts(name) || self.accepts("Null"),
+            (None, true) => self.accepts("Null"),
+            (None, false) => true,
+        };
+        if accepted { None } else { found.name() }
+    }
+
+    // Returns the type of a value that has passed the type check
+    fn narrowed_type(&self) -> Type<'a> {
+        match core_type(self.name) {
+            Some(name) if self.allow_null => Type::Optional(name),
+            Some(name) => Type::Core(name),
+            None => Type::Unknown,
+        }
+    }
+
+    fn expected(&self) -> String {
+        if self.allow_null {
+            format!("{}?", self.name)
+        } else {
+            self.name.into()
+        }
+    }
+}
+
+// The signature of a function
+#[derive(Debug)]
+struct Signature<'a> {
+    // Additional arguments that don't correspond to an argument in the signature are ignored
+    args: Vec<Arg<'a>>,
+    // The type of the value that's returned when the function is called
+    output: Type<'a>,
+    is_generator: bool,
+}
+
+impl Signature<'_> {
+    fn type_name(&self) -> &'static str {
+        if self.is_generator {
+            "Generator"
+        } else {
+            "Function"
+        }
+    }
+}
+
+// A function argument
+#[derive(Clone, Copy, Debug)]
+struct Arg<'a> {
+    hint: Option<Hint<'a>>,
+    // True if the argument has a default value
+    optional: bool,
+}
+
+// The local values that are assigned in a function, mapped to their types
+type Locals<'a> = FxHashMap<&'a str, Type<'a>>;
+
+// Returns the result of merging the local values from two branches of execution
+fn merge_locals<'a>(a: &Locals<'a>, b: &Locals<'a>) -> Locals<'a> {
+    let names: FxHashSet<&str> = a.keys().chain(b.keys()).copied().collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let merged = match (a.get(name), b.get(name)) {
+                (Some(a), Some(b)) => a.clone().join(b.clone()),
+                // Values that are only assigned in one branch could come from an outer scope
+                _ => Type::Unknown,
+            };
+            (name, merged)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Frame<'a> {
+    locals: Locals<'a>,
+    // The function's output type hint
+    output: Option<Hint<'a>>,
+    is_generator: bool,
+    // The combined type of the function's return values
+    returned: Option<Type<'a>>,
+}
+
+// The value produced by each node in a chain
+enum ChainValue<'a> {
+    Value(Type<'a>),
+    // A core library module, e.g. `string`
+    Module(&'static str),
+    // A core library function, along with the instance when called as a method
+    CoreFunction {
+        path: &'static str,
+        instance: Option<Type<'a>>,
+    },
+}
+
+struct TypeChecker<'a> {
+    ast: &'a Ast,
+    // The names of all values that are exported by the script
+    exports: FxHashSet<&'a str>,
+    // The stack of function frames, with the script's top level at the bottom
+    frames: Vec<Frame<'a>>,
+    // Errors are suppressed while analyzing loops
+    suppress_errors: usize,
+    errors: Vec<TypeCheckError>,
+}
+
+impl<'a> TypeChecker<'a> {
+    // Checks a node and returns its type
+    fn check(&mut self, index: AstIndex) -> Type<'a> {
+        let ast = self.ast;
+
+        match &ast.node(index).node {
+            Node::Null => Type::Core("Null"),
+            Node::BoolTrue | Node::BoolFalse => Type::Core("Bool"),
+            Node::SmallInt(_) | Node::Int(_) | Node::Float(_) => Type::Core("Number"),
+            Node::Str(string) => {
+                self.check_string(string);
+                Type::Core("String")
+            }
+            Node::Nested(nested) => self.check(*nested),
+            Node::Id(name, _) => self.local(ast.constants().get_str(*name)),
+            Node::List(elements) => {
+                self.check_all(elements);
+                Type::Core("List")
+            }
+            Node::Tuple { elements, .. } => {
+                self.check_all(elements);
+                Type::Core("Tuple")
+            }
+            Node::TempTuple(elements) => {
+                self.check_all(elements);
+                Type::Unknown
+            }
+            Node::Range { start, end, .. } => {
+                self.check(*start);
+                self.check(*end);
+                Type::Core("Range")
+            }
+            Node::RangeFrom { start: value } | Node::RangeTo { end: value, .. } => {
+                self.check(*value);
+                Type::Core("Range")
+            }
+            Node::Map { entries, .. } => self.check_map(entries),
+            Node::MainBlock { body, .. } | Node::Block(body) => self.check_block(body),
+            Node::Function(function) => self.check_function(function, None),
+            Node::Import { items, .. } => {
+                for item in items {
+                    if let Node::Str(string) = &ast.node(item.item).node {
+                        self.check_string(string);
+                    }
+                    let id = item.name.unwrap_or(item.item);
+                    if let Node::Id(name, _) = &ast.node(id).node {
+                        self.assign(ast.constants().get_str(*name), Type::Unknown);
+                    }
+                }
+                Type::Unknown
+            }
+            Node::Export(exported) => {
+                match &ast.node(*exported).node {
+                    Node::Map { entries, .. } => {
+                        for entry in entries {
+                            if let Node::MapEntry(_, value) = &ast.node(*entry).node {
+                                self.check(*value);
+                            }
+                        }
+                    }
+                    // Exported values aren't assigned locally
+                    Node::Assign {
+                        target, expression, ..
+                    }
+                    | Node::Const { target, expression } => {
+                        let value = self.check(*expression);
+                        self.check_target_hint(*target, &value, *expression);
+                    }
+                    _ => {
+                        self.check(*exported);
+                    }
+                }
+                Type::Unknown
+            }
+            Node::Assign {
+                target, expression, ..
+            }
+            | Node::Const { target, expression } => self.check_assign(*target, *expression),
+            Node::Record { id, fields, .. } => {
+                for field in fields {
+                    if let Node::Assign {
+                        target, expression, ..
+                    } = &ast.node(*field).node
+                    {
+                        let value = self.check(*expression);
+                        self.check_target_hint(*target, &value, *expression);
+                    }
+                }
+                self.bind(*id, Type::Unknown);
+                Type::Unknown
+            }
+            Node::Enum { id, .. } => {
+                self.bind(*id, Type::Unknown);
+                Type::Unknown
+            }
+            Node::MultiAssign {
+                targets,
+                expression,
+                ..
+            } => {
+                let values = match &ast.node(*expression).node {
+                    Node::TempTuple(elements) if elements.len() == targets.len() => elements
+                        .iter()
+                        .map(|element| self.check(*element))
+                        .collect(),
+                    _ => {
+                        self.check(*expression);
+                        vec![Type::Unknown; targets.len()]
+                    }
+                };
+                for (target, value) in targets.iter().zip(values) {
+                    self.check_target_hint(*target, &value, *expression);
+                    self.bind(*target, value);
+                }
+                Type::Unknown
+            }
+            Node::UnaryOp { op, value } => match (op, self.check(*value)) {
+                (AstUnaryOp::Negate, Type::Core("Number")) => Type::Core("Number"),
+                (AstUnaryOp::Not, Type::Core("Bool")) => Type::Core("Bool"),
+                _ => Type::Unknown,
+            },
+            Node::BinaryOp { op, lhs, rhs } => self.check_binary_op(*op, *lhs, *rhs),
+            Node::If(if_expression) => {
+                self.check(if_expression.condition);
+                let start = self.locals().clone();
+
+                let mut result = self.check(if_expression.then_node);
+                let mut merged = self.locals().clone();
+
+                for (condition, block) in &if_expression.else_if_blocks {
+                    *self.locals_mut() = start.clone();
+                    self.check(*condition);
+                    result = result.join(self.check(*block));
+                    merged = merge_locals(&merged, self.locals());
+                }
+
+                if let Some(else_node) = if_expression.else_node {
+                    *self.locals_mut() = start;
+                    result = result.join(self.check(else_node));
+                    merged = merge_locals(&merged, self.locals());
+                } else {
+                    result = result.join(Type::Core("Null"));
+                    merged = merge_locals(&merged, &start);
+                }
+
+                *self.locals_mut() = merged;
+                result
+            }
+            Node::Match { expression, arms } => {
+                let value = self.check(*expression);
+                self.check_arms(arms, |checker, arm| {
+                    let Node::MatchArm {
+                        patterns,
+                        condition,
+                        expression,
+                    } = &ast.node(arm).node
+                    else {
+                        return (Type::Unknown, true);
+                    };
+                    for pattern in patterns {
+                        checker.bind_pattern(*pattern, &value);
+                    }
+                    if let Some(condition) = condition {
+                        checker.check(*condition);
+                    }
+                    (checker.check(*expression), patterns.is_empty())
+                })
+            }
+            Node::Switch(arms) => self.check_arms(arms, |checker, arm| {
+                let Node::SwitchArm {
+                    condition,
+                    expression,
+                } = &ast.node(arm).node
+                else {
+                    return (Type::Unknown, true);
+                };
+                if let Some(condition) = condition {
+                    checker.check(*condition);
+                }
+                (checker.check(*expression), condition.is_none())
+            }),
+            Node::For(for_loop) => {
+                let iterable = self.check(for_loop.iterable);
+                let element = match (iterable, for_loop.args.len()) {
+                    (Type::Core("Range"), 1) => Type::Core("Number"),
+                    (Type::Core("String"), 1) => Type::Core("String"),
+                    _ => Type::Unknown,
+                };
+                self.check_loop(|checker| {
+                    for arg in &for_loop.args {
+                        checker.check_target_hint(*arg, &element, *arg);
+                        checker.bind(*arg, element.clone());
+                    }
+                    checker.check(for_loop.body);
+                });
+                Type::Unknown
+            }
+            Node::Loop { body } => {
+                self.check_loop(|checker| {
+                    checker.check(*body);
+                });
+                Type::Unknown
+            }
+            Node::While { condition, body } | Node::Until { condition, body } => {
+                self.check_loop(|checker| {
+                    checker.check(*condition);
+                    checker.check(*body);
+                });
+                Type::Unknown
+            }
+            Node::Try(AstTry {
+                try_block,
+                catch_blocks,
+                finally_block,
+            }) => {
+                let start = self.locals().clone();
+                let mut result = self.check(*try_block);
+                // Catch blocks can be reached from any point in the try block
+                let catch_start = merge_locals(&start, self.locals());
+                let mut merged = self.locals().clone();
+
+                for catch in catch_blocks {
+                    *self.locals_mut() = catch_start.clone();
+                    self.bind_pattern(catch.arg, &Type::Unknown);
+                    result = result.join(self.check(catch.block));
+                    merged = merge_locals(&merged, self.locals());
+                }
+
+                *self.locals_mut() = merged;
+                if let Some(finally_block) = finally_block {
+                    self.check(*finally_block);
+                }
+                result
+            }
+            Node::Return(value) => {
+                let (value_type, span) = match value {
+                    Some(value) => (self.check(*value), *value),
+                    None => (Type::Core("Null"), index),
+                };
+                if !self.frame().is_generator {
+                    self.check_output(&value_type, span);
+                }
+                let frame = self.frame_mut();
+                frame.returned = Some(match frame.returned.take() {
+                    Some(returned) => returned.join(value_type),
+                    None => value_type,
+                });
+                Type::Never
+            }
+            Node::Yield(value) => {
+                let value_type = self.check(*value);
+                self.check_output(&value_type, *value);
+                Type::Unknown
+            }
+            Node::Throw(value) | Node::Break(Some(value)) => {
+                self.check(*value);
+                Type::Never
+            }
+            Node::Break(None) | Node::Continue => Type::Never,
+            Node::Debug { expression, .. } => self.check(*expression),
+            Node::Chain(_) => self.check_chain(index),
+            Node::Await(value) | Node::PackedExpression(value) => {
+                self.check(*value);
+                Type::Unknown
+            }
+            Node::Meta(..)
+            | Node::MapEntry(..)
+            | Node::MapPattern { .. }
+            | Node::MapKeyRebind { .. }
+            | Node::EnumVariant { .. }
+            | Node::EnumPattern { .. }
+            | Node::RangeFull
+            | Node::Self_
+            | Node::FunctionArgs { .. }
+            | Node::MatchArm { .. }
+            | Node::SwitchArm { .. }
+            | Node::Ignored(..)
+            | Node::PackedId(_)
+            | Node::Type { .. } => Type::Unknown,
+        }
+    }
+
+    fn check_all(&mut self, nodes: &[AstIndex]) {
+        for node in nodes {
+            self.check(*node);
+        }
+    }
+
+    fn check_string(&mut self, string: &AstString) {
+        if let StringContents::Interpolated(nodes) = &string.contents {
+            for node in nodes {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.check(*expression);
+                }
+            }
+        }
+    }
+
+    fn check_block(&mut self, body: &[AstIndex]) -> Type<'a> {
+        let mut result = Type::Core("Null");
+        for expression in body {
+            let expression_type = self.check(*expression);
+            if result != Type::Never {
+                result = expression_type;
+            }
+        }
+        result
+    }
+
+    fn check_map(&mut self, entries: &[AstIndex]) -> Type<'a> {
+        let ast = self.ast;
+        let mut has_meta_entry = false;
+
+        for entry in entries {
+            match &ast.node(*entry).node {
+                Node::MapEntry(key, value) => {
+                    match &ast.node(*key).node {
+                        Node::Meta(..) => has_meta_entry = true,
+                        Node::Str(string) => self.check_string(string),
+                        _ => {}
+                    }
+                    self.check(*value);
+                }
+                _ => {
+                    self.check(*entry);
+                }
+            }
+        }
+
+        // Maps with meta entries can define their own types
+        if has_meta_entry {
+            Type::Unknown
+        } else {
+            Type::Core("Map")
+        }
+    }
+
+    fn check_assign(&mut self, target: AstIndex, expression: AstIndex) -> Type<'a> {
+        let ast = self.ast;
+
+        if let (Node::Id(name, _), Node::Function(function)) =
+            (&ast.node(target).node, &ast.node(expression).node)
+        {
+            // Functions can call themselves, so the function's signature is assigned first
+            let value = self.check_function(function, Some(ast.constants().get_str(*name)));
+            self.check_target_hint(target, &value, expression);
+            self.bind(target, value.clone());
+            return value;
+        }
+
+        let value = self.check(expression);
+        self.check_target_hint(target, &value, expression);
+        self.bind(target, value.clone());
+        value
+    }
+
+    // Checks the value that's assigned to a target against the target's type hint
+    fn check_target_hint(&mut self, target: AstIndex, value: &Type, span: AstIndex) {
+        if let Node::Id(_, Some(hint)) | Node::Ignored(_, Some(hint)) = &self.ast.node(target).node
+        {
+            let hint = self.hint(*hint);
+            self.check_hint(hint, value, span);
+        }
+    }
+
+    // Assigns a value to a target
+    fn bind(&mut self, target: AstIndex, value: Type<'a>) {
+        let ast = self.ast;
+
+        match &ast.node(target).node {
+            Node::Id(name, hint) => {
+                // If the value's type isn't known, then it can be narrowed by the type hint
+                let value = match (value, hint) {
+                    (Type::Unknown, Some(hint)) => self.hint(*hint).narrowed_type(),
+                    (value, _) => value,
+                };
+                self.assign(ast.constants().get_str(*name), value);
+            }
+            Node::Nested(nested) => self.bind(*nested, value),
+            Node::Chain(_) => {
+                self.check(target);
+            }
+            _ => self.bind_pattern(target, &Type::Unknown),
+        }
+    }
+
+    // Assigns the values in a match pattern, or in an unpacked assignment
+    fn bind_pattern(&mut self, pattern: AstIndex, value: &Type<'a>) {
+        let ast = self.ast;
+
+        match &ast.node(pattern).node {
+            Node::Id(name, hint) => {
+                // Values that match a type hint have the hint's type
+                let value = match hint.map(|hint| self.hint(hint).narrowed_type()) {
+                    Some(Type::Unknown) | None => value.clone(),
+                    Some(narrowed) => narrowed,
+                };
+                self.assign(ast.constants().get_str(*name), value);
+            }
+            Node::PackedId(Some(name)) => {
+                self.assign(ast.constants().get_str(*name), Type::Unknown);
+            }
+            Node::Nested(nested) => self.bind_pattern(*nested, value),
+            Node::List(elements)
+            | Node::TempTuple(elements)
+            | Node::Tuple { elements, .. }
+            | Node::MapPattern {
+                entries: elements, ..
+            } => {
+                for element in elements {
+                    self.bind_pattern(*element, &Type::Unknown);
+                }
+            }
+            Node::MapKeyRebind { id_or_ignored, .. } => {
+                self.bind_pattern(*id_or_ignored, &Type::Unknown)
+            }
+            Node::EnumPattern { payload, .. } => {
+                for element in payload {
+                    self.bind_pattern(*element, &Type::Unknown);
+                }
+            }
+            Node::Ignored(..) | Node::PackedId(None) | Node::Str(_) | Node::Meta(..) => {}
+            // Literals in match patterns
+            _ => {
+                self.check(pattern);
+            }
+        }
+    }
+
+    fn check_function(&mut self, function: &'a Function, name: Option<&'a str>) -> Type<'a> {
+        let ast = self.ast;
+
+        let Node::FunctionArgs {
+            args, output_type, ..
+        } = &ast.node(function.args).node
+        else {
+            return Type::Unknown;
+        };
+
+        let output_hint = output_type.map(|output| self.hint(output));
+        let signature_args = args
+            .iter()
+            .filter_map(|arg| match &ast.node(*arg).node {
+                Node::Id(_, hint) | Node::Ignored(_, hint) => Some(Arg {
+                    hint: hint.map(|hint| self.hint(hint)),
+                    optional: false,
+                }),
+                Node::MapPattern { type_hint, .. } => Some(Arg {
+                    hint: type_hint.map(|hint| self.hint(hint)),
+                    optional: false,
+                }),
+                Node::Assign { target, .. } => Some(Arg {
+                    hint: match &ast.node(*target).node {
+                        Node::Id(_, Some(hint)) | Node::Ignored(_, Some(hint)) => {
+                            Some(self.hint(*hint))
+                        }
+                        _ => None,
+                    },
+                    optional: true,
+                }),
+                Node::PackedId(_) => None,
+                _ => Some(Arg {
+                    hint: None,
+                    optional: false,
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        let make_signature = |output| {
+            Rc::new(Signature {
+                args: signature_args.clone(),
+                output,
+                is_generator: function.is_generator,
+            })
+        };
+
+        let declared_output = if function.is_generator {
+            Type::Core("Iterator")
+        } else {
+            output_hint.map_or(Type::Unknown, |hint| hint.narrowed_type())
+        };
+
+        self.frames.push(Frame {
+            output: output_hint,
+            is_generator: function.is_generator,
+            ..Default::default()
+        });
+
+        if let Some(name) = name {
+            self.assign(
+                name,
+                Type::Function(make_signature(declared_output.clone())),
+            );
+        }
+
+        for arg in args {
+            match &ast.node(*arg).node {
+                Node::Id(..) | Node::Ignored(..) => self.bind(*arg, Type::Unknown),
+                Node::PackedId(Some(name)) => {
+                    self.assign(ast.constants().get_str(*name), Type::Core("Tuple"))
+                }
+                Node::Assign {
+                    target, expression, ..
+                } => {
+                    let default = self.check(*expression);
+                    self.check_target_hint(*target, &default, *expression);
+                    self.bind(*target, Type::Unknown);
+                }
+                _ => self.bind_pattern(*arg, &Type::Unknown),
+            }
+        }
+
+        let body = self.check(function.body);
+        if !function.is_generator && body != Type::Never {
+            let last_expression = match &ast.node(function.body).node {
+                Node::Block(expressions) => expressions.last().copied(),
+                _ => Some(function.body),
+            };
+            self.check_output(&body, last_expression.unwrap_or(function.body));
+        }
+
+        let frame = self.frames.pop().unwrap_or_default();
+
+        let output = match declared_output {
+            Type::Unknown => match frame.returned {
+                Some(returned) => returned.join(body),
+                None => body,
+            },
+            declared => declared,
+        };
+
+        Type::Function(make_signature(output))
+    }
+
+    fn check_output(&mut self, value: &Type, span: AstIndex) {
+        if let Some(output) = self.frame().output {
+            self.check_hint(output, value, span);
+        }
+    }
+
+    fn check_binary_op(&mut self, op: AstBinaryOp, lhs: AstIndex, rhs: AstIndex) -> Type<'a> {
+        use AstBinaryOp::*;
+
+        let lhs_type = self.check(lhs);
+
+        // The right-hand side of `and` and `or` might not be evaluated
+        let rhs_type = if matches!(op, And | Or) {
+            let start = self.locals().clone();
+            let rhs_type = self.check(rhs);
+            *self.locals_mut() = merge_locals(&start, self.locals());
+            rhs_type
+        } else {
+            self.check(rhs)
+        };
+
+        let result = match (op, &lhs_type, &rhs_type) {
+            (
+                Add | Subtract | Multiply | Divide | Remainder | Power | AddAssign | SubtractAssign
+                | MultiplyAssign | DivideAssign | RemainderAssign | PowerAssign,
+                Type::Core("Number"),
+                Type::Core("Number"),
+            ) => Type::Core("Number"),
+            (Add | AddAssign, Type::Core("String"), Type::Core("String")) => Type::Core("String"),
+            (And | Or, Type::Core("Bool"), Type::Core("Bool")) => Type::Core("Bool"),
+            (Equal | NotEqual | Less | LessOrEqual | Greater | GreaterOrEqual, _, _)
+                if lhs_type.name().is_some() && rhs_type.name().is_some() =>
+            {
+                Type::Core("Bool")
+            }
+            _ => Type::Unknown,
+        };
+
+        if matches!(
+            op,
+            AddAssign
+                | SubtractAssign
+                | MultiplyAssign
+                | DivideAssign
+                | RemainderAssign
+                | PowerAssign
+        ) && let Node::Id(name, _) = &self.ast.node(lhs).node
+        {
+            self.assign(self.ast.constants().get_str(*name), result.clone());
+        }
+
+        result
+    }
+
+    fn check_chain(&mut self, mut index: AstIndex) -> Type<'a> {
+        let ast = self.ast;
+        let mut value = ChainValue::Value(Type::Unknown);
+        let mut null_checked = false;
+
+        while let Node::Chain((chain_node, next)) = &ast.node(index).node {
+            value = match (chain_node, value) {
+                (ChainNode::Root(root), _) => self.check_chain_root(*root),
+                (ChainNode::Id(id), ChainValue::Module(module)) => {
+                    let path = format!("{module}.{}", ast.constants().get_str(*id));
+                    match core_function(&path) {
+                        Some((path, ..)) => ChainValue::CoreFunction {
+                            path,
+                            instance: None,
+                        },
+                        None => ChainValue::Value(Type::Unknown),
+                    }
+                }
+                (ChainNode::Id(id), ChainValue::Value(instance)) => {
+                    let module = match instance {
+                        Type::Core("List") => Some("list"),
+                        Type::Core("Number") => Some("number"),
+                        Type::Core("String") => Some("string"),
+                        Type::Core("Tuple") => Some("tuple"),
+                        _ => None,
+                    };
+                    let path =
+                        module.map(|module| format!("{module}.{}", ast.constants().get_str(*id)));
+                    match path.as_deref().and_then(core_function) {
+                        Some((path, ..)) => ChainValue::CoreFunction {
+                            path,
+                            instance: Some(instance),
+                        },
+                        None => ChainValue::Value(Type::Unknown),
+                    }
+                }
+                (ChainNode::Call { args, .. }, value) => self.check_call(value, args, index),
+                (ChainNode::Str(string), _) => {
+                    self.check_string(string);
+                    ChainValue::Value(Type::Unknown)
+                }
+                (ChainNode::Index(expression), _) => {
+                    self.check(*expression);
+                    ChainValue::Value(Type::Unknown)
+                }
+                (ChainNode::NullCheck, ChainValue::Value(Type::Optional(name))) => {
+                    null_checked = true;
+                    ChainValue::Value(Type::Core(name))
+                }
+                (ChainNode::NullCheck, value) => {
+                    null_checked = true;
+                    value
+                }
+                (ChainNode::Id(_), _) => ChainValue::Value(Type::Unknown),
+            };
+
+            match next {
+                Some(next) => index = *next,
+                None => break,
+            }
+        }
+
+        let result = match value {
+            ChainValue::Value(result) => result,
+            _ => Type::Unknown,
+        };
+
+        // A failed null check short-circuits the chain with a null result
+        if null_checked {
+            result.join(Type::Core("Null"))
+        } else {
+            result
+        }
+    }
+
+    fn check_chain_root(&mut self, root: AstIndex) -> ChainValue<'a> {
+        let ast = self.ast;
+
+        if let Node::Id(id, _) = &ast.node(root).node {
+            let name = ast.constants().get_str(*id);
+            if self.find_local(name).is_none() && !self.exports.contains(name) {
+                if let Some(module) = CORE_LIB_MODULES.iter().find(|module| **module == name) {
+                    return ChainValue::Module(module);
+                }
+                if let Some((_, path)) = PRELUDE_FUNCTIONS.iter().find(|(id, _)| *id == name) {
+                    return ChainValue::CoreFunction {
+                        path,
+                        instance: None,
+                    };
+                }
+            }
+        }
+
+        ChainValue::Value(self.check(root))
+    }
+
+    fn check_call(
+        &mut self,
+        function: ChainValue<'a>,
+        args: &[AstIndex],
+        call_node: AstIndex,
+    ) -> ChainValue<'a> {
+        let ast = self.ast;
+
+        let mut arg_types = args
+            .iter()
+            .map(|arg| (self.check(*arg), *arg))
+            .collect::<Vec<_>>();
+
+        // Unpacked arguments prevent the arguments from being matched with the signature
+        if args
+            .iter()
+            .any(|arg| matches!(ast.node(*arg).node, Node::PackedExpression(_)))
+        {
+            return ChainValue::Value(Type::Unknown);
+        }
+
+        let result = match function {
+            ChainValue::CoreFunction { path, instance } => {
+                let Some((_, expected_args, output)) = core_function(path) else {
+                    return ChainValue::Value(Type::Unknown);
+                };
+
+                // Instances are checked as the first argument
+                let instance_offset = instance.is_some() as usize;
+                if let Some(instance) = instance {
+                    arg_types.insert(0, (instance, call_node));
+                }
+
+                for (i, (expected, (found, span))) in
+                    expected_args.iter().zip(arg_types.iter()).enumerate()
+                {
+                    if i < instance_offset {
+                        continue;
+                    }
+                    let hint = Hint {
+                        name: expected,
+                        allow_null: false,
+                    };
+                    self.check_hint(hint, found, *span);
+                }
+
+                match output.strip_suffix('?') {
+                    Some(output) => core_type(output).map_or(Type::Unknown, Type::Optional),
+                    None => core_type(output).map_or(Type::Unknown, Type::Core),
+                }
+            }
+            ChainValue::Value(Type::Function(signature)) => {
+                for (i, arg) in signature.args.iter().enumerate() {
+                    let Some(hint) = arg.hint else {
+                        continue;
+                    };
+                    match arg_types.get(i) {
+                        Some((found, span)) => self.check_hint(hint, found, *span),
+                        // Missing arguments are set to null
+                        None if !arg.optional => {
+                            self.check_hint(hint, &Type::Core("Null"), call_node)
+                        }
+                        None => {}
+                    }
+                }
+                signature.output.clone()
+            }
+            _ => Type::Unknown,
+        };
+
+        ChainValue::Value(result)
+    }
+
+    // Checks the arms of a match or switch expression
+    //
+    // The provided function checks an arm, returning the arm's type and whether or not it's an
+    // `else` arm.
+    fn check_arms(
+        &mut self,
+        arms: &[AstIndex],
+        check_arm: impl Fn(&mut Self, AstIndex) -> (Type<'a>, bool),
+    ) -> Type<'a> {
+        let start = self.locals().clone();
+        let mut result = Type::Never;
+        let mut merged: Option<Locals> = None;
+        let mut has_else = false;
+
+        for arm in arms {
+            *self.locals_mut() = start.clone();
+            let (arm_type, is_else) = check_arm(self, *arm);
+            result = result.join(arm_type);
+            has_else |= is_else;
+            merged = Some(match merged {
+                Some(merged) => merge_locals(&merged, self.locals()),
+                None => self.locals().clone(),
+            });
+        }
+
+        // If no arm matches, then the result is null
+        if !has_else {
+            result = result.join(Type::Core("Null"));
+            merged = Some(merge_locals(merged.as_ref().unwrap_or(&start), &start));
+        }
+
+        *self.locals_mut() = merged.unwrap_or(start);
+        result
+    }
+
+    // Checks the body of a loop
+    //
+    // Values that are assigned in the loop are visible in following iterations, so the loop is
+    // checked repeatedly until the types of the local values stop changing, and then checked
+    // again with errors enabled.
+    fn check_loop(&mut self, check_body: impl Fn(&mut Self)) {
+        let mut start = self.locals().clone();
+
+        self.suppress_errors += 1;
+        loop {
+            check_body(self);
+            let merged = merge_locals(&start, self.locals());
+            if merged == start {
+                break;
+            }
+            start = merged;
+            *self.locals_mut() = start.clone();
+        }
+        self.suppress_errors -= 1;
+
+        *self.locals_mut() = start.clone();
+        check_body(self);
+        *self.locals_mut() = merge_locals(&start, self.locals());
+    }
+
+    fn check_hint(&mut self, hint: Hint, found: &Type, span: AstIndex) {
+        if let Some(found) = hint.mismatch(found)
+            && self.suppress_errors == 0
+        {
+            self.errors.push(TypeCheckError {
+                expected: hint.expected(),
+                found,
+                span: *self.ast.span(self.ast.node(span).span),
+            });
+        }
+    }
+
+    fn hint(&self, index: AstIndex) -> Hint<'a> {
+        match &self.ast.node(index).node {
+            Node::Type {
+                type_index,
+                allow_null,
+            } => Hint {
+                name: self.ast.constants().get_str(*type_index),
+                allow_null: *allow_null,
+            },
+            _ => Hint {
+                name: "Any",
+                allow_null: false,
+            },
+        }
+    }
+
+    fn assign(&mut self, name: &'a str, value: Type<'a>) {
+        self.locals_mut().insert(name, value);
+    }
+
+    fn find_local(&self, name: &str) -> Option<&Type<'a>> {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.locals.get(name))
+    }
+
+    fn local(&self, name: &str) -> Type<'a> {
+        self.find_local(name).cloned().unwrap_or(Type::Unknown)
+    }
+
+    fn frame(&self) -> &Frame<'a> {
+        self.frames.last().expect("missing frame")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'a> {
+        self.frames.last_mut().expect("missing frame")
+    }
+
+    fn locals(&self) -> &Locals<'a> {
+        &self.frame().locals
+    }
+
+    fn locals_mut(&mut self) -> &mut Locals<'a> {
+        &mut self.frame_mut().locals
+    }
+}
+
+// Returns the signature of a core library function
+fn core_function(path: &str) -> Option<(&'static str, &'static [&'static str], &'static str)> {
+    CORE_LIB_FUNCTIONS
+        .iter()
+        .find(|(function, ..)| *function == path)
+        .copied()
+}
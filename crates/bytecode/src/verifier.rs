@@ -0,0 +1,740 @@
+use crate::{Chunk, Instruction, InstructionReader, Op};
+use koto_memory::Ptr;
+use koto_parser::{Constant, ConstantIndex};
+use std::{collections::HashSet, fmt};
+use thiserror::Error;
+
+/// The kinds of errors that can be returned from [verify_chunk]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum VerifierErrorKind {
+    #[error("invalid opcode ({0})")]
+    InvalidOpcode(u8),
+    #[error("invalid instruction ({0})")]
+    InvalidInstruction(String),
+    #[error("expected the frame to start with a NewFrame instruction")]
+    MissingNewFrame,
+    #[error("unexpected NewFrame instruction")]
+    UnexpectedNewFrame,
+    #[error("the frame's final instruction continues past the end of the frame")]
+    UnterminatedFrame,
+    #[error("the instruction extends beyond the end of its frame (at {frame_end})")]
+    InstructionOutOfBounds { frame_end: u32 },
+    #[error("register {register} is out of bounds (the frame has {register_count} registers)")]
+    RegisterOutOfBounds { register: u32, register_count: u8 },
+    #[error("constant {index} is missing or isn't a {expected}")]
+    InvalidConstant {
+        index: ConstantIndex,
+        expected: &'static str,
+    },
+    #[error("invalid jump target ({target})")]
+    InvalidJumpTarget { target: i64 },
+    #[error("access cache {cache} is out of bounds (the chunk has {cache_count} access caches)")]
+    AccessCacheOutOfBounds { cache: u32, cache_count: u32 },
+    #[error(
+        "the chunk has {cache_count} access caches, but {used} are used by access instructions"
+    )]
+    AccessCacheCountMismatch { cache_count: u32, used: u32 },
+}
+
+/// An error returned by [verify_chunk]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub struct VerifierError {
+    /// The error
+    pub error: VerifierErrorKind,
+    /// The ip of the instruction that failed verification
+    pub ip: u32,
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at instruction {}", self.error, self.ip)
+    }
+}
+
+/// Checks that a chunk's bytecode is well-formed
+///
+/// The VM assumes that the bytecode it executes was produced by the [Compiler](crate::Compiler),
+/// so chunks that have been loaded from an untrusted source (e.g. via [Chunk::deserialize])
+/// should be verified before being executed.
+///
+/// The following checks are performed:
+///   - Each op is valid, and each instruction can be decoded.
+///   - Each frame (the chunk's main block and each function body) starts with a `NewFrame`
+///     instruction, its instructions stay within the frame's bounds, and its final instruction
+///     doesn't continue past the end of the frame.
+///   - Registers are within the range reserved by the frame's `NewFrame` instruction.
+///   - Constants refer to entries in the chunk's constant pool with the expected type.
+///   - Access instructions refer to cache slots within the chunk's access cache count,
+///     and the access cache count matches the number of access instructions.
+///   - Jumps land on the start of an instruction within the same frame.
+pub fn verify_chunk(chunk: &Ptr<Chunk>) -> Result<(), VerifierError> {
+    let mut verifier = Verifier {
+        chunk,
+        reader: InstructionReader::new(chunk.clone()),
+        access_cache_slots: 0,
+    };
+    verifier.verify_frame(chunk.bytes.len() as u32)?;
+
+    // The VM allocates the chunk's access caches up front, so the count needs to match the
+    // number of slots that are actually used.
+    if chunk.access_cache_count != verifier.access_cache_slots {
+        return verifier_error(
+            VerifierErrorKind::AccessCacheCountMismatch {
+                cache_count: chunk.access_cache_count,
+                used: verifier.access_cache_slots,
+            },
+            0,
+        );
+    }
+
+    Ok(())
+}
+
+struct Verifier<'a> {
+    chunk: &'a Ptr<Chunk>,
+    reader: InstructionReader,
+    // The number of access instructions that have been verified, each with its own cache slot
+    access_cache_slots: u32,
+}
+
+// The state of the frame that's currently being verified
+struct FrameState {
+    register_count: u8,
+    end: u32,
+    // The ips of each of the frame's instructions
+    instruction_ips: HashSet<u32>,
+    // Jump targets, along with the ips of the jump instructions
+    jumps: Vec<(i64, u32)>,
+}
+
+impl Verifier<'_> {
+    // Verifies the frame starting at the reader's current ip, and ending at the given ip
+    fn verify_frame(&mut self, end: u32) -> Result<(), VerifierError> {
+        let start = self.ip();
+
+        let register_count = match self.next_instruction(end)? {
+            Instruction::NewFrame { register_count } => register_count,
+            _ => return verifier_error(VerifierErrorKind::MissingNewFrame, start),
+        };
+
+        let mut frame = FrameState {
+            register_count,
+            end,
+            instruction_ips: HashSet::from([start]),
+            jumps: Vec::new(),
+        };
+
+        let mut last_instruction = (start, None);
+        while self.ip() < end {
+            let ip = self.ip();
+            frame.instruction_ips.insert(ip);
+            let instruction = self.next_instruction(end)?;
+            self.verify_instruction(&instruction, ip, &mut frame)?;
+            last_instruction = (ip, Some(instruction));
+        }
+
+        // Execution must not run off the end of the frame into the following bytecode
+        use Instruction::{Jump, JumpBack, Return, Throw};
+        match last_instruction {
+            (_, Some(Return { .. } | Throw { .. } | Jump { .. } | JumpBack { .. })) => {}
+            (ip, _) => return verifier_error(VerifierErrorKind::UnterminatedFrame, ip),
+        }
+
+        for (target, ip) in frame.jumps {
+            let valid =
+                u32::try_from(target).is_ok_and(|target| frame.instruction_ips.contains(&target));
+            if !valid {
+                return verifier_error(VerifierErrorKind::InvalidJumpTarget { target }, ip);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_instruction(
+        &mut self,
+        instruction: &Instruction,
+        ip: u32,
+        frame: &mut FrameState,
+    ) -> Result<(), VerifierError> {
+        use Instruction::*;
+
+        let next_ip = self.ip() as i64;
+        let register_count = frame.register_count;
+
+        let check_registers = |registers: &[u8]| {
+            for register in registers {
+                check_register_range(*register, 1, ip, register_count)?;
+            }
+            Ok(())
+        };
+
+        match instruction {
+            Error { message } => {
+                return verifier_error(VerifierErrorKind::InvalidInstruction(message.clone()), ip);
+            }
+            NewFrame { .. } => {
+                return verifier_error(VerifierErrorKind::UnexpectedNewFrame, ip);
+            }
+            SetNull { register }
+            | SetBool { register, .. }
+            | SetNumber { register, .. }
+            | ExportEntry { entry: register }
+            | Import { register }
+            | ImportAll { register }
+            | MakeMap { register, .. }
+            | SequencePush { value: register }
+            | SequenceToList { register }
+            | SequenceToTuple { register }
+            | RangeFull { register }
+            | Return { register }
+            | Yield { register }
+            | Throw { register }
+            | CheckSizeEqual { register, .. }
+            | CheckSizeMin { register, .. }
+            | AssertType {
+                value: register, ..
+            }
+            | StringPush {
+                value: register, ..
+            }
+            | StringFinish { register }
+            | MetaExport {
+                value: register, ..
+            }
+            | TryStart {
+                arg_register: register,
+                ..
+            } => check_registers(&[*register])?,
+            Copy {
+                target: a,
+                source: b,
+            }
+            | ExportValue { key: a, value: b }
+            | TempTupleToTuple {
+                register: a,
+                source: b,
+            }
+            | RangeTo {
+                register: a,
+                end: b,
+            }
+            | RangeToInclusive {
+                register: a,
+                end: b,
+            }
+            | RangeFrom {
+                register: a,
+                start: b,
+            }
+            | MakeIterator {
+                register: a,
+                iterable: b,
+            }
+            | Capture {
+                function: a,
+                source: b,
+                ..
+            }
+            | RecordFieldDefault {
+                record: a,
+                value: b,
+            }
+            | Negate {
+                register: a,
+                value: b,
+            }
+            | Not {
+                register: a,
+                value: b,
+            }
+            | AddAssign { lhs: a, rhs: b }
+            | SubtractAssign { lhs: a, rhs: b }
+            | MultiplyAssign { lhs: a, rhs: b }
+            | DivideAssign { lhs: a, rhs: b }
+            | RemainderAssign { lhs: a, rhs: b }
+            | PowerAssign { lhs: a, rhs: b }
+            | Size {
+                register: a,
+                value: b,
+            }
+            | Await {
+                register: a,
+                value: b,
+            }
+            | TempIndex {
+                register: a,
+                value: b,
+                ..
+            }
+            | SliceFrom {
+                register: a,
+                value: b,
+                ..
+            }
+            | SliceTo {
+                register: a,
+                value: b,
+                ..
+            }
+            | MetaInsert {
+                register: a,
+                value: b,
+                ..
+            }
+            | MetaExportNamed {
+                name: a, value: b, ..
+            }
+            | AddU8 {
+                register: a,
+                lhs: b,
+                ..
+            }
+            | SubtractU8 {
+                register: a,
+                lhs: b,
+                ..
+            } => check_registers(&[*a, *b])?,
+            Range {
+                register: a,
+                start: b,
+                end: c,
+            }
+            | RangeInclusive {
+                register: a,
+                start: b,
+                end: c,
+            }
+            | Add {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Subtract {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Multiply {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Divide {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Remainder {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Power {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Less {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | LessOrEqual {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Greater {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | GreaterOrEqual {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Equal {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | NotEqual {
+                register: a,
+                lhs: b,
+                rhs: c,
+            }
+            | Index {
+                register: a,
+                value: b,
+                index: c,
+            }
+            | IndexMut {
+                register: a,
+                index: b,
+                value: c,
+            }
+            | AccessAssign {
+                register: a,
+                key: b,
+                value: c,
+            } => check_registers(&[*a, *b, *c])?,
+            MetaInsertNamed {
+                register,
+                value,
+                name,
+                ..
+            } => check_registers(&[*register, *value, *name])?,
+            LoadFloat { register, constant } => {
+                check_registers(&[*register])?;
+                self.check_constant(*constant, ConstantType::F64, ip)?;
+            }
+            LoadInt { register, constant } => {
+                check_registers(&[*register])?;
+                self.check_constant(*constant, ConstantType::I64, ip)?;
+            }
+            LoadString { register, constant }
+            | LoadNonLocal { register, constant }
+            | Debug { register, constant }
+            | RecordType {
+                register,
+                name: constant,
+            }
+            | RecordField {
+                record: register,
+                name: constant,
+            }
+            | RecordFieldType {
+                record: register,
+                type_string: constant,
+                ..
+            }
+            | EnumType {
+                register,
+                name: constant,
+            }
+            | EnumVariant {
+                enum_type: register,
+                name: constant,
+            }
+            | EnumVariantField {
+                enum_type: register,
+                name: constant,
+            } => {
+                check_registers(&[*register])?;
+                self.check_constant(*constant, ConstantType::Str, ip)?;
+            }
+            MakeTempTuple {
+                register,
+                start,
+                count,
+            } => {
+                check_registers(&[*register])?;
+                check_register_range(*start, *count as u32, ip, register_count)?;
+            }
+            SequenceStart { .. } | StringStart { .. } | TryEnd => {}
+            SequencePushN { start, count } => {
+                check_register_range(*start, *count as u32, ip, register_count)?;
+            }
+            Function {
+                register,
+                arg_count,
+                optional_arg_count,
+                flags,
+                size,
+                ..
+            } => {
+                check_registers(&[*register])?;
+
+                if optional_arg_count > arg_count {
+                    return verifier_error(
+                        VerifierErrorKind::InvalidInstruction(
+                            "the function has more optional arguments than arguments".into(),
+                        ),
+                        ip,
+                    );
+                }
+                if flags.is_variadic() && *arg_count == 0 {
+                    return verifier_error(
+                        VerifierErrorKind::InvalidInstruction(
+                            "a variadic function needs at least one argument".into(),
+                        ),
+                        ip,
+                    );
+                }
+
+                // The function's body immediately follows the instruction
+                let body_end = self.ip() + *size as u32;
+                if body_end > frame.end {
+                    return verifier_error(
+                        VerifierErrorKind::InstructionOutOfBounds {
+                            frame_end: frame.end,
+                        },
+                        ip,
+                    );
+                }
+                self.verify_frame(body_end)?;
+            }
+            Jump { offset } => frame.jumps.push((next_ip + *offset as i64, ip)),
+            JumpBack { offset } => frame.jumps.push((next_ip - *offset as i64, ip)),
+            JumpIfTrue { register, offset }
+            | JumpIfFalse { register, offset }
+            | JumpIfNull { register, offset } => {
+                check_registers(&[*register])?;
+                frame.jumps.push((next_ip + *offset as i64, ip));
+            }
+            JumpIfNotLess { lhs, rhs, offset }
+            | JumpIfNotLessOrEqual { lhs, rhs, offset }
+            | JumpIfNotGreater { lhs, rhs, offset }
+            | JumpIfNotGreaterOrEqual { lhs, rhs, offset }
+            | JumpIfNotEqual { lhs, rhs, offset }
+            | JumpIfEqual { lhs, rhs, offset } => {
+                check_registers(&[*lhs, *rhs])?;
+                frame.jumps.push((next_ip + *offset as i64, ip));
+            }
+            Call {
+                result,
+                function,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            }
+            | TailCall {
+                result,
+                function,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => {
+                check_registers(&[*result, *function])?;
+                // The frame base is followed by the call's arguments,
+                // and then by the registers containing the indices of any packed arguments.
+                let call_registers = *arg_count as u32 + *packed_arg_count as u32 + 1;
+                check_register_range(*frame_base, call_registers, ip, register_count)?;
+            }
+            CallInstance {
+                result,
+                function,
+                instance,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            }
+            | TailCallInstance {
+                result,
+                function,
+                instance,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => {
+                check_registers(&[*result, *function, *instance])?;
+                let call_registers = *arg_count as u32 + *packed_arg_count as u32 + 1;
+                check_register_range(*frame_base, call_registers, ip, register_count)?;
+            }
+            IterNext {
+                result,
+                iterator,
+                jump_offset,
+                ..
+            } => {
+                if let Some(result) = result {
+                    check_registers(&[*result])?;
+                }
+                check_registers(&[*iterator])?;
+                frame.jumps.push((next_ip + *jump_offset as i64, ip));
+            }
+            IterNextRange {
+                result,
+                start,
+                end,
+                jump_offset,
+                ..
+            } => {
+                check_registers(&[*result, *start, *end])?;
+                frame.jumps.push((next_ip + *jump_offset as i64, ip));
+            }
+            IsEnumVariant {
+                register,
+                value,
+                enum_type,
+                variant,
+            } => {
+                check_registers(&[*register, *value, *enum_type])?;
+                self.check_constant(*variant, ConstantType::Str, ip)?;
+            }
+            Access {
+                register,
+                value,
+                key,
+                cache,
+            } => {
+                check_registers(&[*register, *value])?;
+                self.check_constant(*key, ConstantType::Str, ip)?;
+                self.check_access_cache(*cache, ip)?;
+            }
+            TryAccess {
+                register,
+                value,
+                key,
+                cache,
+                jump_offset,
+            } => {
+                check_registers(&[*register, *value])?;
+                self.check_constant(*key, ConstantType::Str, ip)?;
+                self.check_access_cache(*cache, ip)?;
+                frame.jumps.push((next_ip + *jump_offset as i64, ip));
+            }
+            AccessString {
+                register,
+                value,
+                key,
+                cache,
+            } => {
+                check_registers(&[*register, *value, *key])?;
+                self.check_access_cache(*cache, ip)?;
+            }
+            TryAccessString {
+                register,
+                value,
+                key,
+                cache,
+                jump_offset,
+            } => {
+                check_registers(&[*register, *value, *key])?;
+                self.check_access_cache(*cache, ip)?;
+                frame.jumps.push((next_ip + *jump_offset as i64, ip));
+            }
+            CheckType {
+                value,
+                type_string,
+                jump_offset,
+                ..
+            } => {
+                check_registers(&[*value])?;
+                self.check_constant(*type_string, ConstantType::Str, ip)?;
+                frame.jumps.push((next_ip + *jump_offset as i64, ip));
+            }
+        }
+
+        // Some instructions have additional constants to check
+        match instruction {
+            AssertType { type_string, .. } => {
+                self.check_constant(*type_string, ConstantType::Str, ip)?;
+            }
+            StringPush {
+                format_options: Some(options),
+                ..
+            } => {
+                if let Some(fill_character) = options.fill_character {
+                    self.check_constant(fill_character, ConstantType::Str, ip)?;
+                }
+            }
+            TryStart { catch_offset, .. } => {
+                frame.jumps.push((next_ip + *catch_offset as i64, ip));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn ip(&self) -> u32 {
+        self.reader.ip as u32
+    }
+
+    // Reads the next instruction, checking that it's valid and that it ends within the frame
+    fn next_instruction(&mut self, frame_end: u32) -> Result<Instruction, VerifierError> {
+        let ip = self.ip();
+
+        if let Some(&byte) = self.chunk.bytes.get(ip as usize)
+            && Op::from(byte).is_unused()
+        {
+            return verifier_error(VerifierErrorKind::InvalidOpcode(byte), ip);
+        }
+
+        let instruction = match self.reader.next() {
+            Some(Instruction::Error { message }) => {
+                return verifier_error(VerifierErrorKind::InvalidInstruction(message), ip);
+            }
+            Some(instruction) if self.ip() <= frame_end => instruction,
+            _ => {
+                return verifier_error(VerifierErrorKind::InstructionOutOfBounds { frame_end }, ip);
+            }
+        };
+
+        Ok(instruction)
+    }
+
+    fn check_constant(
+        &self,
+        index: ConstantIndex,
+        expected: ConstantType,
+        ip: u32,
+    ) -> Result<(), VerifierError> {
+        let valid = matches!(
+            (self.chunk.constants.get(index.into()), expected),
+            (Some(Constant::F64(_)), ConstantType::F64)
+                | (Some(Constant::I64(_)), ConstantType::I64)
+                | (Some(Constant::Str(_)), ConstantType::Str)
+        );
+
+        if valid {
+            Ok(())
+        } else {
+            let expected = match expected {
+                ConstantType::F64 => "float",
+                ConstantType::I64 => "integer",
+                ConstantType::Str => "string",
+            };
+            verifier_error(VerifierErrorKind::InvalidConstant { index, expected }, ip)
+        }
+    }
+
+    fn check_access_cache(&mut self, cache: u32, ip: u32) -> Result<(), VerifierError> {
+        let cache_count = self.chunk.access_cache_count;
+        if cache < cache_count {
+            self.access_cache_slots += 1;
+            Ok(())
+        } else {
+            verifier_error(
+                VerifierErrorKind::AccessCacheOutOfBounds { cache, cache_count },
+                ip,
+            )
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ConstantType {
+    F64,
+    I64,
+    Str,
+}
+
+// Checks that a range of registers starting at `start` is within the frame's registers
+fn check_register_range(
+    start: u8,
+    count: u32,
+    ip: u32,
+    register_count: u8,
+) -> Result<(), VerifierError> {
+    let end = start as u32 + count;
+    if end > register_count as u32 {
+        verifier_error(
+            VerifierErrorKind::RegisterOutOfBounds {
+                register: end - 1,
+                register_count,
+            },
+            ip,
+        )
+    } else {
+        Ok(())
+    }
+}
+
+fn verifier_error<T>(error: VerifierErrorKind, ip: u32) -> Result<T, VerifierError> {
+    Err(VerifierError { error, ip })
+}
@@ -132,6 +132,121 @@ match 0
                 let source = "
 match [1, 2, 3]
   (x, ..., y) then 0
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn non_exhaustive_enum_match() {
+                let source = "
+enum Shape
+  Circle(radius)
+  Empty
+match Shape.Empty
+  Shape.Circle(r) then r
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn non_exhaustive_enum_match_with_conditional_arm() {
+                let source = "
+enum Shape {Circle(radius), Empty}
+match Shape.Empty
+  Shape.Circle(r) then r
+  Shape.Empty if x then 0
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn non_exhaustive_enum_match_with_refutable_payload() {
+                let source = "
+enum Shape {Circle(radius), Empty}
+match Shape.Empty
+  Shape.Circle(0) then 0
+  Shape.Empty then 0
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn non_exhaustive_enum_match_in_nested_function() {
+                let source = "
+enum Shape {Circle(radius), Empty}
+f = |shape|
+  match shape
+    Shape.Empty then 0
+";
+                check_compilation_fails(source);
+            }
+        }
+
+        mod const_declarations {
+            use super::*;
+
+            #[test]
+            fn reassignment() {
+                let source = "
+const x = 1
+x = 2
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn compound_assignment() {
+                let source = "
+const x = 1
+x += 2
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn shadowing_with_let() {
+                let source = "
+const x = 1
+let x = 2
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn redeclaration() {
+                let source = "
+const x = 1
+const x = 2
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn const_after_assignment() {
+                let source = "
+x = 1
+const x = 2
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn reassignment_as_loop_arg() {
+                let source = "
+const x = 1
+for x in 0..10
+  x
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn reassignment_of_captured_const() {
+                let source = "
+const x = 1
+f = ||
+  y = x
+  x = 2
 ";
                 check_compilation_fails(source);
             }
@@ -0,0 +1,325 @@
+mod lint {
+    use koto_bytecode::{LintKind, LintSettings, lint};
+    use koto_parser::Parser;
+
+    fn check_lints_with_settings(
+        script: &str,
+        settings: &LintSettings,
+        expected: &[(LintKind, u32, u32)],
+    ) {
+        let ast = match Parser::parse(script) {
+            Ok(ast) => ast,
+            Err(error) => panic!("failed to parse script: {error}"),
+        };
+
+        let lints = lint(&ast, settings)
+            .into_iter()
+            .map(|lint| (lint.kind, lint.span.start.line, lint.span.start.column))
+            .collect::<Vec<_>>();
+
+        assert_eq!(lints, expected);
+    }
+
+    // Checks the script's lints, using a prelude that contains `assert` and `print`
+    fn check_lints(script: &str, expected: &[(LintKind, u32, u32)]) {
+        let settings = LintSettings {
+            prelude: Some(vec!["assert".into(), "print".into()]),
+        };
+        check_lints_with_settings(script, &settings, expected);
+    }
+
+    fn unused_variable(name: &str) -> LintKind {
+        LintKind::UnusedVariable(name.into())
+    }
+
+    fn unused_import(name: &str) -> LintKind {
+        LintKind::UnusedImport(name.into())
+    }
+
+    fn shadowed(name: &str) -> LintKind {
+        LintKind::ShadowedBinding(name.into())
+    }
+
+    fn undefined(name: &str) -> LintKind {
+        LintKind::UndefinedId(name.into())
+    }
+
+    mod unused_values {
+        use super::*;
+
+        #[test]
+        fn unused_variable_in_main_block() {
+            let script = "
+x = 1
+y = 2
+print y
+";
+            check_lints(script, &[(unused_variable("x"), 1, 0)]);
+        }
+
+        #[test]
+        fn reassigned_variable() {
+            let script = "
+x = 1
+x = 2
+";
+            check_lints(script, &[(unused_variable("x"), 1, 0)]);
+        }
+
+        #[test]
+        fn ignored_values() {
+            let script = "
+_x = 1
+f = |_, _y, z| z
+f 1, 2, 3
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn unused_function_arg() {
+            let script = "
+f = |a, b| a
+f 1, 2
+";
+            check_lints(script, &[(unused_variable("b"), 1, 8)]);
+        }
+
+        #[test]
+        fn captured_value() {
+            let script = "
+x = 1
+f = || x
+f()
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn recursive_function() {
+            let script = "
+f = |n| if n > 0 then f n - 1
+f 10
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn unpacked_values() {
+            let script = "
+f = |a, (b, c...), {d, e as g}| print a, c, g
+f()
+";
+            check_lints(
+                script,
+                &[(unused_variable("b"), 1, 9), (unused_variable("d"), 1, 20)],
+            );
+        }
+
+        #[test]
+        fn exported_values_are_used() {
+            let script = "
+export x = 1
+export
+  y: 2
+  z: || x + y
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn unused_imports() {
+            let script = "
+import foo, bar
+from baz import qux as q
+print foo
+";
+            check_lints(
+                script,
+                &[(unused_import("bar"), 1, 12), (unused_import("q"), 2, 23)],
+            );
+        }
+
+        #[test]
+        fn assigned_import_items_are_not_locals() {
+            let script = "
+x = from foo import bar
+print x
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn import_from_local_value() {
+            let script = "
+import foo
+from foo import bar
+print bar
+";
+            check_lints(script, &[]);
+        }
+    }
+
+    mod shadowing {
+        use super::*;
+
+        #[test]
+        fn function_arg_shadowing_outer_value() {
+            let script = "
+x = 1
+f = |x| x
+print x, f
+";
+            check_lints(script, &[(shadowed("x"), 2, 5)]);
+        }
+
+        #[test]
+        fn local_assignment_shadowing_outer_value() {
+            let script = "
+x = 1
+f = ||
+  x = 2
+  x
+print x, f
+";
+            check_lints(script, &[(shadowed("x"), 3, 2)]);
+        }
+
+        #[test]
+        fn values_defined_after_function_are_not_shadowed() {
+            let script = "
+f = ||
+  x = 2
+  x
+x = 1
+print x, f
+";
+            check_lints(script, &[]);
+        }
+    }
+
+    mod unreachable_code {
+        use super::*;
+
+        #[test]
+        fn expressions_after_return() {
+            let script = "
+f = ||
+  return 1
+  print 'unreachable'
+  print 'also unreachable'
+f()
+";
+            check_lints(script, &[(LintKind::UnreachableCode, 3, 2)]);
+        }
+
+        #[test]
+        fn expressions_after_throw_break_and_continue() {
+            let script = "
+for x in 0..10
+  if x == 1
+    continue
+    print x
+  else if x == 2
+    break
+    print x
+print 'done'
+throw 'error'
+print 'unreachable'
+";
+            check_lints(
+                script,
+                &[
+                    (LintKind::UnreachableCode, 4, 4),
+                    (LintKind::UnreachableCode, 7, 4),
+                    (LintKind::UnreachableCode, 10, 0),
+                ],
+            );
+        }
+
+        #[test]
+        fn return_at_end_of_block() {
+            let script = "
+f = |x|
+  if x
+    return 1
+  2
+f true
+";
+            check_lints(script, &[]);
+        }
+    }
+
+    mod undefined_ids {
+        use super::*;
+
+        #[test]
+        fn undefined_ids() {
+            let script = "
+x = 1
+print x, y
+f = || z
+f()
+";
+            check_lints(script, &[(undefined("y"), 2, 9), (undefined("z"), 3, 7)]);
+        }
+
+        #[test]
+        fn ids_are_not_checked_without_prelude() {
+            let script = "
+x = y
+print x
+";
+            check_lints_with_settings(script, &LintSettings::default(), &[]);
+        }
+
+        #[test]
+        fn exported_values_are_defined() {
+            let script = "
+f = || x
+export x = 1
+print f()
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn values_defined_after_capture() {
+            let script = "
+f = || y
+y = 1
+print f(), y
+";
+            check_lints(script, &[(undefined("y"), 1, 7)]);
+        }
+
+        #[test]
+        fn wildcard_imports_disable_undefined_checks() {
+            let script = "
+from foo import *
+print bar
+";
+            check_lints(script, &[]);
+        }
+
+        #[test]
+        fn interpolated_strings() {
+            let script = "
+x = 1
+print '{x} {y}'
+";
+            check_lints(script, &[(undefined("y"), 2, 12)]);
+        }
+
+        #[test]
+        fn match_patterns() {
+            let script = "
+match (1, 2)
+  (1, x) then print x
+  (y, 0) if y > 0 then print y
+  (_, z) then print z, w
+";
+            check_lints(script, &[(undefined("w"), 4, 23)]);
+        }
+    }
+}
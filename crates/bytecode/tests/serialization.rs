@@ -0,0 +1,180 @@
+mod serialization {
+    use koto_bytecode::{
+        CHUNK_FORMAT_VERSION, Chunk, ChunkDeserializationError, Compiler, CompilerSettings,
+        ModuleLoader,
+    };
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    const SCRIPT: &str = "
+x = 42
+f = |n: Number|
+  y = n * 1.5
+  '{y}!'
+print f x
+";
+
+    fn compile(script: &str, path: Option<&str>) -> Chunk {
+        match Compiler::compile(script, path.map(|p| p.into()), CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("Error while compiling script: {error}"),
+        }
+    }
+
+    fn check_round_trip(chunk: &Chunk) {
+        let deserialized = match Chunk::deserialize(&chunk.serialize()) {
+            Ok(deserialized) => deserialized,
+            Err(error) => panic!("Error while deserializing chunk: {error}"),
+        };
+
+        assert_eq!(chunk.bytes, deserialized.bytes);
+        assert_eq!(
+            chunk.constants.iter().collect::<Vec<_>>(),
+            deserialized.constants.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(chunk.path, deserialized.path);
+        assert_eq!(chunk.debug_info, deserialized.debug_info);
+        assert_eq!(chunk, &deserialized);
+    }
+
+    #[test]
+    fn round_trip() {
+        check_round_trip(&compile(SCRIPT, None));
+        check_round_trip(&compile(SCRIPT, Some("test.koto")));
+        check_round_trip(&compile("", None));
+    }
+
+    #[test]
+    fn round_trip_test_scripts() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../koto/tests");
+        let mut count = 0;
+
+        for entry in fs::read_dir(tests_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "koto")
+            {
+                let script = fs::read_to_string(&path).unwrap();
+                check_round_trip(&compile(&script, path.to_str()));
+                count += 1;
+            }
+        }
+
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn missing_header() {
+        assert_eq!(
+            Chunk::deserialize(b"hello"),
+            Err(ChunkDeserializationError::MissingHeader)
+        );
+    }
+
+    #[test]
+    fn unsupported_format_version() {
+        let mut data = compile(SCRIPT, None).serialize();
+        let version_start = 5;
+        data[version_start..version_start + 4]
+            .copy_from_slice(&(CHUNK_FORMAT_VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            Chunk::deserialize(&data),
+            Err(ChunkDeserializationError::UnsupportedFormatVersion(
+                CHUNK_FORMAT_VERSION + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn truncated_data() {
+        let data = compile(SCRIPT, None).serialize();
+
+        for len in 0..data.len() {
+            assert!(
+                Chunk::deserialize(&data[..len]).is_err(),
+                "Truncated data of length {len} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_data() {
+        let mut data = compile(SCRIPT, None).serialize();
+        data.push(0);
+
+        assert_eq!(
+            Chunk::deserialize(&data),
+            Err(ChunkDeserializationError::UnexpectedTrailingData)
+        );
+    }
+
+    #[test]
+    fn bytecode_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("cache");
+        let path = Some("test.koto".into());
+
+        let mut loader = ModuleLoader::default();
+        loader.set_bytecode_cache(Some(cache_dir.clone()));
+        let compiled = loader
+            .compile_script(SCRIPT, path.clone(), CompilerSettings::default())
+            .unwrap();
+
+        let cache_files: Vec<PathBuf> = fs::read_dir(&cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(cache_files.len(), 1);
+        let cache_file = &cache_files[0];
+        assert_eq!(cache_file.extension().unwrap(), "kotoc");
+
+        // Replace the cached chunk with one that has different bytecode,
+        // the cached chunk should then be loaded without recompiling the script.
+        let mut modified = compile("x = 99", Some("test.koto"));
+        modified.debug_info.source = SCRIPT.into();
+        fs::write(cache_file, modified.serialize()).unwrap();
+
+        let mut loader = ModuleLoader::default();
+        loader.set_bytecode_cache(Some(cache_dir.clone()));
+        let loaded = loader
+            .compile_script(SCRIPT, path.clone(), CompilerSettings::default())
+            .unwrap();
+        assert_eq!(*loaded, modified);
+
+        // Invalid cache files are ignored, with the script being recompiled
+        fs::write(cache_file, b"invalid").unwrap();
+        let recompiled = loader
+            .compile_script(SCRIPT, path.clone(), CompilerSettings::default())
+            .unwrap();
+        assert_eq!(*recompiled, *compiled);
+
+        // Cached chunks that fail verification are also ignored
+        let mut invalid = modified.clone();
+        invalid.bytes = vec![0xff, 0xff];
+        fs::write(cache_file, invalid.serialize()).unwrap();
+        let recompiled = loader
+            .compile_script(SCRIPT, path, CompilerSettings::default())
+            .unwrap();
+        assert_eq!(*recompiled, *compiled);
+        assert_eq!(
+            Chunk::deserialize(&fs::read(cache_file).unwrap()).unwrap(),
+            *compiled
+        );
+    }
+
+    #[test]
+    fn bytecode_cache_ignores_scripts_without_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("cache");
+
+        let mut loader = ModuleLoader::default();
+        loader.set_bytecode_cache(Some(cache_dir.clone()));
+        loader
+            .compile_script(SCRIPT, None, CompilerSettings::default())
+            .unwrap();
+
+        assert!(!cache_dir.exists());
+    }
+}
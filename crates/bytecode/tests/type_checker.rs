@@ -0,0 +1,441 @@
+mod type_checker {
+    use koto_bytecode::{Compiler, CompilerSettings, check_types};
+    use koto_parser::Parser;
+
+    // Checks the script's type errors, each defined as (expected, found, line, column)
+    fn check_errors(script: &str, expected: &[(&str, &str, u32, u32)]) {
+        let ast = match Parser::parse(script) {
+            Ok(ast) => ast,
+            Err(error) => panic!("failed to parse script: {error}"),
+        };
+
+        let errors = check_types(&ast)
+            .into_iter()
+            .map(|error| {
+                (
+                    error.expected,
+                    error.found,
+                    error.span.start.line,
+                    error.span.start.column,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let expected = expected
+            .iter()
+            .map(|(expected, found, line, column)| {
+                (expected.to_string(), found.to_string(), *line, *column)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(errors, expected);
+    }
+
+    mod assignments {
+        use super::*;
+
+        #[test]
+        fn literal_values() {
+            let script = "
+let a: String = 42
+let b: Number = 1 + 2
+let c: Bool = 'x' == 'y'
+let d: Tuple? = null
+let e: List = (1, 2)
+";
+            check_errors(
+                script,
+                &[("String", "Number", 1, 16), ("List", "Tuple", 5, 14)],
+            );
+        }
+
+        #[test]
+        fn propagated_values() {
+            let script = "
+x = 'hello'
+y = x + ' world'
+let z: Number = y
+";
+            check_errors(script, &[("Number", "String", 3, 16)]);
+        }
+
+        #[test]
+        fn hints_define_the_type_of_unknown_values() {
+            let script = "
+let x: String = foo()
+let y: Number = x
+";
+            check_errors(script, &[("Number", "String", 2, 16)]);
+        }
+
+        #[test]
+        fn multi_assignment() {
+            let script = "
+let a: Number, _, c: Bool = 123, 'x', 99
+";
+            check_errors(script, &[("Bool", "Number", 1, 38)]);
+        }
+
+        #[test]
+        fn abstract_types() {
+            let script = "
+let a: Iterable = 1..10
+let b: Indexable = 'abc'
+let c: Callable = || null
+let d: Any = 42
+let e: Iterable = 42
+let f: Callable = 'abc'
+let g: Indexable = 1..10
+";
+            check_errors(
+                script,
+                &[
+                    ("Iterable", "Number", 5, 18),
+                    ("Callable", "String", 6, 18),
+                    ("Indexable", "Range", 7, 20),
+                ],
+            );
+        }
+
+        #[test]
+        fn unknown_values_are_skipped() {
+            let script = "
+let a: Number = foo.bar
+let b: String = x[0]
+let c: Foo = {@type: 'Foo'}
+let d: Number = import baz
+";
+            check_errors(script, &[]);
+        }
+
+        #[test]
+        fn const_values() {
+            let script = "
+const X: String = 1
+";
+            check_errors(script, &[("String", "Number", 1, 18)]);
+        }
+    }
+
+    mod control_flow {
+        use super::*;
+
+        #[test]
+        fn if_expression_result() {
+            let script = "
+x = if foo() then 1 else 2
+let a: Number = x
+y = if foo() then 1 else 'two'
+let b: Number = y
+z = if foo() then 'hello'
+let c: Number = z
+";
+            check_errors(script, &[("Number", "String?", 6, 16)]);
+        }
+
+        #[test]
+        fn values_assigned_in_branches() {
+            let script = "
+x = 1
+if foo()
+  x = 'hello'
+let a: Number = x
+y = 1
+if foo()
+  y = 2
+else
+  y = 3
+let b: String = y
+";
+            check_errors(script, &[("String", "Number", 10, 16)]);
+        }
+
+        #[test]
+        fn values_assigned_in_loops() {
+            let script = "
+x = 1
+for i in 0..10
+  let a: Number = x
+  x = 'hello'
+let b: Number = x
+";
+            check_errors(script, &[]);
+        }
+
+        #[test]
+        fn loop_arguments() {
+            let script = "
+for i: String in 0..10
+  let x: Number = i
+for c: Number in 'abc'
+  print c
+";
+            check_errors(
+                script,
+                &[("String", "Number", 1, 4), ("Number", "String", 3, 4)],
+            );
+        }
+
+        #[test]
+        fn errors_in_loops_are_reported_once() {
+            let script = "
+while foo()
+  let x: String = 1
+";
+            check_errors(script, &[("String", "Number", 2, 18)]);
+        }
+
+        #[test]
+        fn match_arms() {
+            let script = "
+x = match foo()
+  n: Number then n
+  s: String then size s
+let a: Number = x
+let b: String = x
+y = match foo()
+  n: Number then n
+  else 'hello'
+let c: Number = y
+";
+            check_errors(script, &[("String", "Number?", 5, 16)]);
+        }
+
+        #[test]
+        fn match_patterns_narrow_types() {
+            let script = "
+match foo()
+  s: String then
+    let x: Number = s
+  else null
+";
+            check_errors(script, &[("Number", "String", 3, 20)]);
+        }
+
+        #[test]
+        fn switch_arms() {
+            let script = "
+x = switch
+  foo() then 1
+  else 2
+let a: String = x
+";
+            check_errors(script, &[("String", "Number", 4, 16)]);
+        }
+
+        #[test]
+        fn null_checks_in_chains() {
+            let script = "
+let x: Number = ' abc '.strip_prefix(' ')?.to_uppercase()
+";
+            check_errors(script, &[("Number", "String?", 1, 16)]);
+        }
+    }
+
+    mod functions {
+        use super::*;
+
+        #[test]
+        fn call_arguments() {
+            let script = "
+f = |a: Number, b: String?, c = 42| a
+f 'hello', null
+f 1, 2
+f 1, 'x', 3
+";
+            check_errors(
+                script,
+                &[("Number", "String", 2, 2), ("String?", "Number", 3, 5)],
+            );
+        }
+
+        #[test]
+        fn missing_arguments() {
+            let script = "
+f = |a: Number, b: Number? , c: Number = 1| a
+f()
+f 1
+";
+            check_errors(script, &[("Number", "Null", 2, 0)]);
+        }
+
+        #[test]
+        fn default_argument_values() {
+            let script = "
+f = |a: Number = 'hello'| a
+";
+            check_errors(script, &[("Number", "String", 1, 17)]);
+        }
+
+        #[test]
+        fn return_values() {
+            let script = "
+f = |x| -> String
+  if x
+    return 1
+  'hello'
+g = |x| -> Number
+  if x
+    return
+  'hello'
+";
+            check_errors(
+                script,
+                &[
+                    ("String", "Number", 3, 11),
+                    ("Number", "Null", 7, 4),
+                    ("Number", "String", 8, 2),
+                ],
+            );
+        }
+
+        #[test]
+        fn output_types_are_propagated() {
+            let script = "
+f = |x| -> String x.to_string()
+let a: Number = f 1
+g = |x| x + 1
+let b: String = g 1
+h = |x: Number| x + 1
+let c: String = h 1
+";
+            check_errors(
+                script,
+                &[("Number", "String", 2, 16), ("String", "Number", 6, 16)],
+            );
+        }
+
+        #[test]
+        fn generators() {
+            let script = "
+gen = || -> Number
+  yield 1
+  yield 'two'
+let x: Iterator = gen()
+let y: Number = gen()
+";
+            check_errors(
+                script,
+                &[("Number", "String", 3, 8), ("Number", "Iterator", 5, 16)],
+            );
+        }
+
+        #[test]
+        fn recursive_calls() {
+            let script = "
+f = |n: Number| -> Number
+  if n > 0 then f 'x' else 0
+";
+            check_errors(script, &[("Number", "String", 2, 18)]);
+        }
+
+        #[test]
+        fn captured_values() {
+            let script = "
+x = 'hello'
+f = ||
+  let y: Number = x
+";
+            check_errors(script, &[("Number", "String", 3, 18)]);
+        }
+
+        #[test]
+        fn unpacked_arguments_are_skipped() {
+            let script = "
+f = |a: Number, b: Number| a + b
+f ('x', 'y')...
+";
+            check_errors(script, &[]);
+        }
+    }
+
+    mod core_library {
+        use super::*;
+
+        #[test]
+        fn module_functions() {
+            let script = "
+string.to_uppercase 42
+number.max 1, 'x'
+let x: String = number.abs -1
+";
+            check_errors(
+                script,
+                &[
+                    ("String", "Number", 1, 20),
+                    ("Number", "String", 2, 14),
+                    ("String", "Number", 3, 16),
+                ],
+            );
+        }
+
+        #[test]
+        fn methods() {
+            let script = "
+let a: Number = 'hello'.to_uppercase()
+let b: String = 'hello'.starts_with 'h'
+'hello'.contains 42
+let c: Number = (1.5).floor()
+";
+            check_errors(
+                script,
+                &[
+                    ("Number", "String", 1, 16),
+                    ("String", "Bool", 2, 16),
+                    ("String", "Number", 3, 17),
+                ],
+            );
+        }
+
+        #[test]
+        fn prelude_functions() {
+            let script = "
+let a: String = size [1, 2, 3]
+let b: String = type 42
+";
+            check_errors(script, &[("String", "Number", 1, 16)]);
+        }
+
+        #[test]
+        fn shadowed_core_library_names() {
+            let script = "
+string = {to_uppercase: |x| x}
+string.to_uppercase 42
+size = |x| 'big'
+let a: String = size [1, 2, 3]
+";
+            check_errors(script, &[]);
+        }
+    }
+
+    mod compiler {
+        use super::*;
+
+        #[test]
+        fn static_type_checks_are_disabled_by_default() {
+            let script = "let x: String = 42";
+            assert!(Compiler::compile(script, None, CompilerSettings::default()).is_ok());
+        }
+
+        #[test]
+        fn static_type_check_errors() {
+            let script = "
+f = |x: Number| x
+f 'hello'
+";
+            let settings = CompilerSettings {
+                enable_static_type_checks: true,
+                ..Default::default()
+            };
+            let Err(error) = Compiler::compile(script, None, settings) else {
+                panic!("expected a type check error");
+            };
+            assert_eq!(
+                error.to_string(),
+                "expected a value of type 'Number', found 'String'"
+            );
+            assert_eq!(error.span.start.line, 2);
+            assert_eq!(error.span.start.column, 2);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+mod verifier {
+    use koto_bytecode::{
+        Chunk, Compiler, CompilerSettings, Op, VerifierError, VerifierErrorKind, verify_chunk,
+    };
+    use koto_memory::Ptr;
+    use koto_parser::{Constant, ConstantPool};
+    use std::{env, fs, path::PathBuf};
+
+    fn chunk_with_bytes(bytes: &[u8]) -> Ptr<Chunk> {
+        Chunk {
+            bytes: bytes.to_vec(),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn check_verification_fails(chunk: Ptr<Chunk>, error: VerifierErrorKind, ip: u32) {
+        assert_eq!(verify_chunk(&chunk), Err(VerifierError { error, ip }));
+    }
+
+    #[test]
+    fn compiled_scripts_are_valid() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../koto/tests");
+        let mut count = 0;
+
+        for entry in fs::read_dir(tests_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "koto")
+            {
+                let script = fs::read_to_string(&path).unwrap();
+                let chunk = Compiler::compile(&script, None, CompilerSettings::default()).unwrap();
+                if let Err(error) = verify_chunk(&chunk.into()) {
+                    panic!("Failed to verify '{}': {error}", path.display());
+                }
+                count += 1;
+            }
+        }
+
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn unused_opcode() {
+        let chunk = chunk_with_bytes(&[Op::NewFrame as u8, 1, Op::Unused117 as u8, 0]);
+        check_verification_fails(chunk, VerifierErrorKind::InvalidOpcode(117), 2);
+    }
+
+    #[test]
+    fn missing_new_frame() {
+        let chunk = chunk_with_bytes(&[Op::SetNull as u8, 0, Op::Return as u8, 0]);
+        check_verification_fails(chunk, VerifierErrorKind::MissingNewFrame, 0);
+    }
+
+    #[test]
+    fn truncated_instruction() {
+        let chunk = chunk_with_bytes(&[Op::NewFrame as u8, 1, Op::Copy as u8, 0]);
+        let result = verify_chunk(&chunk);
+        assert!(matches!(
+            result,
+            Err(VerifierError {
+                error: VerifierErrorKind::InvalidInstruction(_),
+                ip: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn register_out_of_bounds() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            2,
+            Op::Add as u8,
+            0,
+            1,
+            2,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::RegisterOutOfBounds {
+                register: 2,
+                register_count: 2,
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn call_args_out_of_bounds() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            3,
+            // result, function, frame_base, arg_count, packed_arg_count
+            Op::Call as u8,
+            0,
+            0,
+            1,
+            2,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::RegisterOutOfBounds {
+                register: 3,
+                register_count: 3,
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn packed_call_args_out_of_bounds() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            4,
+            // result, function, frame_base, arg_count, packed_arg_count
+            Op::Call as u8,
+            0,
+            0,
+            1,
+            1,
+            2,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::RegisterOutOfBounds {
+                register: 4,
+                register_count: 4,
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn missing_constant() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::LoadString as u8,
+            0,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::InvalidConstant {
+                index: 0.into(),
+                expected: "string",
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn constant_with_unexpected_type() {
+        let chunk = Chunk {
+            bytes: vec![
+                Op::NewFrame as u8,
+                1,
+                Op::LoadInt as u8,
+                0,
+                0,
+                Op::Return as u8,
+                0,
+            ],
+            constants: ConstantPool::from_constants([Constant::Str("x")]).unwrap(),
+            ..Default::default()
+        };
+        check_verification_fails(
+            chunk.into(),
+            VerifierErrorKind::InvalidConstant {
+                index: 0.into(),
+                expected: "integer",
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn access_cache_out_of_bounds() {
+        let chunk = Chunk {
+            bytes: vec![
+                Op::NewFrame as u8,
+                2,
+                Op::AccessString as u8,
+                0,
+                1,
+                1,
+                1, // The chunk only has a single access cache
+                Op::Return as u8,
+                0,
+            ],
+            access_cache_count: 1,
+            ..Default::default()
+        };
+        check_verification_fails(
+            chunk.into(),
+            VerifierErrorKind::AccessCacheOutOfBounds {
+                cache: 1,
+                cache_count: 1,
+            },
+            2,
+        );
+    }
+
+    #[test]
+    fn access_cache_count_larger_than_used() {
+        let chunk = Chunk {
+            bytes: vec![
+                Op::NewFrame as u8,
+                2,
+                Op::AccessString as u8,
+                0,
+                1,
+                1,
+                0,
+                Op::Return as u8,
+                0,
+            ],
+            access_cache_count: u32::MAX,
+            ..Default::default()
+        };
+        check_verification_fails(
+            chunk.into(),
+            VerifierErrorKind::AccessCacheCountMismatch {
+                cache_count: u32::MAX,
+                used: 1,
+            },
+            0,
+        );
+    }
+
+    #[test]
+    fn jump_into_the_middle_of_an_instruction() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::Jump as u8,
+            1,
+            0,
+            Op::SetNull as u8,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(chunk, VerifierErrorKind::InvalidJumpTarget { target: 6 }, 2);
+    }
+
+    #[test]
+    fn jump_back_before_the_start_of_the_frame() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::JumpBack as u8,
+            10,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::InvalidJumpTarget { target: -5 },
+            2,
+        );
+    }
+
+    #[test]
+    fn jump_out_of_a_function() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            // register, arg_count, optional_arg_count, capture_count, flags, size
+            Op::Function as u8,
+            0,
+            0,
+            0,
+            0,
+            0,
+            7,
+            0,
+            // The function's body
+            Op::NewFrame as u8,
+            1,
+            Op::Jump as u8,
+            2,
+            0,
+            Op::Return as u8,
+            0,
+            // The function body's jump target in the outer frame
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::InvalidJumpTarget { target: 17 },
+            12,
+        );
+    }
+
+    #[test]
+    fn function_larger_than_its_frame() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::Function as u8,
+            0,
+            0,
+            0,
+            0,
+            0,
+            100,
+            0,
+            Op::NewFrame as u8,
+            1,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(
+            chunk,
+            VerifierErrorKind::InstructionOutOfBounds { frame_end: 14 },
+            2,
+        );
+    }
+
+    #[test]
+    fn function_that_continues_past_its_end() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::Function as u8,
+            0,
+            0,
+            0,
+            0,
+            0,
+            4,
+            0,
+            // The function's body is missing a final Return
+            Op::NewFrame as u8,
+            1,
+            Op::SetNull as u8,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        check_verification_fails(chunk, VerifierErrorKind::UnterminatedFrame, 12);
+    }
+
+    #[test]
+    fn variadic_function_without_arguments() {
+        let chunk = chunk_with_bytes(&[
+            Op::NewFrame as u8,
+            1,
+            Op::Function as u8,
+            0,
+            0,
+            0,
+            0,
+            1, // The function is flagged as variadic
+            4,
+            0,
+            Op::NewFrame as u8,
+            1,
+            Op::Return as u8,
+            0,
+            Op::Return as u8,
+            0,
+        ]);
+        let result = verify_chunk(&chunk);
+        assert!(matches!(
+            result,
+            Err(VerifierError {
+                error: VerifierErrorKind::InvalidInstruction(_),
+                ip: 2
+            })
+        ));
+    }
+}
@@ -0,0 +1,663 @@
+//! A Debug Adapter Protocol server for Koto scripts
+//!
+//! The server communicates with the client via stdin and stdout, so script output is forwarded
+//! to the client as `output` events.
+//!
+//! See https://microsoft.github.io/debug-adapter-protocol/ for the protocol specification.
+
+use crate::add_modules;
+use anyhow::Result;
+use koto::{
+    prelude::*,
+    runtime::{
+        Breakpoint, DebugCommand, DebugContext, DebugPauseHandle, InterruptHandle, KotoDebugger,
+        PauseReason, Result as RuntimeResult,
+    },
+};
+use koto_tooling::{module_search_paths, read_message, write_message};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+};
+
+// Koto scripts run on a single thread
+const THREAD_ID: i64 = 1;
+
+// Displayed values are truncated to keep the variables view readable
+const MAX_VALUE_DISPLAY_LENGTH: usize = 200;
+
+/// Runs the debug adapter until the client disconnects
+pub fn run_server() -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let shared = Arc::new(SharedState::default());
+    spawn_reader(sender, shared.clone());
+    let requests = Arc::new(Requests(Mutex::new(receiver)));
+
+    let mut breakpoints = Breakpoints::default();
+    let mut launch_args = None;
+    let mut configuration_done = false;
+
+    // Handle requests until the script has been launched and configured
+    while !(launch_args.is_some() && configuration_done) {
+        let Some(request) = requests.next() else {
+            return Ok(());
+        };
+
+        match request.command.as_str() {
+            "initialize" => {
+                send_response(
+                    &request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsTerminateRequest": true,
+                    }),
+                );
+                send_event("initialized", Value::Null);
+            }
+            "launch" => match LaunchArguments::deserialize(&request.arguments) {
+                Ok(args) => {
+                    send_response(&request, Value::Null);
+                    launch_args = Some(args);
+                }
+                Err(error) => send_error_response(&request, &error.to_string()),
+            },
+            "setBreakpoints" => {
+                let body = breakpoints.set(&request.arguments, None);
+                send_response(&request, body);
+            }
+            "configurationDone" => {
+                send_response(&request, Value::Null);
+                configuration_done = true;
+            }
+            "threads" => send_threads_response(&request),
+            "disconnect" | "terminate" => {
+                send_response(&request, Value::Null);
+                return Ok(());
+            }
+            _ => send_unsupported_response(&request),
+        }
+    }
+
+    let launch_args = launch_args.expect("missing launch arguments");
+    let exit_code = run_script(launch_args, breakpoints, requests.clone(), &shared);
+
+    if shared.disconnected.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    send_event("exited", json!({"exitCode": exit_code}));
+    send_event("terminated", Value::Null);
+
+    // Wait for the client to disconnect
+    while let Some(request) = requests.next() {
+        match request.command.as_str() {
+            "disconnect" => {
+                send_response(&request, Value::Null);
+                break;
+            }
+            "terminate" => send_response(&request, Value::Null),
+            "threads" => send_threads_response(&request),
+            _ => send_error_response(&request, "the script has finished running"),
+        }
+    }
+
+    Ok(())
+}
+
+// Runs the launched script with the DAP debugger attached, returning the script's exit code
+fn run_script(
+    args: LaunchArguments,
+    breakpoints: Breakpoints,
+    requests: Arc<Requests>,
+    shared: &Arc<SharedState>,
+) -> i64 {
+    let program = normalize_path(&args.program);
+    let script = match fs::read_to_string(&program) {
+        Ok(script) => script,
+        Err(error) => {
+            send_output("stderr", &format!("failed to read '{program}' ({error})\n"));
+            return 1;
+        }
+    };
+
+    let initial_breakpoints: Vec<Breakpoint> = breakpoints.iter().collect();
+    let debugger = DapDebugger {
+        requests,
+        breakpoints,
+        stop_on_entry: args.stop_on_entry,
+        variables: Vec::new(),
+        shared: shared.clone(),
+    };
+
+    let mut koto = Koto::with_settings(
+        KotoSettings::default()
+            .with_args(args.args)
+            .with_module_search_paths(module_search_paths())
+            .with_stdout(DapOutput::new("stdout"))
+            .with_stderr(DapOutput::new("stderr"))
+            .with_debugger(debugger),
+    );
+    add_modules(&koto);
+
+    for breakpoint in initial_breakpoints {
+        koto.set_breakpoint(breakpoint);
+    }
+
+    let pause_handle = koto
+        .debug_pause_handle()
+        .expect("missing debug pause handle");
+    if args.stop_on_entry {
+        pause_handle.request_pause();
+    }
+    if let Ok(mut handles) = shared.handles.lock() {
+        *handles = Some((koto.interrupt_handle(), pause_handle));
+    }
+
+    let result = koto
+        .compile(CompileArgs {
+            script: &script,
+            script_path: Some(program.clone()),
+            compiler_settings: Default::default(),
+        })
+        .and_then(|chunk| koto.run(chunk));
+
+    match result {
+        Ok(_) => 0,
+        Err(_) if shared.terminate_requested.load(Ordering::Relaxed) => 0,
+        Err(error) => {
+            send_output("stderr", &format!("{error}\n"));
+            1
+        }
+    }
+}
+
+// The arguments for the `launch` request
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchArguments {
+    // The path of the script to run
+    program: String,
+    // Arguments to pass into the script
+    #[serde(default)]
+    args: Vec<String>,
+    // Pauses execution when the script starts
+    #[serde(default)]
+    stop_on_entry: bool,
+}
+
+// A request from the client
+#[derive(Deserialize)]
+struct Request {
+    seq: i64,
+    command: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+// Provides access to the client's requests, which are sent by the reader thread
+//
+// The receiver is shared between the server's main loop and the debugger.
+struct Requests(Mutex<Receiver<Request>>);
+
+impl Requests {
+    // Blocks until the next request arrives, returning None if the client has gone away
+    fn next(&self) -> Option<Request> {
+        self.0.lock().ok()?.recv().ok()
+    }
+}
+
+// State that's shared between the reader thread and the thread that's running the script
+#[derive(Default)]
+struct SharedState {
+    // Handles for the running script, available once the script has been launched
+    handles: Mutex<Option<(InterruptHandle, DebugPauseHandle)>>,
+    // Set when the client has asked for the script to be stopped
+    terminate_requested: AtomicBool,
+    // Set when the client has disconnected while the script was paused
+    disconnected: AtomicBool,
+}
+
+// Reads requests from stdin on a separate thread
+//
+// Requests that need to interrupt a running script are handled directly by the reader thread,
+// all other requests are passed along to the thread that's running the script.
+fn spawn_reader(sender: Sender<Request>, shared: Arc<SharedState>) {
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+
+        loop {
+            let request = match read_message(&mut stdin) {
+                Ok(Some(message)) if message["type"] == "request" => {
+                    match Request::deserialize(&message) {
+                        Ok(request) => request,
+                        Err(error) => {
+                            eprintln!("Invalid DAP request: {error}");
+                            continue;
+                        }
+                    }
+                }
+                // Responses to reverse requests aren't used
+                Ok(Some(_)) => continue,
+                Ok(None) => break,
+                Err(error) => {
+                    eprintln!("Error while reading DAP message: {error}");
+                    break;
+                }
+            };
+
+            let handles = shared
+                .handles
+                .lock()
+                .ok()
+                .and_then(|handles| handles.clone());
+
+            match request.command.as_str() {
+                "pause" => {
+                    if let Some((_, pause_handle)) = handles {
+                        pause_handle.request_pause();
+                        send_response(&request, Value::Null);
+                    } else {
+                        send_error_response(&request, "the script isn't running");
+                    }
+                    continue;
+                }
+                "disconnect" | "terminate" => {
+                    shared.terminate_requested.store(true, Ordering::Relaxed);
+                    if let Some((interrupt_handle, _)) = handles {
+                        interrupt_handle.interrupt();
+                    }
+                }
+                _ => {}
+            }
+
+            if sender.send(request).is_err() {
+                break;
+            }
+        }
+
+        // The client has gone away, so stop the script if it's still running
+        if let Ok(Some((interrupt_handle, _))) = shared.handles.lock().as_deref() {
+            shared.terminate_requested.store(true, Ordering::Relaxed);
+            interrupt_handle.interrupt();
+        }
+    });
+}
+
+// Sends a message to the client
+//
+// Write errors are ignored, a broken connection will be detected by the reader thread.
+fn send_message(mut message: Value) {
+    static SEQ: AtomicI64 = AtomicI64::new(1);
+
+    message["seq"] = SEQ.fetch_add(1, Ordering::Relaxed).into();
+    write_message(&mut io::stdout().lock(), &message).ok();
+}
+
+fn send_response(request: &Request, body: Value) {
+    send_message(json!({
+        "type": "response",
+        "request_seq": request.seq,
+        "success": true,
+        "command": request.command,
+        "body": body,
+    }));
+}
+
+fn send_error_response(request: &Request, message: &str) {
+    send_message(json!({
+        "type": "response",
+        "request_seq": request.seq,
+        "success": false,
+        "command": request.command,
+        "message": message,
+    }));
+}
+
+fn send_unsupported_response(request: &Request) {
+    send_error_response(
+        request,
+        &format!("unsupported request '{}'", request.command),
+    );
+}
+
+fn send_threads_response(request: &Request) {
+    send_response(
+        request,
+        json!({"threads": [{"id": THREAD_ID, "name": "main"}]}),
+    );
+}
+
+fn send_event(event: &str, body: Value) {
+    send_message(json!({
+        "type": "event",
+        "event": event,
+        "body": body,
+    }));
+}
+
+fn send_output(category: &str, output: &str) {
+    send_event("output", json!({"category": category, "output": output}));
+}
+
+// Canonicalizes paths so that they match the paths used by the module loader
+fn normalize_path(path: &str) -> KString {
+    match fs::canonicalize(path) {
+        Ok(path) => path.to_string_lossy().as_ref().into(),
+        Err(_) => path.into(),
+    }
+}
+
+// The breakpoints that have been set by the client, with lines counting from 0
+#[derive(Default)]
+struct Breakpoints(HashMap<KString, Vec<u32>>);
+
+impl Breakpoints {
+    fn iter(&self) -> impl Iterator<Item = Breakpoint> + '_ {
+        self.0.iter().flat_map(|(path, lines)| {
+            lines
+                .iter()
+                .map(|line| Breakpoint::new(Some(path.clone()), *line))
+        })
+    }
+
+    // Replaces the breakpoints for a source, returning the body of the response
+    //
+    // If the script is running then the VM's breakpoints are also updated.
+    fn set(&mut self, arguments: &Value, vm: Option<&KotoVm>) -> Value {
+        let Some(path) = arguments["source"]["path"].as_str() else {
+            return json!({"breakpoints": []});
+        };
+        let path = normalize_path(path);
+
+        let lines: Vec<u32> = arguments["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|breakpoint| breakpoint["line"].as_u64())
+            .filter_map(|line| u32::try_from(line).ok()?.checked_sub(1))
+            .collect();
+
+        let previous = self.0.insert(path.clone(), lines.clone());
+
+        if let Some(vm) = vm {
+            for line in previous.into_iter().flatten() {
+                vm.remove_breakpoint(&Breakpoint::new(Some(path.clone()), line));
+            }
+            for line in lines.iter() {
+                vm.set_breakpoint(Breakpoint::new(Some(path.clone()), *line));
+            }
+        }
+
+        let breakpoints: Vec<Value> = lines
+            .iter()
+            .map(|line| json!({"verified": true, "line": line + 1}))
+            .collect();
+        json!({"breakpoints": breakpoints})
+    }
+}
+
+// A container that can be expanded in the client's variables view
+enum Variables {
+    // The local values of a frame in the paused call stack
+    Frame(usize),
+    // The contents of a container value
+    Value(KValue),
+}
+
+// A [KotoDebugger] that waits for commands from the client while the script is paused
+struct DapDebugger {
+    requests: Arc<Requests>,
+    breakpoints: Breakpoints,
+    stop_on_entry: bool,
+    // The expandable variables for the current pause, indexed by `variablesReference - 1`
+    variables: Vec<Variables>,
+    shared: Arc<SharedState>,
+}
+
+impl KotoDebugger for DapDebugger {
+    fn paused(&mut self, ctx: &mut DebugContext) -> DebugCommand {
+        if self.shared.disconnected.load(Ordering::Relaxed) {
+            return DebugCommand::Continue;
+        }
+
+        let reason = match ctx.reason() {
+            PauseReason::Breakpoint => "breakpoint",
+            PauseReason::Step => "step",
+            PauseReason::PauseRequested if self.stop_on_entry => {
+                self.stop_on_entry = false;
+                "entry"
+            }
+            PauseReason::PauseRequested => "pause",
+        };
+        send_event(
+            "stopped",
+            json!({"reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true}),
+        );
+
+        self.variables.clear();
+
+        while let Some(request) = self.requests.next() {
+            let command = match request.command.as_str() {
+                "continue" => DebugCommand::Continue,
+                "next" => DebugCommand::StepOver,
+                "stepIn" => DebugCommand::StepInto,
+                "stepOut" => DebugCommand::StepOut,
+                "disconnect" | "terminate" => {
+                    // The reader thread has already interrupted the VM,
+                    // so continuing will stop the script.
+                    if request.command == "disconnect" {
+                        self.shared.disconnected.store(true, Ordering::Relaxed);
+                    }
+                    send_response(&request, Value::Null);
+                    return DebugCommand::Continue;
+                }
+                "threads" => {
+                    send_threads_response(&request);
+                    continue;
+                }
+                "stackTrace" => {
+                    send_response(&request, stack_trace(ctx));
+                    continue;
+                }
+                "scopes" => {
+                    let frame = request.arguments["frameId"].as_u64().unwrap_or(0) as usize;
+                    self.variables.push(Variables::Frame(frame));
+                    let scopes = json!({"scopes": [{
+                        "name": "Locals",
+                        "variablesReference": self.variables.len(),
+                        "expensive": false,
+                    }]});
+                    send_response(&request, scopes);
+                    continue;
+                }
+                "variables" => {
+                    let reference = request.arguments["variablesReference"]
+                        .as_u64()
+                        .unwrap_or(0) as usize;
+                    let variables = self.variables(ctx, reference);
+                    send_response(&request, json!({"variables": variables}));
+                    continue;
+                }
+                "setBreakpoints" => {
+                    let body = self.breakpoints.set(&request.arguments, Some(&*ctx.vm()));
+                    send_response(&request, body);
+                    continue;
+                }
+                _ => {
+                    send_unsupported_response(&request);
+                    continue;
+                }
+            };
+
+            send_response(&request, json!({"allThreadsContinued": true}));
+            return command;
+        }
+
+        // The client has gone away, the reader thread will have interrupted the VM
+        self.shared.disconnected.store(true, Ordering::Relaxed);
+        DebugCommand::Continue
+    }
+}
+
+impl DapDebugger {
+    // Returns the children of an expandable variable
+    fn variables(&mut self, ctx: &mut DebugContext, reference: usize) -> Vec<Value> {
+        let children: Vec<(String, KValue)> = match reference
+            .checked_sub(1)
+            .and_then(|index| self.variables.get(index))
+        {
+            Some(Variables::Frame(frame)) => ctx
+                .locals(*frame)
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            Some(Variables::Value(KValue::List(list))) => list
+                .data()
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (format!("[{i}]"), value.clone()))
+                .collect(),
+            Some(Variables::Value(KValue::Tuple(tuple))) => tuple
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (format!("[{i}]"), value.clone()))
+                .collect(),
+            Some(Variables::Value(KValue::Map(map))) => map
+                .data()
+                .iter()
+                .map(|(key, value)| (key.value().clone(), value.clone()))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|(key, value)| (display_value(ctx, &key), value))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        children
+            .into_iter()
+            .map(|(name, value)| {
+                let expandable = match &value {
+                    KValue::List(list) => !list.is_empty(),
+                    KValue::Tuple(tuple) => !tuple.is_empty(),
+                    KValue::Map(map) => !map.is_empty(),
+                    _ => false,
+                };
+                let reference = if expandable {
+                    self.variables.push(Variables::Value(value.clone()));
+                    self.variables.len()
+                } else {
+                    0
+                };
+
+                json!({
+                    "name": name,
+                    "value": display_value(ctx, &value),
+                    "type": value.type_as_string().as_str(),
+                    "variablesReference": reference,
+                })
+            })
+            .collect()
+    }
+}
+
+// Returns the body of a `stackTrace` response
+fn stack_trace(ctx: &DebugContext) -> Value {
+    let frames: Vec<Value> = ctx
+        .call_stack()
+        .iter()
+        .enumerate()
+        .map(|(id, frame)| {
+            let chunk = &frame.chunk;
+            let debug_info = &chunk.debug_info;
+            let span = debug_info
+                .get_source_span(frame.instruction)
+                .unwrap_or_default();
+            let file_name = chunk
+                .path
+                .as_ref()
+                .and_then(|path| Path::new(path.as_str()).file_name())
+                .map(|name| name.to_string_lossy().to_string());
+
+            // Functions are named after the line where they're declared
+            let name = match debug_info.get_frame(frame.instruction) {
+                Some(frame_info) if frame_info.start_ip > 0 => debug_info
+                    .get_source_span(frame_info.start_ip)
+                    .and_then(|span| debug_info.source.lines().nth(span.start.line as usize))
+                    .map(|line| line.trim().to_string()),
+                _ => None,
+            }
+            .or_else(|| file_name.clone())
+            .unwrap_or_else(|| "main".into());
+
+            json!({
+                "id": id,
+                "name": name,
+                "source": chunk.path.as_ref().map(|path| json!({
+                    "name": file_name,
+                    "path": path.as_str(),
+                })),
+                "line": span.start.line + 1,
+                "column": span.start.column + 1,
+            })
+        })
+        .collect();
+
+    json!({"stackFrames": frames, "totalFrames": frames.len()})
+}
+
+fn display_value(ctx: &mut DebugContext, value: &KValue) -> String {
+    let mut result = ctx
+        .vm()
+        .value_to_string(value)
+        .unwrap_or_else(|error| format!("<{error}>"));
+
+    if let Some((index, _)) = result.char_indices().nth(MAX_VALUE_DISPLAY_LENGTH) {
+        result.truncate(index);
+        result.push_str("...");
+    }
+
+    result
+}
+
+// Forwards the script's output to the client as `output` events
+struct DapOutput {
+    category: &'static str,
+}
+
+impl DapOutput {
+    fn new(category: &'static str) -> Self {
+        Self { category }
+    }
+}
+
+impl KotoFile for DapOutput {
+    fn id(&self) -> KString {
+        format!("_{}_", self.category).into()
+    }
+}
+
+impl KotoRead for DapOutput {}
+impl KotoWrite for DapOutput {
+    fn write(&self, bytes: &[u8]) -> RuntimeResult<()> {
+        send_output(self.category, &String::from_utf8_lossy(bytes));
+        Ok(())
+    }
+
+    fn write_line(&self, text: &str) -> RuntimeResult<()> {
+        send_output(self.category, &format!("{text}\n"));
+        Ok(())
+    }
+
+    fn flush(&self) -> RuntimeResult<()> {
+        Ok(())
+    }
+}
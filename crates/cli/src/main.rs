@@ -1,17 +1,23 @@
-mod help;
+mod dap;
 mod repl;
 
 use anyhow::{Context, Result, bail};
 use crossterm::{terminal, tty::IsTty};
 use koto::{
     prelude::*,
-    runtime::{SystemStderr, SystemStdin, SystemStdout},
+    runtime::{ProfileReport, SystemStderr, SystemStdin, SystemStdout},
     serde::{from_koto_value, to_koto_value},
 };
 use koto_format::FormatOptions;
+use koto_tooling::module_search_paths;
 use repl::{EditMode, Repl, ReplSettings};
 use serde::{Deserialize, Serialize};
-use std::{env, error::Error, fs, io, path::PathBuf};
+use std::{
+    env,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 #[global_allocator]
 static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -29,7 +35,13 @@ FLAGS:
     -b, --show_bytecode      Show the script's compiled bytecode
     -t, --tests              Run the script's tests before running the script
     -T, --import_tests       Run the script's tests, along with any tests in imported modules
+    --coverage PATH          Writes code coverage in lcov format to PATH after running the script
+    --cache PATH             Caches compiled scripts in PATH, skipping compilation of unchanged scripts
+    --no_optimizations       Disables compiler optimizations like constant folding
+    --check                  Checks the script for likely mistakes and type errors without running it
     -f, --format             Formats the input, reading from the script path if given, or from stdin
+    -d, --dap                Runs a Debug Adapter Protocol server, communicating via stdin/stdout
+    -p, --profile            Profiles the script, writing a report and a collapsed stack file
     -c, --config PATH        Config file to load
     -C, --print_config       Prints the default config
     -v, --version            Prints version information
@@ -48,6 +60,8 @@ CONFIGURATION:
 
 ENV VARS:
     NO_COLOR     Disables colored output (enabled by default)
+    KOTO_PATH    Directories to search for imported modules, separated by the platform's
+                 path separator (':' on Unix, ';' on Windows)
 ",
         version = version_string()
     )
@@ -67,6 +81,12 @@ struct KotoArgs {
     show_bytecode: bool,
     show_instructions: bool,
     format: bool,
+    dap: bool,
+    profile: bool,
+    coverage_path: Option<String>,
+    cache_path: Option<String>,
+    no_optimizations: bool,
+    check: bool,
     script: Option<String>,
     script_args: Vec<String>,
     config_file: Option<String>,
@@ -82,6 +102,12 @@ fn parse_arguments() -> Result<KotoArgs> {
     let run_tests = args.contains(["-t", "--tests"]);
     let run_import_tests = args.contains(["-T", "--import_tests"]);
     let format = args.contains(["-f", "--format"]);
+    let dap = args.contains(["-d", "--dap"]);
+    let profile = args.contains(["-p", "--profile"]);
+    let coverage_path = args.opt_value_from_str("--coverage")?;
+    let cache_path = args.opt_value_from_str("--cache")?;
+    let no_optimizations = args.contains("--no_optimizations");
+    let check = args.contains("--check");
     let config_file = args.opt_value_from_str(["-c", "--config"])?;
     let print_config = args.contains(["-C", "--print_config"]);
     let help = args.contains(["-h", "--help"]);
@@ -108,6 +134,12 @@ fn parse_arguments() -> Result<KotoArgs> {
         show_bytecode,
         show_instructions,
         format,
+        dap,
+        profile,
+        coverage_path,
+        cache_path,
+        no_optimizations,
+        check,
         script,
         script_args,
         config_file,
@@ -137,10 +169,18 @@ fn main() -> Result<()> {
         return Config::print_default();
     }
 
+    if args.dap {
+        return dap::run_server();
+    }
+
     let koto_settings = KotoSettings {
         run_tests: args.run_tests || args.run_import_tests,
         vm_settings: KotoVmSettings {
             run_import_tests: args.run_import_tests,
+            profiling: args.profile,
+            coverage: args.coverage_path.is_some(),
+            bytecode_cache: args.cache_path.map(PathBuf::from),
+            module_search_paths: module_search_paths(),
             args: args.script_args,
             stdin: make_ptr!(SystemStdin::default()),
             stdout: make_ptr!(SystemStdout::default()),
@@ -195,9 +235,13 @@ fn main() -> Result<()> {
 
             match koto.compile(CompileArgs {
                 script: &script,
-                script_path: script_path.map(KString::from),
-                compiler_settings: Default::default(),
+                script_path: script_path.clone().map(KString::from),
+                compiler_settings: CompilerSettings {
+                    enable_optimizations: !args.no_optimizations,
+                    ..Default::default()
+                },
             }) {
+                Ok(_) if args.check => check_script(&koto, &script, script_path.as_deref())?,
                 Ok(chunk) => {
                     if args.show_bytecode {
                         println!("{}\n", &Chunk::bytes_as_string(&chunk));
@@ -211,7 +255,20 @@ fn main() -> Result<()> {
                             Chunk::instructions_as_string(chunk.clone(), &script_lines)
                         );
                     }
-                    match koto.run(chunk) {
+                    let result = koto.run(chunk);
+
+                    if let Some(report) = koto.profile_report() {
+                        write_profile(&report, script_path.as_deref())?;
+                    }
+
+                    if let (Some(report), Some(path)) =
+                        (koto.coverage_report(), &args.coverage_path)
+                    {
+                        fs::write(path, report.to_lcov())
+                            .with_context(|| format!("failed to write coverage to '{path}'"))?;
+                    }
+
+                    match result {
                         Ok(_) => {}
                         Err(error) if error.source().is_some() => {
                             bail!("{error}\n{}", error.source().unwrap())
@@ -245,6 +302,86 @@ fn main() -> Result<()> {
     }
 }
 
+// Checks the script for likely mistakes, printing a warning for each lint that's found,
+// and an error for each value that will fail a type check
+//
+// The script is expected to have been successfully compiled before being checked.
+fn check_script(koto: &Koto, script: &str, script_path: Option<&str>) -> Result<()> {
+    use koto::{
+        bytecode::{LintSettings, check_types, lint},
+        parser::{Parser, format_source_excerpt},
+    };
+
+    let ast = Parser::parse(script)?;
+    let prelude = koto
+        .prelude()
+        .data()
+        .keys()
+        .map(|key| key.to_string())
+        .collect();
+    let lints = lint(
+        &ast,
+        &LintSettings {
+            prelude: Some(prelude),
+        },
+    );
+
+    let type_errors = check_types(&ast);
+
+    let mut problems = lints
+        .iter()
+        .map(|lint| ("warning", lint.to_string(), lint.span))
+        .chain(
+            type_errors
+                .iter()
+                .map(|error| ("error", error.to_string(), error.span)),
+        )
+        .collect::<Vec<_>>();
+    problems.sort_by_key(|(_, _, span)| span.start);
+
+    for (kind, message, span) in problems.iter() {
+        eprintln!(
+            "{kind}: {message}\n{}\n",
+            format_source_excerpt(script, span, script_path)
+        );
+    }
+
+    let count = |count: usize, name: &str| match count {
+        1 => format!("1 {name}"),
+        count => format!("{count} {name}s"),
+    };
+    match (type_errors.len(), lints.len()) {
+        (0, 0) => Ok(()),
+        (0, warnings) => bail!("{} found", count(warnings, "warning")),
+        (errors, 0) => bail!("{} found", count(errors, "error")),
+        (errors, warnings) => bail!(
+            "{} and {} found",
+            count(errors, "error"),
+            count(warnings, "warning")
+        ),
+    }
+}
+
+// Writes the profiler's report and collapsed stacks to the current directory
+//
+// The files are named after the script, or `koto` if the script was provided as a string.
+fn write_profile(report: &ProfileReport, script_path: Option<&str>) -> Result<()> {
+    let name = script_path
+        .and_then(|path| Path::new(path).file_stem())
+        .map_or_else(|| "koto".into(), |stem| stem.to_string_lossy().to_string());
+
+    let report_path = format!("{name}.profile.txt");
+    fs::write(&report_path, report.to_string())
+        .with_context(|| format!("failed to write profile report to '{report_path}'"))?;
+
+    let stacks_path = format!("{name}.folded");
+    fs::write(&stacks_path, report.collapsed_stacks())
+        .with_context(|| format!("failed to write collapsed stacks to '{stacks_path}'"))?;
+
+    eprintln!("Profile written to '{report_path}' and '{stacks_path}'");
+    Ok(())
+}
+
 fn add_modules(koto: &Koto) {
     let prelude = koto.prelude();
     prelude.insert("color", koto_color::make_module());
@@ -8,13 +8,11 @@ use std::{
 use anyhow::Result;
 use crossterm::{execute, style, tty::IsTty};
 use koto::prelude::*;
+use koto_tooling::{HELP_INDENT, Help};
 use rustyline::{CompletionType, Config, Editor, error::ReadlineError, history::DefaultHistory};
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    help::{HELP_INDENT, Help},
-    wrap_string_with_indent, wrap_string_with_prefix,
-};
+use crate::{terminal_width, wrap_string_with_indent, wrap_string_with_prefix};
 
 macro_rules! print_wrapped_indented {
     ($stdout:expr, $indent:expr, $text:expr) => {
@@ -292,11 +290,14 @@ Run `help` for more information
     fn run_help(&mut self, input: &str) -> Option<String> {
         let input = input.trim();
         if input == "help" {
-            Some(help().get_help(None))
+            Some(help().get_help(None, terminal_width()))
         } else {
-            input
-                .strip_prefix("help ")
-                .map(|search_string| format!("\n{}\n", help().get_help(Some(search_string))))
+            input.strip_prefix("help ").map(|search_string| {
+                format!(
+                    "\n{}\n",
+                    help().get_help(Some(search_string), terminal_width())
+                )
+            })
         }
     }
 
@@ -0,0 +1,230 @@
+use koto_tooling::{read_message, write_message};
+use serde_json::{Value, json};
+use std::{
+    env, fs,
+    io::BufReader,
+    path::PathBuf,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+// A minimal DAP client that communicates with `koto --dap`
+struct DapClient {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: i64,
+    // The script output that has been received via output events
+    output: String,
+}
+
+impl DapClient {
+    fn new() -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_koto"))
+            .arg("--dap")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to execute child");
+
+        let stdin = process.stdin.take().expect("failed to get stdin");
+        let stdout = BufReader::new(process.stdout.take().expect("failed to get stdout"));
+
+        Self {
+            process,
+            stdin,
+            stdout,
+            seq: 0,
+            output: String::new(),
+        }
+    }
+
+    fn send_request(&mut self, command: &str, arguments: Value) -> i64 {
+        self.seq += 1;
+        let request = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+        write_message(&mut self.stdin, &request).expect("failed to write request");
+        self.seq
+    }
+
+    fn read_message(&mut self) -> Value {
+        let message = read_message(&mut self.stdout)
+            .expect("failed to read")
+            .expect("unexpected end of output");
+
+        if message["event"] == "output" {
+            self.output
+                .push_str(message["body"]["output"].as_str().unwrap());
+        }
+
+        message
+    }
+
+    // Sends a request and returns the body of the successful response
+    fn request(&mut self, command: &str, arguments: Value) -> Value {
+        let seq = self.send_request(command, arguments);
+        let response = self.wait_for(|message| message["request_seq"] == seq);
+        assert_eq!(response["success"], true, "{response}");
+        response["body"].clone()
+    }
+
+    fn wait_for_event(&mut self, event: &str) -> Value {
+        self.wait_for(|message| message["event"] == event)["body"].clone()
+    }
+
+    fn wait_for(&mut self, predicate: impl Fn(&Value) -> bool) -> Value {
+        loop {
+            let message = self.read_message();
+            if predicate(&message) {
+                return message;
+            }
+        }
+    }
+
+    fn finish(mut self) {
+        self.request("disconnect", Value::Null);
+        drop(self.stdin);
+        let status = self.process.wait().expect("failed to wait for process");
+        assert!(status.success());
+    }
+}
+
+// Writes a script to a temporary file, returning the file's path
+fn write_script(name: &str, script: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("koto_dap_{}_{name}.koto", std::process::id()));
+    fs::write(&path, script).expect("failed to write script");
+    fs::canonicalize(path).unwrap()
+}
+
+mod dap {
+    use super::*;
+
+    #[test]
+    fn breakpoint_and_variables() {
+        let script = "\
+x = 42
+y = [1, 2, 3]
+print x + y[2]
+";
+        let path = write_script("breakpoint", script);
+        let path_string = path.to_string_lossy().to_string();
+
+        let mut client = DapClient::new();
+        client.request("initialize", json!({"adapterID": "koto"}));
+        client.request("launch", json!({"program": path_string}));
+        let breakpoints = client.request(
+            "setBreakpoints",
+            json!({"source": {"path": path_string}, "breakpoints": [{"line": 3}]}),
+        );
+        assert_eq!(breakpoints["breakpoints"][0]["verified"], true);
+        client.request("configurationDone", Value::Null);
+
+        let stopped = client.wait_for_event("stopped");
+        assert_eq!(stopped["reason"], "breakpoint");
+
+        let stack_trace = client.request("stackTrace", json!({"threadId": 1}));
+        let frame = &stack_trace["stackFrames"][0];
+        assert_eq!(frame["line"], 3);
+        assert_eq!(frame["source"]["path"], path_string.as_str());
+
+        let scopes = client.request("scopes", json!({"frameId": frame["id"]}));
+        let reference = scopes["scopes"][0]["variablesReference"].clone();
+        let variables = client.request("variables", json!({"variablesReference": reference}));
+        let variables = variables["variables"].as_array().unwrap();
+        assert_eq!(variables[0]["name"], "x");
+        assert_eq!(variables[0]["value"], "42");
+        assert_eq!(variables[1]["name"], "y");
+        assert_eq!(variables[1]["type"], "List");
+
+        // The list can be expanded
+        let reference = variables[1]["variablesReference"].clone();
+        let elements = client.request("variables", json!({"variablesReference": reference}));
+        assert_eq!(elements["variables"][2]["name"], "[2]");
+        assert_eq!(elements["variables"][2]["value"], "3");
+
+        client.request("continue", json!({"threadId": 1}));
+        let exited = client.wait_for_event("exited");
+        assert_eq!(exited["exitCode"], 0);
+        client.wait_for_event("terminated");
+        assert_eq!(client.output, "45\n");
+
+        client.finish();
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn stop_on_entry_and_step() {
+        let script = "\
+f = |n|
+  n * 2
+x = f 1
+print x
+";
+        let path = write_script("step", script);
+        let path_string = path.to_string_lossy().to_string();
+
+        let mut client = DapClient::new();
+        client.request("initialize", json!({"adapterID": "koto"}));
+        client.request(
+            "launch",
+            json!({"program": path_string, "stopOnEntry": true}),
+        );
+        client.request("configurationDone", Value::Null);
+
+        let stopped = client.wait_for_event("stopped");
+        assert_eq!(stopped["reason"], "entry");
+
+        let current_line = |client: &mut DapClient| {
+            let stack_trace = client.request("stackTrace", json!({"threadId": 1}));
+            stack_trace["stackFrames"][0]["line"].clone()
+        };
+        assert_eq!(current_line(&mut client), 1);
+
+        client.request("next", json!({"threadId": 1}));
+        assert_eq!(client.wait_for_event("stopped")["reason"], "step");
+        assert_eq!(current_line(&mut client), 3);
+
+        client.request("stepIn", json!({"threadId": 1}));
+        client.wait_for_event("stopped");
+        let stack_trace = client.request("stackTrace", json!({"threadId": 1}));
+        assert_eq!(stack_trace["totalFrames"], 2);
+
+        client.request("stepOut", json!({"threadId": 1}));
+        client.wait_for_event("stopped");
+        let stack_trace = client.request("stackTrace", json!({"threadId": 1}));
+        assert_eq!(stack_trace["totalFrames"], 1);
+
+        client.request("continue", json!({"threadId": 1}));
+        client.wait_for_event("terminated");
+        assert_eq!(client.output, "2\n");
+
+        client.finish();
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn terminate_while_paused() {
+        let path = write_script("terminate", "print 'hello'\n");
+        let path_string = path.to_string_lossy().to_string();
+
+        let mut client = DapClient::new();
+        client.request("initialize", json!({"adapterID": "koto"}));
+        client.request(
+            "launch",
+            json!({"program": path_string, "stopOnEntry": true}),
+        );
+        client.request("configurationDone", Value::Null);
+        client.wait_for_event("stopped");
+
+        client.request("terminate", Value::Null);
+        client.wait_for_event("terminated");
+        assert_eq!(client.output, "");
+
+        client.finish();
+        fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,62 @@
+use std::{env, process::Command};
+
+// Runs the script with `--show_instructions`, returning the instructions section of the output
+fn show_instructions(script: &str, extra_args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+        .arg("--show_instructions")
+        .args(extra_args)
+        .arg("--eval")
+        .arg(script)
+        .output()
+        .expect("failed to execute child");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("Failed to get output");
+    stdout
+        .split_once("Instructions\n------------\n")
+        .expect("Missing instructions in output")
+        .1
+        .to_string()
+}
+
+fn instruction_count(instructions: &str) -> usize {
+    instructions
+        .lines()
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_digit()))
+        .count()
+}
+
+mod instructions {
+    use super::*;
+
+    #[test]
+    fn constant_folding() {
+        let script = "print 1 + 2 * 3";
+
+        let optimized = show_instructions(script, &[]);
+        let unoptimized = show_instructions(script, &["--no_optimizations"]);
+
+        assert!(optimized.contains("SetNumber       result: 4       value: 7"));
+        assert!(!optimized.contains("Add"));
+        assert!(!optimized.contains("Multiply"));
+        assert!(unoptimized.contains("Add"));
+        assert!(unoptimized.contains("Multiply"));
+        assert!(instruction_count(&optimized) < instruction_count(&unoptimized));
+    }
+
+    #[test]
+    fn dead_branch_elimination() {
+        let script = "
+const DEBUG = false
+x = if DEBUG and 1 > 2 then 'debug' else 'release'
+print x
+";
+
+        let optimized = show_instructions(script, &[]);
+        let unoptimized = show_instructions(script, &["--no_optimizations"]);
+
+        assert!(!optimized.contains("Jump"));
+        assert!(unoptimized.contains("JumpIfFalse"));
+        assert!(instruction_count(&optimized) < instruction_count(&unoptimized));
+    }
+}
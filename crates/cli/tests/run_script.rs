@@ -1,4 +1,5 @@
 use std::{
+    env, fs,
     io::Write,
     iter,
     path::PathBuf,
@@ -89,6 +90,110 @@ mod cli {
         }
     }
 
+    mod koto_path {
+        use super::*;
+        use tempfile::TempDir;
+
+        #[test]
+        fn import_from_search_path() {
+            let dir = TempDir::new().unwrap();
+            let lib_a = dir.path().join("lib_a");
+            let lib_b = dir.path().join("lib_b");
+            let app = dir.path().join("app");
+            for path in [&lib_a, &lib_b, &app] {
+                fs::create_dir_all(path).unwrap();
+            }
+
+            // Search paths are checked in order
+            fs::write(
+                lib_a.join("greet.koto"),
+                "export greet = |name| 'hello, {name}!'",
+            )
+            .unwrap();
+            fs::write(
+                lib_b.join("greet.koto"),
+                "export greet = |name| 'bye, {name}!'",
+            )
+            .unwrap();
+            fs::create_dir_all(lib_b.join("answer")).unwrap();
+            fs::write(lib_b.join("answer").join("main.koto"), "export answer = 42").unwrap();
+
+            let script_path = app.join("main.koto");
+            fs::write(
+                &script_path,
+                "
+from greet import greet
+from answer import answer
+print greet 'koto'
+print answer
+",
+            )
+            .unwrap();
+
+            check_output(
+                test_bin::get_test_bin!("koto")
+                    .arg(&script_path)
+                    .env("KOTO_PATH", env::join_paths([&lib_a, &lib_b]).unwrap())
+                    .output()
+                    .expect("Failed to run CLI"),
+                "hello, koto!\n42\n",
+                "",
+            );
+        }
+    }
+
+    mod check {
+        use super::*;
+
+        #[test]
+        fn script_without_warnings() {
+            check_output(
+                test_bin::get_test_bin!("koto")
+                    .args(["--check", "--eval", "x = 1\nprint x"])
+                    .output()
+                    .expect("Failed to run CLI"),
+                "",
+                "",
+            );
+        }
+
+        #[test]
+        fn warnings_are_reported() {
+            let output = test_bin::get_test_bin!("koto")
+                .args(["--check", "--eval", "x = 1\nprint y"])
+                .env("RUST_BACKTRACE", "0")
+                .output()
+                .expect("Failed to run CLI");
+
+            // The script isn't run
+            assert!(output.stdout.is_empty());
+            assert!(!output.status.success());
+
+            let stderr = str::from_utf8(&output.stderr).expect("Failed to read stderr");
+            assert!(stderr.contains("warning: unused variable 'x'"), "{stderr}");
+            assert!(stderr.contains("warning: 'y' is not defined"), "{stderr}");
+            assert!(stderr.contains("2 warnings found"), "{stderr}");
+        }
+
+        #[test]
+        fn type_errors_are_reported() {
+            let output = test_bin::get_test_bin!("koto")
+                .args(["--check", "--eval", "f = |x: Number| x\nf 'hello'"])
+                .env("RUST_BACKTRACE", "0")
+                .output()
+                .expect("Failed to run CLI");
+
+            assert!(!output.status.success());
+
+            let stderr = str::from_utf8(&output.stderr).expect("Failed to read stderr");
+            assert!(
+                stderr.contains("error: expected a value of type 'Number', found 'String'"),
+                "{stderr}"
+            );
+            assert!(stderr.contains("1 error found"), "{stderr}");
+        }
+    }
+
     mod piped_input {
         use super::*;
 
@@ -504,6 +504,152 @@ fn format_node<'source>(
                 .node(*expression)
                 .build()
         }
+        Node::Const { target, expression } => GroupBuilder::new(5, node, ctx, trivia)
+            .str("const ")
+            .node(*target)
+            .space_or_indent_if_necessary()
+            .char('=')
+            .space_or_indent_respecting_existing_break(target, expression)
+            .node(*expression)
+            .build(),
+        Node::Record { id, fields, braces } => {
+            if *braces {
+                let span = ctx.span(node);
+                let force_break = span.start.line < span.end.line;
+
+                let mut group = GroupBuilder::new(fields.len() * 2 + 6, node, ctx, trivia)
+                    .str("record ")
+                    .node(*id)
+                    .char(' ')
+                    .char('{')
+                    .maybe_force_indent(force_break);
+
+                let mut previous_line = span.start.line;
+                for (i, field) in fields.iter().enumerate() {
+                    let field_start_line = ctx.span(ctx.node(*field)).start.line;
+
+                    if i > 0 {
+                        if field_start_line > previous_line {
+                            group = group.indented_break();
+                        } else {
+                            group = group.space_or_indent_if_necessary();
+                        }
+                    }
+                    previous_line = field_start_line;
+
+                    group = group.node(*field);
+                    if i < fields.len() - 1 {
+                        group = group.char(',');
+                    } else {
+                        group = group.maybe_char(',');
+                    }
+                }
+
+                group.maybe_return().char('}').build()
+            } else {
+                GroupBuilder::new(3, node, ctx, trivia)
+                    .str("record ")
+                    .node(*id)
+                    .nested(fields.len() * 3, node, |mut nested| {
+                        nested = nested.start_block();
+
+                        for field in fields.iter() {
+                            nested = nested
+                                .line_start(*field)
+                                .node(*field)
+                                .add_trailing_trivia()
+                                .line_break();
+                        }
+
+                        nested.build_block()
+                    })
+                    .build()
+            }
+        }
+        Node::Enum {
+            id,
+            variants,
+            braces,
+        } => {
+            if *braces {
+                let span = ctx.span(node);
+                let force_break = span.start.line < span.end.line;
+
+                let mut group = GroupBuilder::new(variants.len() * 2 + 6, node, ctx, trivia)
+                    .str("enum ")
+                    .node(*id)
+                    .char(' ')
+                    .char('{')
+                    .maybe_force_indent(force_break);
+
+                let mut previous_line = span.start.line;
+                for (i, variant) in variants.iter().enumerate() {
+                    let variant_start_line = ctx.span(ctx.node(*variant)).start.line;
+
+                    if i > 0 {
+                        if variant_start_line > previous_line {
+                            group = group.indented_break();
+                        } else {
+                            group = group.space_or_indent_if_necessary();
+                        }
+                    }
+                    previous_line = variant_start_line;
+
+                    group = group.node(*variant);
+                    if i < variants.len() - 1 {
+                        group = group.char(',');
+                    } else {
+                        group = group.maybe_char(',');
+                    }
+                }
+
+                group.maybe_return().char('}').build()
+            } else {
+                GroupBuilder::new(3, node, ctx, trivia)
+                    .str("enum ")
+                    .node(*id)
+                    .nested(variants.len() * 3, node, |mut nested| {
+                        nested = nested.start_block();
+
+                        for variant in variants.iter() {
+                            nested = nested
+                                .line_start(*variant)
+                                .node(*variant)
+                                .add_trailing_trivia()
+                                .line_break();
+                        }
+
+                        nested.build_block()
+                    })
+                    .build()
+            }
+        }
+        Node::EnumVariant { id, fields } => {
+            if fields.is_empty() {
+                GroupBuilder::new(1, node, ctx, trivia)
+                    .string_constant(*id)
+                    .build()
+            } else {
+                GroupBuilder::new(fields.len() * 3 + 3, node, ctx, trivia)
+                    .string_constant(*id)
+                    .char('(')
+                    .list_elements(fields)
+                    .char(')')
+                    .build()
+            }
+        }
+        Node::EnumPattern {
+            enum_id,
+            variant,
+            payload,
+        } => GroupBuilder::new(payload.len() * 3 + 5, node, ctx, trivia)
+            .node(*enum_id)
+            .char('.')
+            .string_constant(*variant)
+            .char('(')
+            .list_elements(payload)
+            .char(')')
+            .build(),
         Node::MultiAssign {
             targets,
             expression,
@@ -833,6 +979,7 @@ fn format_node<'source>(
         }
         Node::Throw(value) => FormatItem::from_keyword_and_value("throw", value, node, ctx, trivia),
         Node::Yield(value) => FormatItem::from_keyword_and_value("yield", value, node, ctx, trivia),
+        Node::Await(value) => FormatItem::from_keyword_and_value("await", value, node, ctx, trivia),
         Node::Debug { expression, .. } => {
             FormatItem::from_keyword_and_value("debug", expression, node, ctx, trivia)
         }
@@ -1312,11 +1459,10 @@ impl<'source, 'trivia> GroupBuilder<'source, 'trivia> {
                 }
 
                 match position_info {
-                    TriviaPosition::LineStart => {
-                        if item.span.end.line < position.line {
-                            self.group_break(GroupBreak::LineStart);
-                        }
+                    TriviaPosition::LineStart if item.span.end.line < position.line => {
+                        self.group_break(GroupBreak::LineStart);
                     }
+                    TriviaPosition::LineStart => {}
                     _ if self.items.last().is_some_and(|item| !item.is_break()) => {
                         self.group_break(GroupBreak::SpaceOrIndentIfNecessary);
                     }
@@ -1512,10 +1658,8 @@ impl<'source> FormatItem<'source> {
                                 output.push('\n');
                                 group_break = GroupBreak::None;
                             }
-                            GroupBreak::IndentedBreak => {
-                                if indented {
-                                    group_break = GroupBreak::MaybeIndent;
-                                }
+                            GroupBreak::IndentedBreak if indented => {
+                                group_break = GroupBreak::MaybeIndent;
                             }
                             _ => {}
                         }
@@ -344,6 +344,80 @@ let   x  :   String   =   'hello'
 "],
                 "\
 let x: String = 'hello'
+",
+            );
+        }
+
+        #[test]
+        fn const_declaration() {
+            check_format_output(
+                &["
+const   x  :   Number   =   60*60
+"],
+                "\
+const x: Number = 60 * 60
+",
+            );
+        }
+
+        #[test]
+        fn record_declaration_in_block() {
+            check_format_output(
+                &["
+record   Point
+     x :  Number
+     # The y field
+     y:Number=0
+"],
+                "\
+record Point
+  x: Number
+  # The y field
+  y: Number = 0
+",
+            );
+        }
+
+        #[test]
+        fn record_declaration_in_braces() {
+            check_format_output(
+                &["
+record Point{  x:Number,y=1+1 ,}
+"],
+                "\
+record Point {x: Number, y = 1 + 1}
+",
+            );
+        }
+
+        #[test]
+        fn enum_declaration_in_block() {
+            check_format_output(
+                &["
+enum   Shape
+     Circle(  radius )
+     # A rectangle
+     Rect(width,height)
+     Empty
+"],
+                "\
+enum Shape
+  Circle(radius)
+  # A rectangle
+  Rect(width, height)
+  Empty
+",
+            );
+        }
+
+        #[test]
+        fn enum_declaration_in_braces() {
+            check_format_output(
+                &["
+enum Shape{  Circle(radius),Empty ,}
+"],
+                "\
+enum Shape {Circle(radius), Empty}
 ",
             );
         }
@@ -973,6 +1047,26 @@ match foo() # abc
             );
         }
 
+        #[test]
+        fn match_enum_patterns() {
+            check_format_output(
+                &["
+match   shape
+  Shape.Circle(  r )   then r
+  Shape.Rect(w,  _) if w > 0 then w
+  Shape.Empty then 0
+  else   -1
+"],
+                "\
+match shape
+  Shape.Circle(r) then r
+  Shape.Rect(w, _) if w > 0 then w
+  Shape.Empty then 0
+  else -1
+",
+            );
+        }
+
         #[test]
         fn try_catch_finally() {
             check_format_output_with_options(
@@ -1414,6 +1508,18 @@ f = |
             );
         }
 
+        #[test]
+        fn await_expression() {
+            check_format_output(
+                &["\
+x   =   await    fetch   'foo'
+"],
+                "\
+x = await fetch 'foo'
+",
+            );
+        }
+
         #[test]
         fn return_tuple() {
             check_format_output(
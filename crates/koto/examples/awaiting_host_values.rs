@@ -0,0 +1,35 @@
+use koto::{Result, prelude::*, runtime::ReturnOrAwait};
+
+fn main() -> Result<()> {
+    let script = "
+a = await fetch 'a'
+b = await fetch 'b'
+'{a} and {b}'
+";
+    let mut koto = Koto::default();
+
+    // `fetch` returns a pending value rather than blocking while the request is performed
+    koto.prelude().add_fn("fetch", |ctx| match ctx.args() {
+        [KValue::Str(name)] => Ok(KPending::new(name.clone()).into()),
+        unexpected => unexpected_args("|String|", unexpected),
+    });
+
+    let chunk = koto.compile(script)?;
+    let mut output = koto.run_resumable(chunk)?;
+
+    let result = loop {
+        match output {
+            ReturnOrAwait::Await(pending) => {
+                // An async host would await its own future here, resuming the script once the
+                // result is available.
+                let name = koto.value_to_string(pending.data().clone())?;
+                output = koto.resume(format!("result for '{name}'"))?;
+            }
+            ReturnOrAwait::Return(result) => break result,
+        }
+    };
+
+    println!("{}", koto.value_to_string(result)?);
+
+    Ok(())
+}
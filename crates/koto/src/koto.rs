@@ -1,7 +1,11 @@
 use crate::{Error, Ptr, Result, prelude::*};
-use koto_bytecode::CompilerSettings;
-use koto_runtime::{ModuleImportedCallback, SystemStderr, SystemStdin, SystemStdout};
-use std::time::Duration;
+use koto_bytecode::{CompilerSettings, ModuleResolver};
+use koto_runtime::{
+    Breakpoint, Capabilities, CoverageReport, DebugPauseHandle, InterruptHandle, KotoDebugger,
+    KotoFileSystem, ModuleImportedCallback, ProfileReport, ReturnOrAwait, SystemStderr,
+    SystemStdin, SystemStdout,
+};
+use std::{path::PathBuf, time::Duration};
 
 /// The main interface for the Koto language.
 ///
@@ -118,6 +122,30 @@ impl Koto {
         }
     }
 
+    /// Runs a compiled script, allowing execution to be suspended by `await` expressions
+    ///
+    /// When a [KPending] value is awaited, the run is suspended and the pending value is returned.
+    /// Execution can then be continued with [Koto::resume] once the awaited result is available.
+    ///
+    /// Unlike [Koto::run], tests and `@main` aren't run after the script has completed.
+    ///
+    /// See [KotoVm::run_resumable] for more information.
+    pub fn run_resumable(&mut self, chunk: Ptr<Chunk>) -> Result<ReturnOrAwait> {
+        self.runtime.run_resumable(chunk).map_err(From::from)
+    }
+
+    /// Resumes a suspended run with the result of the awaited pending value
+    pub fn resume(&mut self, value: impl Into<KValue>) -> Result<ReturnOrAwait> {
+        self.runtime.resume(value).map_err(From::from)
+    }
+
+    /// Resumes a suspended run, throwing an error from the `await` expression
+    pub fn resume_with_error(&mut self, thrown_value: impl Into<KValue>) -> Result<ReturnOrAwait> {
+        self.runtime
+            .resume_with_error(thrown_value)
+            .map_err(From::from)
+    }
+
     /// Calls a function with the given arguments
     ///
     /// If the provided value isn't [callable](KValue::is_callable) then an error will be returned.
@@ -172,6 +200,75 @@ impl Koto {
         self.runtime.loader().borrow_mut().clear_cache();
     }
 
+    /// Returns the amount of fuel that remains available for execution
+    ///
+    /// `None` will be returned if fuel metering isn't enabled, see [KotoSettings::with_fuel].
+    pub fn remaining_fuel(&self) -> Option<u64> {
+        self.runtime.remaining_fuel()
+    }
+
+    /// Adds fuel to the runtime, allowing further instructions to be executed
+    ///
+    /// This has no effect if fuel metering isn't enabled, see [KotoSettings::with_fuel].
+    pub fn add_fuel(&mut self, amount: u64) {
+        self.runtime.add_fuel(amount);
+    }
+
+    /// Returns a handle that can be used to interrupt the runtime from another thread
+    ///
+    /// See [InterruptHandle] for more information.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.runtime.interrupt_handle()
+    }
+
+    /// Adds a breakpoint that will pause execution when its line is reached
+    ///
+    /// Breakpoints have no effect if a debugger isn't attached, see [KotoSettings::with_debugger].
+    pub fn set_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.runtime.set_breakpoint(breakpoint);
+    }
+
+    /// Removes a previously added breakpoint
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) {
+        self.runtime.remove_breakpoint(breakpoint);
+    }
+
+    /// Removes all breakpoints
+    pub fn clear_breakpoints(&mut self) {
+        self.runtime.clear_breakpoints();
+    }
+
+    /// Returns a handle that can be used to request a debug pause from another thread
+    ///
+    /// `None` is returned if a debugger isn't attached, see [KotoSettings::with_debugger].
+    pub fn debug_pause_handle(&self) -> Option<DebugPauseHandle> {
+        self.runtime.debug_pause_handle()
+    }
+
+    /// Returns a report containing the information that's been recorded by the profiler
+    ///
+    /// `None` is returned if profiling isn't enabled, see [KotoSettings::with_profiling].
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.runtime.profile_report()
+    }
+
+    /// Clears the information that's been recorded by the profiler
+    pub fn reset_profile(&mut self) {
+        self.runtime.reset_profile();
+    }
+
+    /// Returns a report containing the code coverage that's been recorded
+    ///
+    /// `None` is returned if coverage tracking isn't enabled, see [KotoSettings::with_coverage].
+    pub fn coverage_report(&self) -> Option<CoverageReport> {
+        self.runtime.coverage_report()
+    }
+
+    /// Clears the code coverage that's been recorded
+    pub fn reset_coverage(&mut self) {
+        self.runtime.reset_coverage();
+    }
+
     /// Enables or disables the `run_tests` setting
     ///
     /// Currently this is only used when running benchmarks where tests are run once during setup,
@@ -232,6 +329,163 @@ impl KotoSettings {
         }
     }
 
+    /// Helper for conveniently defining the amount of fuel that's available for execution
+    ///
+    /// See [KotoVmSettings::fuel].
+    #[must_use]
+    pub fn with_fuel(self, fuel: u64) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                fuel: Some(fuel),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a memory budget for values built by the runtime
+    ///
+    /// See [KotoVmSettings::memory_limit].
+    #[must_use]
+    pub fn with_memory_limit(self, limit_in_bytes: usize) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                memory_limit: Some(limit_in_bytes),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a limit on the depth of the runtime's call stack
+    ///
+    /// See [KotoVmSettings::max_call_depth].
+    #[must_use]
+    pub fn with_max_call_depth(self, max_call_depth: usize) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                max_call_depth: Some(max_call_depth),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining the capabilities that are available to scripts
+    ///
+    /// See [KotoVmSettings::capabilities].
+    #[must_use]
+    pub fn with_capabilities(self, capabilities: Capabilities) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                capabilities,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently attaching a debugger to the runtime
+    ///
+    /// See [KotoVmSettings::debugger].
+    #[must_use]
+    pub fn with_debugger(self, debugger: impl KotoDebugger + 'static) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                debugger: Some(Box::new(debugger)),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently enabling the profiler
+    ///
+    /// See [KotoVmSettings::profiling].
+    #[must_use]
+    pub fn with_profiling(self) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                profiling: true,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently enabling code coverage tracking
+    ///
+    /// See [KotoVmSettings::coverage].
+    #[must_use]
+    pub fn with_coverage(self) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                coverage: true,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a custom module resolver
+    ///
+    /// See [KotoVmSettings::module_resolver].
+    #[must_use]
+    pub fn with_module_resolver(self, resolver: impl ModuleResolver + 'static) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                module_resolver: make_ptr!(resolver),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining the file system that's used by the `io` module
+    ///
+    /// See [KotoVmSettings::file_system].
+    #[must_use]
+    pub fn with_file_system(self, file_system: impl KotoFileSystem + 'static) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                file_system: make_ptr!(file_system),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining the directories that are searched for modules
+    ///
+    /// See [KotoVmSettings::module_search_paths].
+    #[must_use]
+    pub fn with_module_search_paths(
+        self,
+        search_paths: impl IntoIterator<Item: Into<PathBuf>>,
+    ) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                module_search_paths: search_paths.into_iter().map(Into::into).collect(),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a directory for caching compiled scripts
+    ///
+    /// See [KotoVmSettings::bytecode_cache].
+    #[must_use]
+    pub fn with_bytecode_cache(self, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                bytecode_cache: Some(cache_dir.into()),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
     /// Helper for conveniently defining custom args
     #[must_use]
     pub fn with_args(self, args: impl IntoIterator<Item: Into<String>>) -> Self {
@@ -345,6 +599,12 @@ impl<'a> CompileArgs<'a> {
         self.compiler_settings.export_top_level_ids = enabled;
         self
     }
+
+    /// Sets the [`CompilerSettings::enable_optimizations`] flag, enabled by default.
+    pub fn enable_optimizations(mut self, enabled: bool) -> Self {
+        self.compiler_settings.enable_optimizations = enabled;
+        self
+    }
 }
 
 impl<'a> From<&'a str> for CompileArgs<'a> {
@@ -1,5 +1,5 @@
 //! A collection of useful items to make it easier to work with `koto`
 
 pub use crate::{CompileArgs, Koto, KotoSettings};
-pub use koto_bytecode::{Chunk, CompilerSettings, ModuleLoader, ModuleLoaderError};
+pub use koto_bytecode::{Chunk, CompilerSettings, ModuleLoader, ModuleLoaderError, ModuleResolver};
 pub use koto_runtime::prelude::*;
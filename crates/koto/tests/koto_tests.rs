@@ -98,12 +98,14 @@ mod koto_tests {
     use super::*;
 
     koto_test!(comments);
+    koto_test!(consts);
     koto_test!(enums);
     koto_test!(io);
     koto_test!(load_and_run);
     koto_test!(meta_maps);
     koto_test!(os);
     koto_test!(primes);
+    koto_test!(records);
 
     koto_test!(error_handling, "error_handling_module/main.koto");
     koto_test!(import, "test_module/baz.koto", "test_module/main.koto");
@@ -0,0 +1,803 @@
+//! Analysis of parsed scripts, providing the information that's needed by the server's requests
+
+use koto_parser::{
+    AstIndex, AstString, AstTry, ChainNode, ConstantIndex, ImportItem, Node, Position, Span,
+    StringContents, StringNode,
+};
+
+// Functions that are available in the prelude, along with the modules that they come from
+const PRELUDE_FUNCTIONS: &[(&str, &str)] = &[
+    ("assert", "test"),
+    ("assert_eq", "test"),
+    ("assert_ne", "test"),
+    ("assert_near", "test"),
+    ("print", "io"),
+    ("copy", "koto"),
+    ("size", "koto"),
+    ("type", "koto"),
+];
+
+/// A value that's defined in a script
+pub struct Definition {
+    /// The name of the defined value
+    pub name: String,
+    /// The span of the definition's id
+    pub span: Span,
+    // The scope that contains the definition
+    scope: usize,
+    // If the value was imported, then the path of the imported item, e.g. `["foo", "bar"]`
+    import_path: Option<Vec<String>>,
+    // True if the definition is exported from the module's top level
+    exported: bool,
+}
+
+// An access of a value in a script
+//
+// Chained `.` accesses produce a reference for each id in the chain, e.g. `foo.bar` produces a
+// reference to `foo`, followed by a reference to `foo.bar`.
+struct Reference {
+    // The span of the accessed id
+    span: Span,
+    // The scope that contains the reference
+    scope: usize,
+    // The path of the accessed value
+    path: Vec<String>,
+    // True if the path's root is a module name rather than a value in scope, e.g. in imports
+    absolute: bool,
+}
+
+/// The result of resolving a reference, see [Analysis::resolve]
+pub enum Resolved<'a> {
+    /// A value that's defined in the script
+    Local(&'a Definition),
+    /// An item that's been imported from a module, e.g. `["foo", "bar"]`
+    Import(Vec<String>),
+    /// A value that isn't defined in the script, e.g. a core library module
+    Global(Vec<String>),
+}
+
+impl Resolved<'_> {
+    /// Returns the name of the item's help entry, if the item refers to a module item
+    pub fn help_name(&self) -> Option<String> {
+        match self {
+            Self::Local(_) => None,
+            Self::Import(path) | Self::Global(path) => match path.as_slice() {
+                [name] => PRELUDE_FUNCTIONS
+                    .iter()
+                    .find(|(function, _)| function == name)
+                    .map(|(function, module)| format!("{module}.{function}"))
+                    .or_else(|| Some(name.clone())),
+                _ => Some(path.join(".")),
+            },
+        }
+    }
+}
+
+/// The kind of a [Symbol]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Constant,
+    Enum,
+    EnumMember,
+    Field,
+    Function,
+    Method,
+    Object,
+    Struct,
+    Variable,
+}
+
+/// A named item in a script, e.g. a function or a variable
+pub struct Symbol {
+    /// The symbol's name
+    pub name: String,
+    /// The kind of the symbol
+    pub kind: SymbolKind,
+    /// The full span of the symbol's definition
+    pub span: Span,
+    /// The span of the symbol's id
+    pub selection_span: Span,
+    /// Symbols that are contained in the symbol, e.g. the entries in a map
+    pub children: Vec<Symbol>,
+}
+
+/// The definitions, references, and symbols that are contained in a script
+#[derive(Default)]
+pub struct Analysis {
+    definitions: Vec<Definition>,
+    references: Vec<Reference>,
+    symbols: Vec<Symbol>,
+    // The parent of each scope, the script's top level is scope 0
+    scopes: Vec<Option<usize>>,
+}
+
+impl Analysis {
+    /// Analyzes a parsed script
+    pub fn new(ast: &koto_parser::Ast) -> Self {
+        let mut walker = Walker {
+            ast,
+            scope: 0,
+            exporting: false,
+            analysis: Self {
+                scopes: vec![None],
+                ..Self::default()
+            },
+        };
+
+        if let Some(entry_point) = ast.entry_point() {
+            walker.visit(entry_point);
+            walker.analysis.symbols = symbols_in_body(ast, entry_point);
+        }
+
+        walker.analysis
+    }
+
+    /// The symbols that are defined in the script
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Returns the definition with the given name that's exported from the script
+    ///
+    /// If the name isn't explicitly exported then the first top-level definition is returned.
+    pub fn exported(&self, name: &str) -> Option<&Definition> {
+        let mut top_level = self
+            .definitions
+            .iter()
+            .filter(|definition| definition.scope == 0 && definition.name == name);
+        top_level
+            .clone()
+            .find(|definition| definition.exported)
+            .or_else(|| top_level.next())
+    }
+
+    /// Returns the definition whose id contains the given position
+    pub fn definition_at(&self, position: Position) -> Option<&Definition> {
+        smallest_containing(&self.definitions, position, |definition| definition.span)
+    }
+
+    /// Resolves the value that's accessed at the given position
+    pub fn resolve(&self, position: Position) -> Option<Resolved<'_>> {
+        let reference = smallest_containing(&self.references, position, |r| r.span)?;
+
+        if reference.absolute {
+            return Some(Resolved::Import(reference.path.clone()));
+        }
+
+        let result = match self.find_definition(&reference.path[0], reference) {
+            Some(definition) => match &definition.import_path {
+                Some(import_path) => Resolved::Import(
+                    import_path
+                        .iter()
+                        .chain(&reference.path[1..])
+                        .cloned()
+                        .collect(),
+                ),
+                None if reference.path.len() == 1 => Resolved::Local(definition),
+                // Accesses of a local value's entries aren't resolved
+                None => return None,
+            },
+            None => Resolved::Global(reference.path.clone()),
+        };
+
+        Some(result)
+    }
+
+    // Finds the definition that a reference refers to
+    //
+    // The scopes containing the reference are searched from innermost to outermost, looking for
+    // the latest definition that precedes the reference. If a preceding definition isn't found,
+    // then the first definition in any of the scopes is used, which allows values that are
+    // defined later (e.g. mutually recursive exported functions) to be found.
+    fn find_definition(&self, name: &str, reference: &Reference) -> Option<&Definition> {
+        let scopes = || {
+            std::iter::successors(Some(reference.scope), |scope| self.scopes[*scope]).map(|scope| {
+                self.definitions
+                    .iter()
+                    .filter(move |definition| definition.scope == scope && definition.name == name)
+            })
+        };
+
+        scopes()
+            .find_map(|mut definitions| {
+                definitions.rfind(|definition| definition.span.start <= reference.span.start)
+            })
+            .or_else(|| scopes().find_map(|mut definitions| definitions.next()))
+    }
+}
+
+// Returns the item with the smallest span that contains the position
+fn smallest_containing<T>(
+    items: &[T],
+    position: Position,
+    span: impl Fn(&T) -> Span,
+) -> Option<&T> {
+    items
+        .iter()
+        .filter(|item| {
+            let span = span(item);
+            span.start <= position && position <= span.end
+        })
+        .min_by_key(|item| {
+            let span = span(item);
+            (
+                span.end.line - span.start.line,
+                span.end.column.abs_diff(span.start.column),
+            )
+        })
+}
+
+// Walks the AST, collecting definitions and references
+struct Walker<'a> {
+    ast: &'a koto_parser::Ast,
+    scope: usize,
+    exporting: bool,
+    analysis: Analysis,
+}
+
+impl Walker<'_> {
+    fn visit(&mut self, index: AstIndex) {
+        let ast = self.ast;
+        let node = ast.node(index);
+
+        match &node.node {
+            Node::Nested(expression)
+            | Node::RangeFrom { start: expression }
+            | Node::RangeTo {
+                end: expression, ..
+            }
+            | Node::PackedExpression(expression)
+            | Node::Loop { body: expression }
+            | Node::Throw(expression)
+            | Node::Yield(expression)
+            | Node::Await(expression)
+            | Node::MapEntry(_, expression)
+            | Node::SwitchArm {
+                condition: None,
+                expression,
+            }
+            | Node::Debug { expression, .. }
+            | Node::Break(Some(expression))
+            | Node::Return(Some(expression)) => self.visit(*expression),
+            Node::Id(constant, _) => self.add_reference(*constant, node.span),
+            Node::Chain(_) => self.visit_chain(index),
+            Node::Str(string) => self.visit_string(string),
+            Node::List(elements)
+            | Node::TempTuple(elements)
+            | Node::Tuple { elements, .. }
+            | Node::MainBlock { body: elements, .. }
+            | Node::Block(elements)
+            | Node::Switch(elements) => self.visit_all(elements),
+            Node::Range { start, end, .. } => {
+                self.visit(*start);
+                self.visit(*end);
+            }
+            Node::Map { entries, .. } => {
+                for entry in entries {
+                    // Entries without values are references to values with the same name
+                    match &ast.node(*entry).node {
+                        Node::MapEntry(key, value) => {
+                            self.visit_map_key(*key);
+                            self.visit(*value);
+                        }
+                        _ => self.visit(*entry),
+                    }
+                }
+            }
+            Node::Function(function) => {
+                let parent_scope = self.scope;
+                self.scope = self.analysis.scopes.len();
+                self.analysis.scopes.push(Some(parent_scope));
+
+                if let Node::FunctionArgs { args, .. } = &ast.node(function.args).node {
+                    for arg in args {
+                        self.visit_pattern(*arg);
+                    }
+                }
+                self.visit(function.body);
+
+                self.scope = parent_scope;
+            }
+            Node::Import { from, items } => self.visit_import(from, items),
+            Node::Export(expression) => match &ast.node(*expression).node {
+                Node::Map { entries, .. } => {
+                    for entry in entries {
+                        if let Node::MapEntry(key, value) = &ast.node(*entry).node {
+                            self.visit(*value);
+                            self.exporting = true;
+                            self.visit_pattern(*key);
+                            self.exporting = false;
+                        }
+                    }
+                }
+                _ => {
+                    self.exporting = true;
+                    self.visit(*expression);
+                    self.exporting = false;
+                }
+            },
+            Node::Assign {
+                target, expression, ..
+            } => {
+                // Functions can refer to themselves, so the target is defined first
+                if matches!(ast.node(*target).node, Node::Id(..))
+                    && matches!(ast.node(*expression).node, Node::Function(_))
+                {
+                    self.visit_pattern(*target);
+                    self.visit(*expression);
+                } else {
+                    self.visit(*expression);
+                    self.visit_pattern(*target);
+                }
+            }
+            Node::MultiAssign {
+                targets,
+                expression,
+                ..
+            } => {
+                self.visit(*expression);
+                for target in targets {
+                    self.visit_pattern(*target);
+                }
+            }
+            Node::Const { target, expression } => {
+                self.visit(*expression);
+                self.visit_pattern(*target);
+            }
+            Node::Record { id, fields, .. } => {
+                for field in fields {
+                    if let Node::Assign { expression, .. } = &self.ast.node(*field).node {
+                        self.visit(*expression);
+                    }
+                }
+                self.visit_pattern(*id);
+            }
+            Node::Enum { id, .. } => self.visit_pattern(*id),
+            Node::UnaryOp { value, .. } => self.visit(*value),
+            Node::BinaryOp { lhs, rhs, .. } => {
+                self.visit(*lhs);
+                self.visit(*rhs);
+            }
+            Node::If(if_expression) => {
+                self.visit(if_expression.condition);
+                self.visit(if_expression.then_node);
+                for (condition, block) in &if_expression.else_if_blocks {
+                    self.visit(*condition);
+                    self.visit(*block);
+                }
+                if let Some(else_node) = if_expression.else_node {
+                    self.visit(else_node);
+                }
+            }
+            Node::Match { expression, arms } => {
+                self.visit(*expression);
+                self.visit_all(arms);
+            }
+            Node::MatchArm {
+                patterns,
+                condition,
+                expression,
+            } => {
+                for pattern in patterns {
+                    self.visit_pattern(*pattern);
+                }
+                if let Some(condition) = condition {
+                    self.visit(*condition);
+                }
+                self.visit(*expression);
+            }
+            Node::SwitchArm {
+                condition: Some(condition),
+                expression,
+            } => {
+                self.visit(*condition);
+                self.visit(*expression);
+            }
+            Node::For(for_loop) => {
+                self.visit(for_loop.iterable);
+                for arg in &for_loop.args {
+                    self.visit_pattern(*arg);
+                }
+                self.visit(for_loop.body);
+            }
+            Node::While { condition, body } | Node::Until { condition, body } => {
+                self.visit(*condition);
+                self.visit(*body);
+            }
+            Node::Try(AstTry {
+                try_block,
+                catch_blocks,
+                finally_block,
+            }) => {
+                self.visit(*try_block);
+                for catch in catch_blocks {
+                    self.visit_pattern(catch.arg);
+                    self.visit(catch.block);
+                }
+                if let Some(finally_block) = finally_block {
+                    self.visit(*finally_block);
+                }
+            }
+            Node::Null
+            | Node::Meta(..)
+            | Node::BoolTrue
+            | Node::BoolFalse
+            | Node::SmallInt(_)
+            | Node::Int(_)
+            | Node::Float(_)
+            | Node::RangeFull
+            | Node::MapPattern { .. }
+            | Node::MapKeyRebind { .. }
+            | Node::EnumVariant { .. }
+            | Node::EnumPattern { .. }
+            | Node::Self_
+            | Node::FunctionArgs { .. }
+            | Node::Ignored(..)
+            | Node::PackedId(_)
+            | Node::Break(None)
+            | Node::Continue
+            | Node::Return(None)
+            | Node::Type { .. } => {}
+        }
+    }
+
+    fn visit_all(&mut self, nodes: &[AstIndex]) {
+        for node in nodes {
+            self.visit(*node);
+        }
+    }
+
+    // Visits a node that defines values, e.g. an assignment target or a function argument
+    fn visit_pattern(&mut self, index: AstIndex) {
+        let ast = self.ast;
+        let node = ast.node(index);
+
+        match &node.node {
+            Node::Id(constant, _) | Node::PackedId(Some(constant)) => {
+                self.add_definition(*constant, node.span, None)
+            }
+            Node::Nested(nested) => self.visit_pattern(*nested),
+            Node::List(elements)
+            | Node::TempTuple(elements)
+            | Node::Tuple { elements, .. }
+            | Node::MapPattern {
+                entries: elements, ..
+            } => {
+                for element in elements {
+                    self.visit_pattern(*element);
+                }
+            }
+            Node::MapKeyRebind { id_or_ignored, .. } => self.visit_pattern(*id_or_ignored),
+            Node::EnumPattern {
+                enum_id, payload, ..
+            } => {
+                self.visit(*enum_id);
+                for element in payload {
+                    self.visit_pattern(*element);
+                }
+            }
+            // Arguments with default values
+            Node::Assign {
+                target, expression, ..
+            } => {
+                self.visit(*expression);
+                self.visit_pattern(*target);
+            }
+            Node::Ignored(..) | Node::PackedId(None) | Node::Str(_) | Node::Meta(..) => {}
+            // Chained assignment targets and literals in match patterns
+            _ => self.visit(index),
+        }
+    }
+
+    fn visit_map_key(&mut self, key: AstIndex) {
+        if let Node::Str(string) = &self.ast.node(key).node {
+            self.visit_string(string);
+        }
+    }
+
+    fn visit_string(&mut self, string: &AstString) {
+        if let StringContents::Interpolated(nodes) = &string.contents {
+            for node in nodes {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.visit(*expression);
+                }
+            }
+        }
+    }
+
+    fn visit_chain(&mut self, mut index: AstIndex) {
+        let ast = self.ast;
+        // The path of `.` accesses from the chain's root, or None if the path has been broken
+        let mut path = None;
+
+        loop {
+            let node = ast.node(index);
+            let Node::Chain((chain_node, next)) = &node.node else {
+                break;
+            };
+
+            match chain_node {
+                ChainNode::Root(root) => match &ast.node(*root).node {
+                    Node::Id(constant, _) => {
+                        self.add_reference(*constant, ast.node(*root).span);
+                        path = Some(vec![self.constant(*constant)]);
+                    }
+                    _ => self.visit(*root),
+                },
+                ChainNode::Id(constant) => {
+                    if let Some(path) = &mut path {
+                        path.push(self.constant(*constant));
+                        self.analysis.references.push(Reference {
+                            span: *ast.span(node.span),
+                            scope: self.scope,
+                            path: path.clone(),
+                            absolute: false,
+                        });
+                    }
+                }
+                ChainNode::Str(string) => {
+                    self.visit_string(string);
+                    path = None;
+                }
+                ChainNode::Index(expression) => {
+                    self.visit(*expression);
+                    path = None;
+                }
+                ChainNode::Call { args, .. } => {
+                    self.visit_all(args);
+                    path = None;
+                }
+                ChainNode::NullCheck => path = None,
+            }
+
+            match next {
+                Some(next) => index = *next,
+                None => break,
+            }
+        }
+    }
+
+    fn visit_import(&mut self, from: &[AstIndex], items: &[ImportItem]) {
+        let ast = self.ast;
+        let mut from_path = Vec::new();
+
+        for node in from {
+            let node = ast.node(*node);
+            let Node::Id(constant, _) = node.node else {
+                return;
+            };
+            from_path.push(self.constant(constant));
+            self.analysis.references.push(Reference {
+                span: *ast.span(node.span),
+                scope: self.scope,
+                path: from_path.clone(),
+                absolute: true,
+            });
+        }
+
+        for item in items {
+            let item_node = ast.node(item.item);
+            let Node::Id(constant, _) = item_node.node else {
+                continue;
+            };
+
+            let mut path = from_path.clone();
+            path.push(self.constant(constant));
+            self.analysis.references.push(Reference {
+                span: *ast.span(item_node.span),
+                scope: self.scope,
+                path: path.clone(),
+                absolute: true,
+            });
+
+            match item.name.map(|name| ast.node(name)) {
+                Some(name_node) => {
+                    if let Node::Id(name, _) = name_node.node {
+                        self.add_definition(name, name_node.span, Some(path));
+                    }
+                }
+                None => self.add_definition(constant, item_node.span, Some(path)),
+            }
+        }
+    }
+
+    fn add_definition(
+        &mut self,
+        constant: ConstantIndex,
+        span: AstIndex,
+        import_path: Option<Vec<String>>,
+    ) {
+        self.analysis.definitions.push(Definition {
+            name: self.constant(constant),
+            span: *self.ast.span(span),
+            scope: self.scope,
+            import_path,
+            exported: self.exporting && self.scope == 0,
+        });
+    }
+
+    fn add_reference(&mut self, constant: ConstantIndex, span: AstIndex) {
+        self.analysis.references.push(Reference {
+            span: *self.ast.span(span),
+            scope: self.scope,
+            path: vec![self.constant(constant)],
+            absolute: false,
+        });
+    }
+
+    fn constant(&self, constant: ConstantIndex) -> String {
+        self.ast.constants().get_str(constant).to_string()
+    }
+}
+
+// Returns the symbols that are defined in a function body or block
+fn symbols_in_body(ast: &koto_parser::Ast, body: AstIndex) -> Vec<Symbol> {
+    match &ast.node(body).node {
+        Node::MainBlock { body, .. } | Node::Block(body) => symbols_in_block(ast, body),
+        _ => symbols_in_block(ast, &[body]),
+    }
+}
+
+fn symbols_in_block(ast: &koto_parser::Ast, expressions: &[AstIndex]) -> Vec<Symbol> {
+    let mut result = Vec::new();
+
+    for expression in expressions {
+        let node = ast.node(*expression);
+        match &node.node {
+            Node::Assign {
+                target,
+                expression: value,
+                ..
+            } => result.extend(make_symbol(ast, *target, *value, node.span, false)),
+            Node::Const {
+                target,
+                expression: value,
+            } => result.extend(make_symbol(ast, *target, *value, node.span, true)),
+            Node::Record { id, fields, .. } => {
+                let id_node = ast.node(*id);
+                if let Node::Id(constant, _) = id_node.node {
+                    result.push(Symbol {
+                        name: ast.constants().get_str(constant).into(),
+                        kind: SymbolKind::Struct,
+                        span: *ast.span(node.span),
+                        selection_span: *ast.span(id_node.span),
+                        children: symbols_in_record(ast, fields),
+                    });
+                }
+            }
+            Node::Enum { id, variants, .. } => {
+                let id_node = ast.node(*id);
+                if let Node::Id(constant, _) = id_node.node {
+                    result.push(Symbol {
+                        name: ast.constants().get_str(constant).into(),
+                        kind: SymbolKind::Enum,
+                        span: *ast.span(node.span),
+                        selection_span: *ast.span(id_node.span),
+                        children: symbols_in_enum(ast, variants),
+                    });
+                }
+            }
+            Node::MultiAssign { targets, .. } => {
+                for target in targets {
+                    let target_node = ast.node(*target);
+                    if let Node::Id(constant, _) = target_node.node {
+                        result.push(Symbol {
+                            name: ast.constants().get_str(constant).into(),
+                            kind: SymbolKind::Variable,
+                            span: *ast.span(node.span),
+                            selection_span: *ast.span(target_node.span),
+                            children: Vec::new(),
+                        });
+                    }
+                }
+            }
+            Node::Export(exported) => match &ast.node(*exported).node {
+                Node::Map { entries, .. } => result.extend(symbols_in_map(ast, entries)),
+                _ => result.extend(symbols_in_block(ast, &[*exported])),
+            },
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn symbols_in_map(ast: &koto_parser::Ast, entries: &[AstIndex]) -> Vec<Symbol> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let entry_node = ast.node(*entry);
+            let Node::MapEntry(key, value) = entry_node.node else {
+                return None;
+            };
+            let key_node = ast.node(key);
+            let Node::Id(constant, _) = key_node.node else {
+                return None;
+            };
+            let (kind, children) = match &ast.node(value).node {
+                Node::Function(function) => {
+                    (SymbolKind::Method, symbols_in_body(ast, function.body))
+                }
+                Node::Map { entries, .. } => (SymbolKind::Object, symbols_in_map(ast, entries)),
+                _ => (SymbolKind::Field, Vec::new()),
+            };
+            Some(Symbol {
+                name: ast.constants().get_str(constant).into(),
+                kind,
+                span: *ast.span(entry_node.span),
+                selection_span: *ast.span(key_node.span),
+                children,
+            })
+        })
+        .collect()
+}
+
+fn symbols_in_record(ast: &koto_parser::Ast, fields: &[AstIndex]) -> Vec<Symbol> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let field_node = ast.node(*field);
+            let id_node = match &field_node.node {
+                Node::Assign { target, .. } => ast.node(*target),
+                _ => field_node,
+            };
+            let Node::Id(constant, _) = id_node.node else {
+                return None;
+            };
+            Some(Symbol {
+                name: ast.constants().get_str(constant).into(),
+                kind: SymbolKind::Field,
+                span: *ast.span(field_node.span),
+                selection_span: *ast.span(id_node.span),
+                children: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn symbols_in_enum(ast: &koto_parser::Ast, variants: &[AstIndex]) -> Vec<Symbol> {
+    variants
+        .iter()
+        .filter_map(|variant| {
+            let variant_node = ast.node(*variant);
+            let Node::EnumVariant { id, .. } = variant_node.node else {
+                return None;
+            };
+            Some(Symbol {
+                name: ast.constants().get_str(id).into(),
+                kind: SymbolKind::EnumMember,
+                span: *ast.span(variant_node.span),
+                selection_span: *ast.span(variant_node.span),
+                children: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn make_symbol(
+    ast: &koto_parser::Ast,
+    target: AstIndex,
+    value: AstIndex,
+    span: AstIndex,
+    is_const: bool,
+) -> Option<Symbol> {
+    let target_node = ast.node(target);
+    let Node::Id(constant, _) = target_node.node else {
+        return None;
+    };
+
+    let (kind, children) = match &ast.node(value).node {
+        Node::Function(function) => (SymbolKind::Function, symbols_in_body(ast, function.body)),
+        Node::Map { entries, .. } => (SymbolKind::Object, symbols_in_map(ast, entries)),
+        _ if is_const => (SymbolKind::Constant, Vec::new()),
+        _ => (SymbolKind::Variable, Vec::new()),
+    };
+
+    Some(Symbol {
+        name: ast.constants().get_str(constant).into(),
+        kind,
+        span: *ast.span(span),
+        selection_span: *ast.span(target_node.span),
+        children,
+    })
+}
@@ -0,0 +1,14 @@
+//! A Language Server Protocol server for Koto scripts
+//!
+//! The server communicates with the client via stdin and stdout, providing diagnostics,
+//! go-to-definition, hover docs for the core library, document symbols, and formatting.
+
+mod analysis;
+mod server;
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    let exit_code = server::run_server()?;
+    std::process::exit(exit_code);
+}
@@ -0,0 +1,429 @@
+//! The server's message loop and request handlers
+//!
+//! See https://microsoft.github.io/language-server-protocol/ for the protocol specification.
+
+use crate::analysis::{Analysis, Resolved, Symbol, SymbolKind};
+use anyhow::{Context, Result};
+use koto_bytecode::{Compiler, CompilerSettings, find_module};
+use koto_format::FormatOptions;
+use koto_parser::{Parser, Position, Span};
+use koto_tooling::{Help, module_search_paths, read_message, write_message};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// The error code used when the client sends an unsupported request
+const METHOD_NOT_FOUND: i64 = -32601;
+// The error code used when a request fails
+const REQUEST_FAILED: i64 = -32803;
+
+/// Runs the language server until the client sends the `exit` notification
+///
+/// The returned exit code follows the protocol, with a non-zero code being returned if the client
+/// didn't send a `shutdown` request before exiting.
+pub fn run_server() -> Result<i32> {
+    let mut server = Server::default();
+    let mut stdin = io::stdin().lock();
+
+    while let Some(message) = read_message(&mut stdin).context("failed to read LSP message")? {
+        let Some(method) = message["method"].as_str() else {
+            // Responses from the client aren't needed
+            continue;
+        };
+        let params = &message["params"];
+
+        match message.get("id") {
+            Some(id) => {
+                let response = match server.handle_request(method, params) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err((code, error)) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {"code": code, "message": error},
+                    }),
+                };
+                send_message(&response);
+            }
+            None if method == "exit" => return Ok(if server.shutdown { 0 } else { 1 }),
+            None => server.handle_notification(method, params),
+        }
+    }
+
+    Ok(1)
+}
+
+type RequestResult = std::result::Result<Value, (i64, String)>;
+
+#[derive(Default)]
+struct Server {
+    // The documents that are open in the client, keyed by URI
+    documents: HashMap<String, Document>,
+    // The index of the help docs, initialized on the first hover request
+    help: Option<Help>,
+    // Set to true when the client has sent the `shutdown` request
+    shutdown: bool,
+}
+
+impl Server {
+    fn handle_request(&mut self, method: &str, params: &Value) -> RequestResult {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1, // Full document sync
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": {
+                    "name": "koto-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => {
+                let (document, position) = self.document_position(params)?;
+                Ok(definition(document, position).unwrap_or_default())
+            }
+            "textDocument/hover" => {
+                let (document, position) = self.document_position(params)?;
+                let Some(analysis) = &document.analysis else {
+                    return Ok(Value::Null);
+                };
+                let help_name = analysis.resolve(position).and_then(|r| r.help_name());
+                let help = self.help.get_or_insert_with(Help::new);
+                Ok(help_name
+                    .filter(|name| help.all_entries().any(|(_, entry)| *entry.name == **name))
+                    .map(|name| {
+                        // Hover contents are wrapped by the client
+                        let contents = help.get_help(Some(&name), usize::MAX);
+                        json!({"contents": {"kind": "plaintext", "value": contents}})
+                    })
+                    .unwrap_or_default())
+            }
+            "textDocument/documentSymbol" => {
+                let document = self.document(params)?;
+                let symbols = document.analysis.as_ref().map_or(&[][..], |a| a.symbols());
+                Ok(symbols
+                    .iter()
+                    .map(|symbol| document_symbol(&document.source, symbol))
+                    .collect())
+            }
+            "textDocument/formatting" => {
+                let document = self.document(params)?;
+                let options = FormatOptions {
+                    indent_width: params["options"]["tabSize"]
+                        .as_u64()
+                        .and_then(|width| u8::try_from(width).ok())
+                        .unwrap_or(FormatOptions::default().indent_width),
+                    ..Default::default()
+                };
+                match koto_format::format(&document.source, options) {
+                    Ok(formatted) if formatted == document.source => Ok(json!([])),
+                    Ok(formatted) => {
+                        let end = Position {
+                            line: document.source.lines().count() as u32,
+                            column: 0,
+                        };
+                        let span = Span {
+                            start: Position::default(),
+                            end,
+                        };
+                        Ok(json!([{
+                            "range": lsp_range(&document.source, &span),
+                            "newText": formatted,
+                        }]))
+                    }
+                    Err(error) => Err((REQUEST_FAILED, error.to_string())),
+                }
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported request '{method}'"))),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match method {
+            "textDocument/didOpen" => {
+                let source = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update_document(uri, source.to_string());
+            }
+            "textDocument/didChange" => {
+                // The full contents of the document are sent with each change
+                let changes = params["contentChanges"].as_array();
+                if let Some(source) = changes.and_then(|changes| changes.last()) {
+                    let source = source["text"].as_str().unwrap_or_default();
+                    self.update_document(uri, source.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                publish_diagnostics(uri, json!([]));
+            }
+            _ => {}
+        }
+    }
+
+    fn update_document(&mut self, uri: &str, source: String) {
+        let document = Document::new(uri, source);
+
+        let diagnostics = match &document.error {
+            Some((message, span)) => json!([{
+                "range": lsp_range(&document.source, span),
+                "severity": 1, // Error
+                "source": "koto",
+                "message": message,
+            }]),
+            None => json!([]),
+        };
+        publish_diagnostics(uri, diagnostics);
+
+        self.documents.insert(uri.to_string(), document);
+    }
+
+    fn document(&self, params: &Value) -> std::result::Result<&Document, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (REQUEST_FAILED, format!("unknown document '{uri}'")))
+    }
+
+    fn document_position(
+        &self,
+        params: &Value,
+    ) -> std::result::Result<(&Document, Position), (i64, String)> {
+        let document = self.document(params)?;
+        let position = koto_position(&document.source, &params["position"]);
+        Ok((document, position))
+    }
+}
+
+// A document that's open in the client
+struct Document {
+    source: String,
+    path: Option<PathBuf>,
+    // The analysis of the document, available when the document was successfully parsed
+    analysis: Option<Analysis>,
+    // The error that was produced when compiling the document
+    error: Option<(String, Span)>,
+}
+
+impl Document {
+    fn new(uri: &str, source: String) -> Self {
+        let path = uri_to_path(uri);
+
+        let (analysis, error) = match Parser::parse(&source) {
+            Ok(ast) => {
+                let analysis = Analysis::new(&ast);
+                let script_path = path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().as_ref().into());
+                let error = Compiler::compile_ast(ast, script_path, CompilerSettings::default())
+                    .err()
+                    .map(|error| (error.to_string(), error.span));
+                (Some(analysis), error)
+            }
+            Err(error) => (None, Some((error.to_string(), error.span))),
+        };
+
+        Self {
+            source,
+            path,
+            analysis,
+            error,
+        }
+    }
+}
+
+// Returns the location of the definition of the value at the given position
+fn definition(document: &Document, position: Position) -> Option<Value> {
+    let analysis = document.analysis.as_ref()?;
+
+    let module_path = match analysis.resolve(position) {
+        Some(Resolved::Local(definition)) => {
+            return Some(lsp_location(
+                &path_to_uri(document.path.as_deref()?),
+                &document.source,
+                &definition.span,
+            ));
+        }
+        Some(Resolved::Import(module_path)) => module_path,
+        Some(Resolved::Global(_)) => return None,
+        None => {
+            // Definitions resolve to themselves
+            let definition = analysis.definition_at(position)?;
+            return Some(lsp_location(
+                &path_to_uri(document.path.as_deref()?),
+                &document.source,
+                &definition.span,
+            ));
+        }
+    };
+
+    // Modules are found relative to the document's directory, which allows modules to be found
+    // for documents that haven't been saved yet.
+    let module_file = find_module(
+        &module_path[0],
+        document.path.as_deref().and_then(Path::parent),
+        &module_search_paths(),
+    )
+    .ok()?;
+    let uri = path_to_uri(&module_file);
+    let module_source = fs::read_to_string(&module_file).ok()?;
+
+    // Find the exported item in the module, falling back to the start of the module
+    let export_span = module_path.get(1).and_then(|item| {
+        let ast = Parser::parse(&module_source).ok()?;
+        Analysis::new(&ast).exported(item).map(|export| export.span)
+    });
+
+    Some(lsp_location(
+        &uri,
+        &module_source,
+        &export_span.unwrap_or_default(),
+    ))
+}
+
+fn document_symbol(source: &str, symbol: &Symbol) -> Value {
+    let kind = match symbol.kind {
+        SymbolKind::Method => 6,
+        SymbolKind::Field => 8,
+        SymbolKind::Enum => 10,
+        SymbolKind::Function => 12,
+        SymbolKind::Variable => 13,
+        SymbolKind::Constant => 14,
+        SymbolKind::Object => 19,
+        SymbolKind::EnumMember => 22,
+        SymbolKind::Struct => 23,
+    };
+
+    json!({
+        "name": symbol.name,
+        "kind": kind,
+        "range": lsp_range(source, &symbol.span),
+        "selectionRange": lsp_range(source, &symbol.selection_span),
+        "children": symbol
+            .children
+            .iter()
+            .map(|child| document_symbol(source, child))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Value) {
+    send_message(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics},
+    }));
+}
+
+fn lsp_location(uri: &str, source: &str, span: &Span) -> Value {
+    json!({"uri": uri, "range": lsp_range(source, span)})
+}
+
+fn lsp_range(source: &str, span: &Span) -> Value {
+    json!({
+        "start": lsp_position(source, span.start),
+        "end": lsp_position(source, span.end),
+    })
+}
+
+// Converts a Koto position into an LSP position
+//
+// Koto's columns count characters, while LSP positions count UTF-16 code units.
+fn lsp_position(source: &str, position: Position) -> Value {
+    let character: usize = source
+        .lines()
+        .nth(position.line as usize)
+        .map(|line| {
+            line.chars()
+                .take(position.column as usize)
+                .map(char::len_utf16)
+                .sum()
+        })
+        .unwrap_or_default();
+
+    json!({"line": position.line, "character": character})
+}
+
+// Converts an LSP position into a Koto position
+fn koto_position(source: &str, position: &Value) -> Position {
+    let line = position["line"].as_u64().unwrap_or_default() as u32;
+    let character = position["character"].as_u64().unwrap_or_default() as usize;
+
+    let column = source
+        .lines()
+        .nth(line as usize)
+        .map(|line| {
+            line.chars()
+                .scan(0, |utf16_offset, c| {
+                    *utf16_offset += c.len_utf16();
+                    Some(*utf16_offset)
+                })
+                .take_while(|utf16_offset| *utf16_offset <= character)
+                .count()
+        })
+        .unwrap_or_default();
+
+    Position {
+        line,
+        column: column as u32,
+    }
+}
+
+// Converts a `file://` URI into a path
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+    while let Some(c) = chars.next() {
+        if c == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(c);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+
+    // Windows paths are prefixed with a slash, e.g. `file:///C:/foo`
+    match path.strip_prefix('/') {
+        Some(windows_path) if windows_path.get(1..2) == Some(":") => Some(windows_path.into()),
+        _ => Some(path.into()),
+    }
+}
+
+// Converts a path into a `file://` URI
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+
+    let mut result = String::from("file://");
+    if !path.starts_with('/') {
+        result.push('/');
+    }
+    for c in path.bytes() {
+        match c {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                result.push(c as char)
+            }
+            _ => result.push_str(&format!("%{c:02X}")),
+        }
+    }
+    result
+}
+
+// Sends a message to the client
+//
+// Write errors are ignored, a broken connection will be detected when reading the next message.
+fn send_message(message: &Value) {
+    write_message(&mut io::stdout().lock(), message).ok();
+}
@@ -0,0 +1,327 @@
+use koto_tooling::{read_message, write_message};
+use serde_json::{Value, json};
+use std::{
+    fs,
+    io::BufReader,
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+use tempfile::TempDir;
+
+// A minimal LSP client that communicates with `koto-lsp`
+struct LspClient {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    id: i64,
+}
+
+impl LspClient {
+    fn new() -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_koto-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to execute child");
+
+        let stdin = process.stdin.take().expect("failed to get stdin");
+        let stdout = BufReader::new(process.stdout.take().expect("failed to get stdout"));
+
+        let mut result = Self {
+            process,
+            stdin,
+            stdout,
+            id: 0,
+        };
+
+        let capabilities = result.request("initialize", json!({"capabilities": {}}));
+        assert_eq!(capabilities["capabilities"]["hoverProvider"], true);
+        result.notify("initialized", json!({}));
+
+        result
+    }
+
+    fn send(&mut self, message: Value) {
+        write_message(&mut self.stdin, &message).expect("failed to write message");
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    // Sends a request and returns the result of the successful response
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.id += 1;
+        let id = self.id;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+        let response = self.wait_for(|message| message["id"] == id);
+        assert!(response.get("error").is_none(), "{response}");
+        response["result"].clone()
+    }
+
+    fn read_message(&mut self) -> Value {
+        read_message(&mut self.stdout)
+            .expect("failed to read")
+            .expect("unexpected end of output")
+    }
+
+    fn wait_for(&mut self, predicate: impl Fn(&Value) -> bool) -> Value {
+        loop {
+            let message = self.read_message();
+            if predicate(&message) {
+                return message;
+            }
+        }
+    }
+
+    // Opens a document and returns the published diagnostics
+    fn open(&mut self, path: &Path, text: &str) -> Value {
+        let uri = uri(path);
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {"uri": uri, "languageId": "koto", "version": 1, "text": text},
+            }),
+        );
+        self.wait_for(|message| {
+            message["method"] == "textDocument/publishDiagnostics"
+                && message["params"]["uri"] == uri
+        })["params"]["diagnostics"]
+            .clone()
+    }
+
+    fn request_at(&mut self, method: &str, path: &Path, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": {"uri": uri(path)},
+                "position": {"line": line, "character": character},
+            }),
+        )
+    }
+
+    fn finish(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        let status = self.process.wait().expect("failed to wait for process");
+        assert!(status.success());
+    }
+}
+
+fn uri(path: &Path) -> String {
+    format!("file://{}", path.to_string_lossy())
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": {"line": start.0, "character": start.1},
+        "end": {"line": end.0, "character": end.1},
+    })
+}
+
+mod lsp {
+    use super::*;
+
+    #[test]
+    fn diagnostics() {
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+
+        let diagnostics = client.open(&path, "x = 1\ny = (x +\n");
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+        let diagnostics = client.open(&path, "x = 1\ny = x + 1\n");
+        assert_eq!(diagnostics, json!([]));
+
+        client.finish();
+    }
+
+    #[test]
+    fn compiler_errors_are_reported() {
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+
+        let diagnostics = client.open(&path, "x = 1\nbreak\n");
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1, "{diagnostics}");
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+        client.finish();
+    }
+
+    #[test]
+    fn definition_of_local_values() {
+        let script = "\
+x = 1
+f = |x|
+  y = x + 1
+  f y
+x
+";
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+        client.open(&path, script);
+
+        // The function argument shadows the top-level x
+        let location = client.request_at("textDocument/definition", &path, 2, 6);
+        assert_eq!(location["uri"], uri(&path));
+        assert_eq!(location["range"], range((1, 5), (1, 6)));
+
+        let location = client.request_at("textDocument/definition", &path, 4, 0);
+        assert_eq!(location["range"], range((0, 0), (0, 1)));
+
+        // Recursive function call
+        let location = client.request_at("textDocument/definition", &path, 3, 2);
+        assert_eq!(location["range"], range((1, 0), (1, 1)));
+
+        client.finish();
+    }
+
+    #[test]
+    fn definition_of_imported_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = fs::canonicalize(temp_dir.path()).unwrap();
+        let module_path = dir.join("foo.koto");
+        fs::write(&module_path, "bar = 1\nexport baz = || bar\n").unwrap();
+
+        let script = "\
+import foo
+from foo import baz
+foo.baz()
+baz()
+";
+        let path = dir.join("main.koto");
+        let mut client = LspClient::new();
+        client.open(&path, script);
+
+        // The module name in the import expression
+        let location = client.request_at("textDocument/definition", &path, 0, 8);
+        assert_eq!(location["uri"], uri(&module_path));
+        assert_eq!(location["range"], range((0, 0), (0, 0)));
+
+        // An item accessed via the imported module
+        let location = client.request_at("textDocument/definition", &path, 2, 5);
+        assert_eq!(location["uri"], uri(&module_path));
+        assert_eq!(location["range"], range((1, 7), (1, 10)));
+
+        // An imported item
+        let location = client.request_at("textDocument/definition", &path, 3, 1);
+        assert_eq!(location["uri"], uri(&module_path));
+        assert_eq!(location["range"], range((1, 7), (1, 10)));
+
+        client.finish();
+    }
+
+    #[test]
+    fn hover_docs() {
+        let script = "\
+from string import to_uppercase
+io.print 'hello'
+print (to_uppercase 'hello')
+x = 1
+x
+";
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+        client.open(&path, script);
+
+        let hover = client.request_at("textDocument/hover", &path, 1, 4);
+        let contents = hover["contents"]["value"].as_str().unwrap();
+        assert!(contents.starts_with("io.print\n"), "{contents}");
+
+        let hover = client.request_at("textDocument/hover", &path, 2, 1);
+        let contents = hover["contents"]["value"].as_str().unwrap();
+        assert!(contents.starts_with("io.print\n"), "{contents}");
+
+        let hover = client.request_at("textDocument/hover", &path, 2, 10);
+        let contents = hover["contents"]["value"].as_str().unwrap();
+        assert!(contents.starts_with("string.to_uppercase\n"), "{contents}");
+
+        // Local values don't have help
+        let hover = client.request_at("textDocument/hover", &path, 4, 0);
+        assert_eq!(hover, Value::Null);
+
+        client.finish();
+    }
+
+    #[test]
+    fn document_symbols() {
+        let script = "\
+const LIMIT = 10
+make_point = |x, y|
+  offset = 1
+  {x: x + offset, y}
+point =
+  x: 1
+  add: |other| self.x + other.x
+record Size {width, height = 0}
+enum Shape {Circle(radius), Empty}
+export
+  run: || make_point 1, 2
+";
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+        client.open(&path, script);
+
+        let symbols = client.request(
+            "textDocument/documentSymbol",
+            json!({"textDocument": {"uri": uri(&path)}}),
+        );
+
+        let summary = |symbol: &Value| {
+            (
+                symbol["name"].as_str().unwrap().to_string(),
+                symbol["kind"].as_i64().unwrap(),
+                symbol["children"].as_array().unwrap().len(),
+            )
+        };
+        let symbols: Vec<_> = symbols.as_array().unwrap().iter().map(summary).collect();
+        assert_eq!(
+            symbols,
+            [
+                ("LIMIT".into(), 14, 0),
+                ("make_point".into(), 12, 1),
+                ("point".into(), 19, 2),
+                ("Size".into(), 23, 2),
+                ("Shape".into(), 10, 2),
+                ("run".into(), 6, 0),
+            ]
+        );
+
+        client.finish();
+    }
+
+    #[test]
+    fn formatting() {
+        let dir = TempDir::new().unwrap();
+        let path = fs::canonicalize(dir.path()).unwrap().join("main.koto");
+        let mut client = LspClient::new();
+        client.open(&path, "x   =  [1,2,3]\nf = |a|\n    a\n");
+
+        let edits = client.request(
+            "textDocument/formatting",
+            json!({
+                "textDocument": {"uri": uri(&path)},
+                "options": {"tabSize": 2, "insertSpaces": true},
+            }),
+        );
+        assert_eq!(edits[0]["newText"], "x = [1, 2, 3]\nf = |a|\n  a\n");
+        assert_eq!(edits[0]["range"], range((0, 0), (3, 0)));
+
+        client.finish();
+    }
+
+    #[test]
+    fn exit_without_shutdown() {
+        let mut client = LspClient::new();
+        client.notify("exit", Value::Null);
+        let status = client.process.wait().expect("failed to wait for process");
+        assert_eq!(status.code(), Some(1));
+    }
+}
@@ -19,7 +19,9 @@ mod address;
 mod ptr;
 mod ptr_impl;
 mod ptr_mut;
+mod send_sync;
 
 pub use address::Address;
 pub use ptr::*;
 pub use ptr_mut::*;
+pub use send_sync::{KotoSend, KotoSync};
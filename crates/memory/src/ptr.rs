@@ -5,7 +5,10 @@ use std::{
     ops::Deref,
 };
 
-use crate::{Address, ptr_impl::PtrImpl};
+use crate::{
+    Address,
+    ptr_impl::{PtrImpl, WeakPtrImpl},
+};
 
 /// Provides access to a shared value that is initialized on first use
 ///
@@ -96,6 +99,16 @@ impl<T: ?Sized> Ptr<T> {
     pub fn ref_count(this: &Self) -> usize {
         PtrImpl::strong_count(&this.0)
     }
+
+    /// Returns a [WeakPtr] that refers to the same allocated memory
+    ///
+    /// See also: [`Rc::downgrade`] or [`Arc::downgrade`]
+    ///
+    /// [`Rc::downgrade`]: std::rc::Rc::downgrade
+    /// [`Arc::downgrade`]: std::sync::Arc::downgrade
+    pub fn downgrade(this: &Self) -> WeakPtr<T> {
+        WeakPtr(PtrImpl::downgrade(&this.0))
+    }
 }
 
 impl<T: Clone> Ptr<T> {
@@ -156,6 +169,30 @@ impl From<String> for Ptr<str> {
     }
 }
 
+/// A weak reference to a value owned by a [Ptr]
+///
+/// A `WeakPtr` doesn't keep the value alive, see [WeakPtr::upgrade].
+#[derive(Debug)]
+pub struct WeakPtr<T: ?Sized>(WeakPtrImpl<T>);
+
+impl<T: ?Sized> WeakPtr<T> {
+    /// Returns a [Ptr] to the value, or `None` if the value has been dropped
+    ///
+    /// See also: [`rc::Weak::upgrade`] or [`sync::Weak::upgrade`]
+    ///
+    /// [`rc::Weak::upgrade`]: std::rc::Weak::upgrade
+    /// [`sync::Weak::upgrade`]: std::sync::Weak::upgrade
+    pub fn upgrade(&self) -> Option<Ptr<T>> {
+        self.0.upgrade().map(Ptr)
+    }
+}
+
+impl<T: ?Sized> Clone for WeakPtr<T> {
+    fn clone(&self) -> Self {
+        Self(WeakPtrImpl::clone(&self.0))
+    }
+}
+
 impl<T: ?Sized + PartialEq> PartialEq for Ptr<T> {
     fn eq(&self, other: &Self) -> bool {
         PtrImpl::eq(&self.0, &other.0)
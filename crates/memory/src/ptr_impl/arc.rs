@@ -2,6 +2,7 @@ pub(crate) use parking_lot::MappedRwLockReadGuard as BorrowImpl;
 pub(crate) use parking_lot::MappedRwLockWriteGuard as BorrowMutImpl;
 pub(crate) use parking_lot::RwLock as CellImpl;
 pub(crate) use std::sync::Arc as PtrImpl;
+pub(crate) use std::sync::Weak as WeakPtrImpl;
 
 #[doc(hidden)]
 #[macro_export]
@@ -2,6 +2,7 @@ pub(crate) use std::cell::Ref as BorrowImpl;
 pub(crate) use std::cell::RefCell as CellImpl;
 pub(crate) use std::cell::RefMut as BorrowMutImpl;
 pub(crate) use std::rc::Rc as PtrImpl;
+pub(crate) use std::rc::Weak as WeakPtrImpl;
 
 #[doc(hidden)]
 #[macro_export]
@@ -1,4 +1,4 @@
-//! Definitions of Send and Sync used in the Koto runtime
+//! Definitions of Send and Sync used in Koto
 //!
 //! When Koto is being used in a single-threaded context [KotoSend] and [KotoSync] are empty
 //! traits implemented for all types.
@@ -97,6 +97,54 @@ impl Default for ConstantPool {
 }
 
 impl ConstantPool {
+    /// Builds a pool from a series of constants
+    ///
+    /// This is used when loading a pool that was previously produced by the parser,
+    /// with each constant taking the index of its position in the series.
+    ///
+    /// `None` is returned if the series contains duplicate constants, or if the pool's capacity
+    /// is exceeded.
+    pub fn from_constants<'a>(constants: impl IntoIterator<Item = Constant<'a>>) -> Option<Self> {
+        let mut builder = ConstantPoolBuilder::default();
+
+        for (expected_index, constant) in constants.into_iter().enumerate() {
+            let index = match constant {
+                Constant::F64(n) => builder.add_f64(n),
+                Constant::I64(n) => builder.add_i64(n),
+                Constant::Str(s) => builder.add_string(s),
+            }
+            .ok()?;
+
+            // The builder deduplicates constants, so if the index doesn't match the expected
+            // position then the constant is a duplicate.
+            if usize::from(index) != expected_index {
+                return None;
+            }
+        }
+
+        Some(builder.build())
+    }
+
+    /// Returns a builder that contains the pool's constants
+    ///
+    /// This allows additional constants to be added to the pool after parsing,
+    /// e.g. when the compiler evaluates constant expressions.
+    /// Existing constants keep their indices in the resulting builder.
+    pub fn to_builder(&self) -> ConstantPoolBuilder {
+        let mut builder = ConstantPoolBuilder::default();
+
+        for constant in self.iter() {
+            // The pool was prepared by a builder, so re-adding its constants can't overflow
+            let _ = match constant {
+                Constant::F64(n) => builder.add_f64(n),
+                Constant::I64(n) => builder.add_i64(n),
+                Constant::Str(s) => builder.add_string(s),
+            };
+        }
+
+        builder
+    }
+
     /// Provides the number of constants in the pool
     pub fn size(&self) -> usize {
         self.constants.len()
@@ -225,8 +273,10 @@ impl Hash for ConstantPool {
 ///
 /// [ConstantPoolBuilder::build]() is called when parsing is finished to produce a finalized
 /// ConstantPool.
+///
+/// See [ConstantPool::to_builder] for adding constants to an existing pool.
 #[derive(Default)]
-pub(crate) struct ConstantPoolBuilder {
+pub struct ConstantPoolBuilder {
     // The list of constants
     constants: Vec<ConstantEntry>,
     // The concatenated string constants
@@ -242,6 +292,9 @@ pub(crate) struct ConstantPoolBuilder {
 }
 
 impl ConstantPoolBuilder {
+    /// Adds a string to the pool, returning its index
+    ///
+    /// If the string is already in the pool then the existing index will be returned.
     pub fn add_string(&mut self, s: &str) -> Result<ConstantIndex, InternalError> {
         match self.string_map.get(s) {
             Some(index) => Ok(*index),
@@ -262,6 +315,9 @@ impl ConstantPoolBuilder {
         }
     }
 
+    /// Adds an f64 to the pool, returning its index
+    ///
+    /// If the number is already in the pool then the existing index will be returned.
     pub fn add_f64(&mut self, n: f64) -> Result<ConstantIndex, InternalError> {
         let n_u64 = n.to_bits();
 
@@ -278,6 +334,9 @@ impl ConstantPoolBuilder {
         }
     }
 
+    /// Adds an i64 to the pool, returning its index
+    ///
+    /// If the number is already in the pool then the existing index will be returned.
     pub fn add_i64(&mut self, n: i64) -> Result<ConstantIndex, InternalError> {
         match self.int_map.get(&n) {
             Some(index) => Ok(*index),
@@ -292,6 +351,9 @@ impl ConstantPoolBuilder {
         }
     }
 
+    /// Returns the string corresponding to the provided index as a &str
+    ///
+    /// Warning! Panics if there isn't a string at the provided index
     pub fn get_str(&self, index: ConstantIndex) -> &str {
         match self.constants.get(usize::from(index)) {
             Some(ConstantEntry::Str(range)) => {
@@ -302,6 +364,7 @@ impl ConstantPoolBuilder {
         }
     }
 
+    /// Produces the finalized [ConstantPool]
     pub fn build(self) -> ConstantPool {
         ConstantPool {
             constants: self.constants,
@@ -411,4 +474,49 @@ mod tests {
         assert_eq!(iter.next(), Some(Constant::Str("^_^")));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_from_constants() {
+        let mut builder = ConstantPoolBuilder::default();
+
+        builder.add_i64(-1).unwrap();
+        builder.add_string("O_o").unwrap();
+        builder.add_f64(99.9).unwrap();
+
+        let pool = builder.build();
+        let rebuilt = ConstantPool::from_constants(pool.iter()).unwrap();
+
+        assert_eq!(pool, rebuilt);
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            rebuilt.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_from_constants_with_duplicates() {
+        let constants = [Constant::Str("O_o"), Constant::I64(1), Constant::Str("O_o")];
+        assert!(ConstantPool::from_constants(constants).is_none());
+    }
+
+    #[test]
+    fn test_to_builder() {
+        let mut builder = ConstantPoolBuilder::default();
+
+        builder.add_i64(-1).unwrap();
+        builder.add_string("O_o").unwrap();
+
+        let pool = builder.build();
+        let mut builder = pool.to_builder();
+
+        // Existing constants keep their indices
+        assert_eq!(ConstantIndex(1), builder.add_string("O_o").unwrap());
+        assert_eq!(ConstantIndex(2), builder.add_f64(99.9).unwrap());
+
+        let extended = builder.build();
+        assert_eq!(
+            extended.iter().collect::<Vec<_>>(),
+            [Constant::I64(-1), Constant::Str("O_o"), Constant::F64(99.9)]
+        );
+    }
 }
@@ -75,6 +75,12 @@ pub enum ExpectedIndentation {
 pub enum SyntaxError {
     #[error("ascii value out of range, the maximum is \\x7f")]
     AsciiEscapeCodeOutOfRange,
+    #[error("the variant '{0}' has already been declared in the enum")]
+    DuplicateEnumVariant(String),
+    #[error("the field '{0}' has already been declared in the enum variant")]
+    DuplicateEnumVariantField(String),
+    #[error("the field '{0}' has already been declared in the record")]
+    DuplicateRecordField(String),
     #[error("expected end of arguments ')'")]
     ExpectedArgsEnd,
     #[error("expected target for assignment")]
@@ -87,8 +93,20 @@ pub enum SyntaxError {
     ExpectedCatch,
     #[error("expected closing parenthesis ')'")]
     ExpectedCloseParen,
+    #[error("expected '=' after const id")]
+    ExpectedConstAssignment,
+    #[error("expected id after 'const'")]
+    ExpectedConstId,
     #[error("all arguments following a default value must also have a default value")]
     ExpectedDefaultValue,
+    #[error("all fields following a default value must also have a default value")]
+    ExpectedRecordFieldDefault,
+    #[error("expected '}}' at end of enum declaration")]
+    ExpectedEnumEnd,
+    #[error("expected a variant in enum declaration")]
+    ExpectedEnumVariant,
+    #[error("expected a field in enum variant")]
+    ExpectedEnumVariantField,
     #[error("expected expression after 'else'.")]
     ExpectedElseExpression,
     #[error("expected condition for 'else if'.")]
@@ -157,6 +175,10 @@ pub enum SyntaxError {
     ExpectedLineBreakBeforeMapBlock,
     #[error("expected '}}' at end of string placeholder")]
     ExpectedStringPlaceholderEnd,
+    #[error("expected '}}' at end of record declaration")]
+    ExpectedRecordEnd,
+    #[error("expected a field in record declaration")]
+    ExpectedRecordField,
     #[error("expected expression in switch arm")]
     ExpectedSwitchArmExpression,
     #[error("expected expression after 'then' in switch arm")]
@@ -185,8 +207,6 @@ pub enum SyntaxError {
     NestedTypesArentSupported,
     #[error("floating point literals aren't supported for non-decimal bases")]
     NonDecimalFloatsAreUnsupported,
-    #[error("keyword reserved for future use")]
-    ReservedKeyword,
     #[error("'self' doesn't need to be declared as an argument")]
     SelfArg,
     #[error("'else' can only be used in the last arm in a switch expression")]
@@ -13,8 +13,11 @@ mod string_slice;
 
 pub use crate::{
     ast::*,
-    constant_pool::{Constant, ConstantIndex, ConstantPool},
-    error::{Error, ErrorKind, ExpectedIndentation, Result, SyntaxError, format_source_excerpt},
+    constant_pool::{Constant, ConstantIndex, ConstantPool, ConstantPoolBuilder},
+    error::{
+        Error, ErrorKind, ExpectedIndentation, InternalError, Result, SyntaxError,
+        format_source_excerpt,
+    },
     node::*,
     parser::{Parser, ParserOptions},
     string::KString,
@@ -218,6 +218,67 @@ pub enum Node {
         let_assignment: bool,
     },
 
+    /// A const declaration
+    ///
+    /// E.g. `const x = 42`
+    Const {
+        /// The const's id, with an optional type hint
+        target: AstIndex,
+        /// The const's value
+        expression: AstIndex,
+    },
+
+    /// A record declaration
+    ///
+    /// E.g. `record Point {x: Number, y: Number = 0}`
+    Record {
+        /// The record's id
+        id: AstIndex,
+        /// The record's fields
+        ///
+        /// Each field is an [Id](Self::Id) with an optional type hint,
+        /// or an [Assign](Self::Assign) for fields with default values.
+        fields: AstVec<AstIndex>,
+        /// Whether or not the fields are declared inline with braces
+        braces: bool,
+    },
+
+    /// An enum declaration
+    ///
+    /// E.g. `enum Shape {Circle(radius), Rect(width, height), Empty}`
+    Enum {
+        /// The enum's id
+        id: AstIndex,
+        /// The enum's variants, each variant is an [EnumVariant](Self::EnumVariant)
+        variants: AstVec<AstIndex>,
+        /// Whether or not the variants are declared inline with braces
+        braces: bool,
+    },
+
+    /// A variant in an enum declaration
+    ///
+    /// E.g. `Rect(width, height)`
+    EnumVariant {
+        /// The variant's name
+        id: ConstantIndex,
+        /// The variant's payload fields, each field is an [Id](Self::Id)
+        ///
+        /// Variants without a payload have no fields.
+        fields: AstVec<AstIndex>,
+    },
+
+    /// An enum variant pattern, used in match arms
+    ///
+    /// E.g. `Shape.Rect(w, h)`
+    EnumPattern {
+        /// The enum's id
+        enum_id: AstIndex,
+        /// The name of the variant to match against
+        variant: ConstantIndex,
+        /// Patterns to match against the variant's payload
+        payload: AstVec<AstIndex>,
+    },
+
     /// A unary operation
     UnaryOp {
         /// The operator to use
@@ -340,6 +401,9 @@ pub enum Node {
     /// A yield expression
     Yield(AstIndex),
 
+    /// An await expression
+    Await(AstIndex),
+
     /// A debug expression
     Debug {
         /// The stored string of the debugged expression to be used when printing the result
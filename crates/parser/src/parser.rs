@@ -329,7 +329,7 @@ impl<'source> Parser<'source> {
         while self.peek_token_with_context(&context).is_some() {
             self.consume_until_token_with_context(&context);
 
-            match self.parse_line(&ExpressionContext::permissive()) {
+            match self.parse_statement(&ExpressionContext::permissive()) {
                 Ok(Some(expression)) => {
                     body.push(expression);
                 }
@@ -424,7 +424,7 @@ impl<'source> Parser<'source> {
                 ..ExpressionContext::permissive()
             };
 
-            match self.parse_line(&line_context) {
+            match self.parse_statement(&line_context) {
                 Ok(Some(expression)) => {
                     // If we've consumed a map block then return it as the indented block
                     body.push(expression);
@@ -486,6 +486,23 @@ impl<'source> Parser<'source> {
             .map(Some)
     }
 
+    // Parses a statement from the start of a line
+    //
+    // Declarations are only allowed at the start of a statement, otherwise the line is parsed as a
+    // series of expressions.
+    fn parse_statement(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
+        let declaration = if self.peek_declaration("record") {
+            self.consume_record_declaration(context)?
+        } else if self.peek_declaration("enum") {
+            self.consume_enum_declaration(context)?
+        } else {
+            return self.parse_line(context);
+        };
+
+        self.frame_mut()?.finalize_id_accesses();
+        Ok(Some(declaration))
+    }
+
     // Parses expressions from the start of a line
     fn parse_line(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
         self.parse_expressions(context, TempResult::No)
@@ -1017,6 +1034,20 @@ impl<'source> Parser<'source> {
                     self.consume_token_and_error(SyntaxError::ExpectedExpression)
                 }
             }
+            Token::Await => {
+                self.consume_token_with_context(context);
+                let start_span = self.current_span();
+
+                if let Some(expression) = self.parse_expression(&ExpressionContext {
+                    allow_space_separated_call: true,
+                    expected_indentation: Indentation::Greater,
+                    ..*context
+                })? {
+                    self.push_node_with_start_span(Node::Await(expression), start_span)
+                } else {
+                    self.consume_token_and_error(SyntaxError::ExpectedExpression)
+                }
+            }
             Token::Loop => self.consume_loop_block(context),
             Token::For => self.consume_for_loop(context),
             Token::While => self.consume_while_loop(context),
@@ -1045,9 +1076,7 @@ impl<'source> Parser<'source> {
             Token::Export => self.consume_export(context),
             Token::Try => self.consume_try_expression(context),
             Token::Let => self.consume_let_expression(context),
-            // Reserved keywords
-            Token::Await => self.consume_token_and_error(SyntaxError::ReservedKeyword),
-            Token::Const => self.consume_token_and_error(SyntaxError::ReservedKeyword),
+            Token::Const => self.consume_const_expression(context),
             // An error occurred in the lexer
             Token::Error => self.consume_token_and_error(SyntaxError::UnexpectedToken),
             _ => return Ok(None),
@@ -2238,10 +2267,9 @@ impl<'source> Parser<'source> {
         self.consume_token_with_context(context); // Token::Export
         let start_span = self.current_span();
 
-        if let Some(expression) = self.parse_expressions(
-            &ExpressionContext::permissive().with_exported_map_entries(),
-            TempResult::No,
-        )? {
+        if let Some(expression) =
+            self.parse_statement(&ExpressionContext::permissive().with_exported_map_entries())?
+        {
             self.push_node_with_start_span(Node::Export(expression), start_span)
         } else {
             self.consume_token_and_error(SyntaxError::ExpectedExpression)
@@ -3256,6 +3284,11 @@ impl<'source> Parser<'source> {
                                 return self
                                     .error(SyntaxError::MatchEllipsisOutsideOfNestedPatterns);
                             }
+                        } else if self.peek_token_n(0) == Some(Dot)
+                            && self.peek_token_n(1) == Some(Id)
+                            && self.peek_token_n(2) == Some(RoundOpen)
+                        {
+                            self.consume_enum_pattern(id)?
                         } else {
                             let id_span = self.current_span();
                             let type_hint = self.parse_type_hint(&pattern_context)?;
@@ -3334,6 +3367,38 @@ impl<'source> Parser<'source> {
         Ok(result)
     }
 
+    // Parses an enum variant pattern, with nested patterns for the variant's payload
+    //
+    // e.g.
+    //   match shape
+    //     Shape.Rect(w, h) then ...
+    //   #      ^ You are here
+    fn consume_enum_pattern(&mut self, enum_id: ConstantIndex) -> Result<AstIndex> {
+        let start_span = self.current_span();
+        self.frame_mut()?.add_id_access(enum_id);
+        let enum_id = self.push_node_with_span(Node::Id(enum_id, None), start_span)?;
+
+        self.consume_token(); // Token::Dot
+        self.consume_token(); // Token::Id
+        let variant = self.add_current_slice_as_string_constant()?;
+        self.consume_token(); // Token::RoundOpen
+
+        let payload = self.parse_nested_match_patterns()?;
+
+        if self.consume_next_token_on_same_line() != Some(Token::RoundClose) {
+            return self.error(SyntaxError::ExpectedCloseParen);
+        }
+
+        self.push_node_with_start_span(
+            Node::EnumPattern {
+                enum_id,
+                variant,
+                payload,
+            },
+            start_span,
+        )
+    }
+
     // Recursively parses nested match patterns
     //
     // e.g.
@@ -3451,10 +3516,7 @@ impl<'source> Parser<'source> {
         let mut items = Vec::new();
         let mut context = *context;
 
-        loop {
-            let Some(item) = self.parse_id_or_string(&context)? else {
-                break;
-            };
+        while let Some(item) = self.parse_id_or_string(&context)? {
             let name = match self.peek_token_with_context(&context) {
                 Some(peeked) if peeked.token == Token::As => {
                     self.consume_token_with_context(&context);
@@ -3594,6 +3656,379 @@ impl<'source> Parser<'source> {
         }
     }
 
+    // Parses a const declaration
+    //
+    // e.g.
+    //   const x = 42
+    //   # ^ You are here
+    fn consume_const_expression(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_token_with_context(context); // Token::Const
+        let start_span = self.current_span();
+
+        let binding_context = ExpressionContext::permissive();
+        let id = match self.peek_token_with_context(&binding_context) {
+            Some(PeekInfo {
+                token: Token::Id, ..
+            }) => {
+                let Some(target) = self.parse_binding(&binding_context, BindingContext::Let)?
+                else {
+                    return self.error(InternalError::IdParseFailure);
+                };
+                target
+            }
+            _ => return self.consume_token_and_error(SyntaxError::ExpectedConstId),
+        };
+
+        let Node::Id(id_index, _) = self.ast.node(id).node else {
+            return self.error(InternalError::IdParseFailure);
+        };
+
+        match self
+            .peek_token_with_context(context)
+            .map(|token| token.token)
+        {
+            Some(Token::Assign) => {
+                self.frame_mut()?.add_local_id_assignment(id_index);
+                self.consume_token_with_context(context);
+            }
+            _ => return self.consume_token_and_error(SyntaxError::ExpectedConstAssignment),
+        }
+
+        let Some(expression) = self.parse_expressions(context, TempResult::No)? else {
+            return self.error(ExpectedIndentation::AssignmentExpression);
+        };
+
+        self.push_node_with_start_span(
+            Node::Const {
+                target: id,
+                expression,
+            },
+            start_span,
+        )
+    }
+
+    // Parses a record declaration
+    //
+    // e.g.
+    //   record Point
+    //   # ^ You are here
+    //     x: Number
+    //     y: Number = 0
+    fn consume_record_declaration(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_token_with_context(context); // record
+        let start_span = self.current_span();
+
+        let Some((id_index, _)) = self.parse_id(&ExpressionContext::restricted())? else {
+            return self.consume_token_and_error(InternalError::UnexpectedToken);
+        };
+        let id = self.push_node(Node::Id(id_index, None))?;
+
+        let mut fields = AstVec::new();
+
+        let braces = if self.peek_next_token_on_same_line() == Some(Token::CurlyOpen) {
+            self.consume_next_token_on_same_line(); // {
+
+            let field_context = ExpressionContext::inside_braces();
+
+            while self.peek_token_with_context(&field_context).is_some() {
+                self.consume_until_token_with_context(&field_context);
+
+                if self.peek_token() != Some(Token::Id) {
+                    break;
+                }
+
+                self.consume_record_field(&field_context, &mut fields)?;
+
+                if self
+                    .peek_token_with_context(&field_context)
+                    .map(|peeked| peeked.token)
+                    == Some(Token::Comma)
+                {
+                    self.consume_token_with_context(&field_context);
+                } else {
+                    break;
+                }
+            }
+
+            self.expect_and_consume_token(
+                Token::CurlyClose,
+                SyntaxError::ExpectedRecordEnd.into(),
+                &field_context,
+            )?;
+
+            true
+        } else {
+            let start_indent = self.current_indent();
+            let block_context = ExpressionContext::permissive();
+            match self.peek_token_with_context(&block_context) {
+                Some(peeked) if peeked.info.indent > start_indent => {}
+                _ => return self.consume_token_and_error(InternalError::UnexpectedToken),
+            }
+
+            let block_context = self
+                .consume_until_token_with_context(&block_context)
+                .unwrap(); // Safe to unwrap here given that we've just peeked
+
+            loop {
+                if self.peek_token() != Some(Token::Id) {
+                    return self.consume_token_and_error(SyntaxError::ExpectedRecordField);
+                }
+
+                self.consume_record_field(&ExpressionContext::inline(), &mut fields)?;
+
+                match self.peek_next_token_on_same_line() {
+                    None | Some(Token::NewLine) => {}
+                    _ => return self.consume_token_and_error(SyntaxError::UnexpectedToken),
+                }
+
+                if self.peek_token_with_context(&block_context).is_none() {
+                    break;
+                }
+
+                self.consume_until_token_with_context(&block_context);
+            }
+
+            false
+        };
+
+        self.frame_mut()?.add_local_id_assignment(id_index);
+
+        self.push_node_with_start_span(Node::Record { id, fields, braces }, start_span)
+    }
+
+    // Parses an enum declaration
+    //
+    // e.g.
+    //   enum Shape
+    //   # ^ You are here
+    //     Circle(radius)
+    //     Empty
+    fn consume_enum_declaration(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_token_with_context(context); // enum
+        let start_span = self.current_span();
+
+        let Some((id_index, _)) = self.parse_id(&ExpressionContext::restricted())? else {
+            return self.consume_token_and_error(InternalError::UnexpectedToken);
+        };
+        let id = self.push_node(Node::Id(id_index, None))?;
+
+        let mut variants = AstVec::new();
+
+        let braces = if self.peek_next_token_on_same_line() == Some(Token::CurlyOpen) {
+            self.consume_next_token_on_same_line(); // {
+
+            let variant_context = ExpressionContext::inside_braces();
+
+            while self.peek_token_with_context(&variant_context).is_some() {
+                self.consume_until_token_with_context(&variant_context);
+
+                if self.peek_token() != Some(Token::Id) {
+                    break;
+                }
+
+                self.consume_enum_variant(&variant_context, &mut variants)?;
+
+                if self
+                    .peek_token_with_context(&variant_context)
+                    .map(|peeked| peeked.token)
+                    == Some(Token::Comma)
+                {
+                    self.consume_token_with_context(&variant_context);
+                } else {
+                    break;
+                }
+            }
+
+            self.expect_and_consume_token(
+                Token::CurlyClose,
+                SyntaxError::ExpectedEnumEnd.into(),
+                &variant_context,
+            )?;
+
+            true
+        } else {
+            let start_indent = self.current_indent();
+            let block_context = ExpressionContext::permissive();
+            match self.peek_token_with_context(&block_context) {
+                Some(peeked) if peeked.info.indent > start_indent => {}
+                _ => return self.consume_token_and_error(InternalError::UnexpectedToken),
+            }
+
+            let block_context = self
+                .consume_until_token_with_context(&block_context)
+                .unwrap(); // Safe to unwrap here given that we've just peeked
+
+            loop {
+                if self.peek_token() != Some(Token::Id) {
+                    return self.consume_token_and_error(SyntaxError::ExpectedEnumVariant);
+                }
+
+                self.consume_enum_variant(&ExpressionContext::inline(), &mut variants)?;
+
+                match self.peek_next_token_on_same_line() {
+                    None | Some(Token::NewLine) => {}
+                    _ => return self.consume_token_and_error(SyntaxError::UnexpectedToken),
+                }
+
+                if self.peek_token_with_context(&block_context).is_none() {
+                    break;
+                }
+
+                self.consume_until_token_with_context(&block_context);
+            }
+
+            false
+        };
+
+        self.frame_mut()?.add_local_id_assignment(id_index);
+
+        self.push_node_with_start_span(
+            Node::Enum {
+                id,
+                variants,
+                braces,
+            },
+            start_span,
+        )
+    }
+
+    // Parses a variant in an enum declaration, with optional payload fields
+    //
+    // e.g.
+    //   enum Shape
+    //     Circle(radius)
+    //   # ^ You are here
+    fn consume_enum_variant(
+        &mut self,
+        context: &ExpressionContext,
+        variants: &mut AstVec<AstIndex>,
+    ) -> Result<()> {
+        self.consume_token_with_context(context); // Token::Id
+        let variant_span = self.current_span();
+        let id = self.add_current_slice_as_string_constant()?;
+
+        if variants.iter().any(|variant| {
+            matches!(
+                self.ast.node(*variant).node,
+                Node::EnumVariant { id: existing, .. } if existing == id
+            )
+        }) {
+            let name = self.current_token.slice(self.source).to_string();
+            return self.error(SyntaxError::DuplicateEnumVariant(name));
+        }
+
+        let mut fields = AstVec::new();
+
+        // Payload fields are in parentheses directly following the variant's name
+        if self.peek_token() == Some(Token::RoundOpen) {
+            self.consume_token(); // (
+
+            let field_context = ExpressionContext::inside_braces();
+
+            loop {
+                if !matches!(
+                    self.peek_token_with_context(&field_context),
+                    Some(peeked) if peeked.token == Token::Id
+                ) {
+                    return self.consume_token_and_error(SyntaxError::ExpectedEnumVariantField);
+                }
+
+                self.consume_token_with_context(&field_context); // Token::Id
+                let field_id = self.add_current_slice_as_string_constant()?;
+
+                if fields.iter().any(|field| {
+                    matches!(self.ast.node(*field).node, Node::Id(existing, _) if existing == field_id)
+                }) {
+                    let name = self.current_token.slice(self.source).to_string();
+                    return self.error(SyntaxError::DuplicateEnumVariantField(name));
+                }
+
+                fields.push(self.push_node(Node::Id(field_id, None))?);
+
+                if self
+                    .peek_token_with_context(&field_context)
+                    .map(|peeked| peeked.token)
+                    == Some(Token::Comma)
+                {
+                    self.consume_token_with_context(&field_context);
+                } else {
+                    break;
+                }
+            }
+
+            self.expect_and_consume_token(
+                Token::RoundClose,
+                SyntaxError::ExpectedCloseParen.into(),
+                &field_context,
+            )?;
+        }
+
+        let variant =
+            self.push_node_with_start_span(Node::EnumVariant { id, fields }, variant_span)?;
+        variants.push(variant);
+        Ok(())
+    }
+
+    // Parses a field in a record declaration, with an optional type hint and default value
+    //
+    // e.g.
+    //   record Point
+    //     x: Number = 0
+    //   # ^ You are here
+    fn consume_record_field(
+        &mut self,
+        context: &ExpressionContext,
+        fields: &mut AstVec<AstIndex>,
+    ) -> Result<()> {
+        self.consume_token_with_context(context); // Token::Id
+        let field_span = self.current_span();
+        let id = self.add_current_slice_as_string_constant()?;
+
+        let mut previous_field_has_default = false;
+        for field in fields.iter() {
+            let (field_id, has_default) = match &self.ast.node(*field).node {
+                Node::Assign { target, .. } => (*target, true),
+                _ => (*field, false),
+            };
+            if matches!(self.ast.node(field_id).node, Node::Id(existing, _) if existing == id) {
+                let name = self.current_token.slice(self.source).to_string();
+                return self.error(SyntaxError::DuplicateRecordField(name));
+            }
+            previous_field_has_default = has_default;
+        }
+
+        let type_hint = self.parse_type_hint(context)?;
+        let field = self.push_node_with_span(Node::Id(id, type_hint), field_span)?;
+
+        let result = if self
+            .peek_token_with_context(context)
+            .map(|peeked| peeked.token)
+            == Some(Token::Assign)
+        {
+            self.consume_token_with_context(context); // =
+
+            let Some(default_value) = self.parse_expression(context)? else {
+                return self.consume_token_and_error(SyntaxError::ExpectedExpression);
+            };
+
+            self.push_node_with_start_span(
+                Node::Assign {
+                    target: field,
+                    expression: default_value,
+                    let_assignment: false,
+                },
+                field_span,
+            )?
+        } else if previous_field_has_default {
+            return self.error(SyntaxError::ExpectedRecordFieldDefault);
+        } else {
+            field
+        };
+
+        fields.push(result);
+        Ok(())
+    }
+
     fn parse_string(&mut self, context: &ExpressionContext) -> Result<Option<ParseStringOutput>> {
         use SyntaxError::*;
         use Token::*;
@@ -4091,6 +4526,52 @@ impl<'source> Parser<'source> {
         None
     }
 
+    // Checks if the next token is a contextual keyword that starts a declaration
+    //
+    // `record` and `enum` only start a declaration when they're followed by the declaration's
+    // name, and then by either a `{` or an indented block, e.g. `record Point {x, y}`.
+    // Otherwise they're treated as regular ids, e.g. `record = 1` or `print enum foo`.
+    fn peek_declaration(&mut self, keyword: &str) -> bool {
+        let mut peek_count = 0;
+
+        let keyword_indent = loop {
+            match self.lexer.peek(peek_count) {
+                Some(peeked) if peeked.token.is_whitespace() => peek_count += 1,
+                Some(peeked)
+                    if peeked.token == Token::Id && peeked.slice(self.source) == keyword =>
+                {
+                    break peeked.indent;
+                }
+                _ => return false,
+            }
+        };
+
+        // The declaration's name
+        peek_count += 1;
+        loop {
+            match self.peek_token_n(peek_count) {
+                Some(token) if token.is_whitespace() => peek_count += 1,
+                Some(Token::Id) => break,
+                _ => return false,
+            }
+        }
+
+        // A `{` on the same line, or an indented block
+        peek_count += 1;
+        let mut same_line = true;
+        while let Some(peeked) = self.lexer.peek(peek_count) {
+            match peeked.token {
+                token if token.is_whitespace() => {}
+                Token::NewLine => same_line = false,
+                Token::CurlyOpen if same_line => return true,
+                _ => return !same_line && peeked.indent > keyword_indent,
+            }
+            peek_count += 1;
+        }
+
+        false
+    }
+
     // Peeks past whitespace on the same line until the next token is found
     fn peek_next_token_on_same_line(&mut self) -> Option<Token> {
         let mut peek_count = 0;
@@ -2053,6 +2053,446 @@ let {x as _} = {x: 1}
         }
     }
 
+    mod const_declaration {
+        use super::*;
+
+        #[test]
+        fn number() {
+            let source = "const a = 1";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // a
+                    SmallInt(1),
+                    Node::Const {
+                        target: 0.into(),
+                        expression: 1.into(),
+                    },
+                    MainBlock {
+                        body: nodes(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("a")]),
+            )
+        }
+
+        #[test]
+        fn expression_with_type_hint() {
+            let source = "const a: Number = 60 * 60";
+
+            check_ast(
+                source,
+                &[
+                    type_hint(1),            // Number
+                    id_with_type_hint(0, 0), // a
+                    SmallInt(60),
+                    SmallInt(60),
+                    binary_op(AstBinaryOp::Multiply, 2, 3),
+                    Node::Const {
+                        target: 1.into(),
+                        expression: 4.into(),
+                    },
+                    MainBlock {
+                        body: nodes(&[5]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("a"), Constant::Str("Number")]),
+            )
+        }
+
+        #[test]
+        fn export_const() {
+            let source = "export const a = 'hello'";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // a
+                    string_literal(1, StringQuote::Single),
+                    Node::Const {
+                        target: 0.into(),
+                        expression: 1.into(),
+                    },
+                    Node::Export(2.into()),
+                    MainBlock {
+                        body: nodes(&[3]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("a"), Constant::Str("hello")]),
+            )
+        }
+    }
+
+    mod record_declaration {
+        use super::*;
+
+        #[test]
+        fn fields_in_block() {
+            let source = "
+record Point
+  x: Number
+  y = 0
+";
+
+            check_ast(
+                source,
+                &[
+                    id(0),                   // Point
+                    type_hint(2),            // Number
+                    id_with_type_hint(1, 1), // x
+                    id(3),                   // y
+                    SmallInt(0),
+                    assign(3, 4),
+                    Node::Record {
+                        id: 0.into(),
+                        fields: nodes(&[2, 5]),
+                        braces: false,
+                    },
+                    MainBlock {
+                        body: nodes(&[6]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("Point"),
+                    Constant::Str("x"),
+                    Constant::Str("Number"),
+                    Constant::Str("y"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn fields_in_braces() {
+            let source = "record Point {x, y: String? = 'a'}";
+
+            check_ast(
+                source,
+                &[
+                    id(0),                   // Point
+                    id(1),                   // x
+                    optional_type_hint(3),   // String
+                    id_with_type_hint(2, 2), // y
+                    string_literal(4, StringQuote::Single),
+                    assign(3, 4),
+                    Node::Record {
+                        id: 0.into(),
+                        fields: nodes(&[1, 5]),
+                        braces: true,
+                    },
+                    MainBlock {
+                        body: nodes(&[6]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("Point"),
+                    Constant::Str("x"),
+                    Constant::Str("y"),
+                    Constant::Str("String"),
+                    Constant::Str("a"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn export_record() {
+            let source = "export record Empty {}";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // Empty
+                    Node::Record {
+                        id: 0.into(),
+                        fields: nodes(&[]),
+                        braces: true,
+                    },
+                    Node::Export(1.into()),
+                    MainBlock {
+                        body: nodes(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("Empty")]),
+            )
+        }
+
+        #[test]
+        fn record_as_id() {
+            let source = "record = 1";
+
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    MainBlock {
+                        body: nodes(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("record")]),
+            )
+        }
+
+        #[test]
+        fn record_as_call() {
+            let source = "
+record foo
+print record foo
+";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // record
+                    id(1), // foo
+                    chain_call(&[1], false, None),
+                    chain_root(0, Some(2)),
+                    id(2), // print
+                    id(0), // 5 - record
+                    id(1), // foo
+                    chain_call(&[6], false, None),
+                    chain_root(5, Some(7)),
+                    chain_call(&[8], false, None),
+                    chain_root(4, Some(9)), // 10
+                    MainBlock {
+                        body: nodes(&[3, 10]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("record"),
+                    Constant::Str("foo"),
+                    Constant::Str("print"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn record_as_value() {
+            let source = "y = record Foo {}";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // y
+                    id(1), // record
+                    id(2), // Foo
+                    Map {
+                        entries: nodes(&[]),
+                        braces: true,
+                    },
+                    chain_call(&[3], false, None),
+                    chain_root(2, Some(4)), // 5
+                    chain_call(&[5], false, None),
+                    chain_root(1, Some(6)),
+                    assign(0, 7),
+                    MainBlock {
+                        body: nodes(&[8]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("y"),
+                    Constant::Str("record"),
+                    Constant::Str("Foo"),
+                ]),
+            )
+        }
+    }
+
+    mod enum_declaration {
+        use super::*;
+
+        #[test]
+        fn variants_in_block() {
+            let source = "
+enum Shape
+  Circle(radius)
+  Rect(width, height)
+  Empty
+";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // Shape
+                    id(2), // radius
+                    Node::EnumVariant {
+                        id: 1.into(),
+                        fields: nodes(&[1]),
+                    },
+                    id(4), // width
+                    id(5), // height
+                    Node::EnumVariant {
+                        id: 3.into(),
+                        fields: nodes(&[3, 4]),
+                    }, // 5
+                    Node::EnumVariant {
+                        id: 6.into(),
+                        fields: nodes(&[]),
+                    },
+                    Node::Enum {
+                        id: 0.into(),
+                        variants: nodes(&[2, 5, 6]),
+                        braces: false,
+                    },
+                    MainBlock {
+                        body: nodes(&[7]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("Shape"),
+                    Constant::Str("Circle"),
+                    Constant::Str("radius"),
+                    Constant::Str("Rect"),
+                    Constant::Str("width"),
+                    Constant::Str("height"),
+                    Constant::Str("Empty"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn variants_in_braces() {
+            let source = "export enum Color {Red, Green, Rgb(r, g, b)}";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // Color
+                    Node::EnumVariant {
+                        id: 1.into(),
+                        fields: nodes(&[]),
+                    },
+                    Node::EnumVariant {
+                        id: 2.into(),
+                        fields: nodes(&[]),
+                    },
+                    id(4), // r
+                    id(5), // g
+                    id(6), // 5 - b
+                    Node::EnumVariant {
+                        id: 3.into(),
+                        fields: nodes(&[3, 4, 5]),
+                    },
+                    Node::Enum {
+                        id: 0.into(),
+                        variants: nodes(&[1, 2, 6]),
+                        braces: true,
+                    },
+                    Node::Export(7.into()),
+                    MainBlock {
+                        body: nodes(&[8]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("Color"),
+                    Constant::Str("Red"),
+                    Constant::Str("Green"),
+                    Constant::Str("Rgb"),
+                    Constant::Str("r"),
+                    Constant::Str("g"),
+                    Constant::Str("b"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn enum_as_id() {
+            let source = "enum = 1";
+
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    MainBlock {
+                        body: nodes(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("enum")]),
+            )
+        }
+
+        #[test]
+        fn enum_as_call() {
+            let source = "
+enum foo
+print enum foo
+";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // enum
+                    id(1), // foo
+                    chain_call(&[1], false, None),
+                    chain_root(0, Some(2)),
+                    id(2), // print
+                    id(0), // 5 - enum
+                    id(1), // foo
+                    chain_call(&[6], false, None),
+                    chain_root(5, Some(7)),
+                    chain_call(&[8], false, None),
+                    chain_root(4, Some(9)), // 10
+                    MainBlock {
+                        body: nodes(&[3, 10]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("enum"),
+                    Constant::Str("foo"),
+                    Constant::Str("print"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn enum_as_value() {
+            let source = "y = enum Foo {}";
+
+            check_ast(
+                source,
+                &[
+                    id(0), // y
+                    id(1), // enum
+                    id(2), // Foo
+                    Map {
+                        entries: nodes(&[]),
+                        braces: true,
+                    },
+                    chain_call(&[3], false, None),
+                    chain_root(2, Some(4)), // 5
+                    chain_call(&[5], false, None),
+                    chain_root(1, Some(6)),
+                    assign(0, 7),
+                    MainBlock {
+                        body: nodes(&[8]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("y"),
+                    Constant::Str("enum"),
+                    Constant::Str("Foo"),
+                ]),
+            )
+        }
+    }
+
     mod export {
         use super::*;
 
@@ -4281,6 +4721,30 @@ debug x + x
                 Some(&[Constant::Str("x"), Constant::Str("x + x")]),
             )
         }
+
+        #[test]
+        fn await_expression() {
+            let source = "
+await x
+y = await x
+";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    Await(0.into()),
+                    id(1),
+                    id(0),
+                    Await(3.into()),
+                    assign(2, 4), // 5
+                    MainBlock {
+                        body: nodes(&[1, 5]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
     }
 
     mod semicolons {
@@ -5056,6 +5520,48 @@ match x
             )
         }
 
+        #[test]
+        fn match_enum_pattern() {
+            let source = "
+match x
+  Shape.Rect(w, _) then w
+";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1), // Shape
+                    id(3), // w
+                    Ignored(None, None),
+                    Node::EnumPattern {
+                        enum_id: 1.into(),
+                        variant: 2.into(),
+                        payload: nodes(&[2, 3]),
+                    },
+                    id(3), // 5
+                    MatchArm {
+                        patterns: nodes(&[4]),
+                        condition: None,
+                        expression: 5.into(),
+                    },
+                    Match {
+                        expression: 0.into(),
+                        arms: nodes(&[6]),
+                    },
+                    MainBlock {
+                        body: nodes(&[7]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("x"),
+                    Constant::Str("Shape"),
+                    Constant::Str("Rect"),
+                    Constant::Str("w"),
+                ]),
+            )
+        }
+
         #[test]
         fn match_map() {
             let source = r#"
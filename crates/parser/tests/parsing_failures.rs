@@ -715,17 +715,178 @@ switch
             }
         }
 
-        mod reserved_keywords {
+        mod r#await {
             use super::*;
 
             #[test]
-            fn r#await() {
+            fn missing_expression() {
+                check_parsing_fails("x = await");
+            }
+
+            #[test]
+            fn used_as_id() {
                 check_parsing_fails("await = 99");
             }
+        }
+
+        mod const_declaration {
+            use super::*;
 
             #[test]
-            fn r#const() {
-                check_parsing_fails("const = 99");
+            fn missing_id() {
+                check_parsing_fails_with_error_span(
+                    "const = 99",
+                    SyntaxError::ExpectedConstId,
+                    Span {
+                        start: Position { line: 0, column: 6 },
+                        end: Position { line: 0, column: 7 },
+                    },
+                );
+            }
+
+            #[test]
+            fn missing_assignment() {
+                check_parsing_fails_with_error_span(
+                    "const x",
+                    SyntaxError::ExpectedConstAssignment,
+                    Span {
+                        start: Position { line: 0, column: 6 },
+                        end: Position { line: 0, column: 7 },
+                    },
+                );
+            }
+
+            #[test]
+            fn unpacking() {
+                check_parsing_fails("const a, b = 1, 2");
+            }
+        }
+
+        mod record_declaration {
+            use super::*;
+
+            #[test]
+            fn invalid_field() {
+                let source = "
+record Foo
+  1
+";
+                check_parsing_fails_with_error_span(
+                    source,
+                    SyntaxError::ExpectedRecordField,
+                    Span {
+                        start: Position { line: 2, column: 2 },
+                        end: Position { line: 2, column: 3 },
+                    },
+                );
+            }
+
+            #[test]
+            fn missing_default_value() {
+                check_parsing_fails_with_error_span(
+                    "record Foo {x = 1, y}",
+                    SyntaxError::ExpectedRecordFieldDefault,
+                    Span {
+                        start: Position {
+                            line: 0,
+                            column: 19,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 20,
+                        },
+                    },
+                );
+            }
+
+            #[test]
+            fn duplicate_field() {
+                let source = "
+record Foo
+  x
+  x: Number
+";
+                check_parsing_fails_with_error_span(
+                    source,
+                    SyntaxError::DuplicateRecordField("x".into()),
+                    Span {
+                        start: Position { line: 3, column: 2 },
+                        end: Position { line: 3, column: 3 },
+                    },
+                );
+            }
+
+            #[test]
+            fn missing_closing_brace() {
+                check_parsing_fails_with_error_span(
+                    "record Foo {x, y",
+                    SyntaxError::ExpectedRecordEnd,
+                    Span {
+                        start: Position {
+                            line: 0,
+                            column: 15,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 16,
+                        },
+                    },
+                );
+            }
+        }
+
+        mod enum_declaration {
+            use super::*;
+
+            #[test]
+            fn invalid_variant() {
+                let source = "
+enum Foo
+  1
+";
+                check_parsing_fails_with_error_span(
+                    source,
+                    SyntaxError::ExpectedEnumVariant,
+                    Span {
+                        start: Position { line: 2, column: 2 },
+                        end: Position { line: 2, column: 3 },
+                    },
+                );
+            }
+
+            #[test]
+            fn duplicate_variant() {
+                let source = "
+enum Foo
+  A
+  A(x)
+";
+                check_parsing_fails_with_error_span(
+                    source,
+                    SyntaxError::DuplicateEnumVariant("A".into()),
+                    Span {
+                        start: Position { line: 3, column: 2 },
+                        end: Position { line: 3, column: 3 },
+                    },
+                );
+            }
+
+            #[test]
+            fn empty_payload() {
+                check_parsing_fails_with_error_span(
+                    "enum Foo {A()}",
+                    SyntaxError::ExpectedEnumVariantField,
+                    Span {
+                        start: Position {
+                            line: 0,
+                            column: 12,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 13,
+                        },
+                    },
+                );
             }
         }
     }
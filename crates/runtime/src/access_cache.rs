@@ -0,0 +1,228 @@
+use crate::{KMap, KString, KValue, MetaKey, Ptr, ValueMap, types::MapVersion};
+use indexmap::Equivalent;
+use koto_bytecode::Chunk;
+use rustc_hash::FxHasher;
+use smallvec::SmallVec;
+use std::{collections::HashMap, hash::BuildHasherDefault};
+
+// The maximum number of chunks that will have their caches retained when switching between chunks
+//
+// The caches are only an optimization, so they can be safely discarded when the limit is reached.
+const MAX_RETAINED_CHUNKS: usize = 256;
+
+// The access caches used by the VM, with a cache slot for each `.` access instruction in a chunk
+//
+// The cache slots are assigned to access instructions by the compiler,
+// see [Chunk::access_cache_count].
+#[derive(Clone, Default)]
+pub(crate) struct AccessCaches {
+    // The address of the chunk that the current caches belong to
+    chunk: Option<usize>,
+    // The caches for the current chunk
+    caches: Vec<AccessCache>,
+    // Caches for other chunks that have been executed by the VM
+    retained: HashMap<usize, Vec<AccessCache>, BuildHasherDefault<FxHasher>>,
+}
+
+impl AccessCaches {
+    // Returns the cache slot in the given chunk
+    //
+    // None is returned if the slot is out of range, which is only possible with chunks that
+    // haven't been produced by the compiler.
+    pub fn get(&mut self, chunk: &Ptr<Chunk>, slot: u32) -> Option<&mut AccessCache> {
+        let address = &**chunk as *const Chunk as usize;
+
+        if self.chunk != Some(address) {
+            if let Some(previous) = self.chunk {
+                if self.retained.len() >= MAX_RETAINED_CHUNKS {
+                    self.retained.clear();
+                }
+                self.retained
+                    .insert(previous, std::mem::take(&mut self.caches));
+            }
+
+            // Chunk addresses can be reused after a chunk has been dropped, so the retained caches
+            // might belong to a different chunk. Cache entries are always validated against the
+            // accessed key, so stale entries are ignored.
+            self.caches = self.retained.remove(&address).unwrap_or_default();
+
+            // Each access instruction takes up several bytes, so the cache count is capped by the
+            // size of the chunk's bytecode in case the chunk wasn't produced by the compiler.
+            let cache_count = (chunk.access_cache_count as usize).min(chunk.bytes.len());
+            self.caches.resize_with(cache_count, Default::default);
+            self.chunk = Some(address);
+        }
+
+        self.caches.get_mut(slot as usize)
+    }
+}
+
+// The result of a map lookup performed by [AccessCache::lookup]
+pub(crate) enum MapLookup {
+    // The value was found in the map, its meta map, or in a base map
+    Found(KValue),
+    // The key wasn't found, and the lookup ended at a map without a meta map
+    NotFoundWithoutMetaMap,
+    // The key wasn't found, and the lookup ended at a map without a `@base` entry
+    NotFound,
+    // A `@base` entry was found that doesn't contain a map
+    UnexpectedBase(KValue),
+}
+
+// A map that was passed through during a lookup, with the lookup continuing in its `@base` map
+#[derive(Clone, Copy)]
+struct CachedBase {
+    data: MapVersion,
+    meta: MapVersion,
+    // The index of the `@base` entry in the map's meta map
+    base_index: usize,
+}
+
+// The location of a value that was found during a lookup
+#[derive(Clone, Copy)]
+enum CachedEntry {
+    // The value was found at the given index in the map's data
+    Data(usize),
+    // The value was found at the given index in the map's meta map
+    Meta { data: MapVersion, index: usize },
+}
+
+// The cache used by a single access instruction
+//
+// The cache remembers where a value was found in the previous lookup, along with the path through
+// the `@base` chain that was taken to find it. The cached entry is validated by checking that its
+// key matches the accessed key, which allows the cache to be used with different maps that share
+// the same layout, e.g. maps that are produced by the same constructor function.
+//
+// Maps that were passed through also need to be checked to make sure that they still don't
+// contain the key. The map versions that were recorded during the lookup allow these checks to be
+// skipped for maps that haven't been modified, which is typically the case for base maps that are
+// shared between instances.
+#[derive(Clone, Default)]
+pub(crate) struct AccessCache {
+    // The key that was looked up, along with the location of the value that was found
+    entry: Option<(KString, CachedEntry)>,
+    // The maps that were passed through before the entry was found
+    bases: SmallVec<[CachedBase; 2]>,
+}
+
+impl AccessCache {
+    // Looks up a key in a map, following the map's `@base` chain until the key is found
+    //
+    // Keys are looked up first in a map's data, and then in its meta map,
+    // before moving on to the map's `@base` map.
+    pub fn lookup(&mut self, map: &KMap, key: &KString) -> MapLookup {
+        if let Some(value) = self.get(map, key) {
+            return MapLookup::Found(value);
+        }
+
+        self.entry = None;
+        self.bases.clear();
+
+        let mut access_map = map.clone();
+        loop {
+            let data_version = {
+                let data = access_map.data();
+                if let Some((index, _, value)) = data.get_full(key) {
+                    self.entry = Some((key.clone(), CachedEntry::Data(index)));
+                    return MapLookup::Found(value.clone());
+                }
+                data.version()
+            };
+
+            let Some(meta) = access_map.meta_map() else {
+                return MapLookup::NotFoundWithoutMetaMap;
+            };
+
+            let base = {
+                let meta = meta.borrow();
+                if let Some((index, _, value)) = meta.get_full(&MetaKey::Named(key.clone())) {
+                    self.entry = Some((
+                        key.clone(),
+                        CachedEntry::Meta {
+                            data: data_version,
+                            index,
+                        },
+                    ));
+                    return MapLookup::Found(value.clone());
+                }
+
+                match meta.get_full(&MetaKey::Base) {
+                    Some((base_index, _, KValue::Map(base))) => {
+                        self.bases.push(CachedBase {
+                            data: data_version,
+                            meta: meta.version(),
+                            base_index,
+                        });
+                        base.clone()
+                    }
+                    Some((_, _, unexpected)) => {
+                        return MapLookup::UnexpectedBase(unexpected.clone());
+                    }
+                    None => return MapLookup::NotFound,
+                }
+            };
+
+            access_map = base;
+        }
+    }
+
+    // Returns the cached value if the cache is valid for the given map and key
+    fn get(&self, map: &KMap, key: &KString) -> Option<KValue> {
+        let entry = match &self.entry {
+            Some((cached_key, entry)) if cached_key == key => *entry,
+            _ => return None,
+        };
+
+        if self.bases.is_empty() {
+            return Self::get_entry(map, entry, key);
+        }
+
+        let mut access_map = map.clone();
+        for base in self.bases.iter() {
+            if !data_excludes_key(&access_map.data(), base.data, key) {
+                return None;
+            }
+
+            let next = {
+                let meta = access_map.meta_map()?.borrow();
+                if meta.version() != base.meta && meta.contains_key(&MetaKey::Named(key.clone())) {
+                    return None;
+                }
+                match meta.get_index(base.base_index) {
+                    Some((MetaKey::Base, KValue::Map(next))) => next.clone(),
+                    _ => return None,
+                }
+            };
+
+            access_map = next;
+        }
+
+        Self::get_entry(&access_map, entry, key)
+    }
+
+    fn get_entry(map: &KMap, entry: CachedEntry, key: &KString) -> Option<KValue> {
+        match entry {
+            CachedEntry::Data(index) => {
+                let data = map.data();
+                let (entry_key, value) = data.get_index(index)?;
+                key.equivalent(entry_key).then(|| value.clone())
+            }
+            CachedEntry::Meta { data, index } => {
+                if !data_excludes_key(&map.data(), data, key) {
+                    return None;
+                }
+                let meta = map.meta_map()?.borrow();
+                let (entry_key, value) = meta.get_index(index)?;
+                key.equivalent(entry_key).then(|| value.clone())
+            }
+        }
+    }
+}
+
+// Returns true if the key isn't contained in the map's data
+//
+// The key only needs to be looked up if the map has been modified since the version was recorded.
+fn data_excludes_key(data: &ValueMap, version: MapVersion, key: &KString) -> bool {
+    data.version() == version || !data.contains_key(key)
+}
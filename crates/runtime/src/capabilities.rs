@@ -0,0 +1,124 @@
+use std::fmt;
+
+/// A capability that can be disabled in the runtime, see [Capabilities]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Capability {
+    /// Reading from the file system
+    ///
+    /// Used by `io.current_dir`, `io.exists`, `io.open`, `io.read_to_string`, and `io.temp_dir`.
+    FileSystemRead,
+    /// Writing to the file system
+    ///
+    /// Used by `io.create` and `io.remove_file`.
+    FileSystemWrite,
+    /// Spawning processes
+    ///
+    /// Used by `os.command`.
+    ProcessSpawning,
+    /// Reading environment variables
+    ///
+    /// Used by `os.env`.
+    Environment,
+    /// Compiling and running code at runtime
+    ///
+    /// Used by `koto.load` and `koto.run`.
+    DynamicCodeLoading,
+    /// Importing modules that need to be loaded by the runtime's module loader
+    ///
+    /// Items that are available in the prelude (e.g. the core library modules) can be imported
+    /// when imports are disabled.
+    Imports,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Capability::*;
+
+        f.write_str(match self {
+            FileSystemRead => "file system read access",
+            FileSystemWrite => "file system write access",
+            ProcessSpawning => "process spawning",
+            Environment => "environment access",
+            DynamicCodeLoading => "dynamic code loading",
+            Imports => "module imports",
+        })
+    }
+}
+
+/// The capabilities that are available to scripts, see [KotoVmSettings::capabilities]
+///
+/// All capabilities are enabled by default. When running untrusted scripts, capabilities can be
+/// disabled individually, or [Capabilities::none] can be used as a starting point with only the
+/// required capabilities being enabled.
+///
+/// Core library functions that make use of a disabled capability will throw a
+/// [PermissionDenied](crate::ErrorKind::PermissionDenied) error.
+///
+/// ```
+/// use koto_runtime::{Capabilities, Capability};
+///
+/// let capabilities = Capabilities {
+///     file_system_read: true,
+///     ..Capabilities::none()
+/// };
+/// assert!(capabilities.is_enabled(Capability::FileSystemRead));
+/// assert!(!capabilities.is_enabled(Capability::FileSystemWrite));
+/// ```
+///
+/// [KotoVmSettings::capabilities]: crate::KotoVmSettings::capabilities
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Capabilities {
+    pub file_system_read: bool,
+    pub file_system_write: bool,
+    pub process_spawning: bool,
+    pub environment: bool,
+    pub dynamic_code_loading: bool,
+    pub imports: bool,
+}
+
+impl Capabilities {
+    /// Returns a set of capabilities with all capabilities enabled
+    pub fn all() -> Self {
+        Self {
+            file_system_read: true,
+            file_system_write: true,
+            process_spawning: true,
+            environment: true,
+            dynamic_code_loading: true,
+            imports: true,
+        }
+    }
+
+    /// Returns a set of capabilities with all capabilities disabled
+    pub fn none() -> Self {
+        Self {
+            file_system_read: false,
+            file_system_write: false,
+            process_spawning: false,
+            environment: false,
+            dynamic_code_loading: false,
+            imports: false,
+        }
+    }
+
+    /// Returns true if the given capability is enabled
+    pub fn is_enabled(&self, capability: Capability) -> bool {
+        use Capability::*;
+
+        match capability {
+            FileSystemRead => self.file_system_read,
+            FileSystemWrite => self.file_system_write,
+            ProcessSpawning => self.process_spawning,
+            Environment => self.environment,
+            DynamicCodeLoading => self.dynamic_code_loading,
+            Imports => self.imports,
+        }
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
@@ -1,11 +1,11 @@
 //! The `io` core library module
 
 use crate::{
-    BufferedFile, Error, Ptr, Result, UnavailableStderr, UnavailableStdin, UnavailableStdout,
-    derive::*, prelude::*,
+    BufferedFile, Capability, Error, Ptr, Result, UnavailableStderr, UnavailableStdin,
+    UnavailableStdout, derive::*, prelude::*,
 };
 use std::{
-    fmt, fs,
+    fmt,
     io::{self, BufRead, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
 };
@@ -17,32 +17,38 @@ pub fn make_module() -> KMap {
     let result = KMap::with_type("core.io");
 
     result.add_fn("create", {
-        move |ctx| match ctx.args() {
-            [Str(path)] => {
-                let path = Path::new(path.as_str()).to_path_buf();
-                match fs::File::create(&path) {
-                    Ok(file) => Ok(File::system_file(file, path)),
+        move |ctx| {
+            ctx.vm.check_capability(Capability::FileSystemWrite)?;
+            match ctx.args() {
+                [Str(path)] => match ctx.vm.file_system().create(Path::new(path.as_str())) {
+                    Ok(file) => Ok(File::new(file).into()),
                     Err(error) => runtime_error!("error while creating file: {error}"),
-                }
+                },
+                unexpected => unexpected_args("|String|", unexpected),
             }
-            unexpected => unexpected_args("|String|", unexpected),
         }
     });
 
-    result.add_fn("current_dir", |ctx| match ctx.args() {
-        [] => {
-            let result = match std::env::current_dir() {
-                Ok(path) => Str(path.to_string_lossy().to_string().into()),
-                Err(_) => Null,
-            };
-            Ok(result)
+    result.add_fn("current_dir", |ctx| {
+        ctx.vm.check_capability(Capability::FileSystemRead)?;
+        match ctx.args() {
+            [] => {
+                let result = match ctx.vm.file_system().current_dir() {
+                    Ok(path) => Str(path.to_string_lossy().to_string().into()),
+                    Err(_) => Null,
+                };
+                Ok(result)
+            }
+            unexpected => unexpected_args("||", unexpected),
         }
-        unexpected => unexpected_args("||", unexpected),
     });
 
-    result.add_fn("exists", |ctx| match ctx.args() {
-        [Str(path)] => Ok(Bool(fs::canonicalize(path.as_str()).is_ok())),
-        unexpected => unexpected_args("|String|", unexpected),
+    result.add_fn("exists", |ctx| {
+        ctx.vm.check_capability(Capability::FileSystemRead)?;
+        match ctx.args() {
+            [Str(path)] => Ok(Bool(ctx.vm.file_system().exists(Path::new(path.as_str())))),
+            unexpected => unexpected_args("|String|", unexpected),
+        }
     });
 
     result.add_fn("extend_path", |ctx| match ctx.args() {
@@ -65,15 +71,15 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("open", {
-        |ctx| match ctx.args() {
-            [Str(path)] => match fs::canonicalize(path.as_str()) {
-                Ok(path) => match fs::File::open(&path) {
-                    Ok(file) => Ok(File::system_file(file, path)),
+        |ctx| {
+            ctx.vm.check_capability(Capability::FileSystemRead)?;
+            match ctx.args() {
+                [Str(path)] => match ctx.vm.file_system().open(Path::new(path.as_str())) {
+                    Ok(file) => Ok(File::new(file).into()),
                     Err(error) => runtime_error!("error while opening path: {error}"),
                 },
-                Err(_) => runtime_error!("failed to canonicalize path"),
-            },
-            unexpected => unexpected_args("|String|", unexpected),
+                unexpected => unexpected_args("|String|", unexpected),
+            }
         }
     });
 
@@ -103,29 +109,39 @@ pub fn make_module() -> KMap {
         result.map(|_| Null)
     });
 
-    result.add_fn("read_to_string", |ctx| match ctx.args() {
-        [Str(path)] => match fs::read_to_string(Path::new(path.as_str())) {
-            Ok(result) => Ok(result.into()),
-            Err(error) => {
-                runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
-            }
-        },
-        unexpected => unexpected_args("|String|", unexpected),
+    result.add_fn("read_to_string", |ctx| {
+        ctx.vm.check_capability(Capability::FileSystemRead)?;
+        match ctx.args() {
+            [Str(path)] => match ctx
+                .vm
+                .file_system()
+                .read_to_string(Path::new(path.as_str()))
+            {
+                Ok(result) => Ok(result.into()),
+                Err(error) => {
+                    runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
+                }
+            },
+            unexpected => unexpected_args("|String|", unexpected),
+        }
     });
 
     result.add_fn("remove_file", {
-        |ctx| match ctx.args() {
-            [Str(path)] => {
-                let path = Path::new(path.as_str());
-                match fs::remove_file(path) {
-                    Ok(_) => Ok(KValue::Null),
-                    Err(error) => runtime_error!(
-                        "io.remove_file: Error while removing file '{}': {error}",
-                        path.to_string_lossy(),
-                    ),
+        |ctx| {
+            ctx.vm.check_capability(Capability::FileSystemWrite)?;
+            match ctx.args() {
+                [Str(path)] => {
+                    let path = Path::new(path.as_str());
+                    match ctx.vm.file_system().remove_file(path) {
+                        Ok(_) => Ok(KValue::Null),
+                        Err(error) => runtime_error!(
+                            "io.remove_file: Error while removing file '{}': {error}",
+                            path.to_string_lossy(),
+                        ),
+                    }
                 }
+                unexpected => unexpected_args("|String|", unexpected),
             }
-            unexpected => unexpected_args("|String|", unexpected),
         }
     });
 
@@ -133,9 +149,15 @@ pub fn make_module() -> KMap {
     result.insert("stdout", File::new(make_ptr!(UnavailableStdout::default())));
     result.insert("stderr", File::new(make_ptr!(UnavailableStderr::default())));
 
-    result.add_fn("temp_dir", |ctx| match ctx.args() {
-        [] => Ok(std::env::temp_dir().to_string_lossy().as_ref().into()),
-        unexpected => unexpected_args("||", unexpected),
+    result.add_fn("temp_dir", |ctx| {
+        ctx.vm.check_capability(Capability::FileSystemRead)?;
+        match ctx.args() {
+            [] => match ctx.vm.file_system().temp_dir() {
+                Ok(path) => Ok(path.to_string_lossy().as_ref().into()),
+                Err(error) => runtime_error!("io.temp_dir: {error}"),
+            },
+            unexpected => unexpected_args("||", unexpected),
+        }
     });
 
     result
@@ -253,7 +275,7 @@ impl From<File> for KValue {
     }
 }
 
-struct BufferedSystemFile<T>
+pub(crate) struct BufferedSystemFile<T>
 where
     T: Write + KotoSend + KotoSync,
 {
@@ -4,7 +4,7 @@ pub mod adaptors;
 pub mod generators;
 pub mod peekable;
 
-use crate::{KIteratorOutput as Output, Result, derive::*, prelude::*};
+use crate::{KIteratorOutput as Output, Ptr, Result, derive::*, prelude::*};
 
 static MODULE_NAME: &str = "core.iterator";
 
@@ -766,17 +766,25 @@ pub fn make_module() -> KMap {
                 let iterable = iterable.clone();
                 let iterator = ctx.vm.make_iterator(iterable)?;
                 let (size_hint, _) = iterator.size_hint();
-                let mut result = ValueVec::with_capacity(size_hint);
+                let result = KList::default();
+                ctx.vm.charge_list(&result, size_hint)?;
+                result.data_mut().reserve(size_hint);
 
                 for output in iterator.map(collect_pair) {
                     match output {
-                        Output::Value(value) => result.push(value),
+                        Output::Value(value) => {
+                            let len = result.len();
+                            if len >= size_hint {
+                                ctx.vm.charge_list(&result, len + 1)?;
+                            }
+                            result.data_mut().push(value)
+                        }
                         Output::Error(error) => return Err(error),
                         _ => unreachable!(),
                     }
                 }
 
-                Ok(KValue::List(KList::with_data(result)))
+                Ok(KValue::List(result))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
@@ -845,16 +853,22 @@ pub fn make_module() -> KMap {
                 let iterable = iterable.clone();
                 let iterator = ctx.vm.make_iterator(iterable)?;
                 let (size_hint, _) = iterator.size_hint();
+                ctx.vm.check_sequence_size(size_hint)?;
                 let mut result = Vec::with_capacity(size_hint);
 
                 for output in iterator.map(collect_pair) {
                     match output {
-                        Output::Value(value) => result.push(value),
+                        Output::Value(value) => {
+                            ctx.vm.check_sequence_size(result.len() + 1)?;
+                            result.push(value)
+                        }
                         Output::Error(error) => return Err(error),
                         _ => unreachable!(),
                     }
                 }
 
+                let result = Ptr::from(result);
+                ctx.vm.charge_tuple(&result)?;
                 Ok(KValue::Tuple(result.into()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
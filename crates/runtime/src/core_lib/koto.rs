@@ -1,7 +1,7 @@
 //! The `koto` core library module
 
-use crate::Result;
 use crate::prelude::*;
+use crate::{Capability, Result};
 use koto_bytecode::CompilerSettings;
 use koto_derive::{KotoCopy, KotoType};
 use koto_memory::Ptr;
@@ -78,21 +78,27 @@ pub fn make_module() -> KMap {
 
     result.insert("unimplemented", KObject::from(Unimplemented));
 
-    result.add_fn("load", |ctx| match ctx.args() {
-        [KValue::Str(s)] => Ok(try_load_koto_script(ctx, s)?.into()),
-        unexpected => unexpected_args("|String|", unexpected),
+    result.add_fn("load", |ctx| {
+        ctx.vm.check_capability(Capability::DynamicCodeLoading)?;
+        match ctx.args() {
+            [KValue::Str(s)] => Ok(try_load_koto_script(ctx, s)?.into()),
+            unexpected => unexpected_args("|String|", unexpected),
+        }
     });
 
-    result.add_fn("run", |ctx| match ctx.args() {
-        [KValue::Str(s)] => {
-            let chunk = try_load_koto_script(ctx, s)?;
-            ctx.vm.run(chunk.inner())
-        }
-        [KValue::Object(o)] if o.is_a::<Chunk>() => {
-            let chunk = o.cast::<Chunk>().unwrap().inner();
-            ctx.vm.run(chunk)
+    result.add_fn("run", |ctx| {
+        ctx.vm.check_capability(Capability::DynamicCodeLoading)?;
+        match ctx.args() {
+            [KValue::Str(s)] => {
+                let chunk = try_load_koto_script(ctx, s)?;
+                ctx.vm.run(chunk.inner())
+            }
+            [KValue::Object(o)] if o.is_a::<Chunk>() => {
+                let chunk = o.cast::<Chunk>().unwrap().inner();
+                ctx.vm.run(chunk)
+            }
+            unexpected => unexpected_args("|String|, or |Chunk|", unexpected),
         }
-        unexpected => unexpected_args("|String|, or |Chunk|", unexpected),
     });
 
     result
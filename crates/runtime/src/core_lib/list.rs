@@ -57,10 +57,12 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), [KValue::List(other)]) => {
+                ctx.vm.charge_list(l, l.len() + other.len())?;
                 l.data_mut().extend(other.data().iter().cloned());
                 Ok(KValue::List(l.clone()))
             }
             (KValue::List(l), [KValue::Tuple(other)]) => {
+                ctx.vm.charge_list(l, l.len() + other.len())?;
                 l.data_mut().extend(other.iter().cloned());
                 Ok(KValue::List(l.clone()))
             }
@@ -72,11 +74,16 @@ pub fn make_module() -> KMap {
                 {
                     let mut list_data = l.data_mut();
                     let (size_hint, _) = iterator.size_hint();
-                    list_data.reserve(size_hint);
+                    if ctx.vm.charge_list(&l, list_data.len() + size_hint).is_ok() {
+                        list_data.reserve(size_hint);
+                    }
 
                     for value in iterator.map(collect_pair) {
                         match value {
-                            KIteratorOutput::Value(value) => list_data.push(value.clone()),
+                            KIteratorOutput::Value(value) => {
+                                ctx.vm.charge_list(&l, list_data.len() + 1)?;
+                                list_data.push(value.clone())
+                            }
                             KIteratorOutput::Error(error) => return Err(error),
                             _ => unreachable!(),
                         }
@@ -149,6 +156,7 @@ pub fn make_module() -> KMap {
                     return runtime_error!("index out of bounds");
                 }
 
+                ctx.vm.charge_list(l, l.len() + 1)?;
                 l.data_mut().insert(index, value.clone());
                 Ok(KValue::List(l.clone()))
             }
@@ -194,6 +202,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), [value]) => {
+                ctx.vm.charge_list(l, l.len() + 1)?;
                 l.data_mut().push(value.clone());
                 Ok(KValue::List(l.clone()))
             }
@@ -225,10 +234,12 @@ pub fn make_module() -> KMap {
                 runtime_error!("expected a non-negative size")
             }
             (KValue::List(l), [KValue::Number(n)]) => {
+                ctx.vm.charge_list(l, n.into())?;
                 l.data_mut().resize(n.into(), KValue::Null);
                 Ok(KValue::List(l.clone()))
             }
             (KValue::List(l), [KValue::Number(n), value]) => {
+                ctx.vm.charge_list(l, n.into())?;
                 l.data_mut().resize(n.into(), value.clone());
                 Ok(KValue::List(l.clone()))
             }
@@ -246,6 +257,7 @@ pub fn make_module() -> KMap {
                 }
 
                 let new_size = usize::from(n);
+                ctx.vm.charge_list(l, new_size)?;
                 let len = l.len();
                 let l = l.clone();
                 let f = f.clone();
@@ -368,9 +380,7 @@ pub fn make_module() -> KMap {
 
                 let sorted = sort_by_key(ctx.vm, l.data().as_ref(), f.clone())?;
 
-                for (target_value, (_key, source_value)) in
-                    l.data_mut().iter_mut().zip(sorted.into_iter())
-                {
+                for (target_value, (_key, source_value)) in l.data_mut().iter_mut().zip(sorted) {
                     *target_value = source_value;
                 }
 
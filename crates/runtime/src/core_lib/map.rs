@@ -37,6 +37,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [KValue::Map(other)]) => {
+                ctx.vm.charge_map(m, m.len() + other.len())?;
                 m.data_mut().extend(
                     other
                         .data()
@@ -53,7 +54,9 @@ pub fn make_module() -> KMap {
                 {
                     let mut map_data = m.data_mut();
                     let (size_hint, _) = iterator.size_hint();
-                    map_data.reserve(size_hint);
+                    if ctx.vm.charge_map(&m, map_data.len() + size_hint).is_ok() {
+                        map_data.reserve(size_hint);
+                    }
 
                     for output in iterator {
                         use KIteratorOutput as Output;
@@ -68,7 +71,11 @@ pub fn make_module() -> KMap {
                             Output::Error(error) => return Err(error),
                         };
 
-                        map_data.insert(ValueKey::try_from(key.clone())?, value);
+                        let key = ValueKey::try_from(key.clone())?;
+                        if !map_data.contains_key(&key) {
+                            ctx.vm.charge_map(&m, map_data.len() + 1)?;
+                        }
+                        map_data.insert(key, value);
                     }
                 }
 
@@ -144,18 +151,18 @@ pub fn make_module() -> KMap {
         let expected_error = "|Map, Any|, or |Map, Any, Any|";
 
         match map_instance_and_args(ctx, expected_error)? {
-            (KValue::Map(m), [key]) => match m
-                .data_mut()
-                .insert(ValueKey::try_from(key.clone())?, KValue::Null)
-            {
-                Some(old_value) => Ok(old_value),
-                None => Ok(KValue::Null),
-            },
+            (KValue::Map(m), [key]) => {
+                let key = ValueKey::try_from(key.clone())?;
+                ctx.vm.charge_map_insert(m, &key)?;
+                match m.data_mut().insert(key, KValue::Null) {
+                    Some(old_value) => Ok(old_value),
+                    None => Ok(KValue::Null),
+                }
+            }
             (KValue::Map(m), [key, value]) => {
-                match m
-                    .data_mut()
-                    .insert(ValueKey::try_from(key.clone())?, value.clone())
-                {
+                let key = ValueKey::try_from(key.clone())?;
+                ctx.vm.charge_map_insert(m, &key)?;
+                match m.data_mut().insert(key, value.clone()) {
                     Some(old_value) => Ok(old_value),
                     None => Ok(KValue::Null),
                 }
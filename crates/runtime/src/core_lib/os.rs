@@ -3,7 +3,7 @@
 mod command;
 
 use self::command::Command;
-use crate::{Result, derive::*, prelude::*};
+use crate::{Capability, Result, derive::*, prelude::*};
 use chrono::prelude::*;
 use instant::Instant;
 
@@ -15,14 +15,20 @@ pub fn make_module() -> KMap {
 
     result.insert("args", KValue::Tuple(KTuple::default()));
 
-    result.add_fn("env", |ctx| match ctx.args() {
-        [KValue::Str(key)] => Ok(std::env::var(key.as_str()).ok().into()),
-        unexpected => unexpected_args("|String|", unexpected),
+    result.add_fn("env", |ctx| {
+        ctx.vm.check_capability(Capability::Environment)?;
+        match ctx.args() {
+            [KValue::Str(key)] => Ok(std::env::var(key.as_str()).ok().into()),
+            unexpected => unexpected_args("|String|", unexpected),
+        }
     });
 
-    result.add_fn("command", |ctx| match ctx.args() {
-        [KValue::Str(command)] => Ok(Command::make_value(command)),
-        unexpected => unexpected_args("|String|", unexpected),
+    result.add_fn("command", |ctx| {
+        ctx.vm.check_capability(Capability::ProcessSpawning)?;
+        match ctx.args() {
+            [KValue::Str(command)] => Ok(Command::make_value(command)),
+            unexpected => unexpected_args("|String|", unexpected),
+        }
     });
 
     result.add_fn("name", |ctx| match ctx.args() {
@@ -4,6 +4,7 @@ pub mod iterators;
 
 use super::iterator::collect_pair;
 use crate::{
+    Ptr,
     error::{unexpected_args, unexpected_args_after_instance},
     prelude::*,
 };
@@ -129,8 +130,11 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(is_string, expected_error)? {
             (KValue::Str(input), [KValue::Number(n)]) => {
                 if *n >= 0.0 {
-                    let result = input.as_str().repeat(usize::from(n));
-                    Ok(result.into())
+                    let count = usize::from(n);
+                    ctx.vm.check_memory(input.len().saturating_mul(count))?;
+                    let result = Ptr::from(input.as_str().repeat(count));
+                    ctx.vm.charge_string(&result)?;
+                    Ok(KString::from(result).into())
                 } else {
                     runtime_error!("expected a non-negative number")
                 }
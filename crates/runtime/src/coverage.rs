@@ -0,0 +1,345 @@
+use crate::{KString, Ptr};
+use koto_bytecode::{Chunk, Instruction, InstructionReader};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+/// The number of times that a line was executed, see [FileCoverage]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// The line, counting from 0
+    pub line: u32,
+    /// The number of times that the line was executed
+    pub count: u64,
+}
+
+/// The number of times that a function was called, see [FileCoverage]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionCoverage {
+    /// The function's name, derived from the line where the function is declared
+    ///
+    /// The top-level block of a script is named `<main>`.
+    pub name: String,
+    /// The line where the function is declared, counting from 0
+    pub line: u32,
+    /// The number of times that the function was called
+    pub count: u64,
+}
+
+/// The number of times that each path of a conditional branch was taken, see [FileCoverage]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BranchCoverage {
+    /// The line containing the branch, counting from 0
+    pub line: u32,
+    /// The number of times that the branch's jump was taken
+    pub taken: u64,
+    /// The number of times that execution continued without jumping
+    pub not_taken: u64,
+}
+
+impl BranchCoverage {
+    /// Returns true if the branch was executed at least once
+    pub fn was_executed(&self) -> bool {
+        self.taken > 0 || self.not_taken > 0
+    }
+}
+
+/// Coverage information for a single script, see [CoverageReport]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// The path of the script
+    pub path: Option<KString>,
+    /// The lines in the script that produced instructions, in line order
+    pub lines: Vec<LineCoverage>,
+    /// The functions in the script, in line order
+    pub functions: Vec<FunctionCoverage>,
+    /// The conditional branches in the script, in line order
+    pub branches: Vec<BranchCoverage>,
+}
+
+impl FileCoverage {
+    /// Returns the number of lines that were executed at least once
+    pub fn lines_hit(&self) -> usize {
+        self.lines.iter().filter(|line| line.count > 0).count()
+    }
+}
+
+/// A report produced by the coverage tracker, see [KotoVmSettings::coverage]
+///
+/// [KotoVmSettings::coverage]: crate::KotoVmSettings::coverage
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Coverage information for each script that was executed
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Returns the report in the `lcov` tracefile format
+    ///
+    /// Scripts without a path are excluded from the output.
+    pub fn to_lcov(&self) -> String {
+        let mut result = String::new();
+
+        for file in self.files.iter() {
+            let Some(path) = &file.path else {
+                continue;
+            };
+
+            writeln!(result, "TN:").ok();
+            writeln!(result, "SF:{path}").ok();
+
+            for function in file.functions.iter() {
+                writeln!(result, "FN:{},{}", function.line + 1, function.name).ok();
+            }
+            for function in file.functions.iter() {
+                writeln!(result, "FNDA:{},{}", function.count, function.name).ok();
+            }
+            let functions_hit = file.functions.iter().filter(|f| f.count > 0).count();
+            writeln!(result, "FNF:{}", file.functions.len()).ok();
+            writeln!(result, "FNH:{functions_hit}").ok();
+
+            for (block, branch) in file.branches.iter().enumerate() {
+                let line = branch.line + 1;
+                if branch.was_executed() {
+                    writeln!(result, "BRDA:{line},{block},0,{}", branch.taken).ok();
+                    writeln!(result, "BRDA:{line},{block},1,{}", branch.not_taken).ok();
+                } else {
+                    writeln!(result, "BRDA:{line},{block},0,-").ok();
+                    writeln!(result, "BRDA:{line},{block},1,-").ok();
+                }
+            }
+            let branches_hit: usize = file
+                .branches
+                .iter()
+                .map(|branch| (branch.taken > 0) as usize + (branch.not_taken > 0) as usize)
+                .sum();
+            writeln!(result, "BRF:{}", file.branches.len() * 2).ok();
+            writeln!(result, "BRH:{branches_hit}").ok();
+
+            for line in file.lines.iter() {
+                writeln!(result, "DA:{},{}", line.line + 1, line.count).ok();
+            }
+            writeln!(result, "LF:{}", file.lines.len()).ok();
+            writeln!(result, "LH:{}", file.lines_hit()).ok();
+
+            writeln!(result, "end_of_record").ok();
+        }
+
+        result
+    }
+}
+
+// Returns true if the instruction conditionally jumps
+pub(crate) fn is_branch(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::JumpIfTrue { .. }
+        | Instruction::JumpIfFalse { .. }
+        | Instruction::JumpIfNull { .. }
+        | Instruction::JumpIfNotLess { .. }
+        | Instruction::JumpIfNotLessOrEqual { .. }
+        | Instruction::JumpIfNotGreater { .. }
+        | Instruction::JumpIfNotGreaterOrEqual { .. }
+        | Instruction::JumpIfNotEqual { .. }
+        | Instruction::JumpIfEqual { .. }
+        | Instruction::IterNextRange { .. } => true,
+        Instruction::IterNext { jump_offset, .. } => *jump_offset > 0,
+        _ => false,
+    }
+}
+
+// The recorded coverage for a single chunk
+struct ChunkCoverage {
+    chunk: Ptr<Chunk>,
+    // The number of times that each instruction was executed, indexed by ip
+    counts: Vec<u64>,
+    // The number of times that each branch was taken and not taken, keyed by ip
+    branches: HashMap<u32, (u64, u64)>,
+}
+
+// The coverage state that's shared between VMs in a runtime
+#[derive(Default)]
+pub(crate) struct CoverageState {
+    chunks: Vec<ChunkCoverage>,
+    // The index of the most recently used chunk, avoiding a search on every instruction
+    last_chunk: usize,
+}
+
+impl CoverageState {
+    // Records that the instruction at the given ip has been executed
+    pub fn record_instruction(&mut self, chunk: &Ptr<Chunk>, ip: u32) {
+        if let Some(count) = self.chunk_mut(chunk).counts.get_mut(ip as usize) {
+            *count += 1;
+        }
+    }
+
+    // Records whether or not a branch's jump was taken
+    pub fn record_branch(&mut self, chunk: &Ptr<Chunk>, ip: u32, taken: bool) {
+        let branch = self.chunk_mut(chunk).branches.entry(ip).or_default();
+        if taken {
+            branch.0 += 1;
+        } else {
+            branch.1 += 1;
+        }
+    }
+
+    // Clears all recorded coverage
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn chunk_mut(&mut self, chunk: &Ptr<Chunk>) -> &mut ChunkCoverage {
+        let is_last = self
+            .chunks
+            .get(self.last_chunk)
+            .is_some_and(|coverage| Ptr::ptr_eq(&coverage.chunk, chunk));
+
+        if !is_last {
+            self.last_chunk = match self
+                .chunks
+                .iter()
+                .position(|coverage| Ptr::ptr_eq(&coverage.chunk, chunk))
+            {
+                Some(index) => index,
+                None => {
+                    self.chunks.push(ChunkCoverage {
+                        chunk: chunk.clone(),
+                        counts: vec![0; chunk.bytes.len()],
+                        branches: HashMap::default(),
+                    });
+                    self.chunks.len() - 1
+                }
+            };
+        }
+
+        &mut self.chunks[self.last_chunk]
+    }
+
+    pub fn report(&self) -> CoverageReport {
+        // Chunks that share a path (e.g. a script that has been recompiled) are merged together
+        let mut files: Vec<(Option<KString>, FileData)> = Vec::new();
+
+        for coverage in self.chunks.iter() {
+            let path = &coverage.chunk.path;
+            let index = match files.iter().position(|(file_path, _)| file_path == path) {
+                Some(index) => index,
+                None => {
+                    files.push((path.clone(), FileData::default()));
+                    files.len() - 1
+                }
+            };
+            files[index].1.add_chunk(coverage);
+        }
+
+        CoverageReport {
+            files: files
+                .into_iter()
+                .map(|(path, data)| data.into_file_coverage(path))
+                .collect(),
+        }
+    }
+}
+
+// Coverage data that's been gathered for a file
+#[derive(Default)]
+struct FileData {
+    lines: BTreeMap<u32, u64>,
+    functions: BTreeMap<(u32, String), u64>,
+    // Branches are keyed by line, along with the branch's index in the line
+    branches: BTreeMap<(u32, usize), (u64, u64)>,
+}
+
+impl FileData {
+    fn add_chunk(&mut self, coverage: &ChunkCoverage) {
+        let chunk = &coverage.chunk;
+        let debug_info = &chunk.debug_info;
+
+        // Each entry in the source map corresponds to a span of instructions, with the span's
+        // count taken from its first instruction. A line's count is then taken from its most
+        // frequently executed span.
+        let mut lines = BTreeMap::new();
+        for (ip, span) in debug_info.source_map() {
+            let count = coverage
+                .counts
+                .get(*ip as usize)
+                .copied()
+                .unwrap_or_default();
+            let line_count = lines.entry(span.start.line).or_insert(0);
+            *line_count = count.max(*line_count);
+        }
+
+        let mut branches = BTreeMap::new();
+        let mut branches_in_line = 0;
+        let mut previous_line = None;
+        let mut reader = InstructionReader::new(chunk.clone());
+        let mut ip = reader.ip as u32;
+        while let Some(instruction) = reader.next() {
+            if is_branch(&instruction)
+                && let Some(span) = debug_info.get_source_span(ip)
+            {
+                let line = span.start.line;
+                if previous_line != Some(line) {
+                    branches_in_line = 0;
+                    previous_line = Some(line);
+                }
+
+                let branch = coverage.branches.get(&ip).copied().unwrap_or_default();
+                branches.insert((line, branches_in_line), branch);
+                branches_in_line += 1;
+            }
+            ip = reader.ip as u32;
+        }
+
+        for (line, count) in lines {
+            *self.lines.entry(line).or_default() += count;
+        }
+
+        for (key, (taken, not_taken)) in branches {
+            let branch = self.branches.entry(key).or_default();
+            branch.0 += taken;
+            branch.1 += not_taken;
+        }
+
+        for frame in debug_info.frames() {
+            let (line, name) = if frame.start_ip == 0 {
+                (0, "<main>".to_string())
+            } else {
+                let line = debug_info
+                    .get_source_span(frame.start_ip)
+                    .map_or(0, |span| span.start.line);
+                (line, format!("<function:{}>", line + 1))
+            };
+            let count = coverage
+                .counts
+                .get(frame.start_ip as usize)
+                .copied()
+                .unwrap_or_default();
+            *self.functions.entry((line, name)).or_default() += count;
+        }
+    }
+
+    fn into_file_coverage(self, path: Option<KString>) -> FileCoverage {
+        FileCoverage {
+            path,
+            lines: self
+                .lines
+                .into_iter()
+                .map(|(line, count)| LineCoverage { line, count })
+                .collect(),
+            functions: self
+                .functions
+                .into_iter()
+                .map(|((line, name), count)| FunctionCoverage { name, line, count })
+                .collect(),
+            branches: self
+                .branches
+                .into_iter()
+                .map(|((line, _), (taken, not_taken))| BranchCoverage {
+                    line,
+                    taken,
+                    not_taken,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{InstructionFrame, KString, KValue, KotoSend, KotoSync, KotoVm};
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// A line breakpoint that pauses execution when the line is reached
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Breakpoint {
+    /// The path of the script containing the breakpoint
+    ///
+    /// This is compared against the `path` of the chunk that's being executed.
+    pub path: Option<KString>,
+    /// The line of the breakpoint, counting from 0
+    pub line: u32,
+}
+
+impl Breakpoint {
+    /// Initializes a breakpoint with the given path and line
+    pub fn new(path: Option<KString>, line: u32) -> Self {
+        Self { path, line }
+    }
+}
+
+/// The reason why execution was paused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseReason {
+    /// A breakpoint was reached
+    Breakpoint,
+    /// A step command has been completed
+    Step,
+    /// A pause was requested via [KotoVm::request_debug_pause] or a [DebugPauseHandle]
+    PauseRequested,
+}
+
+/// The command returned from [KotoDebugger::paused], defining how execution should continue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// Continue execution until the next breakpoint is reached
+    #[default]
+    Continue,
+    /// Pause on the next line, stepping into any functions that are called
+    StepInto,
+    /// Pause on the next line in the current function, stepping over any function calls
+    StepOver,
+    /// Pause after returning from the current function
+    StepOut,
+}
+
+/// The trait used to implement debuggers for the Koto runtime
+///
+/// A debugger can be attached to the runtime via [KotoVmSettings::debugger].
+///
+/// The runtime calls [paused](KotoDebugger::paused) whenever execution has been paused,
+/// with the VM remaining paused until the function returns.
+///
+/// [KotoVmSettings::debugger]: crate::KotoVmSettings::debugger
+pub trait KotoDebugger: KotoSend + KotoSync {
+    /// Called when execution has been paused
+    ///
+    /// The provided [DebugContext] can be used to inspect the paused VM's state.
+    fn paused(&mut self, context: &mut DebugContext) -> DebugCommand;
+}
+
+/// Provides access to the state of a paused VM, see [KotoDebugger::paused]
+pub struct DebugContext<'a> {
+    vm: &'a mut KotoVm,
+    reason: PauseReason,
+}
+
+impl<'a> DebugContext<'a> {
+    pub(crate) fn new(vm: &'a mut KotoVm, reason: PauseReason) -> Self {
+        Self { vm, reason }
+    }
+
+    /// The reason why execution was paused
+    pub fn reason(&self) -> PauseReason {
+        self.reason
+    }
+
+    /// The paused VM
+    ///
+    /// Breakpoints aren't triggered while the VM is paused, so the VM can be used to evaluate
+    /// values, e.g. with [KotoVm::value_to_string].
+    pub fn vm(&mut self) -> &mut KotoVm {
+        self.vm
+    }
+
+    /// Returns the paused VM's call stack
+    ///
+    /// The innermost frame (i.e. the location where execution was paused) is first in the list.
+    ///
+    /// Note that only the paused VM's call stack is included. If the VM was spawned during a
+    /// native function call (e.g. when an iterator adaptor calls a Koto function), then the
+    /// frames that led to the native function call won't be included.
+    pub fn call_stack(&self) -> Vec<InstructionFrame> {
+        self.vm.debug_call_stack()
+    }
+
+    /// Returns the registers of a frame in the call stack
+    ///
+    /// Frames are indexed as in [call_stack](Self::call_stack), with 0 being the innermost frame.
+    pub fn registers(&self, frame_index: usize) -> &[KValue] {
+        self.vm.debug_frame_registers(frame_index)
+    }
+
+    /// Returns the named local values of a frame in the call stack
+    ///
+    /// Frames are indexed as in [call_stack](Self::call_stack), with 0 being the innermost frame.
+    pub fn locals(&self, frame_index: usize) -> Vec<(KString, KValue)> {
+        self.vm.debug_frame_locals(frame_index)
+    }
+}
+
+/// A handle that allows a pause to be requested from another thread
+///
+/// Calling [request_pause](Self::request_pause) causes the attached debugger to be called when
+/// the next line is reached, with [PauseReason::PauseRequested] as the reason.
+///
+/// The handle is obtained via [KotoVm::debug_pause_handle].
+#[derive(Clone, Debug, Default)]
+pub struct DebugPauseHandle(Arc<AtomicBool>);
+
+impl DebugPauseHandle {
+    /// Requests that execution is paused when the next line is reached
+    pub fn request_pause(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if a pause has been requested that hasn't been handled yet by the VM
+    pub fn is_pause_requested(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn clear(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+// The position of a VM's current frame, used to decide when step commands are complete
+//
+// When a native function calls back into the runtime (e.g. an iterator adaptor calling a Koto
+// function), the nested execution is considered to be deeper than any frames in the caller,
+// so the execution nesting level is compared before the number of frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct DebugDepth {
+    pub nesting: usize,
+    pub frames: usize,
+}
+
+// The debugging state that's shared between VMs in a runtime
+pub(crate) struct DebugState {
+    // The attached debugger, set to None while execution is paused
+    debugger: Option<Box<dyn KotoDebugger>>,
+    breakpoints: HashSet<Breakpoint>,
+    // The active step command, along with the depth where the command was issued
+    step: Option<(DebugCommand, DebugDepth)>,
+    pause_requested: DebugPauseHandle,
+    // The number of active nested calls to `execute_instructions`
+    pub nesting: usize,
+}
+
+impl DebugState {
+    pub fn new(debugger: Box<dyn KotoDebugger>) -> Self {
+        Self {
+            debugger: Some(debugger),
+            breakpoints: HashSet::default(),
+            step: None,
+            pause_requested: DebugPauseHandle::default(),
+            nesting: 0,
+        }
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut HashSet<Breakpoint> {
+        &mut self.breakpoints
+    }
+
+    pub fn pause_handle(&self) -> DebugPauseHandle {
+        self.pause_requested.clone()
+    }
+
+    // Checks if execution should be paused after arriving at a new line
+    pub fn pause_reason(
+        &self,
+        path: &Option<KString>,
+        line: u32,
+        depth: DebugDepth,
+    ) -> Option<PauseReason> {
+        // The debugger is taken while it's handling a pause
+        self.debugger.as_ref()?;
+
+        if self.pause_requested.is_pause_requested() {
+            return Some(PauseReason::PauseRequested);
+        }
+
+        if !self.breakpoints.is_empty()
+            && self
+                .breakpoints
+                .contains(&Breakpoint::new(path.clone(), line))
+        {
+            return Some(PauseReason::Breakpoint);
+        }
+
+        let step_complete = match self.step {
+            Some((DebugCommand::StepInto, _)) => true,
+            Some((DebugCommand::StepOver, step_depth)) => depth <= step_depth,
+            Some((DebugCommand::StepOut, step_depth)) => depth < step_depth,
+            _ => false,
+        };
+
+        step_complete.then_some(PauseReason::Step)
+    }
+
+    // Takes the debugger so that it can be called without holding a borrow of the debug state
+    pub fn take_debugger(&mut self) -> Option<Box<dyn KotoDebugger>> {
+        self.debugger.take()
+    }
+
+    // Returns the debugger after a pause has been handled, along with the debugger's command
+    pub fn resume(
+        &mut self,
+        debugger: Box<dyn KotoDebugger>,
+        command: DebugCommand,
+        depth: DebugDepth,
+    ) {
+        self.debugger = Some(debugger);
+        self.pause_requested.clear();
+        self.step = match command {
+            DebugCommand::Continue => None,
+            step => Some((step, depth)),
+        };
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Ptr, prelude::*};
+use crate::{Capability, Ptr, prelude::*};
 use koto_bytecode::{Chunk, ModuleLoaderError};
 use koto_parser::format_source_excerpt;
 use std::{error, fmt, time::Duration};
@@ -26,8 +26,24 @@ pub enum ErrorKind {
     },
     #[error("execution timed out (the limit of {} seconds was reached)", .0.as_secs_f64())]
     Timeout(Duration),
+    #[error("execution ran out of fuel")]
+    OutOfFuel,
+    #[error("execution was interrupted")]
+    Interrupted,
+    #[error("memory limit exceeded (the runtime's memory budget is {0} bytes)")]
+    MemoryLimitExceeded(usize),
+    #[error("stack overflow (the call depth limit of {0} was reached)")]
+    StackOverflow(usize),
+    #[error("permission denied ({0} is disabled)")]
+    PermissionDenied(Capability),
     #[error("unable to borrow an object that is already mutably borrowed")]
     UnableToBorrowObject,
+    #[error("unable to suspend execution while awaiting a pending value")]
+    UnableToSuspend,
+    #[error("the VM isn't awaiting a pending value")]
+    NotAwaiting,
+    #[error("a resumable run is already in progress")]
+    ResumableRunInProgress,
     #[error(
         "Unexpected arguments.\n  Expected: {expected}\n  Provided: |{}|",
         value_types_as_string(unexpected)
@@ -78,6 +94,17 @@ pub enum ErrorKind {
     CompileError(#[from] ModuleLoaderError),
 }
 
+impl ErrorKind {
+    /// Returns true if the error can be caught in a script with `try`/`catch`
+    ///
+    /// Errors that are thrown when one of the runtime's execution limits has been reached
+    /// (or when the runtime has been interrupted) can't be caught, which ensures that
+    /// the error will be propagated outside of the runtime.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(self, Self::Timeout(_) | Self::OutOfFuel | Self::Interrupted)
+    }
+}
+
 fn display_thrown_value(value: &KValue, vm: Option<&KotoVm>) -> String {
     if let Some(vm) = vm {
         let mut display_context = DisplayContext::with_vm(vm);
@@ -96,6 +123,9 @@ impl fmt::Debug for ErrorKind {
     }
 }
 
+// The maximum number of frames that are included in the trace of a stack overflow error
+const STACK_OVERFLOW_TRACE_FRAMES: usize = 10;
+
 /// An error thrown by the Koto runtime
 #[derive(Clone, Debug)]
 pub struct Error {
@@ -160,6 +190,14 @@ impl Error {
 
     /// Extends the error stack with the given [Chunk] and instruction pointer
     pub(crate) fn extend_trace(&mut self, instruction_frame: InstructionFrame) {
+        // Only the top frames are included in the trace of a stack overflow,
+        // the remaining frames are typically repeats of the same recursive calls.
+        if matches!(self.error, ErrorKind::StackOverflow(_))
+            && self.trace.len() >= STACK_OVERFLOW_TRACE_FRAMES
+        {
+            return;
+        }
+
         self.trace.push(instruction_frame);
     }
 }
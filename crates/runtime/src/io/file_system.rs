@@ -0,0 +1,96 @@
+use crate::{
+    KotoFile, Ptr, Result,
+    core_lib::io::{BufferedSystemFile, map_io_err},
+    make_ptr,
+    prelude::*,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A trait that provides the file system operations that are used by the `io` core library module
+///
+/// By default the runtime uses [SystemFileSystem], implementing this trait allows hosts to
+/// provide alternative file systems, e.g. an in-memory file system for deterministic tests,
+/// a file system that's restricted to a single directory, or a read-only file system.
+///
+/// See [KotoVmSettings::file_system](crate::KotoVmSettings::file_system).
+pub trait KotoFileSystem: KotoSend + KotoSync {
+    /// Opens an existing file for reading, used by `io.open`
+    fn open(&self, path: &Path) -> Result<Ptr<dyn KotoFile>>;
+
+    /// Returns true if the path refers to an existing file or directory, used by `io.exists`
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Creates a file for writing, replacing any existing file, used by `io.create`
+    fn create(&self, _path: &Path) -> Result<Ptr<dyn KotoFile>> {
+        runtime_error!("unsupported for this file system")
+    }
+
+    /// Returns the contents of the file, used by `io.read_to_string`
+    ///
+    /// The default implementation reads the contents of the file returned by
+    /// [open](KotoFileSystem::open).
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.open(path)?.read_to_string()
+    }
+
+    /// Removes the file at the given path, used by `io.remove_file`
+    fn remove_file(&self, _path: &Path) -> Result<()> {
+        runtime_error!("unsupported for this file system")
+    }
+
+    /// Returns the current working directory, used by `io.current_dir`
+    fn current_dir(&self) -> Result<PathBuf> {
+        runtime_error!("unsupported for this file system")
+    }
+
+    /// Returns a directory that can be used for temporary files, used by `io.temp_dir`
+    fn temp_dir(&self) -> Result<PathBuf> {
+        runtime_error!("unsupported for this file system")
+    }
+}
+
+/// The default [KotoFileSystem], which provides access to the host's file system via [std::fs]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemFileSystem;
+
+impl KotoFileSystem for SystemFileSystem {
+    fn open(&self, path: &Path) -> Result<Ptr<dyn KotoFile>> {
+        let Ok(path) = fs::canonicalize(path) else {
+            return runtime_error!("failed to canonicalize path");
+        };
+        match fs::File::open(&path) {
+            Ok(file) => Ok(make_ptr!(BufferedSystemFile::new(file, path))),
+            Err(error) => runtime_error!("{error}"),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        fs::canonicalize(path).is_ok()
+    }
+
+    fn create(&self, path: &Path) -> Result<Ptr<dyn KotoFile>> {
+        match fs::File::create(path) {
+            Ok(file) => Ok(make_ptr!(BufferedSystemFile::new(file, path.to_path_buf()))),
+            Err(error) => runtime_error!("{error}"),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(map_io_err)
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path).map_err(map_io_err)
+    }
+
+    fn current_dir(&self) -> Result<PathBuf> {
+        std::env::current_dir().map_err(map_io_err)
+    }
+
+    fn temp_dir(&self) -> Result<PathBuf> {
+        Ok(std::env::temp_dir())
+    }
+}
@@ -1,10 +1,12 @@
 mod buffered_file;
 mod file;
+mod file_system;
 mod stdio;
 
 pub use self::{
     buffered_file::BufferedFile,
     file::{KotoFile, KotoRead, KotoWrite},
+    file_system::{KotoFileSystem, SystemFileSystem},
     stdio::{
         SystemStderr, SystemStdin, SystemStdout, UnavailableStderr, UnavailableStdin,
         UnavailableStdout,
@@ -2,37 +2,52 @@
 
 #![warn(missing_docs)]
 
+mod access_cache;
+mod capabilities;
+mod coverage;
+mod debug;
 mod display_context;
 mod error;
 mod io;
+mod memory_budget;
+mod profiler;
 mod types;
 mod vm;
 
 pub mod core_lib;
 pub mod prelude;
-mod send_sync;
 
 pub use crate::{
+    capabilities::{Capabilities, Capability},
+    coverage::{BranchCoverage, CoverageReport, FileCoverage, FunctionCoverage, LineCoverage},
+    debug::{Breakpoint, DebugCommand, DebugContext, DebugPauseHandle, KotoDebugger, PauseReason},
     display_context::DisplayContext,
     error::{
         Error, ErrorKind, InstructionFrame, Result, unexpected_args,
         unexpected_args_after_instance, unexpected_type,
     },
     io::{
-        BufferedFile, KotoFile, KotoRead, KotoWrite, SystemStderr, SystemStdin, SystemStdout,
-        UnavailableStderr, UnavailableStdin, UnavailableStdout,
+        BufferedFile, KotoFile, KotoFileSystem, KotoRead, KotoWrite, SystemFileSystem,
+        SystemStderr, SystemStdin, SystemStdout, UnavailableStderr, UnavailableStdin,
+        UnavailableStdout,
     },
-    send_sync::{KotoSend, KotoSync},
+    profiler::{FunctionProfile, LineProfile, ProfileReport, ProfileStats, StackProfile},
     types::{
-        BinaryOp, CallContext, IsIterable, KFunction, KIterator, KIteratorOutput, KList, KMap,
-        KNativeFunction, KNumber, KObject, KRange, KString, KTuple, KValue, KotoAccess, KotoCopy,
-        KotoField, KotoFunction, KotoHasher, KotoIterator, KotoObject, KotoType, MetaKey, MetaMap,
+        BinaryOp, CallContext, IsIterable, KEnumType, KEnumValue, KEnumVariant, KFunction,
+        KIterator, KIteratorOutput, KList, KMap, KNativeFunction, KNumber, KObject, KPending,
+        KRange, KRecord, KRecordType, KString, KTuple, KValue, KotoAccess, KotoCopy, KotoField,
+        KotoFunction, KotoHasher, KotoIterator, KotoObject, KotoType, MetaKey, MetaMap,
         MethodContext, ReadOp, UnaryOp, ValueKey, ValueMap, ValueVec, WriteOp,
     },
-    vm::{CallArgs, KotoVm, KotoVmSettings, ModuleImportedCallback, ReturnOrYield},
+    vm::{
+        CallArgs, InterruptHandle, KotoVm, KotoVmSettings, ModuleImportedCallback, ReturnOrAwait,
+        ReturnOrYield,
+    },
 };
 pub use koto_derive as derive;
-pub use koto_memory::{Borrow, BorrowMut, KCell, Ptr, PtrMut, lazy, make_ptr, make_ptr_mut};
+pub use koto_memory::{
+    Borrow, BorrowMut, KCell, KotoSend, KotoSync, Ptr, PtrMut, lazy, make_ptr, make_ptr_mut,
+};
 
 #[doc(hidden)]
 pub mod __private;
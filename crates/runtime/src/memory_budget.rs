@@ -0,0 +1,179 @@
+use crate::{KCell, KValue, Ptr, PtrMut, ValueKey, ValueMap, ValueVec};
+use koto_memory::WeakPtr;
+use rustc_hash::FxHasher;
+use std::{collections::HashMap, hash::BuildHasherDefault};
+
+// The number of tracked allocations that triggers the first sweep
+//
+// After each sweep the threshold is set to twice the number of remaining allocations, so that the
+// cost of sweeping is spread over the allocations that are tracked in between.
+const MIN_SWEEP_THRESHOLD: usize = 1024;
+
+// Returns the estimated size in bytes of a list or tuple with the given number of elements
+pub(crate) fn sequence_size(len: usize) -> usize {
+    len.saturating_mul(size_of::<KValue>())
+}
+
+// Returns the estimated size in bytes of a map with the given number of entries
+pub(crate) fn map_size(len: usize) -> usize {
+    len.saturating_mul(size_of::<(ValueKey, KValue)>())
+}
+
+// The runtime's memory budget, see `KotoVmSettings::memory_limit`
+//
+// Values are charged to the budget as they're built or extended by the runtime. The budget keeps
+// weak references to the charged values, and when a charge would exceed the limit, the budget is
+// swept, refunding the values that have been dropped and re-measuring the values that remain.
+pub(crate) struct MemoryBudget {
+    // The maximum number of bytes that can be charged to the budget
+    limit: usize,
+    // The number of bytes that are currently charged to the budget
+    used: usize,
+    // The charged values, keyed by the address of their data
+    //
+    // The weak references keep the addresses from being reused while the values are tracked.
+    charges: HashMap<usize, Charge, BuildHasherDefault<FxHasher>>,
+    // The number of charged values that will trigger a sweep
+    sweep_threshold: usize,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            used: 0,
+            charges: HashMap::default(),
+            sweep_threshold: MIN_SWEEP_THRESHOLD,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    // Checks that the given number of bytes fits within the budget, without charging them
+    pub fn check(&mut self, size: usize) -> bool {
+        if !self.fits(size) {
+            self.sweep();
+        }
+        self.fits(size)
+    }
+
+    // Charges the list to the budget with the given size
+    pub fn charge_list(&mut self, list: &PtrMut<ValueVec>, size: usize) -> bool {
+        self.charge(address(list), size, || Tracked::List(Ptr::downgrade(list)))
+    }
+
+    // Charges the map to the budget with the given size
+    pub fn charge_map(&mut self, map: &PtrMut<ValueMap>, size: usize) -> bool {
+        self.charge(address(map), size, || Tracked::Map(Ptr::downgrade(map)))
+    }
+
+    // Charges the tuple's data to the budget
+    pub fn charge_tuple(&mut self, tuple: &Ptr<Vec<KValue>>) -> bool {
+        let size = sequence_size(tuple.len());
+        self.charge(address(tuple), size, || {
+            Tracked::Tuple(Ptr::downgrade(tuple))
+        })
+    }
+
+    // Charges the string's data to the budget
+    pub fn charge_string(&mut self, string: &Ptr<String>) -> bool {
+        let size = string.len();
+        self.charge(address(string), size, || {
+            Tracked::String(Ptr::downgrade(string))
+        })
+    }
+
+    // Updates the size that's charged for a value, returning false if the budget would be exceeded
+    //
+    // When a value shrinks its charge is reduced, with the difference being refunded.
+    fn charge(&mut self, key: usize, size: usize, tracked: impl FnOnce() -> Tracked) -> bool {
+        let charged = |budget: &Self| budget.charges.get(&key).map_or(0, |charge| charge.size);
+
+        let mut previous = charged(self);
+        if size > previous && !self.fits(size - previous) {
+            // The value's charge might be re-measured during the sweep
+            self.sweep();
+            previous = charged(self);
+            if size > previous && !self.fits(size - previous) {
+                return false;
+            }
+        }
+
+        self.used = self.used - previous + size;
+        self.charges
+            .entry(key)
+            .and_modify(|charge| charge.size = size)
+            .or_insert_with(|| Charge {
+                tracked: tracked(),
+                size,
+            });
+
+        if self.charges.len() >= self.sweep_threshold {
+            self.sweep();
+        }
+
+        true
+    }
+
+    fn fits(&self, size: usize) -> bool {
+        self.used.saturating_add(size) <= self.limit
+    }
+
+    // Refunds the charges for dropped values, and updates the charges for values that remain
+    fn sweep(&mut self) {
+        let mut used = 0;
+        self.charges
+            .retain(|_, charge| match charge.tracked.size() {
+                Some(size) => {
+                    // Values that are currently being modified keep their existing charge
+                    charge.size = size.unwrap_or(charge.size);
+                    used += charge.size;
+                    true
+                }
+                None => false,
+            });
+        self.used = used;
+        self.sweep_threshold = (self.charges.len() * 2).max(MIN_SWEEP_THRESHOLD);
+    }
+}
+
+// A value that has been charged to the budget
+struct Charge {
+    tracked: Tracked,
+    // The number of bytes that are charged for the value
+    size: usize,
+}
+
+enum Tracked {
+    List(WeakPtr<KCell<ValueVec>>),
+    Map(WeakPtr<KCell<ValueMap>>),
+    Tuple(WeakPtr<Vec<KValue>>),
+    String(WeakPtr<String>),
+}
+
+impl Tracked {
+    // Returns the value's current size, or None if the value has been dropped
+    //
+    // Some(None) is returned if the value is alive but can't currently be measured.
+    fn size(&self) -> Option<Option<usize>> {
+        match self {
+            Self::List(list) => list
+                .upgrade()
+                .map(|list| list.try_borrow().map(|data| sequence_size(data.len()))),
+            Self::Map(map) => map
+                .upgrade()
+                .map(|map| map.try_borrow().map(|data| map_size(data.len()))),
+            Self::Tuple(tuple) => tuple
+                .upgrade()
+                .map(|tuple| Some(sequence_size(tuple.len()))),
+            Self::String(string) => string.upgrade().map(|string| Some(string.len())),
+        }
+    }
+}
+
+// Returns the address of a value's data, used to identify the value in the budget
+fn address<T>(ptr: &Ptr<T>) -> usize {
+    &**ptr as *const T as usize
+}
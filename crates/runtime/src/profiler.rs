@@ -0,0 +1,442 @@
+use crate::{KString, Ptr};
+use instant::Instant;
+use koto_bytecode::Chunk;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+    path::Path,
+    time::Duration,
+};
+
+/// The instruction count and elapsed time recorded by the profiler for a location
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfileStats {
+    /// The number of instructions that were executed
+    pub instructions: u64,
+    /// The time spent executing the instructions
+    pub time: Duration,
+}
+
+impl ProfileStats {
+    fn add(&mut self, other: &ProfileStats) {
+        self.instructions += other.instructions;
+        self.time += other.time;
+    }
+}
+
+/// Profiling information for a function, see [ProfileReport]
+#[derive(Clone, Debug)]
+pub struct FunctionProfile {
+    /// The name of the function, derived from the script's path and the function's line
+    ///
+    /// The top-level block of a script is named after the script's file.
+    pub name: String,
+    /// The path of the script containing the function
+    pub path: Option<KString>,
+    /// The line where the function is declared, counting from 0
+    pub line: u32,
+    /// The stats for the function's own instructions, excluding any functions that it calls
+    pub self_stats: ProfileStats,
+    /// The stats for the function, including any functions that it calls
+    pub total_stats: ProfileStats,
+}
+
+/// Profiling information for a line of a script, see [ProfileReport]
+#[derive(Clone, Debug)]
+pub struct LineProfile {
+    /// The path of the script containing the line
+    pub path: Option<KString>,
+    /// The line, counting from 0
+    pub line: u32,
+    /// The stats for the instructions produced by the line
+    pub stats: ProfileStats,
+}
+
+/// Profiling information for a call stack, see [ProfileReport]
+#[derive(Clone, Debug)]
+pub struct StackProfile {
+    /// The names of the functions in the stack, with the outermost function first
+    pub functions: Vec<String>,
+    /// The stats for the instructions executed by the innermost function in the stack
+    pub stats: ProfileStats,
+}
+
+/// A report produced by the profiler, see [KotoVmSettings::profiling]
+///
+/// Functions and lines are sorted by elapsed time, with the slowest first.
+///
+/// [KotoVmSettings::profiling]: crate::KotoVmSettings::profiling
+#[derive(Clone, Debug, Default)]
+pub struct ProfileReport {
+    /// Profiling information for each function that was executed
+    pub functions: Vec<FunctionProfile>,
+    /// Profiling information for each line that was executed
+    pub lines: Vec<LineProfile>,
+    /// Profiling information for each call stack that was executed
+    pub stacks: Vec<StackProfile>,
+}
+
+impl ProfileReport {
+    /// Returns the report's call stacks in the 'collapsed stack' format
+    ///
+    /// Each line contains a call stack with function names separated by `;`, followed by the
+    /// time spent in the stack in microseconds. The output can be used to generate flamegraphs
+    /// with tools like `flamegraph.pl` or `inferno-flamegraph`.
+    pub fn collapsed_stacks(&self) -> String {
+        let mut result = String::new();
+
+        for stack in self.stacks.iter() {
+            let time = stack.stats.time.as_micros();
+            if time > 0 {
+                writeln!(result, "{} {time}", stack.functions.join(";")).ok();
+            }
+        }
+
+        result
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Functions")?;
+        writeln!(f, "---------")?;
+        writeln!(
+            f,
+            "{:>12} {:>12} {:>14} {:>14}  Function",
+            "Total (ms)", "Self (ms)", "Total (instr)", "Self (instr)"
+        )?;
+        for function in self.functions.iter() {
+            writeln!(
+                f,
+                "{:>12.3} {:>12.3} {:>14} {:>14}  {}",
+                as_millis(function.total_stats.time),
+                as_millis(function.self_stats.time),
+                function.total_stats.instructions,
+                function.self_stats.instructions,
+                function.name
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Lines")?;
+        writeln!(f, "-----")?;
+        writeln!(f, "{:>12} {:>14}  Line", "Time (ms)", "Instructions")?;
+        for line in self.lines.iter() {
+            writeln!(
+                f,
+                "{:>12.3} {:>14}  {}",
+                as_millis(line.stats.time),
+                line.stats.instructions,
+                location_name(&line.path, Some(line.line))
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// Returns a name for a location in a script, e.g. `foo.koto:42`
+fn location_name(path: &Option<KString>, line: Option<u32>) -> String {
+    let file_name = path
+        .as_ref()
+        .and_then(|path| Path::new(path.as_str()).file_name())
+        .map_or_else(
+            || "<script>".into(),
+            |name| name.to_string_lossy().to_string(),
+        );
+
+    // Semicolons are used as separators in the collapsed stack format
+    let file_name = file_name.replace(';', "_");
+
+    match line {
+        Some(line) => format!("{file_name}:{}", line + 1),
+        None => file_name,
+    }
+}
+
+// A function in a chunk, identified by the ip of the function's first instruction
+#[derive(Clone)]
+pub(crate) struct FunctionKey {
+    pub chunk: Ptr<Chunk>,
+    pub start_ip: u32,
+}
+
+impl PartialEq for FunctionKey {
+    fn eq(&self, other: &Self) -> bool {
+        Ptr::ptr_eq(&self.chunk, &other.chunk) && self.start_ip == other.start_ip
+    }
+}
+
+impl Eq for FunctionKey {}
+
+impl Hash for FunctionKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Ptr::address(&self.chunk).hash(state);
+        self.start_ip.hash(state);
+    }
+}
+
+// A line in a chunk
+#[derive(Clone)]
+struct LineKey {
+    chunk: Ptr<Chunk>,
+    line: u32,
+}
+
+impl PartialEq for LineKey {
+    fn eq(&self, other: &Self) -> bool {
+        Ptr::ptr_eq(&self.chunk, &other.chunk) && self.line == other.line
+    }
+}
+
+impl Eq for LineKey {}
+
+impl Hash for LineKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Ptr::address(&self.chunk).hash(state);
+        self.line.hash(state);
+    }
+}
+
+// The location of the most recently sampled instruction
+#[derive(Clone)]
+struct Location {
+    // The index of the location's call stack in `ProfilerState::stacks`
+    stack: usize,
+    // The index of the location's line in `ProfilerState::lines`
+    line: usize,
+    // The VM's call stack depth and current chunk, used to detect changes in the call stack
+    depth: usize,
+    chunk: Ptr<Chunk>,
+}
+
+// The profiling state that's shared between VMs in a runtime
+//
+// Each executed instruction is sampled, with the time between samples attributed to the
+// previously sampled location.
+#[derive(Default)]
+pub(crate) struct ProfilerState {
+    functions: Vec<FunctionKey>,
+    function_ids: HashMap<FunctionKey, usize>,
+    // Call stacks as lists of function ids, with the outermost function first
+    stacks: Vec<(Vec<usize>, ProfileStats)>,
+    stack_ids: HashMap<Vec<usize>, usize>,
+    lines: Vec<(LineKey, ProfileStats)>,
+    line_ids: HashMap<LineKey, usize>,
+    // The most recently sampled location
+    current: Option<Location>,
+    // The locations that were active when nested executions were started
+    //
+    // When a native function calls back into the runtime (e.g. an iterator adaptor calling a Koto
+    // function), the nested execution's call stack is appended to the outer location's stack.
+    outer: Vec<Option<Location>>,
+    last_sample: Option<Instant>,
+}
+
+impl ProfilerState {
+    // Called when a VM starts executing instructions
+    pub fn begin_execution(&mut self) {
+        self.flush_elapsed();
+        self.outer.push(self.current.take());
+    }
+
+    // Called when a VM has finished executing instructions
+    pub fn end_execution(&mut self) {
+        self.flush_elapsed();
+        self.current = self.outer.pop().flatten();
+        if self.outer.is_empty() {
+            self.last_sample = None;
+        }
+    }
+
+    // Returns true if the call stack needs to be provided to `sample`
+    pub fn call_stack_changed(&self, depth: usize, chunk: &Ptr<Chunk>) -> bool {
+        match &self.current {
+            Some(current) => current.depth != depth || !Ptr::ptr_eq(&current.chunk, chunk),
+            None => true,
+        }
+    }
+
+    // Records an executed instruction
+    //
+    // The `call_stack` iterator is only consumed when `call_stack_changed` returns true,
+    // and provides the functions in the VM's call stack, with the outermost function first.
+    pub fn sample(
+        &mut self,
+        chunk: &Ptr<Chunk>,
+        line: u32,
+        depth: usize,
+        call_stack: impl Iterator<Item = FunctionKey>,
+    ) {
+        self.flush_elapsed();
+
+        let stack = if self.call_stack_changed(depth, chunk) {
+            let mut functions = self
+                .outer
+                .last()
+                .and_then(|outer| outer.as_ref())
+                .map(|outer| self.stacks[outer.stack].0.clone())
+                .unwrap_or_default();
+            for function in call_stack {
+                let id = self.function_id(function);
+                functions.push(id);
+            }
+            self.stack_id(functions)
+        } else {
+            self.current.as_ref().map_or(0, |current| current.stack)
+        };
+
+        let line = self.line_id(LineKey {
+            chunk: chunk.clone(),
+            line,
+        });
+
+        self.stacks[stack].1.instructions += 1;
+        self.lines[line].1.instructions += 1;
+
+        self.current = Some(Location {
+            stack,
+            line,
+            depth,
+            chunk: chunk.clone(),
+        });
+    }
+
+    // Attributes the time since the last sample to the current location
+    fn flush_elapsed(&mut self) {
+        let now = Instant::now();
+        if let (Some(last_sample), Some(current)) = (self.last_sample, &self.current) {
+            let elapsed = now.duration_since(last_sample);
+            self.stacks[current.stack].1.time += elapsed;
+            self.lines[current.line].1.time += elapsed;
+        }
+        self.last_sample = Some(now);
+    }
+
+    fn function_id(&mut self, function: FunctionKey) -> usize {
+        if let Some(id) = self.function_ids.get(&function) {
+            return *id;
+        }
+        let id = self.functions.len();
+        self.functions.push(function.clone());
+        self.function_ids.insert(function, id);
+        id
+    }
+
+    fn stack_id(&mut self, functions: Vec<usize>) -> usize {
+        if let Some(id) = self.stack_ids.get(&functions) {
+            return *id;
+        }
+        let id = self.stacks.len();
+        self.stacks
+            .push((functions.clone(), ProfileStats::default()));
+        self.stack_ids.insert(functions, id);
+        id
+    }
+
+    fn line_id(&mut self, line: LineKey) -> usize {
+        if let Some(id) = self.line_ids.get(&line) {
+            return *id;
+        }
+        let id = self.lines.len();
+        self.lines.push((line.clone(), ProfileStats::default()));
+        self.line_ids.insert(line, id);
+        id
+    }
+
+    // Clears all recorded samples
+    pub fn reset(&mut self) {
+        let outer_count = self.outer.len();
+        *self = Self::default();
+        // Any active executions will still call `end_execution`
+        self.outer.resize(outer_count, None);
+    }
+
+    pub fn report(&self) -> ProfileReport {
+        let function_names: Vec<String> = self
+            .functions
+            .iter()
+            .map(|function| {
+                // The script's top-level block starts at ip 0
+                let line = (function.start_ip > 0).then(|| function_line(function));
+                location_name(&function.chunk.path, line)
+            })
+            .collect();
+
+        let mut self_stats = vec![ProfileStats::default(); self.functions.len()];
+        let mut total_stats = vec![ProfileStats::default(); self.functions.len()];
+        let mut counted = vec![false; self.functions.len()];
+
+        for (stack, stats) in self.stacks.iter() {
+            if let Some(innermost) = stack.last() {
+                self_stats[*innermost].add(stats);
+            }
+
+            // Recursive functions appear multiple times in a stack, but should only be counted once
+            for id in stack.iter() {
+                if !counted[*id] {
+                    total_stats[*id].add(stats);
+                    counted[*id] = true;
+                }
+            }
+            for id in stack.iter() {
+                counted[*id] = false;
+            }
+        }
+
+        let mut functions: Vec<FunctionProfile> = self
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(id, function)| FunctionProfile {
+                name: function_names[id].clone(),
+                path: function.chunk.path.clone(),
+                line: function_line(function),
+                self_stats: self_stats[id],
+                total_stats: total_stats[id],
+            })
+            .collect();
+        functions.sort_by_key(|function| Reverse(function.total_stats.time));
+
+        let mut lines: Vec<LineProfile> = self
+            .lines
+            .iter()
+            .map(|(key, stats)| LineProfile {
+                path: key.chunk.path.clone(),
+                line: key.line,
+                stats: *stats,
+            })
+            .collect();
+        lines.sort_by_key(|line| Reverse(line.stats.time));
+
+        let stacks = self
+            .stacks
+            .iter()
+            .map(|(stack, stats)| StackProfile {
+                functions: stack.iter().map(|id| function_names[*id].clone()).collect(),
+                stats: *stats,
+            })
+            .collect();
+
+        ProfileReport {
+            functions,
+            lines,
+            stacks,
+        }
+    }
+}
+
+// Returns the line where a function is declared
+fn function_line(function: &FunctionKey) -> u32 {
+    function
+        .chunk
+        .debug_info
+        .get_source_span(function.start_ip)
+        .map_or(0, |span| span.start.line)
+}
@@ -0,0 +1,359 @@
+use crate::{ErrorKind, Ptr, Result, derive::*, prelude::*};
+use koto_memory::Address;
+
+/// An enum type declared in a script with `enum`
+///
+/// The enum's variants are accessed with `.`, e.g. `Shape.Empty`.
+/// Variants with payload fields produce a [KEnumVariant], which is called to make a value.
+///
+/// ```koto
+/// enum Shape
+///   Circle(radius)
+///   Empty
+///
+/// print Shape.Circle 2
+/// # -> Shape.Circle(2)
+/// ```
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(runtime = crate, type_name = "EnumType")]
+pub struct KEnumType(Ptr<EnumDefinition>);
+
+/// A variant of a [KEnumType] that has payload fields
+///
+/// Calling the variant with values for its fields produces a [KEnumValue].
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(runtime = crate, type_name = "EnumVariant")]
+pub struct KEnumVariant {
+    definition: Ptr<EnumDefinition>,
+    variant: usize,
+}
+
+/// A value of a [KEnumType], along with any payload values for its variant
+///
+/// The payload values can be accessed by field name, or by index.
+#[derive(Clone)]
+pub struct KEnumValue {
+    definition: Ptr<EnumDefinition>,
+    variant: usize,
+    payload: Vec<KValue>,
+}
+
+#[derive(Clone)]
+struct EnumDefinition {
+    name: KString,
+    variants: Vec<EnumVariantDefinition>,
+}
+
+#[derive(Clone)]
+struct EnumVariantDefinition {
+    name: KString,
+    fields: Vec<KString>,
+}
+
+impl EnumDefinition {
+    fn variant_index(&self, name: &str) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|variant| variant.name.as_str() == name)
+    }
+}
+
+impl KEnumType {
+    /// Makes a new enum type with the given name and no variants
+    pub fn new(name: KString) -> Self {
+        Self(Ptr::from(EnumDefinition {
+            name,
+            variants: Vec::new(),
+        }))
+    }
+
+    /// The enum type's name
+    pub fn name(&self) -> &KString {
+        &self.0.name
+    }
+
+    /// Returns an iterator over the names of the enum type's variants
+    pub fn variant_names(&self) -> impl Iterator<Item = &KString> {
+        self.0.variants.iter().map(|variant| &variant.name)
+    }
+
+    /// Adds a variant to the enum type
+    pub fn add_variant(&mut self, name: KString) {
+        Ptr::make_mut(&mut self.0)
+            .variants
+            .push(EnumVariantDefinition {
+                name,
+                fields: Vec::new(),
+            });
+    }
+
+    /// Adds a payload field to the most recently added variant
+    pub fn add_variant_field(&mut self, name: KString) -> Result<()> {
+        match Ptr::make_mut(&mut self.0).variants.last_mut() {
+            Some(variant) => {
+                variant.fields.push(name);
+                Ok(())
+            }
+            None => runtime_error!("missing variant in enum type '{}'", self.0.name),
+        }
+    }
+
+    /// Returns true if the value is the named variant of this enum type
+    pub fn is_variant(&self, value: &KValue, variant: &str) -> Result<bool> {
+        match value {
+            KValue::Object(o) if o.is_a::<KEnumValue>() => {
+                let value = o.cast::<KEnumValue>()?;
+                let result = Ptr::ptr_eq(&self.0, &value.definition)
+                    && value.variant_definition().name.as_str() == variant;
+                Ok(result)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl KotoAccess for KEnumType {
+    fn access(&self, key: &KString) -> Result<Option<KValue>> {
+        let Some(variant) = self.0.variant_index(key) else {
+            return Ok(None);
+        };
+
+        let result = if self.0.variants[variant].fields.is_empty() {
+            KEnumValue {
+                definition: self.0.clone(),
+                variant,
+                payload: Vec::new(),
+            }
+            .into()
+        } else {
+            KEnumVariant {
+                definition: self.0.clone(),
+                variant,
+            }
+            .into()
+        };
+
+        Ok(Some(result))
+    }
+}
+
+impl KotoObject for KEnumType {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(&self.0.name);
+        Ok(())
+    }
+
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        let result = match other {
+            KValue::Object(o) if o.is_a::<Self>() => Ptr::ptr_eq(&self.0, &o.cast::<Self>()?.0),
+            _ => false,
+        };
+        Ok(result)
+    }
+}
+
+impl From<KEnumType> for KValue {
+    fn from(enum_type: KEnumType) -> Self {
+        KObject::from(enum_type).into()
+    }
+}
+
+impl KEnumVariant {
+    /// Makes a new value of the variant, with the given payload values
+    pub fn make_value(&self, args: &[KValue]) -> Result<KEnumValue> {
+        let expected = self.definition.variants[self.variant].fields.len();
+
+        if args.len() < expected {
+            return runtime_error!(ErrorKind::InsufficientArguments {
+                expected: expected as u8,
+                actual: args.len() as u8,
+            });
+        } else if args.len() > expected {
+            return runtime_error!(ErrorKind::TooManyArguments {
+                expected: expected as u8,
+                actual: args.len() as u8,
+            });
+        }
+
+        Ok(KEnumValue {
+            definition: self.definition.clone(),
+            variant: self.variant,
+            payload: args.to_vec(),
+        })
+    }
+}
+
+impl KotoAccess for KEnumVariant {}
+
+impl KotoObject for KEnumVariant {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(&self.definition.name);
+        ctx.append('.');
+        ctx.append(&self.definition.variants[self.variant].name);
+        Ok(())
+    }
+
+    fn is_callable(&self) -> bool {
+        true
+    }
+
+    fn call(&mut self, ctx: &mut CallContext) -> Result<KValue> {
+        self.make_value(ctx.args()).map(KValue::from)
+    }
+
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        let result = match other {
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let other = o.cast::<Self>()?;
+                Ptr::ptr_eq(&self.definition, &other.definition) && self.variant == other.variant
+            }
+            _ => false,
+        };
+        Ok(result)
+    }
+}
+
+impl From<KEnumVariant> for KValue {
+    fn from(variant: KEnumVariant) -> Self {
+        KObject::from(variant).into()
+    }
+}
+
+impl KEnumValue {
+    /// The name of the value's enum type
+    pub fn type_name(&self) -> &KString {
+        &self.definition.name
+    }
+
+    /// The name of the value's variant
+    pub fn variant_name(&self) -> &KString {
+        &self.variant_definition().name
+    }
+
+    /// The variant's payload values, in the order that the fields were declared
+    pub fn payload(&self) -> &[KValue] {
+        &self.payload
+    }
+
+    /// Returns true if both values are the same variant of the same enum type
+    pub fn has_same_variant(&self, other: &Self) -> bool {
+        Ptr::ptr_eq(&self.definition, &other.definition) && self.variant == other.variant
+    }
+
+    fn variant_definition(&self) -> &EnumVariantDefinition {
+        &self.definition.variants[self.variant]
+    }
+}
+
+impl KotoType for KEnumValue {
+    fn type_static() -> &'static str {
+        "Enum"
+    }
+
+    fn type_string(&self) -> KString {
+        self.definition.name.clone()
+    }
+}
+
+impl KotoCopy for KEnumValue {
+    fn copy(&self) -> KObject {
+        self.clone().into()
+    }
+
+    fn deep_copy(&self) -> KObject {
+        let payload = self
+            .payload
+            .iter()
+            .map(|value| value.deep_copy().unwrap_or_else(|_| value.clone()))
+            .collect();
+
+        Self {
+            definition: self.definition.clone(),
+            variant: self.variant,
+            payload,
+        }
+        .into()
+    }
+}
+
+impl KotoAccess for KEnumValue {
+    fn access(&self, key: &KString) -> Result<Option<KValue>> {
+        let result = self
+            .variant_definition()
+            .fields
+            .iter()
+            .position(|field| field == key)
+            .map(|i| self.payload[i].clone());
+        Ok(result)
+    }
+}
+
+impl KotoObject for KEnumValue {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(&self.definition.name);
+        ctx.append('.');
+        ctx.append(&self.variant_definition().name);
+
+        if self.payload.is_empty() {
+            return Ok(());
+        }
+
+        ctx.append('(');
+
+        let id = Address::from(self as *const Self);
+
+        if ctx.is_in_parents(id) {
+            ctx.append("...");
+        } else {
+            ctx.push_container(id);
+
+            for (i, value) in self.payload.iter().enumerate() {
+                if i > 0 {
+                    ctx.append(", ");
+                }
+                value.display(ctx)?;
+            }
+
+            ctx.pop_container();
+        }
+
+        ctx.append(')');
+        Ok(())
+    }
+
+    // Comparisons between enum values are handled by the VM so that payloads can be compared,
+    // this covers comparisons against other kinds of values, e.g. in match arms.
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        let result = match other {
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let other = o.cast::<Self>()?;
+                self.has_same_variant(&other) && self.payload.is_empty()
+            }
+            _ => false,
+        };
+        Ok(result)
+    }
+
+    fn index(&self, index: &KValue) -> Result<KValue> {
+        match index {
+            KValue::Number(n) => match self.payload.get(usize::from(n)) {
+                Some(value) => Ok(value.clone()),
+                None => runtime_error!(
+                    "index out of bounds - index: {n}, size: {}",
+                    self.payload.len()
+                ),
+            },
+            unexpected => unexpected_type("Number", unexpected),
+        }
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.payload.len())
+    }
+}
+
+impl From<KEnumValue> for KValue {
+    fn from(value: KEnumValue) -> Self {
+        KObject::from(value).into()
+    }
+}
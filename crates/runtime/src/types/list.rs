@@ -43,6 +43,11 @@ impl KList {
         self.0.borrow_mut()
     }
 
+    // Returns the shared pointer to the list's entries, used when charging the memory budget
+    pub(crate) fn data_ptr(&self) -> &PtrMut<ValueVec> {
+        &self.0
+    }
+
     /// Returns true if the lists refer to the same underlying data
     pub fn is_same_instance(&self, other: &Self) -> bool {
         PtrMut::ptr_eq(&self.0, &other.0)
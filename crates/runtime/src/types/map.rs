@@ -4,6 +4,7 @@ use rustc_hash::FxHasher;
 use std::{
     hash::{BuildHasherDefault, Hash},
     ops::{Deref, DerefMut, RangeBounds},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 /// The hasher used throughout the Koto runtime
@@ -11,19 +12,34 @@ pub type KotoHasher = FxHasher;
 
 type ValueMapType = IndexMap<ValueKey, KValue, BuildHasherDefault<KotoHasher>>;
 
+// Identifies the contents of a map, used to validate the VM's access caches
+//
+// A new version is assigned whenever a map is mutably dereferenced, and versions are never
+// reused, so a map with a previously recorded version is guaranteed to have the same entries
+// that it had when the version was recorded. Empty maps start with the default version.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct MapVersion(u64);
+
+impl MapVersion {
+    pub(crate) fn next() -> Self {
+        static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT_VERSION.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// The (ValueKey -> Value) 'data' hash map used by the Koto runtime
 ///
 /// See also: [KMap]
 #[derive(Clone, Default)]
-pub struct ValueMap(ValueMapType);
+pub struct ValueMap(ValueMapType, MapVersion);
 
 impl ValueMap {
     /// Creates a new map with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(ValueMapType::with_capacity_and_hasher(
-            capacity,
-            Default::default(),
-        ))
+        Self(
+            ValueMapType::with_capacity_and_hasher(capacity, Default::default()),
+            MapVersion::default(),
+        )
     }
 
     /// Creates a new map containing a slice of the map's elements
@@ -36,6 +52,10 @@ impl ValueMap {
             )
         })
     }
+
+    pub(crate) fn version(&self) -> MapVersion {
+        self.1
+    }
 }
 
 impl Deref for ValueMap {
@@ -48,13 +68,14 @@ impl Deref for ValueMap {
 
 impl DerefMut for ValueMap {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.1 = MapVersion::next();
         &mut self.0
     }
 }
 
 impl FromIterator<(ValueKey, KValue)> for ValueMap {
     fn from_iter<T: IntoIterator<Item = (ValueKey, KValue)>>(iter: T) -> ValueMap {
-        Self(ValueMapType::from_iter(iter))
+        Self(ValueMapType::from_iter(iter), MapVersion::next())
     }
 }
 
@@ -114,6 +135,11 @@ impl KMap {
         self.data.borrow_mut()
     }
 
+    // Provides the shared pointer to the data map, used when charging the memory budget
+    pub(crate) fn data_ptr(&self) -> &PtrMut<ValueMap> {
+        &self.data
+    }
+
     /// Provides a reference to the KMap's meta map
     ///
     /// This is returned as a reference to the meta map's PtrMut to allow for cloning.
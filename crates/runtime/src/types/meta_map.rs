@@ -1,4 +1,4 @@
-use crate::{Error, Result, prelude::*};
+use crate::{Error, Result, prelude::*, types::MapVersion};
 use indexmap::{Equivalent, IndexMap};
 use koto_parser::MetaKeyId;
 use std::{
@@ -14,18 +14,21 @@ type MetaMapType = IndexMap<MetaKey, KValue, BuildHasherDefault<KotoHasher>>;
 /// Each KMap contains a metamap, which allows for customized value behaviour by implementing
 /// [`MetaKeys`](crate::MetaKey).
 #[derive(Clone, Default)]
-pub struct MetaMap(MetaMapType);
+pub struct MetaMap(MetaMapType, MapVersion);
 
 impl MetaMap {
     /// Extends the MetaMap with clones of another MetaMap's entries
     pub fn extend(&mut self, other: &MetaMap) {
-        self.0.extend(other.0.clone());
+        self.deref_mut().extend(other.0.clone());
     }
 
     /// Adds a function to the meta map
     pub fn add_fn(&mut self, key: MetaKey, f: impl KotoFunction) {
-        self.0
-            .insert(key, KValue::NativeFunction(KNativeFunction::new(f)));
+        self.insert(key, KValue::NativeFunction(KNativeFunction::new(f)));
+    }
+
+    pub(crate) fn version(&self) -> MapVersion {
+        self.1
     }
 }
 
@@ -39,6 +42,7 @@ impl Deref for MetaMap {
 
 impl DerefMut for MetaMap {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.1 = MapVersion::next();
         &mut self.0
     }
 }
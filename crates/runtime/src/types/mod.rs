@@ -1,5 +1,6 @@
 //! The core types used in the Koto runtime
 
+mod enum_type;
 mod function;
 mod iterator;
 mod list;
@@ -8,7 +9,9 @@ mod meta_map;
 mod native_function;
 mod number;
 mod object;
+mod pending;
 mod range;
+mod record;
 mod tuple;
 pub mod value;
 mod value_key;
@@ -16,6 +19,7 @@ mod value_key;
 pub use koto_parser::KString;
 
 pub use self::{
+    enum_type::{KEnumType, KEnumValue, KEnumVariant},
     function::{FunctionContext, KFunction},
     iterator::{KIterator, KIteratorOutput, KotoIterator},
     list::{KList, ValueVec},
@@ -26,8 +30,12 @@ pub use self::{
     object::{
         IsIterable, KObject, KotoAccess, KotoCopy, KotoField, KotoObject, KotoType, MethodContext,
     },
+    pending::KPending,
     range::KRange,
+    record::{KRecord, KRecordType},
     tuple::KTuple,
     value::KValue,
     value_key::ValueKey,
 };
+
+pub(crate) use self::map::MapVersion;
@@ -0,0 +1,55 @@
+use crate::{Result, derive::*, prelude::*};
+
+/// A value that will be provided later by the host application
+///
+/// Native functions can return a pending value to represent the result of an operation that's
+/// being performed asynchronously by the host, e.g. a network request.
+///
+/// When a pending value is awaited in a script, execution is suspended and the pending value is
+/// returned from [KotoVm::run_resumable]. The host can then use the pending value's data to
+/// identify the operation, and once the result is available, continue execution with
+/// [KotoVm::resume].
+///
+/// ```
+/// use koto_runtime::prelude::*;
+///
+/// let pending = KPending::new(42);
+/// assert!(matches!(pending.data(), KValue::Number(n) if *n == 42));
+/// ```
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(runtime = crate, type_name = "Pending")]
+pub struct KPending(KValue);
+
+impl KPending {
+    /// Makes a new pending value, with data that's used by the host to identify the operation
+    pub fn new(data: impl Into<KValue>) -> Self {
+        Self(data.into())
+    }
+
+    /// Returns the data that was provided when the pending value was created
+    pub fn data(&self) -> &KValue {
+        &self.0
+    }
+}
+
+impl KotoAccess for KPending {}
+
+impl KotoObject for KPending {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(Self::type_static());
+        ctx.append('(');
+
+        let mut data_ctx = DisplayContext::default();
+        self.0.display(&mut data_ctx)?;
+        ctx.append(data_ctx.result());
+
+        ctx.append(')');
+        Ok(())
+    }
+}
+
+impl From<KPending> for KValue {
+    fn from(pending: KPending) -> Self {
+        KObject::from(pending).into()
+    }
+}
@@ -0,0 +1,316 @@
+use crate::{ErrorKind, Ptr, Result, derive::*, prelude::*};
+use koto_memory::Address;
+
+/// A record type declared in a script with `record`
+///
+/// Calling the record type with its field values (in declaration order) produces a [KRecord].
+/// Fields with default values can be omitted from the end of the call's arguments.
+///
+/// ```koto
+/// record Point
+///   x: Number
+///   y: Number = 0
+///
+/// p = Point 1
+/// print p
+/// # -> Point {x: 1, y: 0}
+/// ```
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(runtime = crate, type_name = "RecordType")]
+pub struct KRecordType(Ptr<RecordDefinition>);
+
+/// An instance of a [KRecordType]
+///
+/// Records have a fixed set of fields, assigning to a field that wasn't declared in the record
+/// type produces an error. Values assigned to fields with type hints are checked when the record
+/// is created, and when the field is assigned.
+#[derive(Clone)]
+pub struct KRecord {
+    definition: Ptr<RecordDefinition>,
+    values: Vec<KValue>,
+}
+
+#[derive(Clone)]
+struct RecordDefinition {
+    name: KString,
+    fields: Vec<RecordField>,
+}
+
+#[derive(Clone)]
+struct RecordField {
+    name: KString,
+    default: Option<KValue>,
+    type_hint: Option<(KString, bool)>, // (type, allow null)
+}
+
+impl KRecordType {
+    /// Makes a new record type with the given name and no fields
+    pub fn new(name: KString) -> Self {
+        Self(Ptr::from(RecordDefinition {
+            name,
+            fields: Vec::new(),
+        }))
+    }
+
+    /// The record type's name
+    pub fn name(&self) -> &KString {
+        &self.0.name
+    }
+
+    /// Returns an iterator over the names of the record type's fields
+    pub fn field_names(&self) -> impl Iterator<Item = &KString> {
+        self.0.fields.iter().map(|field| &field.name)
+    }
+
+    /// Adds a field to the record type
+    pub fn add_field(&mut self, name: KString) {
+        Ptr::make_mut(&mut self.0).fields.push(RecordField {
+            name,
+            default: None,
+            type_hint: None,
+        });
+    }
+
+    /// Sets the type hint for the most recently added field
+    pub fn set_field_type(&mut self, type_hint: KString, allow_null: bool) -> Result<()> {
+        match Ptr::make_mut(&mut self.0).fields.last_mut() {
+            Some(field) => {
+                field.type_hint = Some((type_hint, allow_null));
+                Ok(())
+            }
+            None => runtime_error!("missing field in record type '{}'", self.0.name),
+        }
+    }
+
+    /// Sets the default value for the most recently added field
+    ///
+    /// An error is returned if the value doesn't match the field's type hint.
+    pub fn set_field_default(&mut self, value: KValue) -> Result<()> {
+        let definition = Ptr::make_mut(&mut self.0);
+        match definition.fields.last_mut() {
+            Some(field) => {
+                check_field_type(&definition.name, field, &value)?;
+                field.default = Some(value);
+                Ok(())
+            }
+            None => runtime_error!("missing field in record type '{}'", definition.name),
+        }
+    }
+
+    /// Makes a new record, with values provided for the record type's fields in order
+    pub fn make_record(&self, args: &[KValue]) -> Result<KRecord> {
+        let fields = &self.0.fields;
+
+        if args.len() > fields.len() {
+            return runtime_error!(ErrorKind::TooManyArguments {
+                expected: fields.len() as u8,
+                actual: args.len() as u8,
+            });
+        }
+
+        let values = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let value = match (args.get(i), &field.default) {
+                    (Some(arg), _) => arg.clone(),
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => {
+                        let required = fields.iter().filter(|f| f.default.is_none()).count();
+                        return runtime_error!(ErrorKind::InsufficientArguments {
+                            expected: required as u8,
+                            actual: args.len() as u8,
+                        });
+                    }
+                };
+                check_field_type(&self.0.name, field, &value)?;
+                Ok(value)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(KRecord {
+            definition: self.0.clone(),
+            values,
+        })
+    }
+}
+
+impl KotoAccess for KRecordType {}
+
+impl KotoObject for KRecordType {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(&self.0.name);
+        Ok(())
+    }
+
+    fn is_callable(&self) -> bool {
+        true
+    }
+
+    fn call(&mut self, ctx: &mut CallContext) -> Result<KValue> {
+        self.make_record(ctx.args()).map(KValue::from)
+    }
+
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        let result = match other {
+            KValue::Object(o) if o.is_a::<Self>() => Ptr::ptr_eq(&self.0, &o.cast::<Self>()?.0),
+            _ => false,
+        };
+        Ok(result)
+    }
+}
+
+impl From<KRecordType> for KValue {
+    fn from(record_type: KRecordType) -> Self {
+        KObject::from(record_type).into()
+    }
+}
+
+impl KRecord {
+    /// The name of the record's type
+    pub fn type_name(&self) -> &KString {
+        &self.definition.name
+    }
+
+    /// Returns the value of the field with the given name
+    pub fn get(&self, name: &str) -> Option<&KValue> {
+        self.field_index(name).map(|i| &self.values[i])
+    }
+
+    /// Returns an iterator over the record's fields and values
+    pub fn fields(&self) -> impl Iterator<Item = (&KString, &KValue)> {
+        self.definition
+            .fields
+            .iter()
+            .map(|field| &field.name)
+            .zip(self.values.iter())
+    }
+
+    /// Returns true if both records are instances of the same record type
+    pub fn has_same_type(&self, other: &Self) -> bool {
+        Ptr::ptr_eq(&self.definition, &other.definition)
+    }
+
+    /// The record's values, in the order that the fields were declared
+    pub fn values(&self) -> &[KValue] {
+        &self.values
+    }
+
+    fn field_index(&self, name: &str) -> Option<usize> {
+        self.definition
+            .fields
+            .iter()
+            .position(|field| field.name.as_str() == name)
+    }
+}
+
+impl KotoType for KRecord {
+    fn type_static() -> &'static str {
+        "Record"
+    }
+
+    fn type_string(&self) -> KString {
+        self.definition.name.clone()
+    }
+}
+
+impl KotoCopy for KRecord {
+    fn copy(&self) -> KObject {
+        self.clone().into()
+    }
+
+    fn deep_copy(&self) -> KObject {
+        let values = self
+            .values
+            .iter()
+            .map(|value| value.deep_copy().unwrap_or_else(|_| value.clone()))
+            .collect();
+
+        Self {
+            definition: self.definition.clone(),
+            values,
+        }
+        .into()
+    }
+}
+
+impl KotoAccess for KRecord {
+    fn access(&self, key: &KString) -> Result<Option<KValue>> {
+        Ok(self.get(key).cloned())
+    }
+
+    fn access_assign(&mut self, key: &KString, value: &KValue) -> Result<()> {
+        let Some(index) = self.field_index(key) else {
+            return runtime_error!(
+                "'{key}' isn't a field of '{}'",
+                self.definition.name.as_str()
+            );
+        };
+
+        check_field_type(&self.definition.name, &self.definition.fields[index], value)?;
+        self.values[index] = value.clone();
+        Ok(())
+    }
+}
+
+impl KotoObject for KRecord {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(&self.definition.name);
+        ctx.append(" {");
+
+        let id = Address::from(self as *const Self);
+
+        if ctx.is_in_parents(id) {
+            ctx.append("...");
+        } else {
+            ctx.push_container(id);
+
+            for (i, (name, value)) in self.fields().enumerate() {
+                if i > 0 {
+                    ctx.append(", ");
+                }
+                ctx.append(name);
+                ctx.append(": ");
+                value.display(ctx)?;
+            }
+
+            ctx.pop_container();
+        }
+
+        ctx.append('}');
+        Ok(())
+    }
+
+    fn serialize(&self) -> Result<KValue> {
+        let result = KMap::with_capacity(self.values.len());
+        for (name, value) in self.fields() {
+            result.insert(name.clone(), value.clone());
+        }
+        Ok(result.into())
+    }
+}
+
+impl From<KRecord> for KValue {
+    fn from(record: KRecord) -> Self {
+        KObject::from(record).into()
+    }
+}
+
+// Checks that a value matches the field's type hint
+fn check_field_type(record: &KString, field: &RecordField, value: &KValue) -> Result<()> {
+    match &field.type_hint {
+        Some((type_hint, allow_null)) if !value.matches_type_hint(type_hint, *allow_null) => {
+            let expected = if *allow_null {
+                format!("{type_hint}?")
+            } else {
+                type_hint.to_string()
+            };
+            runtime_error!(
+                "expected {expected} for '{}' in '{record}', found {}",
+                field.name,
+                value.type_as_string()
+            )
+        }
+        _ => Ok(()),
+    }
+}
@@ -152,6 +152,50 @@ impl KValue {
         }
     }
 
+    /// Returns true if the value passes a check against the given type hint
+    ///
+    /// This is used at runtime for type hints like `let x: Number = ...`, with the abstract types
+    /// `Any`, `Callable`, `Indexable`, and `Iterable` accepting any value with matching behaviour.
+    /// Maps with a `@base` value will also pass if one of their base values matches the type.
+    pub fn matches_type_hint(&self, expected_type: &str, allow_null: bool) -> bool {
+        if allow_null && matches!(self, KValue::Null) {
+            return true;
+        }
+
+        match expected_type {
+            "Any" => true,
+            "Callable" => self.is_callable(),
+            "Indexable" => self.is_indexable(),
+            "Iterable" => self.is_iterable(),
+            expected_type => {
+                if self.type_as_string() == expected_type {
+                    true
+                } else {
+                    // The type didn't match, so look for a base value to check
+                    let mut value = self.clone();
+
+                    loop {
+                        match value {
+                            KValue::Map(m) if m.contains_meta_key(&MetaKey::Base) => {
+                                let base = m.get_meta_value(&MetaKey::Base).unwrap();
+                                if base.type_as_string() == expected_type {
+                                    return true;
+                                } else {
+                                    // The base didn't match the expected type,
+                                    // but continue looping to check the base's base.
+                                    value = base;
+                                }
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    false
+                }
+            }
+        }
+    }
+
     /// Returns true if the values refer to the same underlying data
     pub fn is_same_instance(&self, other: &Self) -> bool {
         use KValue::*;
@@ -1,13 +1,20 @@
 use crate::{
-    InstructionFrame, KFunction, Ptr, Result, UnavailableStderr, UnavailableStdin,
-    UnavailableStdout,
+    BorrowMut, Capabilities, Capability, InstructionFrame, KFunction, KotoFileSystem, Ptr, Result,
+    SystemFileSystem, UnavailableStderr, UnavailableStdin, UnavailableStdout,
+    access_cache::{AccessCache, AccessCaches, MapLookup},
     core_lib::{CoreLib, io::File, koto::Unimplemented},
+    coverage::{CoverageReport, CoverageState, is_branch},
+    debug::{Breakpoint, DebugContext, DebugDepth, DebugPauseHandle, DebugState, KotoDebugger},
     error::{Error, ErrorKind},
+    memory_budget::{MemoryBudget, map_size, sequence_size},
     prelude::*,
+    profiler::{FunctionKey, ProfileReport, ProfilerState},
     types::{FunctionContext, meta_id_to_key, value::RegisterSlice},
 };
 use instant::Instant;
-use koto_bytecode::{Chunk, Instruction, InstructionReader, ModuleLoader};
+use koto_bytecode::{
+    Chunk, FileSystemModuleResolver, Instruction, InstructionReader, ModuleLoader, ModuleResolver,
+};
 use koto_parser::{
     ConstantIndex, MetaKeyId, StringAlignment, StringFormatOptions, StringFormatRepresentation,
 };
@@ -18,6 +25,10 @@ use std::{
     fmt,
     hash::BuildHasherDefault,
     path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
     time::Duration,
 };
 use unicode_segmentation::UnicodeSegmentation;
@@ -27,6 +38,7 @@ pub enum ControlFlow {
     Continue,
     Return(KValue),
     Yield(KValue),
+    Await { register: u8, pending: KPending },
 }
 
 /// State shared between concurrent VMs
@@ -41,6 +53,18 @@ struct VmContext {
     loader: KCell<ModuleLoader>,
     // The cached export maps of imported modules
     module_cache: KCell<ModuleCache>,
+    // The remaining number of instructions that are allowed to be executed, see `settings.fuel`
+    fuel: Option<AtomicU64>,
+    // The memory that's been charged by values built by the runtime, see `settings.memory_limit`
+    memory: Option<KCell<MemoryBudget>>,
+    // The flag that's set when an interrupt has been requested, see `KotoVm::interrupt_handle`
+    interrupt: InterruptHandle,
+    // The state of the attached debugger, see `settings.debugger`
+    debug: Option<KCell<DebugState>>,
+    // The profiler's recorded samples, see `settings.profiling`
+    profiler: Option<KCell<ProfilerState>>,
+    // The recorded code coverage, see `settings.coverage`
+    coverage: Option<KCell<CoverageState>>,
 }
 
 impl Default for VmContext {
@@ -50,7 +74,7 @@ impl Default for VmContext {
 }
 
 impl VmContext {
-    fn with_settings(settings: KotoVmSettings) -> Self {
+    fn with_settings(mut settings: KotoVmSettings) -> Self {
         let core_lib = CoreLib::default();
 
         core_lib.os.insert(
@@ -77,12 +101,34 @@ impl VmContext {
             .io
             .insert("stderr", File::new(settings.stderr.clone()));
 
+        let fuel = settings.fuel.map(AtomicU64::new);
+        let memory = settings
+            .memory_limit
+            .map(|limit| MemoryBudget::new(limit).into());
+        let debug = settings
+            .debugger
+            .take()
+            .map(|debugger| DebugState::new(debugger).into());
+        let profiler = settings.profiling.then(|| ProfilerState::default().into());
+        let coverage = settings.coverage.then(|| CoverageState::default().into());
+
+        let mut loader = ModuleLoader::default();
+        loader.set_bytecode_cache(settings.bytecode_cache.clone());
+        loader.set_module_resolver(settings.module_resolver.clone());
+        loader.set_search_paths(settings.module_search_paths.clone());
+
         Self {
             settings,
             prelude: core_lib.prelude(),
             core_lib,
-            loader: ModuleLoader::default().into(),
+            loader: loader.into(),
             module_cache: ModuleCache::default().into(),
+            fuel,
+            memory,
+            interrupt: InterruptHandle::default(),
+            debug,
+            profiler,
+            coverage,
         }
     }
 }
@@ -114,12 +160,135 @@ pub struct KotoVmSettings {
     /// Default: `None`
     pub execution_limit: Option<Duration>,
 
+    /// An optional amount of 'fuel' that limits how many instructions are allowed to be executed.
+    ///
+    /// Each instruction executed by the VM consumes one unit of fuel. If the VM runs out of fuel
+    /// then an [OutOfFuel](ErrorKind::OutOfFuel) error will be returned.
+    ///
+    /// Unlike [execution_limit](Self::execution_limit), the fuel limit is deterministic,
+    /// with scripts running out of fuel at the same point regardless of the host's load.
+    ///
+    /// Fuel is shared between all VMs that are spawned from the same runtime, and can be
+    /// queried and topped up with [KotoVm::remaining_fuel] and [KotoVm::add_fuel].
+    ///
+    /// Default: `None`
+    pub fuel: Option<u64>,
+
+    /// An optional budget in bytes for the memory used by values built by the runtime.
+    ///
+    /// Strings, lists, tuples, and maps are charged to the budget as they're constructed or
+    /// extended, e.g. when building a list with a comprehension, when joining values with `+`,
+    /// or when calling `list.resize` or `string.repeat`. If a value would cause the budget to be
+    /// exceeded, then a [MemoryLimitExceeded](ErrorKind::MemoryLimitExceeded) error will be
+    /// thrown, which can be caught in the script with `try`/`catch`.
+    ///
+    /// The budget is shared by all values that are alive, with the memory used by values being
+    /// refunded once they've been dropped or have shrunk. The size of containers is estimated
+    /// from the number of entries that they contain, and nested values are charged separately.
+    ///
+    /// The budget is shared between all VMs that are spawned from the same runtime.
+    /// Native functions can check that an allocation fits in the budget with
+    /// [KotoVm::check_memory].
+    ///
+    /// Default: `None`
+    pub memory_limit: Option<usize>,
+
+    /// An optional limit on the number of frames in the call stack
+    ///
+    /// If calling a function would cause the limit to be exceeded, then a
+    /// [StackOverflow](ErrorKind::StackOverflow) error will be thrown, which can be caught in the
+    /// script with `try`/`catch`. The error's trace includes the frames at the top of the
+    /// call stack.
+    ///
+    /// Calls in tail position reuse the calling function's frame, so they don't increase the
+    /// depth of the call stack. Functions that are called by native functions (e.g. iterator
+    /// adaptors like `iterator.each`) are included in the depth of the call stack.
+    ///
+    /// Default: `None`
+    pub max_call_depth: Option<usize>,
+
+    /// The capabilities that are available to scripts
+    ///
+    /// Core library functions that make use of a disabled capability (e.g. `io.open` when file
+    /// system read access is disabled) will throw a
+    /// [PermissionDenied](ErrorKind::PermissionDenied) error.
+    ///
+    /// Native functions can check that a capability is enabled with [KotoVm::check_capability].
+    ///
+    /// Default: [Capabilities::all]
+    pub capabilities: Capabilities,
+
     /// An optional callback that is called whenever a module is imported by the runtime
     ///
     /// This allows you to track the runtime's dependencies, which might be useful if you want to
     /// reload the script when one of its dependencies has changed.
     pub module_imported_callback: Option<Box<dyn ModuleImportedCallback>>,
 
+    /// An optional debugger that allows execution to be paused and inspected
+    ///
+    /// Breakpoints can be added with [KotoVm::set_breakpoint], and the debugger's
+    /// [paused](KotoDebugger::paused) function will be called whenever execution is paused.
+    ///
+    /// Attaching a debugger has a performance cost, so it should only be enabled when needed.
+    ///
+    /// Default: `None`
+    pub debugger: Option<Box<dyn KotoDebugger>>,
+
+    /// Enables the profiler, which records instruction counts and elapsed time for each function
+    /// and line that's executed
+    ///
+    /// The recorded information can be retrieved with [KotoVm::profile_report].
+    ///
+    /// Profiling has a performance cost, so it should only be enabled when needed.
+    ///
+    /// Default: `false`
+    pub profiling: bool,
+
+    /// Enables code coverage tracking, which records the lines, functions, and conditional
+    /// branches that are executed
+    ///
+    /// The recorded coverage can be retrieved with [KotoVm::coverage_report].
+    ///
+    /// Tracking coverage has a performance cost, so it should only be enabled when needed.
+    ///
+    /// Default: `false`
+    pub coverage: bool,
+
+    /// An optional directory that's used to cache compiled scripts
+    ///
+    /// Compiled scripts are saved to the cache as `.kotoc` files, allowing unchanged scripts and
+    /// modules to be loaded without being recompiled.
+    ///
+    /// See [ModuleLoader::set_bytecode_cache].
+    ///
+    /// Default: `None`
+    pub bytecode_cache: Option<PathBuf>,
+
+    /// The resolver that's used to find the modules that are imported by scripts
+    ///
+    /// See [ModuleLoader::set_module_resolver].
+    ///
+    /// Default: [`FileSystemModuleResolver`]
+    pub module_resolver: Ptr<dyn ModuleResolver>,
+
+    /// Directories that should be searched for imported modules, in order
+    ///
+    /// Modules are first looked for relative to the importing script, and then in each of the
+    /// search paths. The Koto CLI populates the search paths from the `KOTO_PATH` environment
+    /// variable.
+    ///
+    /// See [ModuleLoader::set_search_paths].
+    ///
+    /// Default: empty
+    pub module_search_paths: Vec<PathBuf>,
+
+    /// The file system that's used by the `io` core library module
+    ///
+    /// See [KotoFileSystem].
+    ///
+    /// Default: [`SystemFileSystem`]
+    pub file_system: Ptr<dyn KotoFileSystem>,
+
     /// The runtime's `stdin`that can be accessed from within the script via `io.stdin`
     ///
     /// Default: [`UnavailableStdin`]
@@ -146,7 +315,18 @@ impl Default for KotoVmSettings {
         Self {
             run_import_tests: true,
             execution_limit: None,
+            fuel: None,
+            memory_limit: None,
+            max_call_depth: None,
+            capabilities: Capabilities::all(),
             module_imported_callback: None,
+            debugger: None,
+            profiling: false,
+            coverage: false,
+            bytecode_cache: None,
+            module_resolver: make_ptr!(FileSystemModuleResolver),
+            module_search_paths: Vec::new(),
+            file_system: make_ptr!(SystemFileSystem),
             stdin: make_ptr!(UnavailableStdin::default()),
             stdout: make_ptr!(UnavailableStdout::default()),
             stderr: make_ptr!(UnavailableStderr::default()),
@@ -172,6 +352,9 @@ pub struct KotoVm {
     min_frame_registers: usize,
     // The VM's call stack
     call_stack: Vec<Frame>,
+    // The depth of the spawning VM's call stack when this VM was spawned,
+    // see `KotoVmSettings::max_call_depth`.
+    call_depth_offset: usize,
     // A stack of sequences that are currently under construction
     sequence_builders: Vec<Vec<KValue>>,
     // A stack of strings that are currently under construction
@@ -180,6 +363,21 @@ pub struct KotoVm {
     instruction_ip: u32,
     // The current execution state
     execution_state: ExecutionState,
+    // Set before executing instructions in a resumable run, allowing `await` to suspend execution
+    suspend_on_await: bool,
+    // The state of the current resumable run, see `run_resumable`
+    resumable_run: Option<ResumableRun>,
+    // The caches used by `.` access instructions
+    access_caches: AccessCaches,
+}
+
+// The state of a run that can be suspended by `await` expressions
+#[derive(Clone)]
+struct ResumableRun {
+    // The start of the run's frame, used to reset the register stack once the run is complete
+    frame_base: u8,
+    // The register that will receive the awaited value, along with the pending value
+    awaiting: Option<(u8, KPending)>,
 }
 
 /// The execution state of a VM
@@ -191,6 +389,8 @@ pub enum ExecutionState {
     Active,
     /// The VM is executing a generator function that has just yielded a value
     Suspended,
+    /// The VM has been suspended while awaiting a pending value
+    Awaiting,
 }
 
 impl Default for KotoVm {
@@ -210,10 +410,14 @@ impl KotoVm {
             register_base: 0,
             min_frame_registers: 0,
             call_stack: Vec::new(),
+            call_depth_offset: 0,
             sequence_builders: Vec::new(),
             string_builders: Vec::new(),
             instruction_ip: 0,
             execution_state: ExecutionState::Inactive,
+            suspend_on_await: false,
+            resumable_run: None,
+            access_caches: AccessCaches::default(),
         }
     }
 
@@ -233,10 +437,16 @@ impl KotoVm {
             register_base: 0,
             min_frame_registers: 0,
             call_stack: Vec::new(),
+            // Functions called by the spawned VM are included in the call depth,
+            // which prevents unbounded recursion via native functions.
+            call_depth_offset: self.call_depth_offset + self.call_stack.len(),
             sequence_builders: Vec::new(),
             string_builders: Vec::new(),
             instruction_ip: 0,
             execution_state: ExecutionState::Inactive,
+            suspend_on_await: false,
+            resumable_run: None,
+            access_caches: AccessCaches::default(),
         }
     }
 
@@ -263,6 +473,11 @@ impl KotoVm {
         &mut self.exports
     }
 
+    /// The file system used by the VM, see [KotoVmSettings::file_system]
+    pub fn file_system(&self) -> &Ptr<dyn KotoFileSystem> {
+        &self.context.settings.file_system
+    }
+
     /// The `stdin` wrapper used by the VM
     pub fn stdin(&self) -> &Ptr<dyn KotoFile> {
         &self.context.settings.stdin
@@ -278,9 +493,303 @@ impl KotoVm {
         &self.context.settings.stderr
     }
 
+    /// Returns the amount of fuel that remains available for execution
+    ///
+    /// `None` will be returned if fuel metering isn't enabled, see [KotoVmSettings::fuel].
+    pub fn remaining_fuel(&self) -> Option<u64> {
+        self.context
+            .fuel
+            .as_ref()
+            .map(|fuel| fuel.load(Ordering::Relaxed))
+    }
+
+    /// Adds fuel to the runtime, allowing further instructions to be executed
+    ///
+    /// This can be used to top up the runtime's fuel between calls to
+    /// [continue_running](Self::continue_running), or after an
+    /// [OutOfFuel](ErrorKind::OutOfFuel) error has been returned.
+    ///
+    /// This has no effect if fuel metering isn't enabled, see [KotoVmSettings::fuel].
+    pub fn add_fuel(&self, amount: u64) {
+        if let Some(fuel) = &self.context.fuel {
+            // The closure always returns Some, so the result can be safely ignored
+            let _ = fuel.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                Some(remaining.saturating_add(amount))
+            });
+        }
+    }
+
+    /// Returns a handle that can be used to interrupt the VM's execution from another thread
+    ///
+    /// See [InterruptHandle] for more information.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.context.interrupt.clone()
+    }
+
+    /// Adds a breakpoint that will pause execution when its line is reached
+    ///
+    /// Breakpoints have no effect if a debugger isn't attached, see [KotoVmSettings::debugger].
+    pub fn set_breakpoint(&self, breakpoint: Breakpoint) {
+        if let Some(debug) = &self.context.debug {
+            debug.borrow_mut().breakpoints_mut().insert(breakpoint);
+        }
+    }
+
+    /// Removes a previously added breakpoint
+    pub fn remove_breakpoint(&self, breakpoint: &Breakpoint) {
+        if let Some(debug) = &self.context.debug {
+            debug.borrow_mut().breakpoints_mut().remove(breakpoint);
+        }
+    }
+
+    /// Removes all breakpoints
+    pub fn clear_breakpoints(&self) {
+        if let Some(debug) = &self.context.debug {
+            debug.borrow_mut().breakpoints_mut().clear();
+        }
+    }
+
+    /// Requests that execution is paused when the next line is reached
+    ///
+    /// This has no effect if a debugger isn't attached, see [KotoVmSettings::debugger].
+    pub fn request_debug_pause(&self) {
+        if let Some(handle) = self.debug_pause_handle() {
+            handle.request_pause();
+        }
+    }
+
+    /// Returns a handle that can be used to request a debug pause from another thread
+    ///
+    /// `None` is returned if a debugger isn't attached, see [KotoVmSettings::debugger].
+    pub fn debug_pause_handle(&self) -> Option<DebugPauseHandle> {
+        self.context
+            .debug
+            .as_ref()
+            .map(|debug| debug.borrow().pause_handle())
+    }
+
+    /// Returns a report containing the information that's been recorded by the profiler
+    ///
+    /// `None` is returned if profiling isn't enabled, see [KotoVmSettings::profiling].
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.context
+            .profiler
+            .as_ref()
+            .map(|profiler| profiler.borrow().report())
+    }
+
+    /// Clears the information that's been recorded by the profiler
+    pub fn reset_profile(&self) {
+        if let Some(profiler) = &self.context.profiler {
+            profiler.borrow_mut().reset();
+        }
+    }
+
+    /// Returns a report containing the code coverage that's been recorded
+    ///
+    /// `None` is returned if coverage tracking isn't enabled, see [KotoVmSettings::coverage].
+    pub fn coverage_report(&self) -> Option<CoverageReport> {
+        self.context
+            .coverage
+            .as_ref()
+            .map(|coverage| coverage.borrow().report())
+    }
+
+    /// Clears the code coverage that's been recorded
+    pub fn reset_coverage(&self) {
+        if let Some(coverage) = &self.context.coverage {
+            coverage.borrow_mut().reset();
+        }
+    }
+
+    /// Checks that an allocation of the given size in bytes fits in the runtime's memory budget
+    ///
+    /// A [MemoryLimitExceeded](ErrorKind::MemoryLimitExceeded) error will be returned if the
+    /// allocation would exceed the budget, see [KotoVmSettings::memory_limit].
+    ///
+    /// The allocation isn't charged to the budget.
+    pub fn check_memory(&self, size_in_bytes: usize) -> Result<()> {
+        self.update_memory_budget(|budget| budget.check(size_in_bytes))
+    }
+
+    /// Checks that the given capability is enabled
+    ///
+    /// A [PermissionDenied](ErrorKind::PermissionDenied) error will be returned if the capability
+    /// has been disabled, see [KotoVmSettings::capabilities].
+    pub fn check_capability(&self, capability: Capability) -> Result<()> {
+        if self.context.settings.capabilities.is_enabled(capability) {
+            Ok(())
+        } else {
+            runtime_error!(ErrorKind::PermissionDenied(capability))
+        }
+    }
+
+    /// Checks that a list or tuple with the given number of elements fits in the memory budget
+    ///
+    /// This is used while values are being built, before they're charged to the budget.
+    pub(crate) fn check_sequence_size(&self, len: usize) -> Result<()> {
+        self.check_memory(sequence_size(len))
+    }
+
+    /// Charges the memory budget for the list, with the given number of elements
+    pub(crate) fn charge_list(&self, list: &KList, len: usize) -> Result<()> {
+        self.update_memory_budget(|budget| budget.charge_list(list.data_ptr(), sequence_size(len)))
+    }
+
+    /// Charges the memory budget for the map, with the given number of entries
+    pub(crate) fn charge_map(&self, map: &KMap, len: usize) -> Result<()> {
+        self.update_memory_budget(|budget| budget.charge_map(map.data_ptr(), map_size(len)))
+    }
+
+    /// Charges the memory budget for a new entry in the map, if the key isn't already present
+    pub(crate) fn charge_map_insert(&self, map: &KMap, key: &ValueKey) -> Result<()> {
+        if self.context.memory.is_some() && !map.data().contains_key(key) {
+            self.charge_map(map, map.len() + 1)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Charges the memory budget for the tuple's elements
+    pub(crate) fn charge_tuple(&self, tuple: &Ptr<Vec<KValue>>) -> Result<()> {
+        self.update_memory_budget(|budget| budget.charge_tuple(tuple))
+    }
+
+    /// Charges the memory budget for the string's data
+    pub(crate) fn charge_string(&self, string: &Ptr<String>) -> Result<()> {
+        self.update_memory_budget(|budget| budget.charge_string(string))
+    }
+
+    // Calls the function with the memory budget, if memory limits are enabled
+    //
+    // A MemoryLimitExceeded error is returned if the function returns false.
+    fn update_memory_budget(&self, f: impl FnOnce(&mut MemoryBudget) -> bool) -> Result<()> {
+        let Some(memory) = &self.context.memory else {
+            return Ok(());
+        };
+
+        let mut budget = memory.borrow_mut();
+        if f(&mut budget) {
+            Ok(())
+        } else {
+            runtime_error!(ErrorKind::MemoryLimitExceeded(budget.limit()))
+        }
+    }
+
     /// Runs the provided [Chunk], returning the resulting [KValue]
+    ///
+    /// An error will be thrown if the script awaits a [KPending] value,
+    /// see [run_resumable](Self::run_resumable).
     pub fn run(&mut self, chunk: Ptr<Chunk>) -> Result<KValue> {
-        // Set up an execution frame to run the chunk in
+        let frame_base = self.push_chunk_frame(chunk);
+
+        // Run the chunk
+        let result = self.execute_instructions();
+        if result.is_err() {
+            self.pop_frame(KValue::Null)?;
+        }
+
+        // Reset the register stack back to where it was at the start of the run
+        self.truncate_registers(frame_base);
+        result
+    }
+
+    /// Runs the provided [Chunk], allowing execution to be suspended by `await` expressions
+    ///
+    /// When the script awaits a [KPending] value, execution is suspended and the pending value is
+    /// returned in [ReturnOrAwait::Await]. Once the awaited result is available, execution can be
+    /// continued with [resume](Self::resume) (or [resume_with_error](Self::resume_with_error) if
+    /// the pending operation failed).
+    ///
+    /// Execution can only be suspended when the `await` expression is evaluated directly by the
+    /// run, so awaiting a pending value in a generator, or in a function that has been called
+    /// from a native function (e.g. a function passed to `iterator.each`), will throw an error.
+    ///
+    /// ```
+    /// use koto_bytecode::{Compiler, CompilerSettings};
+    /// use koto_runtime::{ReturnOrAwait, prelude::*};
+    ///
+    /// let mut vm = KotoVm::default();
+    /// vm.prelude().add_fn("fetch", |ctx| match ctx.args() {
+    ///     [KValue::Str(url)] => Ok(KPending::new(url.clone()).into()),
+    ///     unexpected => unexpected_args("|String|", unexpected),
+    /// });
+    ///
+    /// let script = "(await fetch 'foo') + (await fetch 'bar')";
+    /// let chunk = Compiler::compile(script, None, CompilerSettings::default()).unwrap();
+    ///
+    /// let mut output = vm.run_resumable(chunk.into()).unwrap();
+    /// let result = loop {
+    ///     match output {
+    ///         ReturnOrAwait::Await(pending) => {
+    ///             // Here the host would perform the pending operation asynchronously
+    ///             let result = match pending.data() {
+    ///                 KValue::Str(url) if url.as_str() == "foo" => 1,
+    ///                 _ => 2,
+    ///             };
+    ///             output = vm.resume(result).unwrap();
+    ///         }
+    ///         ReturnOrAwait::Return(result) => break result,
+    ///     }
+    /// };
+    ///
+    /// assert!(matches!(result, KValue::Number(n) if n == 3));
+    /// ```
+    pub fn run_resumable(&mut self, chunk: Ptr<Chunk>) -> Result<ReturnOrAwait> {
+        if self.resumable_run.is_some() {
+            return runtime_error!(ErrorKind::ResumableRunInProgress);
+        }
+
+        let frame_base = self.push_chunk_frame(chunk);
+        self.resumable_run = Some(ResumableRun {
+            frame_base,
+            awaiting: None,
+        });
+
+        self.suspend_on_await = true;
+        let result = self.execute_instructions();
+        self.resumable_output(result)
+    }
+
+    /// Resumes a suspended run, providing the result of the pending value that was awaited
+    ///
+    /// See [run_resumable](Self::run_resumable).
+    pub fn resume(&mut self, value: impl Into<KValue>) -> Result<ReturnOrAwait> {
+        let register = self.take_await_register()?;
+        self.set_register(register, value.into());
+
+        self.suspend_on_await = true;
+        let result = self.execute_instructions();
+        self.resumable_output(result)
+    }
+
+    /// Resumes a suspended run, throwing an error from the `await` expression
+    ///
+    /// The error can be caught in the script with `try`/`catch`, otherwise it will be returned.
+    ///
+    /// See [run_resumable](Self::run_resumable).
+    pub fn resume_with_error(&mut self, thrown_value: impl Into<KValue>) -> Result<ReturnOrAwait> {
+        self.take_await_register()?;
+
+        let result = match self.catch_error(Error::from_koto_value(thrown_value.into())) {
+            Ok(()) => {
+                self.suspend_on_await = true;
+                self.execute_instructions()
+            }
+            Err(error) => Err(error),
+        };
+        self.resumable_output(result)
+    }
+
+    /// Returns true if the VM has been suspended while awaiting a pending value
+    pub fn is_awaiting(&self) -> bool {
+        self.resumable_run
+            .as_ref()
+            .is_some_and(|run| run.awaiting.is_some())
+    }
+
+    // Sets up an execution frame to run a chunk in, returning the frame's base register
+    fn push_chunk_frame(&mut self, chunk: Ptr<Chunk>) -> u8 {
         let frame_base = self.next_register();
         self.registers.push(KValue::Null); // Instance register
         self.push_frame(
@@ -298,15 +807,45 @@ impl KotoVm {
         // Ensure that execution stops here if an error is thrown
         self.frame_mut().execution_barrier = true;
 
-        // Run the chunk
-        let result = self.execute_instructions();
+        frame_base
+    }
+
+    fn take_await_register(&mut self) -> Result<u8> {
+        match self
+            .resumable_run
+            .as_mut()
+            .and_then(|run| run.awaiting.take())
+        {
+            Some((register, _)) => Ok(register),
+            None => runtime_error!(ErrorKind::NotAwaiting),
+        }
+    }
+
+    // Produces the output of a resumable run after execution has stopped
+    //
+    // If execution has been suspended by `await` then the pending value is returned,
+    // otherwise the run is complete and its frame can be cleaned up.
+    fn resumable_output(&mut self, result: Result<KValue>) -> Result<ReturnOrAwait> {
+        if result.is_ok()
+            && let Some((_, pending)) = self
+                .resumable_run
+                .as_ref()
+                .and_then(|run| run.awaiting.as_ref())
+        {
+            return Ok(ReturnOrAwait::Await(pending.clone()));
+        }
+
+        let Some(run) = self.resumable_run.take() else {
+            return runtime_error!(ErrorKind::UnexpectedError);
+        };
+
         if result.is_err() {
             self.pop_frame(KValue::Null)?;
         }
 
         // Reset the register stack back to where it was at the start of the run
-        self.truncate_registers(frame_base);
-        result
+        self.truncate_registers(run.frame_base);
+        result.map(ReturnOrAwait::Return)
     }
 
     /// Continues execution in a suspended VM
@@ -323,7 +862,8 @@ impl KotoVm {
         match self.execution_state {
             ExecutionState::Inactive => Ok(ReturnOrYield::Return(result)),
             ExecutionState::Suspended => Ok(ReturnOrYield::Yield(result)),
-            ExecutionState::Active => unreachable!(),
+            // Execution is only suspended by `await` in resumable runs
+            ExecutionState::Active | ExecutionState::Awaiting => unreachable!(),
         }
     }
 
@@ -573,7 +1113,7 @@ impl KotoVm {
                     KValue::Str(s) => s,
                     other => return unexpected_type("a String", &other),
                 };
-                self.run_access(result_register, container_register, key_string)?;
+                self.run_access(result_register, container_register, key_string, None)?;
             }
         }
 
@@ -752,6 +1292,32 @@ impl KotoVm {
     }
 
     fn execute_instructions(&mut self) -> Result<KValue> {
+        let hooks = InstructionHooks {
+            debugger: self.context.debug.is_some(),
+            profiler: self.context.profiler.is_some(),
+            coverage: self.context.coverage.is_some(),
+        };
+
+        if let Some(debug) = &self.context.debug {
+            debug.borrow_mut().nesting += 1;
+        }
+        if let Some(profiler) = &self.context.profiler {
+            profiler.borrow_mut().begin_execution();
+        }
+
+        let result = self.execute_instructions_inner(hooks);
+
+        if let Some(debug) = &self.context.debug {
+            debug.borrow_mut().nesting -= 1;
+        }
+        if let Some(profiler) = &self.context.profiler {
+            profiler.borrow_mut().end_execution();
+        }
+
+        result
+    }
+
+    fn execute_instructions_inner(&mut self, hooks: InstructionHooks) -> Result<KValue> {
         let mut timeout = self
             .context
             .settings
@@ -760,25 +1326,50 @@ impl KotoVm {
 
         self.instruction_ip = self.ip();
 
+        // Execution can only be suspended by `await` when instructions are being executed
+        // directly by a resumable run, nested calls (e.g. from native functions) need to run to
+        // completion.
+        let suspend_on_await = std::mem::take(&mut self.suspend_on_await);
+
         // Every code path in this function must set the execution state to something other
         // than Active before exiting.
         self.execution_state = ExecutionState::Active;
 
         while let Some(instruction) = self.reader.next() {
-            if let Some(timeout) = timeout.as_mut()
-                && timeout.check_for_timeout()
-            {
+            if let Some(error) = self.check_execution_limits(timeout.as_mut()) {
                 self.execution_state = ExecutionState::Inactive;
                 return self
-                    .pop_call_stack_on_error(
-                        ErrorKind::Timeout(timeout.execution_limit).into(),
-                        false,
-                    )
+                    .pop_call_stack_on_error(error.into(), false)
                     .map(|_| KValue::Null);
             }
 
-            match self.execute_instruction(instruction) {
-                Ok(ControlFlow::Continue) => {}
+            if hooks.debugger {
+                self.run_debugger();
+            }
+
+            if hooks.profiler {
+                self.run_profiler();
+            }
+
+            let branch_fallthrough_ip = if hooks.coverage {
+                self.record_coverage(&instruction)
+            } else {
+                None
+            };
+
+            let result = match self.execute_instruction(instruction) {
+                Ok(ControlFlow::Await { .. }) if !suspend_on_await => {
+                    runtime_error!(ErrorKind::UnableToSuspend)
+                }
+                result => result,
+            };
+
+            match result {
+                Ok(ControlFlow::Continue) => {
+                    if let Some(fallthrough_ip) = branch_fallthrough_ip {
+                        self.record_branch_coverage(fallthrough_ip);
+                    }
+                }
                 Ok(ControlFlow::Return(value)) => {
                     self.execution_state = ExecutionState::Inactive;
                     return Ok(value);
@@ -787,27 +1378,19 @@ impl KotoVm {
                     self.execution_state = ExecutionState::Suspended;
                     return Ok(value);
                 }
-                Err(error) => match self.pop_call_stack_on_error(error.clone(), true) {
-                    Ok((recover_register, ip)) => {
-                        let catch_value = match error.error {
-                            ErrorKind::KotoError { thrown_value, .. } => thrown_value,
-                            _ => KValue::Str(error.to_string().into()),
-                        };
-
-                        self.set_register(recover_register, catch_value);
-                        self.set_ip(ip);
+                Ok(ControlFlow::Await { register, pending }) => {
+                    if let Some(run) = self.resumable_run.as_mut() {
+                        run.awaiting = Some((register, pending));
                     }
-                    Err(mut error) => {
-                        // The error hasn't been caught, so is being propagated outside of this.
-                        // Koto errors need a VM to allow the error value to be displayed,
-                        // so spawn one now.
-                        if let ErrorKind::KotoError { vm, .. } = &mut error.error {
-                            *vm = Some(self.spawn_shared_vm().into());
-                        }
+                    self.execution_state = ExecutionState::Awaiting;
+                    return Ok(KValue::Null);
+                }
+                Err(error) => {
+                    if let Err(error) = self.catch_error(error) {
                         self.execution_state = ExecutionState::Inactive;
                         return Err(error);
                     }
-                },
+                }
             }
 
             self.instruction_ip = self.ip();
@@ -817,6 +1400,223 @@ impl KotoVm {
         Ok(KValue::Null)
     }
 
+    // Jumps to the nearest catch block that can handle the error
+    //
+    // If the error isn't caught then it's returned, ready to be propagated outside of the VM.
+    fn catch_error(&mut self, error: Error) -> Result<()> {
+        match self.pop_call_stack_on_error(error.clone(), error.error.is_catchable()) {
+            Ok((recover_register, ip)) => {
+                let catch_value = match error.error {
+                    ErrorKind::KotoError { thrown_value, .. } => thrown_value,
+                    _ => KValue::Str(error.to_string().into()),
+                };
+
+                self.set_register(recover_register, catch_value);
+                self.set_ip(ip);
+                Ok(())
+            }
+            Err(mut error) => {
+                // The error hasn't been caught, so is being propagated outside of this.
+                // Koto errors need a VM to allow the error value to be displayed,
+                // so spawn one now.
+                if let ErrorKind::KotoError { vm, .. } = &mut error.error {
+                    *vm = Some(self.spawn_shared_vm().into());
+                }
+                Err(error)
+            }
+        }
+    }
+
+    // Checks if execution should be paused by the debugger, calling the debugger if necessary
+    fn run_debugger(&mut self) {
+        let context = self.context.clone();
+        let Some(debug) = &context.debug else {
+            return;
+        };
+
+        let Some(span) = self
+            .reader
+            .chunk
+            .debug_info
+            .get_source_span(self.instruction_ip)
+        else {
+            return;
+        };
+        let line = span.start.line;
+
+        // Only consider pausing when execution arrives at a new line in the current frame
+        let frame = self.frame_mut();
+        if frame.debug_line == Some(line) {
+            return;
+        }
+        frame.debug_line = Some(line);
+
+        let depth = DebugDepth {
+            nesting: debug.borrow().nesting,
+            frames: self.call_stack.len(),
+        };
+
+        let mut debug_state = debug.borrow_mut();
+        let Some(reason) = debug_state.pause_reason(&self.reader.chunk.path, line, depth) else {
+            return;
+        };
+        let Some(mut debugger) = debug_state.take_debugger() else {
+            return;
+        };
+        // Release the borrow while the debugger is running,
+        // allowing it to make use of the VM (e.g. to evaluate values or set breakpoints).
+        drop(debug_state);
+
+        let command = debugger.paused(&mut DebugContext::new(self, reason));
+
+        debug.borrow_mut().resume(debugger, command, depth);
+    }
+
+    // Records the current instruction in the profiler
+    fn run_profiler(&mut self) {
+        let context = self.context.clone();
+        let Some(profiler) = &context.profiler else {
+            return;
+        };
+
+        let chunk = &self.reader.chunk;
+        let line = chunk
+            .debug_info
+            .get_source_span(self.instruction_ip)
+            .map_or(0, |span| span.start.line);
+
+        let depth = self.call_stack.len();
+        let call_stack = self.call_stack.iter().enumerate().map(|(i, frame)| {
+            let ip = if i == depth - 1 {
+                self.instruction_ip
+            } else {
+                frame.return_instruction_ip
+            };
+            FunctionKey {
+                chunk: frame.chunk.clone(),
+                start_ip: frame
+                    .chunk
+                    .debug_info
+                    .get_frame(ip)
+                    .map_or(0, |frame_info| frame_info.start_ip),
+            }
+        });
+
+        profiler.borrow_mut().sample(chunk, line, depth, call_stack);
+    }
+
+    // Records the current instruction in the coverage state
+    //
+    // If the instruction is a conditional branch, then the ip that will be reached if the branch
+    // doesn't jump is returned.
+    fn record_coverage(&self, instruction: &Instruction) -> Option<u32> {
+        let coverage = self.context.coverage.as_ref()?;
+        coverage
+            .borrow_mut()
+            .record_instruction(&self.reader.chunk, self.instruction_ip);
+        is_branch(instruction).then(|| self.ip())
+    }
+
+    // Records whether or not the branch at the current instruction ip jumped
+    fn record_branch_coverage(&self, fallthrough_ip: u32) {
+        if let Some(coverage) = &self.context.coverage {
+            coverage.borrow_mut().record_branch(
+                &self.reader.chunk,
+                self.instruction_ip,
+                self.ip() != fallthrough_ip,
+            );
+        }
+    }
+
+    // Returns the call stack for the debugger, with the innermost frame first
+    pub(crate) fn debug_call_stack(&self) -> Vec<InstructionFrame> {
+        self.call_stack
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, frame)| InstructionFrame {
+                chunk: frame.chunk.clone(),
+                instruction: if i == 0 {
+                    self.instruction_ip
+                } else {
+                    frame.return_instruction_ip
+                },
+            })
+            .collect()
+    }
+
+    // Returns the registers of a frame for the debugger, with 0 being the innermost frame
+    pub(crate) fn debug_frame_registers(&self, frame_index: usize) -> &[KValue] {
+        let Some(frame) = self.call_stack.iter().rev().nth(frame_index) else {
+            return &[];
+        };
+
+        let start = frame.register_base.min(self.registers.len());
+        let end =
+            (frame.register_base + frame.required_registers as usize).min(self.registers.len());
+        &self.registers[start..end]
+    }
+
+    // Returns the named local values of a frame for the debugger
+    pub(crate) fn debug_frame_locals(&self, frame_index: usize) -> Vec<(KString, KValue)> {
+        let Some(instruction_frame) = self.debug_call_stack().into_iter().nth(frame_index) else {
+            return Vec::new();
+        };
+        let chunk = &instruction_frame.chunk;
+        let Some(frame_info) = chunk.debug_info.get_frame(instruction_frame.instruction) else {
+            return Vec::new();
+        };
+
+        let registers = self.debug_frame_registers(frame_index);
+        frame_info
+            .locals
+            .iter()
+            .filter_map(|(register, name)| {
+                registers.get(*register as usize).map(|value| {
+                    (
+                        KString::from(chunk.constants.get_string_slice(*name)),
+                        value.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    // Checks the runtime's execution limits, returning an error if execution should be stopped
+    //
+    // This should only be called once per instruction.
+    fn check_execution_limits(&self, timeout: Option<&mut ExecutionTimeout>) -> Option<ErrorKind> {
+        if self.context.interrupt.take_request() {
+            return Some(ErrorKind::Interrupted);
+        }
+
+        if !self.consume_fuel() {
+            return Some(ErrorKind::OutOfFuel);
+        }
+
+        if let Some(timeout) = timeout
+            && timeout.check_for_timeout()
+        {
+            return Some(ErrorKind::Timeout(timeout.execution_limit));
+        }
+
+        None
+    }
+
+    // Consumes a unit of fuel, returning false if the runtime has run out of fuel
+    //
+    // If fuel metering is disabled then true is always returned.
+    fn consume_fuel(&self) -> bool {
+        match &self.context.fuel {
+            Some(fuel) => fuel
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                    remaining.checked_sub(1)
+                })
+                .is_ok(),
+            None => true,
+        }
+    }
+
     fn execute_instruction(&mut self, instruction: Instruction) -> Result<ControlFlow> {
         use Instruction::*;
 
@@ -915,10 +1715,55 @@ impl KotoVm {
                 target,
                 source,
             } => self.run_capture_value(function, target, source)?,
+            RecordType { register, name } => {
+                let record_type = KRecordType::new(self.koto_string_from_constant(name));
+                self.set_register(register, record_type.into());
+            }
+            RecordField { record, name } => {
+                let name = self.koto_string_from_constant(name);
+                self.record_type_mut(record)?.add_field(name);
+            }
+            RecordFieldDefault { record, value } => {
+                let value = self.clone_register(value);
+                self.record_type_mut(record)?.set_field_default(value)?;
+            }
+            RecordFieldType {
+                record,
+                allow_null,
+                type_string,
+            } => {
+                let type_string = self.koto_string_from_constant(type_string);
+                self.record_type_mut(record)?
+                    .set_field_type(type_string, allow_null)?;
+            }
+            EnumType { register, name } => {
+                let enum_type = KEnumType::new(self.koto_string_from_constant(name));
+                self.set_register(register, enum_type.into());
+            }
+            EnumVariant { enum_type, name } => {
+                let name = self.koto_string_from_constant(name);
+                self.enum_type_mut(enum_type)?.add_variant(name);
+            }
+            EnumVariantField { enum_type, name } => {
+                let name = self.koto_string_from_constant(name);
+                self.enum_type_mut(enum_type)?.add_variant_field(name)?;
+            }
+            IsEnumVariant {
+                register,
+                value,
+                enum_type,
+                variant,
+            } => self.run_is_enum_variant(register, value, enum_type, variant)?,
             Negate { register, value } => self.run_negate(register, value)?,
             Not { register, value } => self.run_not(register, value)?,
             Add { register, lhs, rhs } => self.run_add(register, lhs, rhs)?,
             Subtract { register, lhs, rhs } => self.run_subtract(register, lhs, rhs)?,
+            AddU8 { register, lhs, rhs } => {
+                self.run_arithmetic_u8(BinaryOp::Add, register, lhs, rhs)?
+            }
+            SubtractU8 { register, lhs, rhs } => {
+                self.run_arithmetic_u8(BinaryOp::Subtract, register, lhs, rhs)?
+            }
             Multiply { register, lhs, rhs } => self.run_multiply(register, lhs, rhs)?,
             Divide { register, lhs, rhs } => self.run_divide(register, lhs, rhs)?,
             Remainder { register, lhs, rhs } => self.run_remainder(register, lhs, rhs)?,
@@ -942,6 +1787,24 @@ impl KotoVm {
             JumpIfTrue { register, offset } => self.run_jump_if_true(register, offset as u32)?,
             JumpIfFalse { register, offset } => self.run_jump_if_false(register, offset as u32)?,
             JumpIfNull { register, offset } => self.run_jump_if_null(register, offset as u32)?,
+            JumpIfNotLess { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::Less, lhs, rhs, offset as u32)?
+            }
+            JumpIfNotLessOrEqual { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::LessOrEqual, lhs, rhs, offset as u32)?
+            }
+            JumpIfNotGreater { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::Greater, lhs, rhs, offset as u32)?
+            }
+            JumpIfNotGreaterOrEqual { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::GreaterOrEqual, lhs, rhs, offset as u32)?
+            }
+            JumpIfNotEqual { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::Equal, lhs, rhs, offset as u32)?
+            }
+            JumpIfEqual { lhs, rhs, offset } => {
+                self.run_compare_and_jump(BinaryOp::NotEqual, lhs, rhs, offset as u32)?
+            }
             Call {
                 result,
                 function,
@@ -975,6 +1838,39 @@ impl KotoVm {
                 },
                 self.clone_register(function),
             )?,
+            TailCall {
+                result,
+                function,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => self.tail_call_callable(
+                CallInfo {
+                    result_register: Some(result),
+                    frame_base,
+                    instance: None,
+                    arg_count,
+                    packed_arg_count,
+                },
+                self.clone_register(function),
+            )?,
+            TailCallInstance {
+                result,
+                function,
+                instance,
+                frame_base,
+                arg_count,
+                packed_arg_count,
+            } => self.tail_call_callable(
+                CallInfo {
+                    result_register: Some(result),
+                    frame_base,
+                    instance: Some(instance),
+                    arg_count,
+                    packed_arg_count,
+                },
+                self.clone_register(function),
+            )?,
             Return { register } => {
                 if let Some(return_value) = self.pop_frame(self.clone_register(register))? {
                     // If pop_frame returns a new return_value, then execution should stop.
@@ -982,6 +1878,14 @@ impl KotoVm {
                 }
             }
             Yield { register } => control_flow = ControlFlow::Yield(self.clone_register(register)),
+            Await { register, value } => match self.clone_register(value) {
+                KValue::Object(o) if o.is_a::<KPending>() => {
+                    let pending = o.cast::<KPending>()?.clone();
+                    control_flow = ControlFlow::Await { register, pending };
+                }
+                // Awaiting a value that isn't pending produces the value itself
+                value => self.set_register(register, value),
+            },
             Throw { register } => {
                 return Err(crate::Error::from_koto_value(self.clone_register(register)));
             }
@@ -992,6 +1896,13 @@ impl KotoVm {
                 jump_offset,
                 temporary_output,
             } => self.run_iterator_next(result, iterator, jump_offset, temporary_output)?,
+            IterNextRange {
+                result,
+                start,
+                end,
+                jump_offset,
+                inclusive,
+            } => self.run_iterator_next_range(result, start, end, jump_offset as u32, inclusive)?,
             TempIndex {
                 register,
                 value,
@@ -1039,40 +1950,50 @@ impl KotoVm {
                 register,
                 value,
                 key,
-            } => self.run_access(register, value, self.koto_string_from_constant(key))?,
+                cache,
+            } => self.run_access(
+                register,
+                value,
+                self.koto_string_from_constant(key),
+                Some(cache),
+            )?,
             TryAccess {
                 register,
                 value,
                 key,
+                cache,
                 jump_offset,
             } => self.run_try_access(
                 register,
                 value,
                 self.koto_string_from_constant(key),
+                cache,
                 jump_offset as u32,
             )?,
             AccessString {
                 register,
                 value,
                 key,
+                cache,
             } => {
                 let key_string = match self.clone_register(key) {
                     KValue::Str(s) => s,
                     other => return unexpected_type("a String", &other),
                 };
-                self.run_access(register, value, key_string)?;
+                self.run_access(register, value, key_string, Some(cache))?;
             }
             TryAccessString {
                 register,
                 value,
                 key,
+                cache,
                 jump_offset,
             } => {
                 let key_string = match self.clone_register(key) {
                     KValue::Str(s) => s,
                     other => return unexpected_type("a String", &other),
                 };
-                self.run_try_access(register, value, key_string, jump_offset as u32)?;
+                self.run_try_access(register, value, key_string, cache, jump_offset as u32)?;
             }
             TryStart {
                 arg_register,
@@ -1158,10 +2079,10 @@ impl KotoVm {
                     self.register_slice_raw(temp_registers.start, temp_registers.count),
                 );
                 self.set_register(register, KValue::Tuple(tuple));
+                Ok(())
             }
-            _ => unreachable!(),
+            unexpected => unexpected_type("TemporaryTuple", &unexpected),
         }
-        Ok(())
     }
 
     fn run_make_range(
@@ -1260,6 +2181,50 @@ impl KotoVm {
         Ok(())
     }
 
+    // Runs the IterNextRange instruction
+    //
+    // The start register is used as the loop's counter, and is incremented in place until it
+    // reaches the end of the range.
+    fn run_iterator_next_range(
+        &mut self,
+        result_register: u8,
+        start_register: u8,
+        end_register: u8,
+        jump_offset: u32,
+        inclusive: bool,
+    ) -> Result<()> {
+        use KValue::Number;
+
+        let (start, end) = match (
+            self.get_register(start_register),
+            self.get_register(end_register),
+        ) {
+            (Number(start), Number(end)) => (i64::from(start), i64::from(end)),
+            (Number(_), unexpected) => {
+                return unexpected_type("a Number for the range's end", unexpected);
+            }
+            (unexpected, _) => {
+                return unexpected_type("a Number for the range's start", unexpected);
+            }
+        };
+
+        if start < end {
+            self.set_register(start_register, (start + 1).into());
+        } else if start == end && inclusive {
+            match start.checked_add(1) {
+                Some(next) => self.set_register(start_register, next.into()),
+                // Avoid overflowing the counter by moving the end of the range instead
+                None => self.set_register(end_register, (end - 1).into()),
+            }
+        } else {
+            self.jump_ip(jump_offset);
+            return Ok(());
+        }
+
+        self.set_register(result_register, start.into());
+        Ok(())
+    }
+
     fn run_iterator_next(
         &mut self,
         result_register: Option<u8>,
@@ -1625,7 +2590,11 @@ impl KotoVm {
         match function {
             KValue::Function(f) => {
                 if let Some(captures) = f.captures() {
-                    captures.data_mut()[capture_index as usize] = self.clone_register(value);
+                    let value = self.clone_register(value);
+                    match captures.data_mut().get_mut(capture_index as usize) {
+                        Some(capture) => *capture = value,
+                        None => return runtime_error!("invalid capture index ({capture_index})"),
+                    }
                 }
                 Ok(())
             }
@@ -1633,6 +2602,39 @@ impl KotoVm {
         }
     }
 
+    // Used while declaring a record type, see the RecordField* instructions
+    fn record_type_mut(&self, register: u8) -> Result<BorrowMut<'_, KRecordType>> {
+        match self.get_register(register) {
+            KValue::Object(o) if o.is_a::<KRecordType>() => o.cast_mut::<KRecordType>(),
+            unexpected => unexpected_type("RecordType", unexpected),
+        }
+    }
+
+    // Used while declaring an enum type, see the EnumVariant* instructions
+    fn enum_type_mut(&self, register: u8) -> Result<BorrowMut<'_, KEnumType>> {
+        match self.get_register(register) {
+            KValue::Object(o) if o.is_a::<KEnumType>() => o.cast_mut::<KEnumType>(),
+            unexpected => unexpected_type("EnumType", unexpected),
+        }
+    }
+
+    fn run_is_enum_variant(
+        &mut self,
+        result: u8,
+        value: u8,
+        enum_type: u8,
+        variant: ConstantIndex,
+    ) -> Result<()> {
+        let result_value = match self.get_register(enum_type) {
+            KValue::Object(o) if o.is_a::<KEnumType>() => o
+                .cast::<KEnumType>()?
+                .is_variant(self.get_register(value), self.get_constant_str(variant))?,
+            unexpected => return unexpected_type("EnumType", unexpected),
+        };
+        self.set_register(result, result_value.into());
+        Ok(())
+    }
+
     fn run_negate(&mut self, result: u8, value: u8) -> Result<()> {
         use KValue::*;
         use UnaryOp::Negate;
@@ -1717,15 +2719,23 @@ impl KotoVm {
         let result_value = match (lhs_value, rhs_value) {
             (Number(a), Number(b)) => Number(a + b),
             (Str(a), Str(b)) => {
-                let result = a.to_string() + b.as_ref();
+                self.check_memory(a.len().saturating_add(b.len()))?;
+                let result = Ptr::from(a.to_string() + b.as_ref());
+                self.charge_string(&result)?;
                 Str(result.into())
             }
             (List(a), List(b)) => {
+                self.check_sequence_size(a.len().saturating_add(b.len()))?;
                 let result: ValueVec = a.data().iter().chain(b.data().iter()).cloned().collect();
-                List(KList::with_data(result))
+                let result = KList::with_data(result);
+                self.charge_list(&result, result.len())?;
+                List(result)
             }
             (Tuple(a), Tuple(b)) => {
-                let result: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
+                self.check_sequence_size(a.len().saturating_add(b.len()))?;
+                let result: Ptr<Vec<_>> =
+                    a.iter().chain(b.iter()).cloned().collect::<Vec<_>>().into();
+                self.charge_tuple(&result)?;
                 Tuple(result.into())
             }
             (Map(m), _) if m.contains_meta_key(&Add.into()) => {
@@ -1741,6 +2751,7 @@ impl KotoVm {
             }
             (_, Object(o)) => call_object_binary_op!(AddRhs, add_rhs, o, lhs_value, rhs_value),
             (Map(a), Map(b)) => {
+                self.check_memory(map_size(a.len().saturating_add(b.len())))?;
                 let mut data = a.data().clone();
                 data.extend(b.data().iter().map(|(k, v)| (k.clone(), v.clone())));
                 let meta = match (a.meta_map(), b.meta_map()) {
@@ -1753,7 +2764,9 @@ impl KotoVm {
                         Some(result)
                     }
                 };
-                Map(KMap::with_contents(data, meta))
+                let result = KMap::with_contents(data, meta);
+                self.charge_map(&result, result.len())?;
+                Map(result)
             }
             _ => return binary_op_error(lhs_value, rhs_value, Add),
         };
@@ -1849,6 +2862,31 @@ impl KotoVm {
         )
     }
 
+    // Runs the AddU8 and SubtractU8 instructions
+    //
+    // Numbers are handled directly, other values are passed along to the generic binary op.
+    fn run_arithmetic_u8(&mut self, op: BinaryOp, result: u8, lhs: u8, n: u8) -> Result<()> {
+        let result_value = match self.get_register(lhs) {
+            KValue::Number(a) => match op {
+                BinaryOp::Add => (a + &KNumber::from(n)).into(),
+                _ => (a - &KNumber::from(n)).into(),
+            },
+            lhs_value => self.run_binary_op_fallback(op, lhs_value.clone(), n.into())?,
+        };
+        self.set_register(result, result_value);
+        Ok(())
+    }
+
+    // Runs a binary op on behalf of one of the specialized instructions
+    //
+    // The temporary registers used by `run_binary_op` are cleaned up if the operation fails,
+    // which allows the specialized instructions to be used in loops containing `try` blocks.
+    fn run_binary_op_fallback(&mut self, op: BinaryOp, lhs: KValue, rhs: KValue) -> Result<KValue> {
+        let register_count = self.registers.len();
+        self.run_binary_op(op, lhs, rhs)
+            .inspect_err(|_| self.registers.truncate(register_count))
+    }
+
     fn run_add_assign(&mut self, lhs: u8, rhs: u8) -> Result<()> {
         macros::run_compound_assign_op!(
             self,
@@ -1898,7 +2936,11 @@ impl KotoVm {
             self,
             RemainderAssign,
             remainder_assign,
-            |a: &KNumber, b: &KNumber| a % b,
+            |a: &KNumber, b: &KNumber| match b {
+                // Avoid a panic for integer remainders with a zero divisor, see `run_remainder`
+                KNumber::I64(0) => f64::NAN.into(),
+                _ => a % b,
+            },
             lhs,
             rhs
         )
@@ -2077,6 +3119,16 @@ impl KotoVm {
                     false
                 }
             }
+            (Object(a), Object(b)) if a.is_a::<KRecord>() && b.is_a::<KRecord>() => {
+                let a = a.clone();
+                let b = b.clone();
+                self.compare_records(a, b)?
+            }
+            (Object(a), Object(b)) if a.is_a::<KEnumValue>() && b.is_a::<KEnumValue>() => {
+                let a = a.clone();
+                let b = b.clone();
+                self.compare_enum_values(a, b)?
+            }
             (Object(o), _) => o.try_borrow()?.equal(rhs_value)?,
             (Function(a), Function(b)) => {
                 let a = a.clone();
@@ -2135,6 +3187,16 @@ impl KotoVm {
                     true
                 }
             }
+            (Object(a), Object(b)) if a.is_a::<KRecord>() && b.is_a::<KRecord>() => {
+                let a = a.clone();
+                let b = b.clone();
+                !self.compare_records(a, b)?
+            }
+            (Object(a), Object(b)) if a.is_a::<KEnumValue>() && b.is_a::<KEnumValue>() => {
+                let a = a.clone();
+                let b = b.clone();
+                !self.compare_enum_values(a, b)?
+            }
             (Object(o), _) => o.try_borrow()?.not_equal(rhs_value)?,
             (Function(a), Function(b)) => {
                 let a = a.clone();
@@ -2166,6 +3228,38 @@ impl KotoVm {
         }
     }
 
+    // Records are equal if they share the same record type and their values are equal
+    fn compare_records(&mut self, record_a: KObject, record_b: KObject) -> Result<bool> {
+        let (values_a, values_b) = {
+            let a = record_a.cast::<KRecord>()?;
+            let b = record_b.cast::<KRecord>()?;
+
+            if !a.has_same_type(&b) {
+                return Ok(false);
+            }
+
+            (a.values().to_vec(), b.values().to_vec())
+        };
+
+        self.compare_value_ranges(&values_a, &values_b)
+    }
+
+    // Enum values are equal if they're the same variant and their payload values are equal
+    fn compare_enum_values(&mut self, value_a: KObject, value_b: KObject) -> Result<bool> {
+        let (payload_a, payload_b) = {
+            let a = value_a.cast::<KEnumValue>()?;
+            let b = value_b.cast::<KEnumValue>()?;
+
+            if !a.has_same_variant(&b) {
+                return Ok(false);
+            }
+
+            (a.payload().to_vec(), b.payload().to_vec())
+        };
+
+        self.compare_value_ranges(&payload_a, &payload_b)
+    }
+
     // Called from run_equal / run_not_equal to compare the contents of lists and tuples
     fn compare_value_ranges(&mut self, range_a: &[KValue], range_b: &[KValue]) -> Result<bool> {
         if range_a.len() != range_b.len() {
@@ -2324,6 +3418,33 @@ impl KotoVm {
         Ok(())
     }
 
+    // Runs the compare-and-jump instructions, jumping if the result of the comparison is false
+    fn run_compare_and_jump(&mut self, op: BinaryOp, lhs: u8, rhs: u8, offset: u32) -> Result<()> {
+        use KValue::*;
+
+        let comparison_passed = match (self.get_register(lhs), self.get_register(rhs)) {
+            (Number(a), Number(b)) => match op {
+                BinaryOp::Less => a < b,
+                BinaryOp::LessOrEqual => a <= b,
+                BinaryOp::Greater => a > b,
+                BinaryOp::GreaterOrEqual => a >= b,
+                BinaryOp::Equal => a == b,
+                BinaryOp::NotEqual => a != b,
+                _ => return runtime_error!("unexpected comparison op ({op:?})"),
+            },
+            (lhs_value, rhs_value) => {
+                let (lhs_value, rhs_value) = (lhs_value.clone(), rhs_value.clone());
+                let result = self.run_binary_op_fallback(op, lhs_value, rhs_value)?;
+                !matches!(result, Null | Bool(false))
+            }
+        };
+
+        if !comparison_passed {
+            self.jump_ip(offset);
+        }
+        Ok(())
+    }
+
     fn run_jump_if_null(&mut self, register: u8, offset: u32) -> Result<()> {
         if matches!(self.get_register(register), KValue::Null) {
             self.jump_ip(offset)
@@ -2404,6 +3525,8 @@ impl KotoVm {
             return self.successful_import(import_register, value, import_all);
         }
 
+        self.check_capability(Capability::Imports)?;
+
         // Attempt to compile the imported module from disk,
         // using the current source path as the relative starting location
         let source_path = self.reader.chunk.path.clone();
@@ -2686,6 +3809,7 @@ impl KotoVm {
             }
             KValue::Map(map) => {
                 let key = ValueKey::try_from(key.clone())?;
+                self.charge_map_insert(map, &key)?;
                 map.data_mut().insert(key, value.clone());
                 Ok(())
             }
@@ -2775,8 +3899,15 @@ impl KotoVm {
         result_register: u8,
         value_register: u8,
         key_string: KString,
+        cache_slot: Option<u32>,
     ) -> Result<()> {
-        self.run_access_inner(result_register, value_register, key_string, true)?;
+        self.run_access_inner(
+            result_register,
+            value_register,
+            key_string,
+            cache_slot,
+            true,
+        )?;
         Ok(())
     }
 
@@ -2785,9 +3916,16 @@ impl KotoVm {
         result_register: u8,
         value_register: u8,
         key_string: KString,
+        cache_slot: u32,
         jump_offset: u32,
     ) -> Result<()> {
-        if !self.run_access_inner(result_register, value_register, key_string, false)? {
+        if !self.run_access_inner(
+            result_register,
+            value_register,
+            key_string,
+            Some(cache_slot),
+            false,
+        )? {
             self.jump_ip(jump_offset);
         }
         Ok(())
@@ -2799,11 +3937,14 @@ impl KotoVm {
     //
     // If `error_if_not_found` is `true`, then an error will be returned if the key wasn't found,
     // otherwise `false` will be returned.
+    //
+    // Map lookups use the chunk's access cache for the given slot, if one is provided.
     fn run_access_inner(
         &mut self,
         result_register: u8,
         value_register: u8,
         key_string: KString,
+        cache_slot: Option<u32>,
         error_if_not_found: bool,
     ) -> Result<bool> {
         use KValue::*;
@@ -2841,31 +3982,22 @@ impl KotoVm {
                 Ok(true)
             }
             Map(map) => {
-                let mut access_map = map.clone();
-                let mut access_result = None;
-                while access_result.is_none() {
-                    let maybe_value = access_map.get(&key);
-                    match maybe_value {
-                        Some(value) => access_result = Some(value),
-                        // Fallback to the map module when there's no metamap
-                        None if access_map.meta_map().is_none() => {
-                            return core_op!(map, error_if_not_found);
-                        }
-                        _ => match access_map.get_meta_value(&MetaKey::Named(key_string.clone())) {
-                            Some(value) => access_result = Some(value),
-                            None => match access_map.get_meta_value(&MetaKey::Base) {
-                                Some(Map(base)) => {
-                                    // Attempt the access again with the base map
-                                    access_map = base;
-                                }
-                                Some(unexpected) => {
-                                    return unexpected_type("Map as base value", &unexpected);
-                                }
-                                None => break,
-                            },
-                        },
+                let lookup = match cache_slot
+                    .and_then(|slot| self.access_caches.get(&self.reader.chunk, slot))
+                {
+                    Some(cache) => cache.lookup(map, &key_string),
+                    None => AccessCache::default().lookup(map, &key_string),
+                };
+
+                let mut access_result = match lookup {
+                    MapLookup::Found(value) => Some(value),
+                    // Fallback to the map module when there's no metamap
+                    MapLookup::NotFoundWithoutMetaMap => return core_op!(map, error_if_not_found),
+                    MapLookup::NotFound => None,
+                    MapLookup::UnexpectedBase(unexpected) => {
+                        return unexpected_type("Map as base value", &unexpected);
                     }
-                }
+                };
 
                 // Iterator fallback?
                 if access_result.is_none()
@@ -2982,8 +4114,22 @@ impl KotoVm {
         Ok(())
     }
 
+    // Throws a StackOverflow error if calling a function would exceed the call depth limit
+    fn check_call_depth(&self) -> Result<()> {
+        match self.context.settings.max_call_depth {
+            Some(max_call_depth)
+                if self.call_depth_offset + self.call_stack.len() >= max_call_depth =>
+            {
+                runtime_error!(ErrorKind::StackOverflow(max_call_depth))
+            }
+            _ => Ok(()),
+        }
+    }
+
     // Similar to `call_koto_function`, but sets up the frame in a new VM for the generator
     fn call_generator(&mut self, call_info: &CallInfo, f: &KFunction) -> Result<()> {
+        self.check_call_depth()?;
+
         // Spawn a VM for the generator
         let mut generator_vm = self.spawn_shared_vm();
         // Push a frame for running the generator function
@@ -3054,6 +4200,23 @@ impl KotoVm {
     }
 
     fn call_koto_function(&mut self, call_info: &CallInfo, f: &KFunction) -> Result<()> {
+        self.check_call_depth()?;
+        self.prepare_koto_function_args(call_info, f)?;
+
+        // Set up a new frame for the called function
+        self.push_frame(
+            f.chunk.clone(),
+            f.ip,
+            call_info.frame_base,
+            call_info.result_register,
+            f.non_locals(),
+        );
+
+        Ok(())
+    }
+
+    // Prepares the registers following the frame base for a call to a Koto function
+    fn prepare_koto_function_args(&mut self, call_info: &CallInfo, f: &KFunction) -> Result<()> {
         debug_assert!(!f.flags.is_generator());
 
         // The caller instance is in the frame base register,
@@ -3086,21 +4249,51 @@ impl KotoVm {
         // Captures and temp tuple values are placed in the registers following the arguments
         apply_captures(&mut self.registers, f);
 
-        // Set up a new frame for the called function
-        self.push_frame(
-            f.chunk.clone(),
-            f.ip,
-            call_info.frame_base,
-            call_info.result_register,
-            f.non_locals(),
-        );
-
         Ok(())
     }
 
-    fn call_callable(&mut self, mut info: CallInfo, callable: KValue) -> Result<()> {
-        use KValue::*;
+    // Calls a function in tail position, replacing the current frame with the function's frame
+    //
+    // The current frame can only be replaced when calling a Koto function that isn't a generator,
+    // and when there are no active catch points in the frame. Otherwise a regular call is made,
+    // and the result will be returned by the `Return` instruction that follows the tail call.
+    fn tail_call_callable(&mut self, mut info: CallInfo, callable: KValue) -> Result<()> {
+        let f = match callable {
+            KValue::Function(f)
+                if !f.flags.is_generator() && self.frame().catch_stack.is_empty() =>
+            {
+                f
+            }
+            other => return self.call_callable(info, other),
+        };
+
+        self.prepare_call_frame_base(&mut info)?;
+
+        // Move the instance and arguments to the start of the current frame,
+        // discarding the current frame's registers.
+        let register_base = self.register_base;
+        let call_arg_end = self.register_index(info.frame_base + 1) + info.arg_count as usize;
+        self.registers.truncate(call_arg_end);
+        self.registers
+            .drain(register_base..register_base + info.frame_base as usize);
+        info.frame_base = 0;
+
+        self.prepare_koto_function_args(&info, &f)?;
+
+        // Replace the current frame, keeping the execution barrier so that execution stops at the
+        // same point that it would have stopped at when returning from the replaced frame.
+        let frame = self.frame_mut();
+        *frame = Frame {
+            execution_barrier: frame.execution_barrier,
+            ..Frame::new(f.chunk.clone(), f.non_locals(), register_base)
+        };
+        self.set_chunk_and_ip(f.chunk.clone(), f.ip);
+
+        Ok(())
+    }
 
+    // Places the call's instance in the frame base, and unpacks any packed arguments
+    fn prepare_call_frame_base(&mut self, info: &mut CallInfo) -> Result<()> {
         if let Some(instance) = info.instance {
             // The instance will only match the frame base when the call stack has been set up
             // manually, like in `call_and_run_function`.
@@ -3113,7 +4306,13 @@ impl KotoVm {
             self.set_register(info.frame_base, KValue::Null);
         }
 
-        self.unpack_packed_arguments(&mut info)?;
+        self.unpack_packed_arguments(info)
+    }
+
+    fn call_callable(&mut self, mut info: CallInfo, callable: KValue) -> Result<()> {
+        use KValue::*;
+
+        self.prepare_call_frame_base(&mut info)?;
 
         match callable {
             Function(f) => {
@@ -3172,8 +4371,17 @@ impl KotoVm {
             // e.g. `f []..., x...`
             //         ^ The first argument is empty, so the second argument is shifted by -1
             let arg_offset = info.arg_count as isize - original_arg_count;
-            let unpack_index =
-                ((first_arg_index + packed_arg_register) as isize + arg_offset) as usize;
+            let unpack_index = (first_arg_index + packed_arg_register) as isize + arg_offset;
+
+            // Packed argument indices are provided by the compiler, but chunks can be loaded from
+            // untrusted sources so the index is validated before it's used.
+            if *packed_arg_register >= original_arg_count as usize
+                || unpack_index < first_arg_index as isize
+                || unpack_index >= self.registers.len() as isize
+            {
+                return runtime_error!("invalid packed argument index ({packed_arg_register})");
+            }
+            let unpack_index = unpack_index as usize;
 
             // First, swap-remove the argument to be unpacked,
             // replacing the argument with null and keeping any trailing registers in place.
@@ -3188,7 +4396,9 @@ impl KotoVm {
             })?;
 
             // Process the iterator output, checking for errors and collecting `ValuePair`s
-            let max_unpacked_args = (u8::MAX - info.arg_count - 1) as usize; // -1 for frame base
+            // -1 for frame base
+            let max_unpacked_args =
+                u8::MAX.saturating_sub(info.arg_count).saturating_sub(1) as usize;
             for output in iterator {
                 if unpacked_values.len() == max_unpacked_args {
                     return runtime_error!("Call argument limit reached during unpacking");
@@ -3302,44 +4512,8 @@ impl KotoVm {
         type_index: ConstantIndex,
         allow_null: bool,
     ) -> bool {
-        let value = self.get_register(value_register);
-
-        if allow_null && matches!(value, KValue::Null) {
-            return true;
-        }
-
-        match self.get_constant_str(type_index) {
-            "Any" => true,
-            "Callable" => value.is_callable(),
-            "Indexable" => value.is_indexable(),
-            "Iterable" => value.is_iterable(),
-            expected_type => {
-                if value.type_as_string() == expected_type {
-                    true
-                } else {
-                    // The type didn't match, so look for a base value to check
-                    let mut value = value.clone();
-
-                    loop {
-                        match value {
-                            KValue::Map(m) if m.contains_meta_key(&MetaKey::Base) => {
-                                let base = m.get_meta_value(&MetaKey::Base).unwrap();
-                                if base.type_as_string() == expected_type {
-                                    return true;
-                                } else {
-                                    // The base didn't match the expected type,
-                                    // but continue looping to check the base's base.
-                                    value = base;
-                                }
-                            }
-                            _ => break,
-                        }
-                    }
-
-                    false
-                }
-            }
-        }
+        self.get_register(value_register)
+            .matches_type_hint(self.get_constant_str(type_index), allow_null)
     }
 
     fn get_value_size(&mut self, value_register: u8) -> Result<usize> {
@@ -3351,17 +4525,20 @@ impl KotoVm {
 
     fn run_sequence_push(&mut self, value_register: u8) -> Result<()> {
         let value = self.clone_register(value_register);
+        let Some(len) = self.sequence_builders.last().map(Vec::len) else {
+            return runtime_error!(ErrorKind::MissingSequenceBuilder);
+        };
+        self.check_sequence_size(len + 1)?;
         if let Some(builder) = self.sequence_builders.last_mut() {
             builder.push(value);
-            Ok(())
-        } else {
-            runtime_error!(ErrorKind::MissingSequenceBuilder)
         }
+        Ok(())
     }
 
     fn run_sequence_to_list(&mut self, register: u8) -> Result<()> {
         if let Some(result) = self.sequence_builders.pop() {
             let list = KList::with_data(ValueVec::from_vec(result));
+            self.charge_list(&list, list.len())?;
             self.set_register(register, list.into());
             Ok(())
         } else {
@@ -3371,6 +4548,8 @@ impl KotoVm {
 
     fn run_sequence_to_tuple(&mut self, register: u8) -> Result<()> {
         if let Some(result) = self.sequence_builders.pop() {
+            let result = Ptr::from(result);
+            self.charge_tuple(&result)?;
             self.set_register(register, KTuple::from(result).into());
             Ok(())
         } else {
@@ -3488,18 +4667,22 @@ impl KotoVm {
         };
 
         // Add the result to the string builder
+        let Some(len) = self.string_builders.last().map(String::len) else {
+            return runtime_error!(ErrorKind::MissingStringBuilder);
+        };
+        self.check_memory(len.saturating_add(result.len()))?;
         if let Some(builder) = self.string_builders.last_mut() {
             builder.push_str(&result);
-            Ok(())
-        } else {
-            runtime_error!(ErrorKind::MissingStringBuilder)
         }
+        Ok(())
     }
 
     fn run_string_finish(&mut self, register: u8) -> Result<()> {
         // Move the string builder out of its register to avoid cloning the string data
         if let Some(result) = self.string_builders.pop() {
-            self.set_register(register, result.into());
+            let result = Ptr::from(result);
+            self.charge_string(&result)?;
+            self.set_register(register, KString::from(result).into());
             Ok(())
         } else {
             runtime_error!(ErrorKind::MissingStringBuilder)
@@ -3957,6 +5140,8 @@ struct Frame {
     //   - an external function is calling back into the VM with a functor
     //   - a module is being imported
     pub execution_barrier: bool,
+    // The most recently executed line in this frame, used by the debugger to detect line changes
+    pub debug_line: Option<u32>,
 }
 
 impl Frame {
@@ -3971,6 +5156,7 @@ impl Frame {
             return_instruction_ip: 0,
             catch_stack: vec![],
             execution_barrier: false,
+            debug_line: None,
         }
     }
 
@@ -4114,6 +5300,47 @@ impl ExecutionTimeout {
     }
 }
 
+// The optional tools that are run for each executed instruction
+#[derive(Clone, Copy)]
+struct InstructionHooks {
+    debugger: bool,
+    profiler: bool,
+    coverage: bool,
+}
+
+/// A handle that allows a running [KotoVm] to be interrupted from another thread
+///
+/// Calling [interrupt](Self::interrupt) requests that execution should stop, and the VM will
+/// abort at the next instruction boundary with an [Interrupted](ErrorKind::Interrupted) error.
+/// The interrupted error can't be caught within the script.
+///
+/// The request is cleared once the VM has stopped, so the VM can continue to be used afterwards.
+/// If the VM isn't executing when the interrupt is requested, then the next execution will be
+/// interrupted.
+///
+/// The handle is obtained via [KotoVm::interrupt_handle], and is shared by all VMs that are
+/// spawned from the same runtime.
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Requests that the VM stops execution at the next instruction boundary
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if an interrupt has been requested that hasn't been handled yet by the VM
+    pub fn is_interrupt_requested(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // Returns true if an interrupt was requested, clearing the request
+    fn take_request(&self) -> bool {
+        // Checking the flag before swapping avoids a write on every instruction
+        self.is_interrupt_requested() && self.0.swap(false, Ordering::Relaxed)
+    }
+}
+
 /// An output value from [KotoVm::continue_running], either from a `return` or `yield` expression
 #[allow(missing_docs)]
 pub enum ReturnOrYield {
@@ -4121,6 +5348,14 @@ pub enum ReturnOrYield {
     Yield(KValue),
 }
 
+/// An output value from a resumable run, see [KotoVm::run_resumable]
+pub enum ReturnOrAwait {
+    /// Execution is complete, with the run's result
+    Return(KValue),
+    /// Execution has been suspended while awaiting the pending value
+    Await(KPending),
+}
+
 // A collection of macros that avoid duplicated boilerplate in the various operator functions
 mod macros {
     macro_rules! call_metamap_binary_op_rhs {
@@ -0,0 +1,241 @@
+mod access_cache {
+    use koto_test_utils::*;
+
+    // The scripts in these tests repeatedly run the same access instructions,
+    // with the results checked after the accessed maps have been modified.
+
+    #[test]
+    fn modified_value() {
+        let script = "
+m = {x: 1}
+result = []
+for i in 0..3
+  result.push m.x
+  m.x = i * 10
+result.to_tuple()
+";
+        check_script_output(script, number_tuple(&[1, 0, 10]));
+    }
+
+    #[test]
+    fn removed_entry_changes_index() {
+        let script = "
+m = {a: 1, b: 2, c: 3}
+get_c = |m| m.c
+result = [get_c m]
+m.remove 'a'
+result.push get_c m
+m.insert 'a', 4
+result.push get_c m
+result.to_tuple()
+";
+        check_script_output(script, number_tuple(&[3, 3, 3]));
+    }
+
+    #[test]
+    fn maps_with_different_layouts() {
+        let script = "
+get_x = |m| m.x
+(
+  get_x {x: 1, y: 2},
+  get_x {y: 3, x: 4},
+  get_x {x: 5},
+  get_x {x: 6, y: 7},
+)
+";
+        check_script_output(script, number_tuple(&[1, 4, 5, 6]));
+    }
+
+    #[test]
+    fn missing_key_after_cached_lookup() {
+        let script = "
+get_x = |m|
+  try
+    m.x
+  catch error
+    '{error}'
+(get_x({x: 1}), get_x({y: 2}))
+";
+        check_script_output(
+            script,
+            tuple(&[1.into(), "'x' not found in the 'map' module".into()]),
+        );
+    }
+
+    #[test]
+    fn map_module_fallback_after_cached_lookup() {
+        let script = "
+is_empty = |m| m.is_empty()
+(is_empty({is_empty: || 99}), is_empty({a: 1}), is_empty({}))
+";
+        check_script_output(script, tuple(&[99.into(), false.into(), true.into()]));
+    }
+
+    #[test]
+    fn base_map_modified() {
+        let script = "
+base = {greet: || 'hello'}
+a = {@base: base}
+result = [a.greet()]
+base.greet = || 'hi'
+result.push a.greet()
+result.to_tuple()
+";
+        check_script_output(script, tuple(&["hello".into(), "hi".into()]));
+    }
+
+    #[test]
+    fn entry_added_to_instance_shadows_base() {
+        let script = "
+base = {name: 'base'}
+a = {@base: base}
+get_name = |m| m.name
+result = [get_name a]
+a.name = 'a'
+result.push get_name a
+map.remove a, 'name'
+result.push get_name a
+result.to_tuple()
+";
+        check_script_output(script, tuple(&["base".into(), "a".into(), "base".into()]));
+    }
+
+    #[test]
+    fn instances_with_different_bases() {
+        let script = "
+make = |base| {@base: base}
+get_name = |m| m.name
+a = make {name: 'a'}
+b = make {name: 'b'}
+c = make {other: 'c', name: 'c'}
+(get_name(a), get_name(b), get_name(c), get_name(a))
+";
+        check_script_output(
+            script,
+            tuple(&["a".into(), "b".into(), "c".into(), "a".into()]),
+        );
+    }
+
+    #[test]
+    fn nested_bases() {
+        let script = "
+root = {x: 'root'}
+middle = {@base: root}
+leaf = {@base: middle}
+get_x = |m| m.x
+result = [get_x leaf]
+middle.x = 'middle'
+result.push get_x leaf
+root.x = 'modified root'
+result.push get_x leaf
+map.remove middle, 'x'
+result.push get_x leaf
+result.to_tuple()
+";
+        check_script_output(
+            script,
+            tuple(&[
+                "root".into(),
+                "middle".into(),
+                "middle".into(),
+                "modified root".into(),
+            ]),
+        );
+    }
+
+    #[test]
+    fn named_meta_entries() {
+        let script = "
+make = |n|
+  data: n
+  @meta info: 'meta'
+get_info = |m| m.info
+a = make 1
+result = [get_info a]
+a.info = 'data'
+result.push get_info a
+result.push get_info make 2
+result.to_tuple()
+";
+        check_script_output(
+            script,
+            tuple(&["meta".into(), "data".into(), "meta".into()]),
+        );
+    }
+
+    #[test]
+    fn access_override_after_cached_lookup() {
+        let script = "
+get_x = |m| m.x
+a = {x: 1}
+b =
+  x: 2
+  @access: |key| 'overridden {key}'
+(get_x(a), get_x(b), get_x(a))
+";
+        check_script_output(script, tuple(&[1.into(), "overridden x".into(), 1.into()]));
+    }
+
+    #[test]
+    fn string_keys() {
+        let script = "
+m = {'key with spaces': 1}
+result = []
+for i in 0..3
+  result.push m.'key with spaces'
+  m.'key with spaces' += 1
+result.to_tuple()
+";
+        check_script_output(script, number_tuple(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn dynamic_string_keys() {
+        let script = "
+base = {a: 'base a', b: 'base b'}
+m = {b: 'm b', @base: base}
+get = |m, key| m.'{key}'
+(get(m, 'a'), get(m, 'b'), get(m, 'a'), get(base, 'b'))
+";
+        check_script_output(
+            script,
+            tuple(&[
+                "base a".into(),
+                "m b".into(),
+                "base a".into(),
+                "base b".into(),
+            ]),
+        );
+    }
+
+    #[test]
+    fn match_patterns() {
+        let script = "
+check = |m|
+  match m
+    {x} then x
+    else 'no x'
+(check({x: 1}), check({y: 2}), check({y: 3, x: 4}))
+";
+        check_script_output(script, tuple(&[1.into(), "no x".into(), 4.into()]));
+    }
+
+    #[test]
+    fn method_calls() {
+        let script = "
+Counter =
+  increment: || self.count += 1
+make_counter = || {count: 0, @base: Counter}
+a = make_counter()
+b = make_counter()
+for _ in 0..3
+  a.increment()
+b.increment()
+Counter.increment = || self.count += 10
+a.increment()
+b.increment()
+(a.count, b.count)
+";
+        check_script_output(script, number_tuple(&[13, 11]));
+    }
+}
@@ -0,0 +1,192 @@
+mod await_tests {
+    use koto_runtime::{Error, ErrorKind, ReturnOrAwait, prelude::*};
+    use koto_test_utils::compile_test_script;
+
+    // Makes a VM with a `pending` function that returns its argument as a pending value
+    fn make_vm() -> KotoVm {
+        let vm = KotoVm::default();
+        vm.prelude().add_fn("pending", |ctx| match ctx.args() {
+            [data] => Ok(KPending::new(data.clone()).into()),
+            unexpected => unexpected_args("|Any|", unexpected),
+        });
+        vm
+    }
+
+    fn expect_await(result: Result<ReturnOrAwait, Error>) -> KPending {
+        match result {
+            Ok(ReturnOrAwait::Await(pending)) => pending,
+            Ok(ReturnOrAwait::Return(_)) => panic!("Expected await, found return"),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    fn expect_return(vm: &mut KotoVm, result: Result<ReturnOrAwait, Error>, expected: &str) {
+        match result {
+            Ok(ReturnOrAwait::Return(value)) => {
+                assert_eq!(vm.value_to_string(&value).unwrap(), expected)
+            }
+            Ok(ReturnOrAwait::Await(_)) => panic!("Expected return, found await"),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    fn expect_pending_data(pending: &KPending, expected: &str) {
+        match pending.data() {
+            KValue::Str(data) => assert_eq!(data.as_str(), expected),
+            other => panic!("Unexpected pending data: {}", other.type_as_string()),
+        }
+    }
+
+    #[test]
+    fn await_and_resume() {
+        let mut vm = make_vm();
+
+        let result = vm.run_resumable(compile_test_script(
+            "
+x = await pending 'a'
+y = await pending 'b'
+x + y
+",
+        ));
+
+        let pending = expect_await(result);
+        expect_pending_data(&pending, "a");
+        assert!(vm.is_awaiting());
+
+        let pending = expect_await(vm.resume(10));
+        expect_pending_data(&pending, "b");
+
+        let result = vm.resume(32);
+        expect_return(&mut vm, result, "42");
+        assert!(!vm.is_awaiting());
+    }
+
+    #[test]
+    fn await_in_nested_function() {
+        let mut vm = make_vm();
+
+        let result = vm.run_resumable(compile_test_script(
+            "
+fetch = |id|
+  result = await pending id
+  result * 2
+[fetch('a'), fetch('b')]
+",
+        ));
+
+        expect_pending_data(&expect_await(result), "a");
+        expect_pending_data(&expect_await(vm.resume(1)), "b");
+
+        let result = vm.resume(2);
+        expect_return(&mut vm, result, "[2, 4]");
+    }
+
+    #[test]
+    fn awaiting_a_value_that_isnt_pending() {
+        let mut vm = make_vm();
+
+        let result = vm.run_resumable(compile_test_script("await 1 + 2"));
+        expect_return(&mut vm, result, "3");
+    }
+
+    #[test]
+    fn resume_with_caught_error() {
+        let mut vm = make_vm();
+
+        let result = vm.run_resumable(compile_test_script(
+            "
+try
+  await pending 'x'
+catch error
+  'caught: {error}'
+",
+        ));
+
+        expect_await(result);
+        let result = vm.resume_with_error("oops");
+        expect_return(&mut vm, result, "caught: oops");
+    }
+
+    #[test]
+    fn resume_with_uncaught_error() {
+        let mut vm = make_vm();
+
+        expect_await(vm.run_resumable(compile_test_script("await pending 'x'")));
+        assert!(vm.resume_with_error("oops").is_err());
+        assert!(!vm.is_awaiting());
+
+        // The VM can be used again after the error
+        let result = vm.run_resumable(compile_test_script("99"));
+        expect_return(&mut vm, result, "99");
+    }
+
+    #[test]
+    fn resume_when_not_awaiting() {
+        let mut vm = make_vm();
+
+        assert!(matches!(
+            vm.resume(42),
+            Err(Error {
+                error: ErrorKind::NotAwaiting,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn await_pending_value_in_non_resumable_run() {
+        let mut vm = make_vm();
+
+        let result = vm.run(compile_test_script("await pending 'x'"));
+        assert!(matches!(
+            result,
+            Err(Error {
+                error: ErrorKind::UnableToSuspend,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn await_pending_value_in_call_from_native_function() {
+        let mut vm = make_vm();
+
+        let result = vm.run_resumable(compile_test_script(
+            "
+(1, 2)
+  .each |n| await pending n
+  .to_tuple()
+",
+        ));
+        assert!(matches!(
+            result,
+            Err(Error {
+                error: ErrorKind::UnableToSuspend,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn run_while_awaiting() {
+        let mut vm = make_vm();
+
+        expect_await(vm.run_resumable(compile_test_script("(await pending 'x') + 1")));
+
+        // Functions can be called while the VM is suspended
+        let result = vm.run(compile_test_script("1 + 1")).unwrap();
+        assert_eq!(vm.value_to_string(&result).unwrap(), "2");
+
+        // A second resumable run isn't allowed until the first has completed
+        assert!(matches!(
+            vm.run_resumable(compile_test_script("1 + 1")),
+            Err(Error {
+                error: ErrorKind::ResumableRunInProgress,
+                ..
+            })
+        ));
+
+        let result = vm.resume(41);
+        expect_return(&mut vm, result, "42");
+    }
+}
@@ -0,0 +1,106 @@
+mod call_depth {
+    use koto_runtime::{Error, ErrorKind, prelude::*};
+    use koto_test_utils::compile_test_script;
+
+    // Recursion via native functions makes use of the native stack, so the limit is kept low to
+    // avoid overflowing the test thread's stack.
+    const MAX_CALL_DEPTH: usize = 20;
+
+    fn make_vm() -> KotoVm {
+        KotoVm::with_settings(KotoVmSettings {
+            max_call_depth: Some(MAX_CALL_DEPTH),
+            ..Default::default()
+        })
+    }
+
+    fn check_script_output(vm: &mut KotoVm, script: &str, expected: &str) {
+        match vm.run(compile_test_script(script)) {
+            Ok(result) => assert_eq!(vm.value_to_string(&result).unwrap(), expected),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    fn check_stack_overflow(vm: &mut KotoVm, script: &str) -> Error {
+        match vm.run(compile_test_script(script)) {
+            Err(
+                error @ Error {
+                    error: ErrorKind::StackOverflow(limit),
+                    ..
+                },
+            ) => {
+                assert_eq!(limit, MAX_CALL_DEPTH);
+                error
+            }
+            Err(error) => panic!("Unexpected error: {error}"),
+            Ok(_) => panic!("Expected a stack overflow"),
+        }
+    }
+
+    #[test]
+    fn recursion_within_limit() {
+        let script = "
+f = |n| if n == 0 then 0 else 1 + f n - 1
+f 15
+";
+        check_script_output(&mut make_vm(), script, "15");
+    }
+
+    #[test]
+    fn runaway_recursion() {
+        let script = "
+f = |n| 1 + f n + 1
+f 0
+";
+        let error = check_stack_overflow(&mut make_vm(), script);
+
+        // Only the top frames are included in the trace
+        assert_eq!(error.trace.len(), 10);
+        assert!(error.to_string().contains("1 + f n + 1"));
+    }
+
+    #[test]
+    fn caught_stack_overflow() {
+        let script = "
+f = |n| 1 + f n + 1
+try
+  f 0
+catch error
+  'caught: {error}'
+";
+        check_script_output(
+            &mut make_vm(),
+            script,
+            "caught: stack overflow (the call depth limit of 20 was reached)",
+        );
+    }
+
+    #[test]
+    fn tail_calls_dont_increase_depth() {
+        let script = "
+f = |n| if n == 0 then 'done' else f n - 1
+f 10_000
+";
+        check_script_output(&mut make_vm(), script, "done");
+    }
+
+    #[test]
+    fn recursion_via_native_function() {
+        let script = "
+f = |n| (n,).each(|x| f x + 1).to_tuple()
+f 0
+";
+        check_stack_overflow(&mut make_vm(), script);
+    }
+
+    #[test]
+    fn vm_can_be_reused_after_stack_overflow() {
+        let mut vm = make_vm();
+
+        check_stack_overflow(&mut vm, "f = |n| 1 + f n + 1\nf 0");
+        check_script_output(
+            &mut vm,
+            "f = |n| if n == 0 then 0 else 1 + f n - 1\nf 15",
+            "15",
+        );
+    }
+}
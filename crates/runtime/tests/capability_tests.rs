@@ -0,0 +1,99 @@
+mod capabilities {
+    use koto_bytecode::{CompilerSettings, MemoryModuleResolver};
+    use koto_runtime::{Capabilities, Capability, Error, ErrorKind, Result, make_ptr, prelude::*};
+    use koto_test_utils::compile_test_script_with_settings;
+    use test_case::test_case;
+
+    fn run_with_capabilities(script: &str, capabilities: Capabilities) -> Result<String> {
+        let mut resolver = MemoryModuleResolver::default();
+        resolver.add_module("foo.koto", "export x = 42");
+
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            capabilities,
+            module_resolver: make_ptr!(resolver),
+            ..Default::default()
+        });
+
+        let chunk = compile_test_script_with_settings(
+            script,
+            Some("main.koto".into()),
+            CompilerSettings::default(),
+        );
+
+        let result = vm.run(chunk)?;
+        vm.value_to_string(&result)
+    }
+
+    #[test_case("io.create 'foo.txt'", Capability::FileSystemWrite)]
+    #[test_case("io.remove_file 'foo.txt'", Capability::FileSystemWrite)]
+    #[test_case("io.current_dir()", Capability::FileSystemRead)]
+    #[test_case("io.exists 'foo.txt'", Capability::FileSystemRead)]
+    #[test_case("io.open 'foo.txt'", Capability::FileSystemRead)]
+    #[test_case("io.read_to_string 'foo.txt'", Capability::FileSystemRead)]
+    #[test_case("io.temp_dir()", Capability::FileSystemRead)]
+    #[test_case("os.command 'ls'", Capability::ProcessSpawning)]
+    #[test_case("os.env 'HOME'", Capability::Environment)]
+    #[test_case("koto.load '1 + 1'", Capability::DynamicCodeLoading)]
+    #[test_case("koto.run '1 + 1'", Capability::DynamicCodeLoading)]
+    #[test_case("import foo", Capability::Imports)]
+    fn disabled_capability(script: &str, expected: Capability) {
+        match run_with_capabilities(script, Capabilities::none()) {
+            Err(Error {
+                error: ErrorKind::PermissionDenied(capability),
+                ..
+            }) => assert_eq!(capability, expected),
+            Err(error) => panic!("Unexpected error: {error}"),
+            Ok(_) => panic!("Expected a permission error"),
+        }
+    }
+
+    #[test_case("os.env('KOTO_UNDEFINED_VARIABLE') == null", "true")]
+    #[test_case("koto.run '1 + 1'", "2")]
+    #[test_case("import foo\nfoo.x", "42")]
+    fn enabled_by_default(script: &str, expected: &str) {
+        match run_with_capabilities(script, Capabilities::default()) {
+            Ok(result) => assert_eq!(result, expected),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn individual_capabilities_can_be_enabled() {
+        let capabilities = Capabilities {
+            dynamic_code_loading: true,
+            ..Capabilities::none()
+        };
+
+        let result = run_with_capabilities("koto.run '1 + 1'", capabilities);
+        assert_eq!(result.unwrap(), "2");
+
+        let result = run_with_capabilities("import foo", capabilities);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn prelude_imports_are_available_when_imports_are_disabled() {
+        let script = "
+from string import to_uppercase
+import number
+to_uppercase 'abc'
+";
+        let result = run_with_capabilities(script, Capabilities::none());
+        assert_eq!(result.unwrap(), "ABC");
+    }
+
+    #[test]
+    fn permission_errors_can_be_caught() {
+        let script = "
+try
+  io.open 'foo.txt'
+catch error
+  'caught: {error}'
+";
+        let result = run_with_capabilities(script, Capabilities::none());
+        assert_eq!(
+            result.unwrap(),
+            "caught: permission denied (file system read access is disabled)"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+mod coverage {
+    use koto_bytecode::CompilerSettings;
+    use koto_runtime::{CoverageReport, FileCoverage, prelude::*};
+    use koto_test_utils::compile_test_script_with_settings;
+
+    fn run_with_coverage(script: &str) -> (KotoVm, CoverageReport) {
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            coverage: true,
+            ..Default::default()
+        });
+
+        let chunk = compile_test_script_with_settings(
+            script,
+            Some("test.koto".into()),
+            CompilerSettings::default(),
+        );
+
+        if let Err(error) = vm.run(chunk) {
+            panic!("Unexpected error: {error}");
+        }
+
+        let report = vm.coverage_report().expect("missing coverage report");
+        (vm, report)
+    }
+
+    fn file_coverage(report: &CoverageReport) -> &FileCoverage {
+        assert_eq!(report.files.len(), 1);
+        &report.files[0]
+    }
+
+    // Returns the lines and counts as (line, count) pairs, with lines counting from 1
+    fn line_counts(file: &FileCoverage) -> Vec<(u32, u64)> {
+        file.lines
+            .iter()
+            .map(|line| (line.line + 1, line.count))
+            .collect()
+    }
+
+    const SCRIPT: &str = "\
+f = |x|
+  if x > 0
+    'positive'
+  else
+    'negative'
+g = ||
+  'unused'
+f 1
+f 2
+";
+
+    #[test]
+    fn disabled_by_default() {
+        let vm = KotoVm::default();
+        assert!(vm.coverage_report().is_none());
+    }
+
+    #[test]
+    fn lines() {
+        let (_, report) = run_with_coverage(SCRIPT);
+        let file = file_coverage(&report);
+
+        assert_eq!(file.path.as_ref().unwrap().as_str(), "test.koto");
+        assert_eq!(
+            line_counts(file),
+            &[
+                (1, 1),
+                (2, 2),
+                (3, 2),
+                (5, 0),
+                (6, 1),
+                (7, 0),
+                (8, 1),
+                (9, 1)
+            ]
+        );
+        assert_eq!(file.lines_hit(), 6);
+    }
+
+    #[test]
+    fn functions() {
+        let (_, report) = run_with_coverage(SCRIPT);
+        let file = file_coverage(&report);
+
+        let functions: Vec<(&str, u64)> = file
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.count))
+            .collect();
+        assert_eq!(
+            functions,
+            &[("<function:1>", 2), ("<main>", 1), ("<function:6>", 0)]
+        );
+    }
+
+    #[test]
+    fn branches() {
+        let (_, report) = run_with_coverage(SCRIPT);
+        let file = file_coverage(&report);
+
+        assert_eq!(file.branches.len(), 1);
+        let branch = file.branches[0];
+        assert_eq!(branch.line, 1);
+        // The jump to the else block is never taken
+        assert_eq!(branch.taken, 0);
+        assert_eq!(branch.not_taken, 2);
+    }
+
+    #[test]
+    fn loop_branches() {
+        let script = "\
+for i in 0..3
+  i
+";
+        let (_, report) = run_with_coverage(script);
+        let file = file_coverage(&report);
+
+        assert_eq!(file.branches.len(), 1);
+        let branch = file.branches[0];
+        // The loop exits once, after 3 iterations
+        assert_eq!(branch.taken, 1);
+        assert_eq!(branch.not_taken, 3);
+    }
+
+    #[test]
+    fn lcov() {
+        // Note that `if x` doesn't produce any instructions other than the conditional jump,
+        // so the branch is attributed to the function's first line.
+        let script = "\
+f = |x|
+  if x
+    1
+f true
+";
+        let (_, report) = run_with_coverage(script);
+
+        let expected = "\
+TN:
+SF:test.koto
+FN:1,<function:1>
+FN:1,<main>
+FNDA:1,<function:1>
+FNDA:1,<main>
+FNF:2
+FNH:2
+BRDA:1,0,0,0
+BRDA:1,0,1,1
+BRF:2
+BRH:1
+DA:1,1
+DA:3,1
+DA:4,1
+LF:3
+LH:3
+end_of_record
+";
+        assert_eq!(report.to_lcov(), expected);
+    }
+
+    #[test]
+    fn reset() {
+        let (vm, report) = run_with_coverage(SCRIPT);
+        assert!(!report.files.is_empty());
+
+        vm.reset_coverage();
+        assert!(vm.coverage_report().unwrap().files.is_empty());
+    }
+}
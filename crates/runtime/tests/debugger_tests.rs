@@ -0,0 +1,205 @@
+mod debugger {
+    use koto_runtime::{
+        Breakpoint, DebugCommand, DebugContext, KotoDebugger, PauseReason, PtrMut, make_ptr_mut,
+        prelude::*,
+    };
+    use koto_test_utils::compile_test_script;
+
+    // A record of a single pause
+    #[derive(Debug)]
+    struct Pause {
+        reason: PauseReason,
+        // The paused line, counting from 1
+        line: u32,
+        call_depth: usize,
+        locals: Vec<(String, String)>,
+    }
+
+    // A debugger that records each pause, responding with a predefined list of commands
+    struct TestDebugger {
+        pauses: PtrMut<Vec<Pause>>,
+        commands: Vec<DebugCommand>,
+    }
+
+    impl KotoDebugger for TestDebugger {
+        fn paused(&mut self, ctx: &mut DebugContext) -> DebugCommand {
+            let call_stack = ctx.call_stack();
+            let top = &call_stack[0];
+            let line = top
+                .chunk
+                .debug_info
+                .get_source_span(top.instruction)
+                .unwrap()
+                .start
+                .line;
+
+            let locals = ctx
+                .locals(0)
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = ctx.vm().value_to_string(&value).unwrap();
+                    (name.to_string(), value)
+                })
+                .collect();
+
+            let mut pauses = self.pauses.borrow_mut();
+            pauses.push(Pause {
+                reason: ctx.reason(),
+                line: line + 1,
+                call_depth: call_stack.len(),
+                locals,
+            });
+
+            self.commands
+                .get(pauses.len() - 1)
+                .copied()
+                .unwrap_or_default()
+        }
+    }
+
+    fn run_with_debugger(
+        script: &str,
+        breakpoint_lines: &[u32],
+        commands: &[DebugCommand],
+    ) -> Vec<Pause> {
+        let pauses: PtrMut<Vec<Pause>> = make_ptr_mut!(Vec::new());
+
+        let vm = KotoVm::with_settings(KotoVmSettings {
+            debugger: Some(Box::new(TestDebugger {
+                pauses: pauses.clone(),
+                commands: commands.to_vec(),
+            })),
+            ..Default::default()
+        });
+
+        for line in breakpoint_lines {
+            // Breakpoint lines count from 0
+            vm.set_breakpoint(Breakpoint::new(None, line - 1));
+        }
+
+        let chunk = compile_test_script(script);
+
+        let mut vm = vm;
+        if let Err(error) = vm.run(chunk) {
+            panic!("Unexpected error: {error}");
+        }
+
+        std::mem::take(&mut *pauses.borrow_mut())
+    }
+
+    fn paused_lines(pauses: &[Pause]) -> Vec<u32> {
+        pauses.iter().map(|pause| pause.line).collect()
+    }
+
+    const SCRIPT: &str = "
+f = |x|
+  y = x * 2
+  y + 1
+a = f 1
+b = f 2
+a + b
+";
+
+    #[test]
+    fn breakpoints() {
+        let pauses = run_with_debugger(SCRIPT, &[3, 6], &[]);
+
+        assert_eq!(paused_lines(&pauses), [3, 6, 3]);
+        assert!(
+            pauses
+                .iter()
+                .all(|pause| pause.reason == PauseReason::Breakpoint)
+        );
+        assert_eq!(pauses[0].call_depth, 2);
+        assert_eq!(pauses[1].call_depth, 1);
+    }
+
+    #[test]
+    fn breakpoint_in_function_called_by_native_function() {
+        let script = "
+(1, 2, 3)
+  .each |x|
+    x * 2
+  .to_tuple()
+";
+        let pauses = run_with_debugger(script, &[4], &[]);
+
+        assert_eq!(paused_lines(&pauses), [4, 4, 4]);
+    }
+
+    #[test]
+    fn step_over() {
+        use DebugCommand::*;
+        let pauses = run_with_debugger(SCRIPT, &[5], &[StepOver, StepOver, StepOver]);
+
+        assert_eq!(paused_lines(&pauses), [5, 6, 7]);
+        assert!(
+            pauses[1..]
+                .iter()
+                .all(|pause| pause.reason == PauseReason::Step)
+        );
+    }
+
+    #[test]
+    fn step_into() {
+        use DebugCommand::*;
+        let pauses = run_with_debugger(
+            SCRIPT,
+            &[5],
+            &[StepInto, StepInto, StepInto, StepInto, StepInto],
+        );
+
+        // Stepping into the function pauses first on the function's declaration
+        assert_eq!(paused_lines(&pauses), [5, 2, 3, 4, 6, 2]);
+    }
+
+    #[test]
+    fn step_out() {
+        use DebugCommand::*;
+        let pauses = run_with_debugger(SCRIPT, &[3], &[StepOut, Continue]);
+
+        assert_eq!(paused_lines(&pauses), [3, 6, 3]);
+        assert_eq!(pauses[1].call_depth, 1);
+    }
+
+    #[test]
+    fn locals() {
+        let pauses = run_with_debugger(SCRIPT, &[4, 7], &[]);
+
+        let find_local = |pause: &Pause, name: &str| {
+            pause
+                .locals
+                .iter()
+                .find(|(local, _)| local == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        assert_eq!(find_local(&pauses[0], "x").as_deref(), Some("1"));
+        assert_eq!(find_local(&pauses[0], "y").as_deref(), Some("2"));
+        assert_eq!(find_local(&pauses[1], "x").as_deref(), Some("2"));
+        assert_eq!(find_local(&pauses[1], "y").as_deref(), Some("4"));
+        assert_eq!(find_local(&pauses[2], "a").as_deref(), Some("3"));
+        assert_eq!(find_local(&pauses[2], "b").as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn request_pause() {
+        let pauses: PtrMut<Vec<Pause>> = make_ptr_mut!(Vec::new());
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            debugger: Some(Box::new(TestDebugger {
+                pauses: pauses.clone(),
+                commands: Vec::new(),
+            })),
+            ..Default::default()
+        });
+
+        vm.request_debug_pause();
+
+        let chunk = compile_test_script(SCRIPT);
+        vm.run(chunk).unwrap();
+
+        let pauses = pauses.borrow();
+        assert_eq!(pauses.len(), 1);
+        assert_eq!(pauses[0].reason, PauseReason::PauseRequested);
+    }
+}
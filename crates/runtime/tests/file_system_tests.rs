@@ -0,0 +1,265 @@
+mod file_system {
+    use koto_runtime::{KotoFileSystem, Ptr, Result, make_ptr, prelude::*, runtime_error};
+    use koto_test_utils::compile_test_script;
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    };
+
+    type Files = Arc<Mutex<HashMap<PathBuf, String>>>;
+
+    // An in-memory file system, with an optional read-only mode
+    #[derive(Clone, Default)]
+    struct MemoryFileSystem {
+        files: Files,
+        read_only: bool,
+    }
+
+    impl MemoryFileSystem {
+        fn with_file(self, path: &str, contents: &str) -> Self {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.into(), contents.into());
+            self
+        }
+
+        fn read_only(self) -> Self {
+            Self {
+                read_only: true,
+                ..self
+            }
+        }
+    }
+
+    impl KotoFileSystem for MemoryFileSystem {
+        fn open(&self, path: &Path) -> Result<Ptr<dyn KotoFile>> {
+            if !self.exists(path) {
+                return runtime_error!("file not found");
+            }
+            Ok(make_ptr!(MemoryFile {
+                path: path.to_path_buf(),
+                files: self.files.clone(),
+                position: Mutex::new(0),
+            }))
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.files.lock().unwrap().contains_key(path)
+        }
+
+        fn create(&self, path: &Path) -> Result<Ptr<dyn KotoFile>> {
+            if self.read_only {
+                return runtime_error!("read-only file system");
+            }
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), String::new());
+            self.open(path)
+        }
+
+        fn remove_file(&self, path: &Path) -> Result<()> {
+            if self.read_only {
+                return runtime_error!("read-only file system");
+            }
+            match self.files.lock().unwrap().remove(path) {
+                Some(_) => Ok(()),
+                None => runtime_error!("file not found"),
+            }
+        }
+
+        fn current_dir(&self) -> Result<PathBuf> {
+            Ok("/memory".into())
+        }
+    }
+
+    struct MemoryFile {
+        path: PathBuf,
+        files: Files,
+        position: Mutex<usize>,
+    }
+
+    impl MemoryFile {
+        fn remaining(&self) -> String {
+            let files = self.files.lock().unwrap();
+            let contents = files
+                .get(&self.path)
+                .map_or("", |contents| contents.as_str());
+            let position = *self.position.lock().unwrap();
+            contents.get(position..).unwrap_or_default().to_string()
+        }
+    }
+
+    impl KotoFile for MemoryFile {
+        fn id(&self) -> KString {
+            self.path.to_string_lossy().to_string().into()
+        }
+
+        fn path(&self) -> Result<KString> {
+            Ok(self.id())
+        }
+    }
+
+    impl KotoRead for MemoryFile {
+        fn read_line(&self) -> Result<Option<String>> {
+            let remaining = self.remaining();
+            if remaining.is_empty() {
+                return Ok(None);
+            }
+            let line = match remaining.find('\n') {
+                Some(end) => &remaining[..=end],
+                None => &remaining,
+            };
+            *self.position.lock().unwrap() += line.len();
+            Ok(Some(line.to_string()))
+        }
+
+        fn read_to_string(&self) -> Result<String> {
+            let remaining = self.remaining();
+            *self.position.lock().unwrap() += remaining.len();
+            Ok(remaining)
+        }
+    }
+
+    impl KotoWrite for MemoryFile {
+        fn write(&self, bytes: &[u8]) -> Result<()> {
+            let mut files = self.files.lock().unwrap();
+            let contents = files.entry(self.path.clone()).or_default();
+            contents.push_str(&String::from_utf8_lossy(bytes));
+            Ok(())
+        }
+
+        fn write_line(&self, text: &str) -> Result<()> {
+            self.write(format!("{text}\n").as_bytes())
+        }
+
+        fn flush(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run_script(script: &str, file_system: MemoryFileSystem) -> Result<String> {
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            file_system: make_ptr!(file_system),
+            ..Default::default()
+        });
+
+        let chunk = compile_test_script(script);
+
+        let result = vm.run(chunk)?;
+        vm.value_to_string(&result)
+    }
+
+    fn check_script_output(script: &str, file_system: MemoryFileSystem, expected: &str) {
+        match run_script(script, file_system) {
+            Ok(result) => assert_eq!(result, expected),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn read_to_string() {
+        let file_system = MemoryFileSystem::default().with_file("foo.txt", "hello");
+        check_script_output("io.read_to_string 'foo.txt'", file_system, "hello");
+    }
+
+    #[test]
+    fn exists() {
+        let file_system = MemoryFileSystem::default().with_file("foo.txt", "");
+        check_script_output(
+            "(io.exists 'foo.txt'), (io.exists 'bar.txt')",
+            file_system,
+            "(true, false)",
+        );
+    }
+
+    #[test]
+    fn open_and_read_lines() {
+        let script = "
+f = io.open 'foo.txt'
+(f.path(), f.read_line(), f.read_line(), f.read_line())
+";
+        let file_system = MemoryFileSystem::default().with_file("foo.txt", "abc\nxyz\n");
+        check_script_output(script, file_system, "('foo.txt', 'abc', 'xyz', null)");
+    }
+
+    #[test]
+    fn create_and_write() {
+        let script = "
+f = io.create 'foo.txt'
+f.write_line 'hello'
+f.write 'world'
+io.read_to_string 'foo.txt'
+";
+        let file_system = MemoryFileSystem::default();
+        check_script_output(script, file_system.clone(), "hello\nworld");
+        assert_eq!(
+            file_system.files.lock().unwrap()[Path::new("foo.txt")],
+            "hello\nworld"
+        );
+    }
+
+    #[test]
+    fn remove_file() {
+        let script = "
+io.remove_file 'foo.txt'
+io.exists 'foo.txt'
+";
+        let file_system = MemoryFileSystem::default().with_file("foo.txt", "");
+        check_script_output(script, file_system.clone(), "false");
+        assert!(file_system.files.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn current_dir() {
+        check_script_output("io.current_dir()", MemoryFileSystem::default(), "/memory");
+    }
+
+    #[test]
+    fn missing_file() {
+        let script = "
+try
+  io.open 'foo.txt'
+catch error
+  '{error}'
+";
+        check_script_output(
+            script,
+            MemoryFileSystem::default(),
+            "error while opening path: file not found",
+        );
+    }
+
+    #[test]
+    fn read_only_file_system() {
+        let script = "
+try
+  io.create 'bar.txt'
+catch error
+  '{error}'
+";
+        let file_system = MemoryFileSystem::default().read_only();
+        check_script_output(
+            script,
+            file_system,
+            "error while creating file: read-only file system",
+        );
+    }
+
+    #[test]
+    fn unsupported_operation() {
+        let script = "
+try
+  io.temp_dir()
+catch error
+  '{error}'
+";
+        check_script_output(
+            script,
+            MemoryFileSystem::default(),
+            "io.temp_dir: unsupported for this file system",
+        );
+    }
+}
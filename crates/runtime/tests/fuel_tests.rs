@@ -0,0 +1,161 @@
+mod fuel {
+    use koto_runtime::{Error, ErrorKind, prelude::*};
+    use koto_test_utils::compile_test_script;
+
+    fn vm_with_fuel(fuel: u64) -> KotoVm {
+        KotoVm::with_settings(KotoVmSettings {
+            fuel: Some(fuel),
+            ..Default::default()
+        })
+    }
+
+    fn is_out_of_fuel(result: &Result<KValue, Error>) -> bool {
+        matches!(
+            result,
+            Err(Error {
+                error: ErrorKind::OutOfFuel,
+                ..
+            })
+        )
+    }
+
+    #[test]
+    fn fuel_disabled_by_default() {
+        let mut vm = KotoVm::default();
+        assert_eq!(vm.remaining_fuel(), None);
+
+        vm.add_fuel(100);
+        assert_eq!(vm.remaining_fuel(), None);
+
+        assert!(vm.run(compile_test_script("1 + 1")).is_ok());
+    }
+
+    #[test]
+    fn sufficient_fuel() {
+        let mut vm = vm_with_fuel(1_000);
+
+        let result = vm.run(compile_test_script(
+            "
+n = 0
+while n < 10
+  n += 1
+n
+",
+        ));
+
+        match result {
+            Ok(KValue::Number(n)) => assert_eq!(n, 10),
+            Ok(other) => panic!("Unexpected result: {}", other.type_as_string()),
+            Err(e) => panic!("Unexpected error: {e}"),
+        }
+
+        let remaining = vm.remaining_fuel().unwrap();
+        assert!(remaining > 0 && remaining < 1_000);
+    }
+
+    #[test]
+    fn infinite_loop() {
+        let mut vm = vm_with_fuel(1_000);
+
+        let result = vm.run(compile_test_script(
+            "
+while true
+  ()
+",
+        ));
+
+        assert!(is_out_of_fuel(&result), "Script didn't run out of fuel");
+        assert_eq!(vm.remaining_fuel(), Some(0));
+    }
+
+    #[test]
+    fn out_of_fuel_is_deterministic() {
+        let script = compile_test_script(
+            "
+export state = {n: 0}
+while true
+  state.n += 1
+",
+        );
+
+        let run_until_empty = || {
+            let mut vm = vm_with_fuel(10_000);
+            assert!(is_out_of_fuel(&vm.run(script.clone())));
+            match vm.exports().get("state") {
+                Some(KValue::Map(state)) => state.get("n").unwrap(),
+                _ => panic!("Expected a map"),
+            }
+        };
+
+        match (run_until_empty(), run_until_empty()) {
+            (KValue::Number(a), KValue::Number(b)) => {
+                assert!(a > 0);
+                assert_eq!(a, b);
+            }
+            _ => panic!("Expected numbers"),
+        }
+    }
+
+    #[test]
+    fn out_of_fuel_cant_be_caught() {
+        let mut vm = vm_with_fuel(1_000);
+
+        let result = vm.run(compile_test_script(
+            "
+try
+  while true
+    ()
+catch _
+  'caught'
+",
+        ));
+
+        assert!(is_out_of_fuel(&result), "Script didn't run out of fuel");
+    }
+
+    #[test]
+    fn top_up_fuel_between_generator_calls() {
+        let mut vm = vm_with_fuel(1_000);
+
+        let result = vm.run(compile_test_script(
+            "
+gen = ||
+  n = 0
+  loop
+    # Burn some fuel
+    for _ in 0..10
+      ()
+    yield n
+    n += 1
+
+export iterator = gen()
+",
+        ));
+        assert!(result.is_ok());
+
+        let mut iterator = match vm.exports().get("iterator") {
+            Some(KValue::Iterator(i)) => i,
+            _ => panic!("Expected an iterator"),
+        };
+
+        let mut yielded = 0;
+        loop {
+            match iterator.next() {
+                Some(KIteratorOutput::Value(_)) => yielded += 1,
+                Some(KIteratorOutput::Error(error)) => {
+                    assert!(matches!(error.error, ErrorKind::OutOfFuel));
+                    break;
+                }
+                _ => panic!("Unexpected iterator output"),
+            }
+            assert!(yielded < 1_000, "Generator didn't run out of fuel");
+        }
+        assert!(yielded > 0);
+
+        vm.add_fuel(1_000);
+        assert_eq!(vm.remaining_fuel(), Some(1_000));
+
+        let result = vm.run(compile_test_script("1 + 1"));
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,98 @@
+mod interrupt {
+    use koto_runtime::{Error, ErrorKind, prelude::*};
+    use koto_test_utils::compile_test_script;
+    use std::{thread, time::Duration};
+
+    fn is_interrupted(result: &Result<KValue, Error>) -> bool {
+        matches!(
+            result,
+            Err(Error {
+                error: ErrorKind::Interrupted,
+                ..
+            })
+        )
+    }
+
+    #[test]
+    fn interrupt_from_another_thread() {
+        let mut vm = KotoVm::default();
+        let handle = vm.interrupt_handle();
+
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            handle.interrupt();
+        });
+
+        let result = vm.run(compile_test_script(
+            "
+while true
+  ()
+",
+        ));
+
+        interrupter.join().unwrap();
+        assert!(is_interrupted(&result), "Script wasn't interrupted");
+    }
+
+    #[test]
+    fn interrupt_cant_be_caught() {
+        let mut vm = KotoVm::default();
+        vm.interrupt_handle().interrupt();
+
+        let result = vm.run(compile_test_script(
+            "
+try
+  while true
+    ()
+catch _
+  'caught'
+",
+        ));
+
+        assert!(is_interrupted(&result), "Script wasn't interrupted");
+    }
+
+    #[test]
+    fn interrupt_in_nested_call_cant_be_caught() {
+        let mut vm = KotoVm::default();
+        let handle = vm.interrupt_handle();
+
+        vm.prelude().add_fn("interrupt", move |_| {
+            handle.interrupt();
+            Ok(KValue::Null)
+        });
+
+        let result = vm.run(compile_test_script(
+            "
+try
+  # The interrupt is triggered during the nested execution of the iterator adaptor
+  (1, 2, 3)
+    .each |x|
+      interrupt()
+      x
+    .to_tuple()
+catch _
+  'caught'
+",
+        ));
+
+        assert!(is_interrupted(&result), "Script wasn't interrupted");
+    }
+
+    #[test]
+    fn interrupt_request_is_cleared_after_stopping() {
+        let mut vm = KotoVm::default();
+        let handle = vm.interrupt_handle();
+
+        handle.interrupt();
+        assert!(handle.is_interrupt_requested());
+        assert!(is_interrupted(&vm.run(compile_test_script("1 + 1"))));
+        assert!(!handle.is_interrupt_requested());
+
+        match vm.run(compile_test_script("1 + 1")) {
+            Ok(KValue::Number(n)) => assert_eq!(n, 2),
+            Ok(other) => panic!("Unexpected result: {}", other.type_as_string()),
+            Err(e) => panic!("Unexpected error: {e}"),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+mod memory_limit {
+    use koto_runtime::{Error, ErrorKind, prelude::*};
+    use koto_test_utils::compile_test_script;
+
+    const MEMORY_LIMIT: usize = 4096;
+
+    fn run_script(script: &str) -> Result<KValue, Error> {
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            memory_limit: Some(MEMORY_LIMIT),
+            ..Default::default()
+        });
+
+        let chunk = compile_test_script(script);
+
+        vm.run(chunk)
+    }
+
+    fn check_script_succeeds(script: &str) {
+        if let Err(e) = run_script(script) {
+            panic!("Unexpected error: {e}");
+        }
+    }
+
+    fn check_script_exceeds_limit(script: &str) {
+        match run_script(script) {
+            Err(Error {
+                error: ErrorKind::MemoryLimitExceeded(limit),
+                ..
+            }) => assert_eq!(limit, MEMORY_LIMIT),
+            Err(e) => panic!("Unexpected error: {e}"),
+            Ok(_) => panic!("Script didn't exceed the memory limit"),
+        }
+    }
+
+    #[test]
+    fn small_values() {
+        check_script_succeeds(
+            "
+x = [1, 2, 3]
+y = (1..10).to_list()
+z = 'hello'.repeat 10
+m = {a: 1, b: 2}
+m.insert 'c', 3
+",
+        );
+    }
+
+    fn make_sequence_elements(count: usize) -> String {
+        (0..count)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn list_literal() {
+        check_script_exceeds_limit(&format!("[{}]", make_sequence_elements(1000)));
+    }
+
+    #[test]
+    fn tuple_literal() {
+        check_script_exceeds_limit(&format!("({})", make_sequence_elements(1000)));
+    }
+
+    #[test]
+    fn string_interpolation() {
+        check_script_exceeds_limit(
+            "
+x = 'x'
+loop
+  x = '{x}{x}'
+",
+        );
+    }
+
+    #[test]
+    fn string_add() {
+        check_script_exceeds_limit(
+            "
+x = 'x'
+loop
+  x = x + x
+",
+        );
+    }
+
+    #[test]
+    fn list_add() {
+        check_script_exceeds_limit(
+            "
+x = [1]
+loop
+  x = x + x
+",
+        );
+    }
+
+    #[test]
+    fn tuple_add() {
+        check_script_exceeds_limit(
+            "
+x = (1,)
+loop
+  x = x + x
+",
+        );
+    }
+
+    #[test]
+    fn map_add() {
+        check_script_exceeds_limit(
+            "
+m = {}
+for i in 0..1000000
+  m = m + {'key_{i}': i}
+",
+        );
+    }
+
+    #[test]
+    fn list_resize() {
+        check_script_exceeds_limit("[].resize 1000000");
+    }
+
+    #[test]
+    fn list_push() {
+        check_script_exceeds_limit(
+            "
+x = []
+loop
+  x.push 42
+",
+        );
+    }
+
+    #[test]
+    fn string_repeat() {
+        check_script_exceeds_limit("'abc'.repeat 1000000");
+    }
+
+    #[test]
+    fn iterator_to_list() {
+        check_script_exceeds_limit("(0..1000000).to_list()");
+    }
+
+    #[test]
+    fn map_insert() {
+        check_script_exceeds_limit(
+            "
+m = {}
+for i in 0..1000000
+  m.insert i, i
+",
+        );
+    }
+
+    #[test]
+    fn map_extend() {
+        check_script_exceeds_limit(
+            "
+m = {}
+m.extend (0..1000000).each |i| i, i
+",
+        );
+    }
+
+    #[test]
+    fn map_access_assign() {
+        check_script_exceeds_limit(
+            "
+m = {}
+for i in 0..1000000
+  m.'key_{i}' = i
+",
+        );
+    }
+
+    #[test]
+    fn many_small_values() {
+        check_script_exceeds_limit(
+            "
+x = []
+loop
+  x.push [1, 2, 3, 4, 5, 6, 7, 8]
+",
+        );
+    }
+
+    #[test]
+    fn many_small_strings() {
+        check_script_exceeds_limit(
+            "
+x = []
+loop
+  x.push 'abc'.repeat 20
+",
+        );
+    }
+
+    #[test]
+    fn dropped_values_are_refunded() {
+        check_script_succeeds(
+            "
+for _ in 0..1000
+  x = (0..50).to_list()
+  y = 'abc'.repeat 100
+",
+        );
+    }
+
+    #[test]
+    fn cleared_list_is_refunded() {
+        check_script_succeeds(
+            "
+x = []
+x.resize 100, 0
+x.clear()
+y = []
+y.resize 100, 0
+",
+        );
+    }
+
+    #[test]
+    fn shrunk_list_is_refunded() {
+        check_script_succeeds(
+            "
+x = []
+x.resize 100, 0
+x.resize 10
+y = []
+y.resize 100, 0
+",
+        );
+    }
+
+    #[test]
+    fn error_can_be_caught() {
+        let result = run_script(
+            "
+try
+  [].resize 1000000
+catch error
+  'caught'
+",
+        );
+
+        match result {
+            Ok(KValue::Str(s)) => assert_eq!(s, "caught"),
+            Ok(other) => panic!("Unexpected result: {}", other.type_as_string()),
+            Err(e) => panic!("Unexpected error: {e}"),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+mod module_resolver {
+    use koto_bytecode::{
+        CompilerSettings, MemoryModuleResolver, ModuleLoader, ModuleLoaderError,
+        ModuleLoaderErrorKind, ModuleResolver,
+    };
+    use koto_runtime::{Ptr, Result, make_ptr, prelude::*};
+    use koto_test_utils::compile_test_script_with_settings;
+    use std::path::{Path, PathBuf};
+
+    fn run_with_resolver(script: &str, resolver: Ptr<dyn ModuleResolver>) -> Result<String> {
+        run_with_settings(
+            script,
+            KotoVmSettings {
+                module_resolver: resolver,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn run_with_settings(script: &str, settings: KotoVmSettings) -> Result<String> {
+        let mut vm = KotoVm::with_settings(settings);
+
+        let chunk = compile_test_script_with_settings(
+            script,
+            Some("main.koto".into()),
+            CompilerSettings::default(),
+        );
+
+        let result = vm.run(chunk)?;
+        vm.value_to_string(&result)
+    }
+
+    fn check_result(result: Result<String>, expected: &str) {
+        match result {
+            Ok(result) => assert_eq!(result, expected),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn memory_resolver() {
+        let mut resolver = MemoryModuleResolver::default();
+        resolver.add_module("foo.koto", "export x = 42");
+        resolver.add_module(
+            "bar/main.koto",
+            "
+import baz
+export y = baz.z * 2
+",
+        );
+        resolver.add_module("bar/baz.koto", "export z = 10");
+
+        let script = "
+import foo, bar
+foo.x + bar.y
+";
+        check_result(run_with_resolver(script, make_ptr!(resolver)), "62");
+    }
+
+    #[test]
+    fn memory_resolver_with_missing_module() {
+        let script = "import foo";
+        let result = run_with_resolver(script, make_ptr!(MemoryModuleResolver::default()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn memory_resolver_with_search_paths() {
+        let mut resolver = MemoryModuleResolver::default();
+        resolver.add_module("foo.koto", "export x = 1");
+        resolver.add_module("lib_a/foo.koto", "export x = 2");
+        resolver.add_module("lib_a/bar/main.koto", "export y = 10");
+        resolver.add_module("lib_b/bar.koto", "export y = 20");
+        resolver.add_module("lib_b/baz.koto", "export z = 100");
+
+        // Modules relative to the script take priority, then the search paths are checked in order
+        let script = "
+import foo, bar, baz
+foo.x + bar.y + baz.z
+";
+        let result = run_with_settings(
+            script,
+            KotoVmSettings {
+                module_resolver: make_ptr!(resolver),
+                module_search_paths: vec!["lib_a".into(), "lib_b".into()],
+                ..Default::default()
+            },
+        );
+        check_result(result, "111");
+    }
+
+    #[test]
+    fn missing_module_error_lists_searched_paths() {
+        let mut loader = ModuleLoader::default();
+        loader.set_module_resolver(make_ptr!(MemoryModuleResolver::default()));
+        loader.set_search_paths(vec!["lib".into()]);
+
+        let error = match loader.compile_module("foo", Some(Path::new("main.koto"))) {
+            Ok(_) => panic!("Expected an error"),
+            Err(error) => error,
+        };
+
+        let expected: Vec<PathBuf> = vec![
+            Path::new("foo.koto").into(),
+            Path::new("foo").join("main.koto"),
+            Path::new("lib").join("foo.koto"),
+            Path::new("lib").join("foo").join("main.koto"),
+        ];
+
+        match &*error.error {
+            ModuleLoaderErrorKind::UnableToFindModule { name, searched } => {
+                assert_eq!(name, "foo");
+                assert_eq!(searched, &expected);
+            }
+            other => panic!("Unexpected error: {other}"),
+        }
+
+        let tried = expected
+            .iter()
+            .map(|path| format!("'{}'", path.display()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        assert_eq!(
+            error.error.to_string(),
+            format!("unable to find module 'foo' (tried {tried})")
+        );
+    }
+
+    // A resolver that generates a module for any import name
+    struct GeneratedModules;
+
+    impl ModuleResolver for GeneratedModules {
+        fn resolve(
+            &self,
+            module_name: &str,
+            _current_script_path: Option<&Path>,
+            _search_paths: &[PathBuf],
+        ) -> std::result::Result<PathBuf, ModuleLoaderError> {
+            if module_name.starts_with("generated_") {
+                Ok(PathBuf::from(module_name))
+            } else {
+                Err(ModuleLoaderErrorKind::UnableToFindModule {
+                    name: module_name.into(),
+                    searched: Vec::new(),
+                }
+                .into())
+            }
+        }
+
+        fn load(&self, module_path: &Path) -> std::result::Result<String, ModuleLoaderError> {
+            Ok(format!("export name = '{}'", module_path.display()))
+        }
+    }
+
+    #[test]
+    fn custom_resolver() {
+        let script = "
+from generated_foo import name
+name
+";
+        check_result(
+            run_with_resolver(script, make_ptr!(GeneratedModules)),
+            "generated_foo",
+        );
+    }
+}
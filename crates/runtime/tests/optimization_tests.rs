@@ -0,0 +1,187 @@
+mod optimizations {
+    use koto_bytecode::{Chunk, CompilerSettings};
+    use koto_runtime::{Ptr, prelude::*};
+    use koto_test_utils::compile_test_script_with_settings;
+    use test_case::test_case;
+
+    fn compile(script: &str, enable_optimizations: bool) -> Ptr<Chunk> {
+        let settings = CompilerSettings {
+            enable_optimizations,
+            ..Default::default()
+        };
+        compile_test_script_with_settings(script, None, settings)
+    }
+
+    fn run(chunk: Ptr<Chunk>) -> String {
+        let mut vm = KotoVm::default();
+        match vm.run(chunk) {
+            Ok(result) => vm.value_to_string(&result).unwrap(),
+            Err(error) => panic!("Error while running script: {error}"),
+        }
+    }
+
+    // Checks that the optimized script produces the same result as the unoptimized script,
+    // and that the optimized bytecode is smaller.
+    #[test_case("1 + 2 * 3", "7")]
+    #[test_case("(10 - 4) / 4", "1.5")]
+    #[test_case("-(2 ^ 3)", "-8")]
+    #[test_case("2 ^ -1", "0.5")]
+    #[test_case("7 % 0", "NaN")]
+    #[test_case("7 % 0.0", "NaN")]
+    #[test_case("1 / 0", "inf")]
+    #[test_case("9223372036854775807 + 1", "-9223372036854775808")]
+    #[test_case("1 < 2.5", "true")]
+    #[test_case("(0 / 0) > 1", "true")]
+    #[test_case("1 == 1.0", "true")]
+    #[test_case("'a' == 1", "false")]
+    #[test_case("'abc' < 'abd'", "true")]
+    #[test_case("'a' + 'b' + 'c'", "abc")]
+    #[test_case("not null", "true")]
+    #[test_case("0 and 'x'", "x")]
+    #[test_case("false or null", "null")]
+    #[test_case("if 1 > 2 then 'a' else 'b'", "b")]
+    #[test_case("if false\n  'a'\nelse if true\n  'b'\nelse\n  'c'", "b")]
+    #[test_case("if null then 'a'", "null")]
+    #[test_case(
+        "x = 3\nswitch\n  false then 1\n  x > 2 then 2\n  true then 3\n  else 4",
+        "2"
+    )]
+    #[test_case("switch\n  1 > 2 then 'a'\n  else 'b'", "b")]
+    fn folded_result(script: &str, expected: &str) {
+        let optimized = compile(script, true);
+        let unoptimized = compile(script, false);
+
+        assert!(
+            optimized.bytes.len() < unoptimized.bytes.len(),
+            "Expected the optimized bytecode to be smaller ({} >= {})",
+            optimized.bytes.len(),
+            unoptimized.bytes.len()
+        );
+
+        assert_eq!(run(optimized), expected);
+        assert_eq!(run(unoptimized), expected);
+    }
+
+    // Checks that the specialized instruction is emitted when optimizations are enabled, and that
+    // the script produces the same result with and without optimizations.
+    #[test_case("x = 1.5\nif x < 2 then 'a' else 'b'", "JumpIfNotLt", "a")]
+    #[test_case("x = 0 / 0\nif x <= 2 then 'a' else 'b'", "JumpIfNotLe", "b")]
+    #[test_case("x = 'abc'\nif x > 'abb' then 'a' else 'b'", "JumpIfNotGt", "a")]
+    #[test_case("x = 3\nif x >= 4 then 'a' else 'b'", "JumpIfNotGe", "b")]
+    #[test_case("x = 2.0\nif x == 2 then 'a' else 'b'", "JumpIfNotEq", "a")]
+    #[test_case("x = null\nif x != null then 'a' else 'b'", "JumpIfEq", "b")]
+    #[test_case("x = [1, 2]\nif x == [1, 2] then 'a' else 'b'", "JumpIfNotEq", "a")]
+    #[test_case(
+        "foo = {@<: |other| self.x < other.x, x: 1}\nif foo < {x: 2} then 'a' else 'b'",
+        "JumpIfNotLt",
+        "a"
+    )]
+    #[test_case(
+        "foo = {@==: |_| null}\nif foo == 1 then 'a' else 'b'",
+        "JumpIfNotEq",
+        "b"
+    )]
+    #[test_case("x = 0\nwhile x < 10\n  x += 3\nx", "JumpIfNotLt", "12")]
+    #[test_case(
+        "x = 5\nswitch\n  x < 3 then 'a'\n  x > 3 then 'b'",
+        "JumpIfNotGt",
+        "b"
+    )]
+    #[test_case("match 42\n  x if x > 40 then x\n  else 0", "JumpIfNotGt", "42")]
+    #[test_case("x = 1\ny = 2\nif x < y < 3 then 'a' else 'b'", "JumpIfFalse", "a")]
+    #[test_case("x = 41\nx + 1", "AddU8", "42")]
+    #[test_case("x = 2.5\nx - 1", "SubtractU8", "1.5")]
+    #[test_case("const N = 255\nx = -255\nx + N", "AddU8", "0")]
+    #[test_case("x = -9223372036854775807\nx - 2", "SubtractU8", "9223372036854775807")]
+    #[test_case("foo = {@+: |n| 'foo + {n}'}\nfoo + 1", "AddU8", "foo + 1")]
+    #[test_case("x = 0\nfor i in 0..5\n  x += i\nx", "IterNextRange", "10")]
+    #[test_case(
+        "x = []\nfor i in 3..=5\n  x.push i\nx",
+        "inclusive: true",
+        "[3, 4, 5]"
+    )]
+    #[test_case("x = 0\nfor _ in 0..3\n  x += 1\nx", "IterNextRange", "3")]
+    #[test_case("x = 0\nfor i in 5..0\n  x += 1\nx", "IterNextRange", "0")]
+    #[test_case("x = []\nfor i in 0..2.5\n  x.push i\nx", "IterNextRange", "[0, 1]")]
+    #[test_case(
+        "x = []\nfor i: Number in 1..=1\n  x.push i\nx",
+        "IterNextRange",
+        "[1]"
+    )]
+    #[test_case(
+        "start, end = 0, 3\nfor i in start..end\n  i = 99\nstart, end",
+        "IterNextRange",
+        "(0, 3)"
+    )]
+    #[test_case(
+        "x = []\nfor i in 9223372036854775806..=9223372036854775807\n  x.push i\nx",
+        "IterNextRange",
+        "[9223372036854775806, 9223372036854775807]"
+    )]
+    #[test_case(
+        "gen = ||\n  for i in 0..3\n    yield i\ngen().to_tuple()",
+        "IterNextRange",
+        "(0, 1, 2)"
+    )]
+    #[test_case("f = |n| if n > 0 then f n - 1 else 'done'\nf 10", "TailCall ", "done")]
+    #[test_case(
+        "x = {f: |n| if n > 0 then self.f n - 1 else 'done'}\nx.f 10",
+        "TailCallInstance",
+        "done"
+    )]
+    fn specialized_instruction(script: &str, expected_instruction: &str, expected: &str) {
+        let optimized = compile(script, true);
+        let unoptimized = compile(script, false);
+
+        let instructions = Chunk::instructions_as_string(optimized.clone(), &[]);
+        assert!(
+            instructions.contains(expected_instruction),
+            "Expected '{expected_instruction}' in instructions:\n{instructions}"
+        );
+
+        assert_eq!(run(optimized), expected);
+        assert_eq!(run(unoptimized), expected);
+    }
+
+    // Errors thrown by specialized instructions match the generic instructions' errors
+    #[test_case("x = null\nif x < 1 then 'a'")]
+    #[test_case("x = 'a'\nx + 1")]
+    #[test_case("x = 'a'\nfor i in x..10\n  i")]
+    #[test_case("x = 'a'\nfor i in 0..x\n  i")]
+    fn specialized_instruction_error(script: &str) {
+        let error_message = |enable_optimizations| match KotoVm::default()
+            .run(compile(script, enable_optimizations))
+        {
+            Ok(_) => panic!("Expected an error"),
+            Err(error) => error.to_string(),
+        };
+
+        assert_eq!(error_message(true), error_message(false));
+    }
+
+    // Errors caught from specialized instructions mustn't leave behind temporary registers
+    #[test]
+    fn caught_errors_in_loop() {
+        let script = "
+x = 0
+for i in 0..1000
+  try
+    if i < null then x += 1
+  catch _
+    x += 1
+x
+";
+        assert_eq!(run(compile(script, true)), "1000");
+    }
+
+    // Operations that would throw at runtime aren't folded
+    #[test_case("1 + 'a'")]
+    #[test_case("null < 1")]
+    fn unfoldable_errors(script: &str) {
+        let optimized = compile(script, true);
+        let unoptimized = compile(script, false);
+
+        assert_eq!(optimized.bytes, unoptimized.bytes);
+        assert!(KotoVm::default().run(optimized).is_err());
+    }
+}
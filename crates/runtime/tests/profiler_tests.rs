@@ -0,0 +1,178 @@
+mod profiler {
+    use koto_runtime::{ProfileReport, prelude::*};
+    use koto_test_utils::compile_test_script;
+
+    fn run_with_profiler(script: &str) -> (KotoVm, ProfileReport) {
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            profiling: true,
+            ..Default::default()
+        });
+
+        let chunk = compile_test_script(script);
+
+        if let Err(error) = vm.run(chunk) {
+            panic!("Unexpected error: {error}");
+        }
+
+        let report = vm.profile_report().expect("missing profile report");
+        (vm, report)
+    }
+
+    fn function_names(report: &ProfileReport) -> Vec<&str> {
+        let mut result: Vec<&str> = report
+            .functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+        result.sort();
+        result
+    }
+
+    fn stack_names(report: &ProfileReport) -> Vec<String> {
+        let mut result: Vec<String> = report
+            .stacks
+            .iter()
+            .map(|stack| stack.functions.join(";"))
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let vm = KotoVm::default();
+        assert!(vm.profile_report().is_none());
+    }
+
+    #[test]
+    fn function_calls() {
+        let script = "
+f = |x|
+  x * 2
+g = |x|
+  f(x) + f(x)
+g 1
+g 2
+";
+        let (_, report) = run_with_profiler(script);
+
+        assert_eq!(
+            function_names(&report),
+            &["<script>", "<script>:2", "<script>:4"]
+        );
+        assert_eq!(
+            stack_names(&report),
+            &[
+                "<script>",
+                "<script>;<script>:4",
+                "<script>;<script>:4;<script>:2"
+            ]
+        );
+
+        let f = report
+            .functions
+            .iter()
+            .find(|function| function.name == "<script>:2")
+            .unwrap();
+        assert_eq!(f.line, 1);
+
+        let g = report
+            .functions
+            .iter()
+            .find(|function| function.name == "<script>:4")
+            .unwrap();
+        // g calls f, so f's instructions are included in g's total
+        assert_eq!(
+            g.total_stats.instructions,
+            g.self_stats.instructions + f.total_stats.instructions
+        );
+
+        let main = report
+            .functions
+            .iter()
+            .find(|function| function.name == "<script>")
+            .unwrap();
+        let total_instructions: u64 = report
+            .stacks
+            .iter()
+            .map(|stack| stack.stats.instructions)
+            .sum();
+        assert_eq!(main.total_stats.instructions, total_instructions);
+    }
+
+    #[test]
+    fn recursive_functions_are_counted_once() {
+        let script = "
+f = |n|
+  if n > 0
+    f n - 1
+f 5
+";
+        let (_, report) = run_with_profiler(script);
+
+        let f = report
+            .functions
+            .iter()
+            .find(|function| function.name == "<script>:2")
+            .unwrap();
+        assert_eq!(f.self_stats, f.total_stats);
+    }
+
+    #[test]
+    fn functions_called_from_native_functions() {
+        let script = "
+double = |x| x * 2
+(1, 2, 3)
+  .each double
+  .to_tuple()
+";
+        let (_, report) = run_with_profiler(script);
+
+        // Calls made by the iterator adaptor are nested in the script's stack
+        assert_eq!(stack_names(&report), &["<script>", "<script>;<script>:2"]);
+    }
+
+    #[test]
+    fn lines() {
+        let script = "\
+x = 1
+y = 2
+";
+        let (_, report) = run_with_profiler(script);
+
+        let mut lines: Vec<u32> = report.lines.iter().map(|line| line.line).collect();
+        lines.sort();
+        assert_eq!(lines, &[0, 1]);
+    }
+
+    #[test]
+    fn collapsed_stacks() {
+        let script = "
+f = |n|
+  result = 0
+  for i in 0..n
+    result += i
+  result
+f 1000
+";
+        let (_, report) = run_with_profiler(script);
+
+        let collapsed = report.collapsed_stacks();
+        for line in collapsed.lines() {
+            let (stack, time) = line.rsplit_once(' ').unwrap();
+            assert!(stack.starts_with("<script>"));
+            assert!(time.parse::<u64>().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let (vm, report) = run_with_profiler("x = 1");
+        assert!(!report.functions.is_empty());
+
+        vm.reset_profile();
+        let report = vm.profile_report().unwrap();
+        assert!(report.functions.is_empty());
+        assert!(report.lines.is_empty());
+    }
+}
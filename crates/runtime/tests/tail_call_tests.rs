@@ -0,0 +1,238 @@
+mod tail_calls {
+    use koto_bytecode::CompilerSettings;
+    use koto_runtime::{ErrorKind, prelude::*};
+    use koto_test_utils::*;
+
+    // Tail calls reuse the caller's frame, so deep recursion should succeed with a call depth
+    // that would be exceeded if each call pushed a new frame.
+    const MAX_CALL_DEPTH: usize = 10;
+
+    fn check_output_with_limited_call_depth(script: &str, expected_output: impl Into<KValue>) {
+        let vm = KotoVm::with_settings(KotoVmSettings {
+            max_call_depth: Some(MAX_CALL_DEPTH),
+            ..Default::default()
+        });
+        if let Err(error) = check_script_output_with_vm(vm, script, expected_output) {
+            panic!("{error}");
+        }
+    }
+
+    #[test]
+    fn million_deep_recursion() {
+        let script = "
+count = |n, acc|
+  if n == 0
+    acc
+  else
+    count n - 1, acc + 1
+count 1_000_000, 0
+";
+        check_output_with_limited_call_depth(script, 1_000_000);
+    }
+
+    #[test]
+    fn explicit_return() {
+        let script = "
+count_down = |n|
+  if n > 0 then return count_down n - 1
+  'done'
+count_down 1_000_000
+";
+        check_output_with_limited_call_depth(script, "done");
+    }
+
+    #[test]
+    fn mutual_recursion() {
+        let script = "
+export is_even = |n| if n == 0 then true else is_odd n - 1
+export is_odd = |n| if n == 0 then false else is_even n - 1
+is_even 100_001
+";
+        check_output_with_limited_call_depth(script, false);
+    }
+
+    #[test]
+    fn match_and_switch_arms() {
+        let script = "
+export f = |n|
+  match n
+    0 then 'f'
+    else g n - 1
+export g = |n|
+  switch
+    n == 0 then 'g'
+    else f n - 1
+(f 1000, f 1001)
+";
+        check_output_with_limited_call_depth(script, tuple(&["f".into(), "g".into()]));
+    }
+
+    #[test]
+    fn instance_function() {
+        let script = "
+counter =
+  count: 0
+  run: |n|
+    if n == 0 then return self.count
+    self.count += 1
+    self.run n - 1
+counter.run 10_000
+";
+        check_output_with_limited_call_depth(script, 10_000);
+    }
+
+    #[test]
+    fn optional_and_variadic_args() {
+        let script = "
+f = |n, total = 0, rest...|
+  if n == 0
+    (total, rest)
+  else
+    f n - 1, total + n, n
+f 4
+";
+        check_script_output(script, tuple(&[10.into(), number_tuple(&[1])]));
+    }
+
+    #[test]
+    fn captured_values() {
+        let script = "
+offset = 100
+f = |n, acc|
+  if n == 0 then acc + offset else f n - 1, acc + 1
+f 10, 0
+";
+        check_script_output(script, 110);
+    }
+
+    #[test]
+    fn packed_args() {
+        let script = "
+f = |n, xs...|
+  if n == 0 then xs else f n - 1, xs...
+f 3, 1, 2, 3
+";
+        check_script_output(script, number_tuple(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn native_function_in_tail_position() {
+        let script = "
+f = |xs| xs.to_tuple()
+f [1, 2, 3]
+";
+        check_script_output(script, number_tuple(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn callable_map_in_tail_position() {
+        let script = "
+callable = {@call: |n| n * 2}
+f = |n| callable n
+f 21
+";
+        check_script_output(script, 42);
+    }
+
+    #[test]
+    fn generator_in_tail_position() {
+        let script = "
+gen = |n|
+  for i in 0..n
+    yield i
+f = |n| gen n
+f(3).to_tuple()
+";
+        check_script_output(script, number_tuple(&[0, 1, 2]));
+    }
+
+    #[test]
+    fn call_inside_try_block() {
+        let script = "
+f = |n|
+  try
+    if n == 0 then throw 'bottom'
+    return f n - 1
+  catch error
+    'caught {error} at {n}'
+f 3
+";
+        check_script_output(script, "caught bottom at 0");
+    }
+
+    #[test]
+    fn output_type_is_checked() {
+        let script = "
+g = |n| n
+f = |n| -> String
+  g n
+try
+  f 1
+catch _
+  'type check failed'
+";
+        check_script_output(script, "type check failed");
+    }
+
+    #[test]
+    fn function_called_from_native_function() {
+        let script = "
+count_down = |n| if n > 0 then count_down n - 1 else 'done'
+(1, 10, 100)
+  .each |n| count_down n
+  .to_tuple()
+";
+        check_script_output(
+            script,
+            tuple(&["done".into(), "done".into(), "done".into()]),
+        );
+    }
+
+    #[test]
+    fn call_depth_is_exceeded_without_tail_calls() {
+        let script = "
+count = |n, acc|
+  if n == 0
+    acc
+  else
+    count n - 1, acc + 1
+count 100, 0
+";
+        let settings = CompilerSettings {
+            enable_optimizations: false,
+            ..Default::default()
+        };
+        let chunk = compile_test_script_with_settings(script, None, settings);
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            max_call_depth: Some(MAX_CALL_DEPTH),
+            ..Default::default()
+        });
+        match vm.run(chunk) {
+            Err(error) => assert!(matches!(error.error, ErrorKind::StackOverflow(_))),
+            Ok(_) => panic!("Expected a stack overflow"),
+        }
+    }
+
+    // Frames replaced by tail calls don't appear in error traces
+    #[test]
+    fn error_trace_after_tail_calls() {
+        let script = "
+f = |n| if n == 0 then throw 'oops' else f n - 1
+f 100
+";
+        let trace_length = |enable_optimizations| {
+            let settings = CompilerSettings {
+                enable_optimizations,
+                ..Default::default()
+            };
+            let chunk = compile_test_script_with_settings(script, None, settings);
+            match KotoVm::default().run(chunk) {
+                Ok(_) => panic!("Expected an error"),
+                Err(error) => error.trace.len(),
+            }
+        };
+
+        assert_eq!(trace_length(true), 2);
+        assert_eq!(trace_length(false), 102);
+    }
+}
@@ -67,6 +67,15 @@ a = 99
             check_script_output("(1 % 0).is_nan()", true);
         }
 
+        #[test]
+        fn remainder_assign_with_a_divisor_of_zero() {
+            let script = "
+x = 1
+x %= 0
+x.is_nan()";
+            check_script_output(script, true);
+        }
+
         #[test]
         fn power_negative() {
             check_script_output("2 ^ -1", 0.5);
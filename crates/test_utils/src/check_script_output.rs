@@ -1,5 +1,6 @@
 use crate::{OutputCapture, run_test_script};
-use koto_runtime::{Result, prelude::*};
+use koto_bytecode::{Chunk, CompilerSettings, ModuleLoader};
+use koto_runtime::{Ptr, Result, prelude::*};
 
 /// Runs a script and validates its output
 pub fn check_script_output(script: &str, expected_output: impl Into<KValue>) {
@@ -22,3 +23,21 @@ pub fn check_script_output_with_vm(
 ) -> Result<()> {
     run_test_script(vm, script, None, Some(expected_output.into()))
 }
+
+/// Compiles a script with the default compiler settings, panicking if compilation fails
+pub fn compile_test_script(script: &str) -> Ptr<Chunk> {
+    compile_test_script_with_settings(script, None, CompilerSettings::default())
+}
+
+/// Compiles a script with the provided path and settings, panicking if compilation fails
+pub fn compile_test_script_with_settings(
+    script: &str,
+    script_path: Option<KString>,
+    settings: CompilerSettings,
+) -> Ptr<Chunk> {
+    let mut loader = ModuleLoader::default();
+    match loader.compile_script(script, script_path, settings) {
+        Ok(chunk) => chunk,
+        Err(error) => panic!("Error while compiling script: {error}"),
+    }
+}
@@ -9,7 +9,10 @@ mod run_test_script;
 mod script_instructions;
 mod type_helpers;
 
-pub use check_script_output::{check_script_output, check_script_output_with_vm};
+pub use check_script_output::{
+    check_script_output, check_script_output_with_vm, compile_test_script,
+    compile_test_script_with_settings,
+};
 pub use doc_examples::run_koto_examples_in_markdown;
 pub use output_capture::OutputCapture;
 pub use run_test_script::run_test_script;
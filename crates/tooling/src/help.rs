@@ -5,9 +5,8 @@ use std::{
     rc::Rc,
 };
 
-use crate::wrap_string_with_indent;
-
 const HELP_RESULT_STR: &str = "➝ ";
+/// The indentation used for help entries
 pub const HELP_INDENT: &str = "  ";
 
 macro_rules! include_doc {
@@ -16,6 +15,8 @@ macro_rules! include_doc {
     };
 }
 
+/// An entry in the help index
+#[allow(missing_docs)]
 pub struct HelpEntry {
     // The entry's user-displayed name
     pub name: Rc<str>,
@@ -27,6 +28,10 @@ pub struct HelpEntry {
     pub see_also: Vec<Rc<str>>,
 }
 
+/// An index of the help entries found in Koto's docs
+///
+/// Entries are taken from the language guide, and from the reference docs of the core library and
+/// the additional modules that are included with the CLI.
 pub struct Help {
     // All help entries, keys are lower_snake_case
     help_map: IndexMap<Rc<str>, HelpEntry>,
@@ -39,6 +44,7 @@ pub struct Help {
 }
 
 impl Help {
+    /// Makes a new help index
     pub fn new() -> Self {
         let mut result = Self {
             help_map: IndexMap::new(),
@@ -84,6 +90,7 @@ impl Help {
         result
     }
 
+    /// Returns the names of the index's help topics
     pub fn topics(&self) -> impl Iterator<Item = Rc<str>> {
         self.core_lib_names
             .iter()
@@ -92,11 +99,16 @@ impl Help {
             .cloned()
     }
 
+    /// Returns all of the index's entries, along with their search keys
     pub fn all_entries(&self) -> impl Iterator<Item = (&Rc<str>, &HelpEntry)> {
         self.help_map.iter()
     }
 
-    pub fn get_help(&self, search: Option<&str>) -> String {
+    /// Returns the help for the given search string
+    ///
+    /// If no search string is provided then an overview of the available topics is returned,
+    /// with the list of topics wrapped to the given width.
+    pub fn get_help(&self, search: Option<&str>, wrap_width: usize) -> String {
         match search {
             Some(search) => {
                 let search_key = text_to_key(search);
@@ -155,7 +167,7 @@ See also:";
 
                         match matches.as_slice() {
                             [] => format!("No matches for '{search}' found."),
-                            [(only_match, _)] => self.get_help(Some(only_match)),
+                            [(only_match, _)] => self.get_help(Some(only_match), wrap_width),
                             _ => {
                                 let mut help = String::new();
                                 help.push_str("More than one match found: ");
@@ -200,7 +212,12 @@ Help is available for the following topics:",
 {HELP_INDENT}{topic}:
 {}
 ",
-                        wrap_string_with_indent(&topics_string, &topics_indent)
+                        textwrap::fill(
+                            &topics_string,
+                            textwrap::Options::new(wrap_width.saturating_sub(topics_indent.len()))
+                                .initial_indent(&topics_indent)
+                                .subsequent_indent(&topics_indent),
+                        )
                     ));
                 };
 
@@ -348,6 +365,12 @@ Help is available for the following topics:",
     }
 }
 
+impl Default for Help {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 fn text_to_key(text: &str) -> Rc<str> {
     text.chars()
         .filter_map(|c| match c {
@@ -0,0 +1,13 @@
+//! Shared support for the Koto CLI and language server
+
+#![warn(missing_docs)]
+
+mod help;
+mod message;
+mod module_paths;
+
+pub use crate::{
+    help::{HELP_INDENT, Help, HelpEntry},
+    message::{MAX_CONTENT_LENGTH, read_message, write_message},
+    module_paths::module_search_paths,
+};
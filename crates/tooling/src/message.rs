@@ -0,0 +1,62 @@
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// The largest message content that will be accepted by [read_message], in bytes
+pub const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Reads a JSON message that's preceded by a `Content-Length` header
+///
+/// This is the message framing used by both the Language Server Protocol and the Debug Adapter
+/// Protocol.
+///
+/// `None` is returned when the input has ended.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(length) = line.strip_prefix("Content-Length:") {
+            let length = length.trim();
+            match length.parse::<usize>() {
+                Ok(length) => content_length = Some(length),
+                Err(_) => return invalid_data(format!("invalid Content-Length ({length})")),
+            }
+        }
+    }
+
+    let Some(content_length) = content_length else {
+        return invalid_data("missing Content-Length header".into());
+    };
+
+    // The content buffer is allocated up front, so the length needs to be checked first
+    if content_length > MAX_CONTENT_LENGTH {
+        return invalid_data(format!(
+            "Content-Length ({content_length}) exceeds the maximum of {MAX_CONTENT_LENGTH} bytes"
+        ));
+    }
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    let message = serde_json::from_slice(&content)?;
+    Ok(Some(message))
+}
+
+/// Writes a JSON message, preceded by a `Content-Length` header
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    writer.flush()
+}
+
+fn invalid_data<T>(message: String) -> io::Result<T> {
+    Err(io::Error::new(io::ErrorKind::InvalidData, message))
+}
@@ -0,0 +1,14 @@
+use std::{env, path::PathBuf};
+
+/// Returns the module search paths defined in the `KOTO_PATH` environment variable
+///
+/// The paths are separated by the platform's path separator, with empty entries being skipped.
+pub fn module_search_paths() -> Vec<PathBuf> {
+    env::var_os("KOTO_PATH")
+        .map(|paths| {
+            env::split_paths(&paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
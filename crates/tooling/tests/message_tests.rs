@@ -0,0 +1,47 @@
+mod message {
+    use koto_tooling::{MAX_CONTENT_LENGTH, read_message, write_message};
+    use serde_json::json;
+    use std::io::{Cursor, ErrorKind};
+
+    #[test]
+    fn write_then_read() {
+        let message = json!({"id": 1, "method": "initialize"});
+
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &message).unwrap();
+        write_message(&mut buffer, &message).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message.clone()));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn additional_headers_are_ignored() {
+        let input = "Content-Type: application/json\r\nContent-Length: 2\r\n\r\n{}";
+        let message = read_message(&mut Cursor::new(input)).unwrap();
+        assert_eq!(message, Some(json!({})));
+    }
+
+    #[test]
+    fn missing_content_length() {
+        let input = "Content-Type: application/json\r\n\r\n{}";
+        let error = read_message(&mut Cursor::new(input)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn content_length_above_the_maximum() {
+        let input = format!("Content-Length: {}\r\n\r\n{{}}", MAX_CONTENT_LENGTH + 1);
+        let error = read_message(&mut Cursor::new(input)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_content() {
+        let input = "Content-Length: 10\r\n\r\n{}";
+        let error = read_message(&mut Cursor::new(input)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}